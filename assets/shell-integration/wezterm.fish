@@ -0,0 +1,57 @@
+# This file hooks up shell integration for wezterm.
+# It is suitable for fish.
+#
+# wezterm loads this automatically from vendor_conf.d when the
+# `shell_integration` option is enabled.  You may also source it
+# from your config.fish.
+#
+# WEZTERM_SHELL_SKIP_ALL - disables all
+# WEZTERM_SHELL_SKIP_SEMANTIC_ZONES - disables zones
+# WEZTERM_SHELL_SKIP_CWD - disables OSC 7 cwd setting
+
+# Undo the XDG_DATA_DIRS adjustment that wezterm made in order to
+# have fish load this file, so that it isn't inherited by children
+if set -q WEZTERM_XDG_DATA_DIRS
+    if test -n "$WEZTERM_XDG_DATA_DIRS"
+        set -gx XDG_DATA_DIRS $WEZTERM_XDG_DATA_DIRS
+    else
+        set -e XDG_DATA_DIRS
+    end
+    set -e WEZTERM_XDG_DATA_DIRS
+end
+
+if not status is-interactive
+    exit 0
+end
+
+if test "$WEZTERM_SHELL_SKIP_ALL" = "1"
+    exit 0
+end
+
+if set -q __wezterm_integration_loaded
+    exit 0
+end
+set -g __wezterm_integration_loaded 1
+
+if test -z "$WEZTERM_SHELL_SKIP_SEMANTIC_ZONES"
+    function __wezterm_semantic_prompt --on-event fish_prompt
+        # Fresh line and start the prompt
+        printf '\e]133;A;cl=m;aid=%s\a' $fish_pid
+    end
+
+    function __wezterm_semantic_preexec --on-event fish_preexec
+        # Indicate that the command output begins here
+        printf '\e]133;C;\a'
+    end
+
+    function __wezterm_semantic_postexec --on-event fish_postexec
+        # Report last command status
+        printf '\e]133;D;%s;aid=%s\a' $status $fish_pid
+    end
+end
+
+if test -z "$WEZTERM_SHELL_SKIP_CWD"
+    function __wezterm_osc7 --on-event fish_prompt
+        printf '\e]7;file://%s%s\a' $hostname (string escape --style=url -- $PWD)
+    end
+end
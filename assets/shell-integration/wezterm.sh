@@ -34,6 +34,13 @@ case "$TERM" in
   ;;
 esac
 
+if [[ -n "${__wezterm_integration_loaded}" ]] ; then
+  # Already loaded, perhaps both from the system profile and
+  # by the wezterm `shell_integration` option
+  return 0
+fi
+__wezterm_integration_loaded=1
+
 # This function wraps bash-preexec.sh so that it can be included verbatim
 # in this file, even though it uses `return` to short-circuit in some cases.
 __wezterm_install_bash_prexec() {
@@ -416,7 +416,7 @@ macro_rules! pdu {
 /// The overall version of the codec.
 /// This must be bumped when backwards incompatible changes
 /// are made to the types and protocol.
-pub const CODEC_VERSION: usize = 24;
+pub const CODEC_VERSION: usize = 25;
 
 // Defines the Pdu enum.
 // Each struct has an explicit identifying number.
@@ -464,6 +464,9 @@ pdu! {
     GetImageCellResponse: 47,
     MovePaneToNewTab: 48,
     MovePaneToNewTabResponse: 49,
+    SetPaneOutputPaused: 50,
+    SetTabTitle: 51,
+    RenameWorkspace: 52,
 }
 
 impl Pdu {
@@ -589,6 +592,9 @@ pub struct ListPanes {}
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 pub struct ListPanesResponse {
     pub tabs: Vec<PaneNode>,
+    /// The explicitly assigned title of each of the `tabs`,
+    /// or an empty string if none has been assigned
+    pub tab_titles: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
@@ -767,6 +773,24 @@ pub struct SetPaneZoomed {
     pub zoomed: bool,
 }
 
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct SetPaneOutputPaused {
+    pub pane_id: PaneId,
+    pub paused: bool,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct SetTabTitle {
+    pub tab_id: TabId,
+    pub title: String,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct RenameWorkspace {
+    pub old_workspace: String,
+    pub new_workspace: String,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Debug)]
 pub struct GetPaneRenderChanges {
     pub pane_id: PaneId,
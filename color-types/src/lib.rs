@@ -580,6 +580,22 @@ impl LinearRgba {
     pub fn tuple(self) -> (f32, f32, f32, f32) {
         (self.0, self.1, self.2, self.3)
     }
+
+    /// Returns the relative luminance of the color, as defined by
+    /// <https://www.w3.org/TR/WCAG20/#relativeluminancedef>
+    pub fn relative_luminance(self) -> f32 {
+        0.2126 * self.0 + 0.7152 * self.1 + 0.0722 * self.2
+    }
+
+    /// Returns the contrast ratio between self and other, in the range
+    /// 1.0 (no contrast) to 21.0 (black vs. white), as defined by
+    /// <https://www.w3.org/TR/WCAG20/#contrast-ratiodef>
+    pub fn contrast_ratio(self, other: Self) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+        (lighter + 0.05) / (darker + 0.05)
+    }
 }
 
 #[cfg(test)]
@@ -591,6 +607,15 @@ mod tests {
         assert_eq!(dark_green.to_rgb_string(), "#006400");
     }
 
+    #[test]
+    fn contrast_ratio() {
+        let black = LinearRgba::with_components(0., 0., 0., 1.);
+        let white = LinearRgba::with_components(1., 1., 1., 1.);
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 0.01);
+        assert!((white.contrast_ratio(black) - 21.0).abs() < 0.01);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 0.01);
+    }
+
     #[test]
     fn from_hsl() {
         let foo = SrgbaTuple::from_str("hsl:235 100  50").unwrap();
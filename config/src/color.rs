@@ -100,8 +100,8 @@ pub struct Palette {
     /// A list of 8 colors corresponding to bright versions of the
     /// ANSI palette
     pub brights: Option<[RgbaColor; 8]>,
-    /// A map for setting arbitrary colors ranging from 16 to 256 in the color
-    /// palette
+    /// A map for setting arbitrary colors ranging from 0 to 255 in the color
+    /// palette.  Entries here override those set via `ansi` and `brights`
     #[dynamic(default)]
     pub indexed: HashMap<u8, RgbaColor>,
     /// Configure the colors and styling of the tab bar
@@ -149,15 +149,9 @@ impl From<Palette> for wezterm_term::color::ColorPalette {
                 p.colors.0[idx + 8] = (*col).into();
             }
         }
+        // Applied after ansi and brights so that an explicit index
+        // takes precedence over those
         for (&idx, &col) in &cfg.indexed {
-            if idx < 16 {
-                log::warn!(
-                    "Ignoring invalid colors.indexed index {}; \
-                           use `ansi` or `brights` to specify lower indices",
-                    idx
-                );
-                continue;
-            }
             p.colors.0[idx as usize] = col.into();
         }
         p
@@ -290,6 +284,20 @@ pub struct TabBarStyle {
     pub new_tab: String,
     #[dynamic(default = "default_new_tab")]
     pub new_tab_hover: String,
+    /// The close button that is shown after the title of each
+    /// tab when use_fancy_tab_bar is false
+    #[dynamic(default = "default_close_tab")]
+    pub close_tab: String,
+    #[dynamic(default = "default_close_tab")]
+    pub close_tab_hover: String,
+    /// Prefixed to the default title of an inactive tab that
+    /// has produced output since it was last active
+    #[dynamic(default = "default_activity_indicator")]
+    pub activity_indicator: String,
+    /// Prefixed to the default title of an inactive tab that
+    /// has rung the bell since it was last active
+    #[dynamic(default = "default_bell_indicator")]
+    pub bell_indicator: String,
 }
 
 impl Default for TabBarStyle {
@@ -297,6 +305,10 @@ impl Default for TabBarStyle {
         Self {
             new_tab: default_new_tab(),
             new_tab_hover: default_new_tab(),
+            close_tab: default_close_tab(),
+            close_tab_hover: default_close_tab(),
+            activity_indicator: default_activity_indicator(),
+            bell_indicator: default_bell_indicator(),
         }
     }
 }
@@ -305,6 +317,18 @@ fn default_new_tab() -> String {
     " + ".to_string()
 }
 
+fn default_close_tab() -> String {
+    "x ".to_string()
+}
+
+fn default_activity_indicator() -> String {
+    "• ".to_string()
+}
+
+fn default_bell_indicator() -> String {
+    "🔔 ".to_string()
+}
+
 #[derive(Debug, Clone, FromDynamic, ToDynamic)]
 pub struct WindowFrameConfig {
     #[dynamic(default = "default_inactive_titlebar_bg")]
@@ -10,7 +10,8 @@ use crate::font::{
 };
 use crate::frontend::FrontEndSelection;
 use crate::keyassignment::{
-    KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MouseEventTrigger, SpawnCommand,
+    KeyAssignment, KeyTable, KeyTableEntry, KeyTables, MouseEventTrigger, QuickSelectPatternAction,
+    SpawnCommand,
 };
 use crate::keys::{Key, LeaderKey, Mouse};
 use crate::lua::make_lua_context;
@@ -28,7 +29,8 @@ use anyhow::Context;
 use luahelper::impl_lua_conversion_dynamic;
 use mlua::FromLua;
 use portable_pty::{CommandBuilder, PtySize};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -37,7 +39,7 @@ use std::time::Duration;
 use termwiz::hyperlink;
 use termwiz::surface::CursorShape;
 use wezterm_bidi::ParagraphDirectionHint;
-use wezterm_dynamic::{FromDynamic, ToDynamic};
+use wezterm_dynamic::{FromDynamic, FromDynamicOptions, ToDynamic, UnknownFieldAction};
 use wezterm_input_types::{Modifiers, WindowDecorations};
 
 #[derive(Debug, Clone, FromDynamic, ToDynamic)]
@@ -52,6 +54,31 @@ pub struct Config {
     #[dynamic(default = "default_one_point_oh_f64")]
     pub cell_width: f64,
 
+    /// Overrides the thickness of underlines and of the lines
+    /// in box drawing glyphs, rather than taking it from the font
+    #[dynamic(try_from = "crate::units::OptPixelUnit", default)]
+    pub underline_thickness: Option<Dimension>,
+
+    /// Overrides the position of the underline relative to the
+    /// baseline, rather than taking it from the font
+    #[dynamic(try_from = "crate::units::OptPixelUnit", default)]
+    pub underline_position: Option<Dimension>,
+
+    /// Overrides the position of the strikethrough relative to
+    /// the baseline, rather than taking it from the font
+    #[dynamic(try_from = "crate::units::OptPixelUnit", default)]
+    pub strikethrough_position: Option<Dimension>,
+
+    /// Overrides the position of the overline relative to
+    /// the baseline, rather than using the ascender of the font
+    #[dynamic(try_from = "crate::units::OptPixelUnit", default)]
+    pub overline_position: Option<Dimension>,
+
+    /// Overrides the thickness of the bar, underline and
+    /// outline cursor shapes
+    #[dynamic(try_from = "crate::units::OptPixelUnit", default)]
+    pub cursor_thickness: Option<Dimension>,
+
     #[dynamic(default)]
     pub allow_square_glyphs_to_overflow_width: AllowSquareGlyphOverflow,
 
@@ -153,6 +180,27 @@ pub struct Config {
     #[dynamic(default)]
     pub set_environment_variables: HashMap<String, String>,
 
+    /// Specifies the names of environment variables whose values are
+    /// copied from the foreground process of the current pane when
+    /// spawning a new tab or splitting a pane.
+    #[dynamic(default)]
+    pub inherit_environment_variables: Vec<String>,
+
+    /// When true, commands that run a recognized shell are started
+    /// as login shells, just as the default shell is.
+    #[dynamic(default)]
+    pub login_shell: bool,
+
+    /// When true, bash, zsh and fish started in the local domain
+    /// load the wezterm shell integration automatically.
+    #[dynamic(default)]
+    pub shell_integration: bool,
+
+    /// How to highlight the output of commands that reported a
+    /// non-zero exit status via OSC 133;D
+    #[dynamic(default)]
+    pub failed_command_highlight: FailedCommandHighlight,
+
     /// Specifies the height of a new window, expressed in character cells.
     #[dynamic(default = "default_initial_rows")]
     pub initial_rows: u16,
@@ -164,9 +212,22 @@ pub struct Config {
     #[dynamic(default = "default_initial_cols")]
     pub initial_cols: u16,
 
+    /// If true (the default), new windows are created with the
+    /// size of the most recently resized window, rather than
+    /// the size specified by initial_cols and initial_rows, and
+    /// the first window is placed where the most recently moved
+    /// window was.
+    #[dynamic(default = "default_true")]
+    pub remember_window_placement: bool,
+
     #[dynamic(default = "default_hyperlink_rules")]
     pub hyperlink_rules: Vec<hyperlink::Rule>,
 
+    /// Specifies the program used to open hyperlinks and files,
+    /// instead of the default handler for the system.  The url or
+    /// path is passed as an additional argument.
+    pub open_command: Option<Vec<String>>,
+
     /// What to set the TERM variable to
     #[dynamic(default = "default_term")]
     pub term: String,
@@ -194,6 +255,16 @@ pub struct Config {
     #[dynamic(default)]
     pub freetype_pcf_long_family_names: bool,
 
+    /// When a bold font is requested but the family has no bold
+    /// face, embolden the regular face instead of using it as-is.
+    #[dynamic(default = "default_true")]
+    pub allow_synthetic_bold: bool,
+
+    /// When an italic font is requested but the family has no
+    /// italic face, slant the regular face instead of using it as-is.
+    #[dynamic(default = "default_true")]
+    pub allow_synthetic_italic: bool,
+
     /// Specify the features to enable when using harfbuzz for font shaping.
     /// There is some light documentation here:
     /// <https://harfbuzz.github.io/shaping-opentype-features.html>
@@ -270,6 +341,12 @@ pub struct Config {
     #[dynamic(default = "default_mux_output_parser_buffer_size")]
     pub mux_output_parser_buffer_size: usize,
 
+    /// The maximum number of bytes of output that will be
+    /// held back from a pane whose output has been paused.
+    /// Once exceeded, the pane is automatically resumed.
+    #[dynamic(default = "default_max_paused_output_bytes")]
+    pub max_paused_output_bytes: usize,
+
     #[dynamic(default = "default_mux_env_remove")]
     pub mux_env_remove: Vec<String>,
 
@@ -292,6 +369,8 @@ pub struct Config {
     pub disable_default_quick_select_patterns: bool,
     #[dynamic(default)]
     pub quick_select_patterns: Vec<String>,
+    #[dynamic(default)]
+    pub quick_select_actions: Vec<QuickSelectPatternAction>,
     #[dynamic(default = "default_alphabet")]
     pub quick_select_alphabet: String,
 
@@ -330,6 +409,15 @@ pub struct Config {
     #[dynamic(default)]
     pub tab_bar_at_bottom: bool,
 
+    /// If true, each tab in the tab bar has a button that closes it
+    #[dynamic(default = "default_true")]
+    pub show_close_tab_button_in_tabs: bool,
+
+    /// If true, and show_close_tab_button_in_tabs is also true, each tab
+    /// in the retro tab bar (use_fancy_tab_bar=false) has a close button
+    #[dynamic(default)]
+    pub show_close_tab_button_in_retro_tab_bar: bool,
+
     /// If true, tab bar titles are prefixed with the tab index
     #[dynamic(default = "default_true")]
     pub show_tab_index_in_tab_bar: bool,
@@ -344,10 +432,24 @@ pub struct Config {
     #[dynamic(default = "default_tab_max_width")]
     pub tab_max_width: usize,
 
+    /// If set, the active tab may be this wide, rather than being
+    /// limited to tab_max_width, when there is room in the tab bar.
+    #[dynamic(default)]
+    pub active_tab_max_width: Option<usize>,
+
+    /// Where to remove text from titles that are too wide for their tab
+    #[dynamic(default)]
+    pub tab_title_elision: TitleElision,
+
     /// If true, hide the tab bar if the window only has a single tab.
     #[dynamic(default)]
     pub hide_tab_bar_if_only_one_tab: bool,
 
+    /// If true, closing the active tab activates the previously
+    /// active tab, rather than the tab to its right.
+    #[dynamic(default)]
+    pub switch_to_last_active_tab_when_closing_tab: bool,
+
     #[dynamic(default)]
     pub enable_scroll_bar: bool,
 
@@ -394,6 +496,11 @@ pub struct Config {
     #[dynamic(default)]
     pub foreground_text_hsb: HsbTransform,
 
+    /// Selects the color space in which translucent layers are
+    /// blended together when rendering the window.
+    #[dynamic(default)]
+    pub blend_color_space: BlendColorSpace,
+
     #[dynamic(default)]
     pub background: Vec<BackgroundLayer>,
 
@@ -442,6 +549,11 @@ pub struct Config {
     #[dynamic(default = "default_inactive_pane_hsb")]
     pub inactive_pane_hsb: HsbTransform,
 
+    /// When true, a row at the top of each pane is used to show
+    /// the title, current working directory and size of that pane
+    #[dynamic(default)]
+    pub show_pane_header: bool,
+
     #[dynamic(default = "default_one_point_oh")]
     pub text_background_opacity: f32,
 
@@ -461,9 +573,31 @@ pub struct Config {
     #[dynamic(default = "default_anim_fps")]
     pub animation_fps: u8,
 
+    /// Caps the rate at which animated images (GIF, APNG, WebP)
+    /// advance their frames; 0 means no cap.
+    #[dynamic(default = "default_max_fps")]
+    pub max_image_animation_fps: u8,
+
+    /// When true, animated images stop advancing while the
+    /// window doesn't have focus.
+    #[dynamic(default = "default_true")]
+    pub pause_image_animation_when_unfocused: bool,
+
+    /// When true, and the system has been asked to reduce motion,
+    /// the cursor and text don't blink, the visual bell doesn't
+    /// fade and animated images don't advance on their own.
+    #[dynamic(default = "default_true")]
+    pub respect_reduced_motion: bool,
+
     #[dynamic(default)]
     pub force_reverse_video_cursor: bool,
 
+    /// When true, selected text is rendered using reverse video
+    /// (swapping the foreground and background colors of each cell)
+    /// rather than using selection_fg and selection_bg
+    #[dynamic(default)]
+    pub force_reverse_video_selection: bool,
+
     /// Specifies the default cursor style.  various escape sequences
     /// can override the default style in different situations (eg:
     /// an editor can change it depending on the mode), but this value
@@ -501,6 +635,14 @@ pub struct Config {
     #[dynamic(default = "linear_ease")]
     pub text_blink_rapid_ease_out: EasingFunction,
 
+    /// Controls how text with the blink attribute is rendered.
+    /// `Blink` animates the text between visible and invisible,
+    /// `Tint` renders it steadily with its background tinted
+    /// towards the foreground color, and `None` renders it
+    /// the same as regular text.
+    #[dynamic(default)]
+    pub text_blink_style: TextBlinkStyle,
+
     /// If non-zero, specifies the period (in seconds) at which various
     /// statistics are logged.  Note that there is a minimum period of
     /// 10 seconds.
@@ -521,6 +663,11 @@ pub struct Config {
     #[dynamic(default)]
     pub ime_preedit_rendering: ImePreeditRendering,
 
+    /// When true, the visible text of the active pane is exposed
+    /// to screen readers via the platform accessibility APIs
+    #[dynamic(default)]
+    pub screen_reader_support: bool,
+
     #[dynamic(default = "default_true")]
     pub use_dead_keys: bool,
 
@@ -553,6 +700,11 @@ pub struct Config {
     #[dynamic(default)]
     pub native_macos_fullscreen_mode: bool,
 
+    /// When true, new windows on macOS are grouped together as native
+    /// window tabs rather than opening as separate windows.
+    #[dynamic(default)]
+    pub native_macos_window_tabs: bool,
+
     #[dynamic(default = "default_word_boundary")]
     pub selection_word_boundary: String,
 
@@ -568,6 +720,11 @@ pub struct Config {
     #[dynamic(default = "default_alternate_buffer_wheel_scroll_speed")]
     pub alternate_buffer_wheel_scroll_speed: u8,
 
+    /// Scales the number of lines that the viewport is scrolled
+    /// by the mouse wheel or touchpad
+    #[dynamic(default = "default_one_point_oh_f64")]
+    pub wheel_scroll_speed: f64,
+
     #[dynamic(default = "default_status_update_interval")]
     pub status_update_interval: u64,
 
@@ -616,18 +773,39 @@ pub struct Config {
     #[dynamic(default)]
     pub audible_bell: AudibleBell,
 
+    /// When the bell rings in a pane that is not visible to the
+    /// user, ask the desktop environment to draw attention to
+    /// the window
+    #[dynamic(default)]
+    pub window_attention_on_bell: bool,
+
     #[dynamic(default)]
     pub canonicalize_pasted_newlines: Option<NewlineCanon>,
 
+    /// When true, pasted and input-method composed text is converted
+    /// to Unicode NFC before it is sent to the pty
+    #[dynamic(default)]
+    pub normalize_output_to_unicode_nfc: bool,
+
     #[dynamic(default = "default_unicode_version")]
     pub unicode_version: u8,
 
     #[dynamic(default)]
     pub treat_east_asian_ambiguous_width_as_wide: bool,
 
+    /// When true, characters that can be presented either as text
+    /// or as emoji default to emoji presentation
+    #[dynamic(default)]
+    pub prefer_emoji_presentation: bool,
+
     #[dynamic(default = "default_true")]
     pub allow_download_protocols: bool,
 
+    /// The largest file, in bytes, that will be accepted via
+    /// a download protocol
+    #[dynamic(default = "default_max_download_size")]
+    pub max_download_size: usize,
+
     #[dynamic(default = "default_true")]
     pub allow_win32_input_mode: bool,
 
@@ -637,6 +815,12 @@ pub struct Config {
     #[dynamic(default)]
     pub default_workspace: Option<String>,
 
+    /// When using `wezterm connect`, show a launcher listing the
+    /// workspaces of the multiplexer once it has been attached,
+    /// so that the user can choose which of them to work in
+    #[dynamic(default)]
+    pub show_workspace_launcher_on_connect: bool,
+
     #[dynamic(default)]
     pub xcursor_theme: Option<String>,
 
@@ -649,6 +833,26 @@ pub struct Config {
     #[dynamic(default)]
     pub quote_dropped_files: DroppedFileQuoting,
 
+    /// What to do when a single directory is dropped onto the
+    /// terminal: paste its path, or `cd` into it
+    #[dynamic(default)]
+    pub dropped_directory_action: DroppedDirectoryAction,
+
+    /// What to do with files dropped onto a pane from an ssh domain:
+    /// paste their local paths, or upload them to the remote host
+    /// and paste the remote paths
+    #[dynamic(default)]
+    pub dropped_file_action: DroppedFileAction,
+
+    #[dynamic(default)]
+    pub clipboard_image_paste: ClipboardImagePaste,
+
+    /// If true (the default), text with the concealed (invisible)
+    /// attribute is included when copying the selection.  If false,
+    /// concealed text is copied as spaces.
+    #[dynamic(default = "default_true")]
+    pub copy_concealed_text: bool,
+
     #[dynamic(default = "default_focus_change_repaint_delay")]
     pub focus_change_repaint_delay: u64,
 }
@@ -725,39 +929,10 @@ impl Config {
             let mut s = String::new();
             file.read_to_string(&mut s)?;
 
-            let cfg: Self;
-
-            let lua = make_lua_context(p)?;
-            let config: mlua::Value = smol::block_on(
-                // Skip a potential BOM that Windows software may have placed in the
-                // file. Note that we can't catch this happening for files that are
-                // imported via the lua require function.
-                lua.load(s.trim_start_matches('\u{FEFF}'))
-                    .set_name(p.to_string_lossy())?
-                    .eval_async(),
-            )?;
-            let config = Self::apply_overrides_to(&lua, config)?;
-            let config = Self::apply_overrides_obj_to(&lua, config, overrides)?;
-            cfg = Config::from_lua(config, &lua).with_context(|| {
-                format!(
-                    "Error converting lua value returned by script {} to Config struct",
-                    p.display()
-                )
-            })?;
-
-            // Compute but discard the key bindings here so that we raise any
-            // problems earlier than we use them.
-            let _ = cfg.key_bindings();
-
-            std::env::set_var("WEZTERM_CONFIG_FILE", p);
-            if let Some(dir) = p.parent() {
-                std::env::set_var("WEZTERM_CONFIG_DIR", dir);
-            }
-            return Ok(LoadedConfig {
-                config: cfg.compute_extra_defaults(Some(p)),
-                file_name: Some(p.to_path_buf()),
-                lua: Some(lua),
-            });
+            let (loaded, includes) =
+                Self::load_from_source(p, &s, p.to_string_lossy().to_string(), overrides, None)?;
+            save_last_known_good(p, &s, includes);
+            return Ok(loaded);
         }
 
         // We didn't find (or were asked to skip) a wezterm.lua file, so
@@ -775,6 +950,249 @@ impl Config {
         })
     }
 
+    /// Processes the `include` key of the config table returned by the
+    /// config file at `config_file`.  `include` is a list of glob patterns,
+    /// relative to the directory containing the config file, that match
+    /// additional lua files.  Each of those files is evaluated in turn and
+    /// is expected to return a table; the tables are merged together in
+    /// order and finally the values from the main config file are merged
+    /// on top.
+    /// If `saved_includes` is Some, the included files are taken from
+    /// it, rather than by matching the `include` patterns against the
+    /// filesystem.
+    /// Returns the merged configuration along with the files that were
+    /// included.
+    fn apply_includes_to<'l>(
+        lua: &'l mlua::Lua,
+        config: mlua::Value<'l>,
+        config_file: &Path,
+        saved_includes: Option<Vec<IncludedFile>>,
+        reported_keys: &mut HashSet<String>,
+    ) -> anyhow::Result<(mlua::Value<'l>, Vec<IncludedFile>)> {
+        let tbl = match &config {
+            mlua::Value::Table(tbl) => tbl.clone(),
+            _ => return Ok((config, vec![])),
+        };
+
+        let patterns = match string_or_list(lua, tbl.get("include")?)
+            .context("include must be a string or a list of strings")?
+        {
+            Some(patterns) => patterns,
+            None => return Ok((config, vec![])),
+        };
+        tbl.set("include", mlua::Value::Nil)?;
+
+        let includes = match saved_includes {
+            Some(includes) => includes,
+            None => {
+                let config_dir = config_file.parent().unwrap_or_else(|| Path::new("/"));
+                let mut includes = vec![];
+                for pattern in patterns {
+                    let glob = filenamegen::Glob::new(&pattern)
+                        .with_context(|| format!("invalid include pattern {}", pattern))?;
+                    let mut paths: Vec<PathBuf> = glob
+                        .walk(config_dir)
+                        .map(|path| config_dir.join(path))
+                        .collect();
+                    if paths.is_empty() {
+                        log::warn!(
+                            "include pattern {} in {} didn't match any files",
+                            pattern,
+                            config_file.display()
+                        );
+                    }
+                    paths.sort();
+
+                    for path in paths {
+                        let source = std::fs::read_to_string(&path)
+                            .with_context(|| format!("Error reading {}", path.display()))?;
+                        includes.push(IncludedFile { path, source });
+                    }
+                }
+                includes
+            }
+        };
+
+        let merged = lua.create_table()?;
+        let mut watch_paths = vec![];
+
+        for IncludedFile { path, source } in &includes {
+            let fragment: mlua::Value = smol::block_on(
+                lua.load(source.trim_start_matches('\u{FEFF}'))
+                    .set_name(path.to_string_lossy())?
+                    .eval_async(),
+            )?;
+            match fragment {
+                mlua::Value::Table(fragment) => {
+                    warn_about_unknown_keys(path, source, &fragment, reported_keys);
+                    merge_lua_tables(&merged, fragment)?
+                }
+                value => anyhow::bail!(
+                    "{} must return a table, but returned a {}",
+                    path.display(),
+                    value.type_name()
+                ),
+            }
+            if let Some(path) = path.to_str() {
+                watch_paths.push(path.to_string());
+            }
+        }
+
+        merge_lua_tables(&merged, tbl)?;
+        crate::lua::add_to_config_reload_watch_list(lua, watch_paths.into_iter().collect())?;
+
+        Ok((mlua::Value::Table(merged), includes))
+    }
+
+    /// Processes the `conditional_overrides` key of the config table.
+    /// Each entry specifies some conditions and a `config` table that
+    /// is merged into the configuration when all of those conditions
+    /// are met by the local system.
+    fn apply_conditional_overrides_to<'l>(
+        lua: &'l mlua::Lua,
+        config: mlua::Value<'l>,
+    ) -> anyhow::Result<mlua::Value<'l>> {
+        let tbl = match &config {
+            mlua::Value::Table(tbl) => tbl.clone(),
+            _ => return Ok(config),
+        };
+
+        let overrides = match tbl.get::<_, mlua::Value>("conditional_overrides")? {
+            mlua::Value::Nil => return Ok(config),
+            mlua::Value::Table(overrides) => overrides,
+            value => anyhow::bail!(
+                "conditional_overrides must be a list of tables, but is a {}",
+                value.type_name()
+            ),
+        };
+        tbl.set("conditional_overrides", mlua::Value::Nil)?;
+
+        let hostname = hostname::get()
+            .ok()
+            .and_then(|name| name.to_str().map(|s| s.to_string()));
+
+        for (idx, entry) in overrides.sequence_values::<mlua::Table>().enumerate() {
+            let entry = entry
+                .with_context(|| format!("conditional_overrides[{}] must be a table", idx + 1))?;
+            if !conditional_override_matches(lua, &entry, hostname.as_deref())
+                .with_context(|| format!("conditional_overrides[{}]", idx + 1))?
+            {
+                continue;
+            }
+            match entry.get::<_, mlua::Value>("config")? {
+                mlua::Value::Table(overrides) => merge_lua_tables(&tbl, overrides)?,
+                mlua::Value::Nil => {}
+                value => anyhow::bail!(
+                    "conditional_overrides[{}].config must be a table, but is a {}",
+                    idx + 1,
+                    value.type_name()
+                ),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Evaluates the lua source `s` as though it were loaded from the
+    /// config file at `p`, and produces the resulting configuration.
+    /// If `saved_includes` is Some, the files pulled in via `include`
+    /// are taken from it rather than being read from disk.
+    /// Returns the loaded configuration along with the files that
+    /// were pulled in via `include`.
+    fn load_from_source(
+        p: &Path,
+        s: &str,
+        chunk_name: String,
+        overrides: &wezterm_dynamic::Value,
+        saved_includes: Option<Vec<IncludedFile>>,
+    ) -> anyhow::Result<(LoadedConfig, Vec<IncludedFile>)> {
+        let lua = make_lua_context(p)?;
+        let config: mlua::Value = smol::block_on(
+            // Skip a potential BOM that Windows software may have placed in the
+            // file. Note that we can't catch this happening for files that are
+            // imported via the lua require function.
+            lua.load(s.trim_start_matches('\u{FEFF}'))
+                .set_name(chunk_name)?
+                .eval_async(),
+        )?;
+        let mut reported_keys = HashSet::new();
+        if let mlua::Value::Table(tbl) = &config {
+            warn_about_unknown_keys(p, s, tbl, &mut reported_keys);
+        }
+        let (config, includes) =
+            Self::apply_includes_to(&lua, config, p, saved_includes, &mut reported_keys)?;
+        let config = Self::apply_conditional_overrides_to(&lua, config)?;
+        let config = Self::apply_overrides_to(&lua, config)?;
+        let config = Self::apply_overrides_obj_to(&lua, config, overrides)?;
+
+        // Unknown keys from the files have been reported with their
+        // locations above, so only report those that came from elsewhere,
+        // such as conditional_overrides, here.
+        if let mlua::Value::Table(tbl) = &config {
+            let possible = Config::possible_field_names();
+            for pair in tbl.clone().pairs::<String, mlua::Value>() {
+                if let Ok((key, _)) = pair {
+                    if !possible.contains(&key.as_str()) && !reported_keys.contains(&key) {
+                        let err = wezterm_dynamic::Error::UnknownFieldForStruct {
+                            field_name: key,
+                            type_name: "Config",
+                            possible,
+                        };
+                        log::warn!("{:#}", err);
+                    }
+                }
+            }
+        }
+        let dyn_config = luahelper::lua_value_to_dynamic(config)?;
+        let cfg = Config::from_dynamic(
+            &dyn_config,
+            FromDynamicOptions {
+                unknown_fields: UnknownFieldAction::Ignore,
+                deprecated_fields: UnknownFieldAction::Warn,
+            },
+        )
+        .with_context(|| {
+            format!(
+                "Error converting lua value returned by script {} to Config struct",
+                p.display()
+            )
+        })?;
+
+        // Compute but discard the key bindings here so that we raise any
+        // problems earlier than we use them.
+        let _ = cfg.key_bindings();
+
+        std::env::set_var("WEZTERM_CONFIG_FILE", p);
+        if let Some(dir) = p.parent() {
+            std::env::set_var("WEZTERM_CONFIG_DIR", dir);
+        }
+        Ok((
+            LoadedConfig {
+                config: cfg.compute_extra_defaults(Some(p)),
+                file_name: Some(p.to_path_buf()),
+                lua: Some(lua),
+            },
+            includes,
+        ))
+    }
+
+    /// Loads the most recent configuration that was successfully
+    /// loaded from the config file, if any.  This is used in place of
+    /// the default configuration when the config file fails to load.
+    pub fn load_last_known_good() -> anyhow::Result<LoadedConfig> {
+        let data = std::fs::read_to_string(last_known_good_path())?;
+        let saved: LastKnownGoodConfig = toml::from_str(&data)?;
+        let chunk_name = format!("{} (last known good)", saved.path.display());
+        let (loaded, _) = Self::load_from_source(
+            &saved.path,
+            &saved.source,
+            chunk_name,
+            &wezterm_dynamic::Value::default(),
+            Some(saved.includes),
+        )?;
+        Ok(loaded)
+    }
+
     pub(crate) fn apply_overrides_obj_to<'l>(
         lua: &'l mlua::Lua,
         mut config: mlua::Value<'l>,
@@ -831,6 +1249,15 @@ impl Config {
         Ok(config)
     }
 
+    /// Returns the configuration, including any default values,
+    /// rendered as TOML.  Options that have no value are omitted.
+    pub fn to_toml_string(&self) -> anyhow::Result<String> {
+        let value = crate::dynamic_to_toml(&self.to_dynamic())
+            .context("converting config to TOML")?
+            .ok_or_else(|| anyhow::anyhow!("config has no representation as TOML"))?;
+        Ok(toml::to_string_pretty(&value)?)
+    }
+
     pub fn default_config() -> Self {
         Self::default().compute_extra_defaults(None)
     }
@@ -888,6 +1315,13 @@ impl Config {
     pub fn compute_extra_defaults(&self, config_path: Option<&Path>) -> Self {
         let mut cfg = self.clone();
 
+        // Expand a leading `~` in font dirs to the home directory
+        for font_dir in &mut cfg.font_dirs {
+            if let Ok(rest) = font_dir.strip_prefix("~") {
+                *font_dir = HOME_DIR.join(rest);
+            }
+        }
+
         // Convert any relative font dirs to their config file relative locations
         if let Some(config_dir) = config_path.as_ref().and_then(|p| p.parent()) {
             for font_dir in &mut cfg.font_dirs {
@@ -1155,6 +1589,178 @@ impl Config {
     }
 }
 
+/// A file that was pulled into the config via `include`
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+struct IncludedFile {
+    path: PathBuf,
+    source: String,
+}
+
+/// Records the source of the most recently successfully loaded config
+/// file, along with the files that it included
+#[derive(Deserialize, Serialize, PartialEq)]
+struct LastKnownGoodConfig {
+    path: PathBuf,
+    source: String,
+    #[serde(default)]
+    includes: Vec<IncludedFile>,
+}
+
+fn last_known_good_path() -> PathBuf {
+    crate::RUNTIME_DIR.join("last-known-good-config.toml")
+}
+
+fn save_last_known_good(path: &Path, source: &str, includes: Vec<IncludedFile>) {
+    let saved = LastKnownGoodConfig {
+        path: path.to_path_buf(),
+        source: source.to_string(),
+        includes,
+    };
+    let file_name = last_known_good_path();
+    if let Ok(data) = std::fs::read_to_string(&file_name) {
+        if toml::from_str::<LastKnownGoodConfig>(&data).ok().as_ref() == Some(&saved) {
+            return;
+        }
+    }
+    let result = crate::create_user_owned_dirs(&crate::RUNTIME_DIR)
+        .and_then(|_| toml::to_string(&saved).map_err(anyhow::Error::from))
+        .and_then(|data| std::fs::write(&file_name, data).map_err(anyhow::Error::from));
+    if let Err(err) = result {
+        log::warn!(
+            "Failed to save last known good config to {}: {:#}",
+            file_name.display(),
+            err
+        );
+    }
+}
+
+/// Once the fragments are merged together, we lose track of which file
+/// a given key came from, so check for unknown keys up front in order
+/// to report the file and line that contains them.
+/// The reported keys are added to `reported_keys`.
+fn warn_about_unknown_keys(
+    path: &Path,
+    source: &str,
+    fragment: &mlua::Table,
+    reported_keys: &mut HashSet<String>,
+) {
+    let possible = Config::possible_field_names();
+    for pair in fragment.clone().pairs::<String, mlua::Value>() {
+        if let Ok((key, _)) = pair {
+            if key == "include" || key == "conditional_overrides" {
+                continue;
+            }
+            if !possible.contains(&key.as_str()) {
+                let location = match find_key_line(source, &key) {
+                    Some(line) => format!("{}:{}", path.display(), line),
+                    None => path.display().to_string(),
+                };
+                reported_keys.insert(key.clone());
+                let err = wezterm_dynamic::Error::UnknownFieldForStruct {
+                    field_name: key,
+                    type_name: "Config",
+                    possible,
+                };
+                log::warn!("{}: {:#}", location, err);
+            }
+        }
+    }
+}
+
+/// Returns the 1-based number of the first line in `source` that
+/// appears to assign to `key`, as in `key = value`.
+/// The table that was evaluated from the source doesn't retain any
+/// location information, so this is a best effort search of the text.
+fn find_key_line(source: &str, key: &str) -> Option<usize> {
+    fn is_ident_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    for (idx, line) in source.lines().enumerate() {
+        for (start, _) in line.match_indices(key) {
+            let before = line[..start].chars().next_back();
+            let after = line[start + key.len()..].trim_start();
+            if !before.map(is_ident_char).unwrap_or(false)
+                && after.starts_with('=')
+                && !after.starts_with("==")
+            {
+                return Some(idx + 1);
+            }
+        }
+    }
+    None
+}
+
+/// Accepts either a single string or a list of strings
+fn string_or_list<'l>(
+    lua: &'l mlua::Lua,
+    value: mlua::Value<'l>,
+) -> anyhow::Result<Option<Vec<String>>> {
+    match value {
+        mlua::Value::Nil => Ok(None),
+        mlua::Value::String(s) => Ok(Some(vec![s.to_str()?.to_string()])),
+        value => Ok(Some(Vec::<String>::from_lua(value, lua)?)),
+    }
+}
+
+/// Returns true if all of the conditions specified by a
+/// `conditional_overrides` entry are satisfied
+fn conditional_override_matches<'l>(
+    lua: &'l mlua::Lua,
+    entry: &mlua::Table<'l>,
+    hostname: Option<&str>,
+) -> anyhow::Result<bool> {
+    if let Some(names) = string_or_list(lua, entry.get("hostname")?)? {
+        let hostname = match hostname {
+            Some(hostname) => hostname,
+            None => return Ok(false),
+        };
+        // Allow matching either the full or the short form of the name
+        let short_name = hostname.split('.').next().unwrap_or(hostname);
+        if !names.iter().any(|name| {
+            name.eq_ignore_ascii_case(hostname) || name.eq_ignore_ascii_case(short_name)
+        }) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(vars) = string_or_list(lua, entry.get("env")?)? {
+        if !vars.iter().all(|name| match std::env::var_os(name) {
+            Some(value) => !value.is_empty(),
+            None => false,
+        }) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(ssh) = entry.get::<_, Option<bool>>("ssh")? {
+        if ssh != std::env::var_os("SSH_CONNECTION").is_some() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Merge the entries from source into target.  Where both contain a table
+/// that has no array portion under the same key, those tables are merged
+/// recursively, otherwise the value from source replaces that in target.
+fn merge_lua_tables<'l>(target: &mlua::Table<'l>, source: mlua::Table<'l>) -> mlua::Result<()> {
+    for pair in source.pairs::<mlua::Value, mlua::Value>() {
+        let (key, value) = pair?;
+        if let (mlua::Value::Table(existing), mlua::Value::Table(value)) =
+            (target.get::<_, mlua::Value>(key.clone())?, &value)
+        {
+            if existing.raw_len() == 0 && value.raw_len() == 0 {
+                merge_lua_tables(&existing, value.clone())?;
+                continue;
+            }
+        }
+        target.set(key, value)?;
+    }
+    Ok(())
+}
+
 fn default_pane_select_fg_color() -> RgbaColor {
     SrgbaTuple(0.75, 0.75, 0.75, 1.0).into()
 }
@@ -1175,6 +1781,10 @@ fn default_mux_output_parser_buffer_size() -> usize {
     128 * 1024
 }
 
+fn default_max_paused_output_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
 fn default_ratelimit_line_prefetches_per_second() -> u32 {
     10
 }
@@ -1284,6 +1894,10 @@ fn default_anim_fps() -> u8 {
     10
 }
 
+fn default_max_download_size() -> usize {
+    100 * 1024 * 1024
+}
+
 fn default_max_fps() -> u8 {
     60
 }
@@ -1503,6 +2117,54 @@ impl Default for DroppedFileQuoting {
     }
 }
 
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum DroppedDirectoryAction {
+    /// Paste the path of the directory, the same as for a file
+    PastePath,
+    /// Send a `cd` command to change to the directory
+    ChangeDirectory,
+}
+
+impl Default for DroppedDirectoryAction {
+    fn default() -> Self {
+        Self::PastePath
+    }
+}
+
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum DroppedFileAction {
+    /// Paste the local paths of the files
+    PastePath,
+    /// Upload the files to the host of the ssh domain and paste
+    /// their remote paths
+    UploadViaSsh,
+}
+
+impl Default for DroppedFileAction {
+    fn default() -> Self {
+        Self::PastePath
+    }
+}
+
+/// What to do when pasting from a clipboard that holds an image
+/// rather than text
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardImagePaste {
+    /// Only text is pasted; images are ignored
+    Ignore,
+    /// Send the image to the pane as an iTerm2 inline image
+    /// escape sequence
+    InlineImage,
+    /// Save the image to a temporary file and paste its path
+    TempFile,
+}
+
+impl Default for ClipboardImagePaste {
+    fn default() -> Self {
+        Self::Ignore
+    }
+}
+
 impl DroppedFileQuoting {
     pub fn escape(self, s: &str) -> String {
         match self {
@@ -1523,6 +2185,71 @@ impl DroppedFileQuoting {
     }
 }
 
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum BlendColorSpace {
+    /// Blend using the sRGB encoded values, which matches the
+    /// way that most other applications composite colors
+    Srgb,
+    /// Blend using linear light values, which keeps the hue of
+    /// translucent and dimmed colors stable
+    Linear,
+}
+
+impl Default for BlendColorSpace {
+    fn default() -> Self {
+        BlendColorSpace::Srgb
+    }
+}
+
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum FailedCommandHighlight {
+    /// Don't highlight failed commands
+    None,
+    /// Draw a marker alongside the output of the command
+    Gutter,
+    /// Tint the background of the output of the command
+    Tint,
+}
+
+impl Default for FailedCommandHighlight {
+    fn default() -> Self {
+        FailedCommandHighlight::None
+    }
+}
+
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum TextBlinkStyle {
+    /// Blinking text transitions between visible and invisible
+    /// at the rate specified by text_blink_rate/text_blink_rate_rapid
+    Blink,
+    /// Blinking text is rendered steadily with a tinted background
+    Tint,
+    /// Blinking text is rendered as regular text
+    None,
+}
+
+impl Default for TextBlinkStyle {
+    fn default() -> Self {
+        TextBlinkStyle::Blink
+    }
+}
+
+#[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
+pub enum TitleElision {
+    /// Keep the end of the title, eliding text at the start
+    Start,
+    /// Keep the start and end of the title, eliding text in the middle
+    Middle,
+    /// Keep the start of the title, eliding text at the end
+    End,
+}
+
+impl Default for TitleElision {
+    fn default() -> Self {
+        TitleElision::End
+    }
+}
+
 #[derive(Debug, FromDynamic, ToDynamic, Clone, Copy, PartialEq, Eq)]
 pub enum ImePreeditRendering {
     /// IME preedit is rendered by WezTerm itself
@@ -1536,3 +2263,23 @@ impl Default for ImePreeditRendering {
         ImePreeditRendering::Builtin
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_line() {
+        let source = "local wezterm = require 'wezterm'\n\
+                      return {\n  \
+                        fontsize = 12,\n  \
+                        font_size=14,\n  \
+                        enable_tab_bar = x == y,\n\
+                      }\n";
+        assert_eq!(find_key_line(source, "fontsize"), Some(3));
+        assert_eq!(find_key_line(source, "size"), None);
+        assert_eq!(find_key_line(source, "font_size"), Some(4));
+        assert_eq!(find_key_line(source, "x"), None);
+        assert_eq!(find_key_line(source, "wezterm"), Some(1));
+    }
+}
@@ -345,6 +345,10 @@ pub struct FontAttributes {
 
     #[dynamic(default)]
     pub harfbuzz_features: Option<Vec<String>>,
+    /// Design coordinates for variable font axes, expressed
+    /// as strings like "wght=450"
+    #[dynamic(default)]
+    pub variations: Option<Vec<String>>,
     #[dynamic(default)]
     pub freetype_load_target: Option<FreeTypeLoadTarget>,
     #[dynamic(default)]
@@ -376,6 +380,7 @@ impl FontAttributes {
             is_fallback: false,
             is_synthetic: false,
             harfbuzz_features: None,
+            variations: None,
             freetype_load_target: None,
             freetype_render_target: None,
             freetype_load_flags: None,
@@ -392,6 +397,7 @@ impl FontAttributes {
             is_fallback: true,
             is_synthetic: false,
             harfbuzz_features: None,
+            variations: None,
             freetype_load_target: None,
             freetype_render_target: None,
             freetype_load_flags: None,
@@ -410,6 +416,7 @@ impl Default for FontAttributes {
             is_fallback: false,
             is_synthetic: false,
             harfbuzz_features: None,
+            variations: None,
             freetype_load_target: None,
             freetype_render_target: None,
             freetype_load_flags: None,
@@ -27,6 +27,7 @@ bitflags::bitflags! {
         const KEY_ASSIGNMENTS = 16;
         const WORKSPACES = 32;
         const COMMANDS = 64;
+        const TAB_ACTIONS = 128;
     }
 }
 
@@ -66,6 +67,9 @@ impl ToString for LauncherFlags {
         if self.contains(Self::COMMANDS) {
             s.push("COMMANDS");
         }
+        if self.contains(Self::TAB_ACTIONS) {
+            s.push("TAB_ACTIONS");
+        }
         s.join("|")
     }
 }
@@ -85,6 +89,7 @@ impl TryFrom<String> for LauncherFlags {
                 "KEY_ASSIGNMENTS" => flags |= Self::KEY_ASSIGNMENTS,
                 "WORKSPACES" => flags |= Self::WORKSPACES,
                 "COMMANDS" => flags |= Self::COMMANDS,
+                "TAB_ACTIONS" => flags |= Self::TAB_ACTIONS,
                 _ => {
                     return Err(format!("invalid LauncherFlags `{}` in `{}`", ele, s));
                 }
@@ -191,6 +196,21 @@ pub struct SpawnCommand {
     pub domain: SpawnTabDomain,
 }
 
+impl SpawnCommand {
+    /// Returns the label that is shown for this command in the
+    /// launcher; either the configured label, or one derived from
+    /// the arguments.
+    pub fn launcher_label(&self) -> String {
+        match self.label.as_ref() {
+            Some(label) => label.to_string(),
+            None => match self.args.as_ref() {
+                Some(args) => args.join(" "),
+                None => "(default shell)".to_string(),
+            },
+        }
+    }
+}
+
 impl std::fmt::Debug for SpawnCommand {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(fmt, "{}", self)
@@ -286,6 +306,35 @@ pub struct PaneSelectArguments {
     pub mode: PaneSelectMode,
 }
 
+/// What to do with text selected by quick select
+#[derive(Debug, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
+pub enum QuickSelectAction {
+    /// Copy to the clipboard and primary selection
+    Copy,
+    /// Send the text to the pane, as though it were pasted
+    Paste,
+    /// Open the text with the default application for it
+    OpenUrl,
+    /// Spawn the specified program in the background.
+    /// `$0` in the arguments is replaced by the selected text,
+    /// and `$1`, `$2` and so on by the capture groups of the pattern.
+    RunCommand(Vec<String>),
+}
+
+impl Default for QuickSelectAction {
+    fn default() -> Self {
+        Self::Copy
+    }
+}
+
+/// A quick select pattern with an associated action
+#[derive(Default, Debug, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
+pub struct QuickSelectPatternAction {
+    pub pattern: String,
+    #[dynamic(default)]
+    pub action: QuickSelectAction,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
 pub struct QuickSelectArguments {
     /// Overrides the main quick_select_alphabet config
@@ -308,6 +357,8 @@ pub enum KeyAssignment {
     ToggleFullScreen,
     Copy,
     CopyTo(ClipboardCopyDestination),
+    CopyAsHtmlTo(ClipboardCopyDestination),
+    CopyWithEscapesTo(ClipboardCopyDestination),
     Paste,
     PastePrimarySelection,
     PasteFrom(ClipboardPasteSource),
@@ -325,9 +376,14 @@ pub enum KeyAssignment {
     DisableDefaultAssignment,
     Hide,
     Show,
+    RequestAttention,
     CloseCurrentTab {
         confirm: bool,
     },
+    CloseOtherTabs {
+        confirm: bool,
+    },
+    RenameTab,
     ReloadConfiguration,
     MoveTabRelative(isize),
     MoveTab(usize),
@@ -342,6 +398,7 @@ pub enum KeyAssignment {
     QuitApplication,
     SpawnCommandInNewTab(SpawnCommand),
     SpawnCommandInNewWindow(SpawnCommand),
+    SpawnLaunchMenuEntry(String),
     SplitHorizontal(SpawnCommand),
     SplitVertical(SpawnCommand),
     ShowLauncher,
@@ -353,6 +410,7 @@ pub enum KeyAssignment {
     SelectTextAtMouseCursor(SelectionMode),
     ExtendSelectionToMouseCursor(Option<SelectionMode>),
     OpenLinkAtMouseCursor,
+    CopyLinkAtMouseCursor(ClipboardCopyDestination),
     ClearSelection,
     CompleteSelection(ClipboardCopyDestination),
     CompleteSelectionOrOpenLinkAtMouseCursor(ClipboardCopyDestination),
@@ -362,6 +420,7 @@ pub enum KeyAssignment {
     ActivatePaneDirection(PaneDirection),
     ActivatePaneByIndex(usize),
     TogglePaneZoomState,
+    TogglePaneOutputPaused,
     CloseCurrentPane {
         confirm: bool,
     },
@@ -376,6 +435,7 @@ pub enum KeyAssignment {
         spawn: Option<SpawnCommand>,
     },
     SwitchWorkspaceRelative(isize),
+    RenameWorkspace,
 
     ActivateKeyTable {
         name: String,
@@ -395,6 +455,8 @@ pub enum KeyAssignment {
     RotatePanes(RotationDirection),
     SplitPane(SplitPane),
     PaneSelect(PaneSelectArguments),
+    CharSelect,
+    ShowCommandHistory,
 }
 impl_lua_conversion_dynamic!(KeyAssignment);
 
@@ -427,6 +489,13 @@ pub enum RotationDirection {
     CounterClockwise,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
+pub enum SearchMatchType {
+    CaseSensitive,
+    CaseInSensitive,
+    Regex,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, FromDynamic, ToDynamic)]
 pub enum CopyModeAssignment {
     MoveToViewportBottom,
@@ -454,6 +523,7 @@ pub enum CopyModeAssignment {
     PriorMatchPage,
     NextMatchPage,
     CycleMatchType,
+    SetMatchType(SearchMatchType),
     ClearPattern,
     EditPattern,
     AcceptPattern,
@@ -8,6 +8,7 @@ use smol::channel::{Receiver, Sender};
 use smol::prelude::*;
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
 use std::ffi::OsString;
 use std::fs::DirBuilder;
 #[cfg(unix)]
@@ -94,6 +95,56 @@ fn toml_to_dynamic(value: &toml::Value) -> Value {
     }
 }
 
+/// Converts a dynamic value to its TOML equivalent.
+/// TOML has no representation of null, so null yields `None`, and
+/// any table entry whose value is null is omitted.
+/// A null array element, or a table key that is not a string or an
+/// integer, cannot be represented and produces an error.
+fn dynamic_to_toml(value: &Value) -> anyhow::Result<Option<toml::Value>> {
+    Ok(Some(match value {
+        Value::Null => return Ok(None),
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::String(s) => toml::Value::String(s.to_string()),
+        Value::U64(n) => match i64::try_from(*n) {
+            Ok(n) => toml::Value::Integer(n),
+            Err(_) => toml::Value::Float(*n as f64),
+        },
+        Value::I64(n) => toml::Value::Integer(*n),
+        Value::F64(n) => toml::Value::Float(n.into_inner()),
+        Value::Array(a) => {
+            let mut array = vec![];
+            for (idx, v) in a.iter().enumerate() {
+                match dynamic_to_toml(v).with_context(|| format!("array index {}", idx))? {
+                    Some(v) => array.push(v),
+                    None => bail!(
+                        "array index {} is null, which cannot be represented in TOML",
+                        idx
+                    ),
+                }
+            }
+            toml::Value::Array(array)
+        }
+        Value::Object(o) => {
+            let mut table = toml::value::Table::new();
+            for (k, v) in o.iter() {
+                let key = match k {
+                    Value::String(s) => s.to_string(),
+                    Value::U64(n) => n.to_string(),
+                    Value::I64(n) => n.to_string(),
+                    _ => bail!(
+                        "table key {:?} cannot be represented in TOML; only string and integer keys are supported",
+                        k
+                    ),
+                };
+                if let Some(v) = dynamic_to_toml(v).with_context(|| format!("key {}", key))? {
+                    table.insert(key, v);
+                }
+            }
+            toml::Value::Table(table)
+        }
+    }))
+}
+
 pub fn build_default_schemes() -> HashMap<String, Palette> {
     let mut color_schemes = HashMap::new();
     for (scheme_name, data) in SCHEMES.iter() {
@@ -487,16 +538,40 @@ impl ConfigInner {
     /// On success, clear any error and replace the current
     /// configuration.
     /// On failure, retain the existing configuration but
-    /// replace any captured error message.
+    /// replace any captured error message.  If there is no existing
+    /// configuration, try the last known good configuration.
     fn reload(&mut self) {
-        match Config::load() {
+        let mut error = None;
+        let result = match Config::load() {
+            Ok(loaded) => Ok(loaded),
+            Err(err) if self.generation == 0 && CONFIG_FILE_OVERRIDE.lock().unwrap().is_none() => {
+                // We have no prior config to fall back on; rather than
+                // starting up with the defaults, try the most recent
+                // config that we successfully loaded.
+                let loaded = Config::load_last_known_good();
+                if loaded.is_ok() {
+                    log::warn!(
+                        "Using the last known good configuration because of: {:#}",
+                        err
+                    );
+                    error.replace(format!(
+                        "{:#}\n\nThe last known good configuration has been \
+                         used instead.",
+                        err
+                    ));
+                }
+                loaded.map_err(|_| err)
+            }
+            Err(err) => Err(err),
+        };
+        match result {
             Ok(LoadedConfig {
                 config,
                 file_name,
                 lua,
             }) => {
                 self.config = Arc::new(config);
-                self.error.take();
+                self.error = error;
                 self.generation += 1;
 
                 let mut watch_paths = vec![];
@@ -711,3 +786,70 @@ fn default_one_point_oh() -> f32 {
 fn default_true() -> bool {
     true
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn object(pairs: Vec<(Value, Value)>) -> Value {
+        Value::Object(pairs.into_iter().collect())
+    }
+
+    #[test]
+    fn dynamic_to_toml_scalars() {
+        assert_eq!(dynamic_to_toml(&Value::Null).unwrap(), None);
+        assert_eq!(
+            dynamic_to_toml(&Value::Bool(true)).unwrap(),
+            Some(toml::Value::Boolean(true))
+        );
+        assert_eq!(
+            dynamic_to_toml(&Value::U64(12)).unwrap(),
+            Some(toml::Value::Integer(12))
+        );
+        assert_eq!(
+            dynamic_to_toml(&Value::U64(u64::MAX)).unwrap(),
+            Some(toml::Value::Float(u64::MAX as f64))
+        );
+        assert_eq!(
+            dynamic_to_toml(&Value::String("hello".to_string())).unwrap(),
+            Some(toml::Value::String("hello".to_string()))
+        );
+    }
+
+    #[test]
+    fn dynamic_to_toml_tables() {
+        let value = object(vec![
+            (
+                Value::String("font_size".to_string()),
+                Value::F64(12.0.into()),
+            ),
+            (Value::String("term".to_string()), Value::Null),
+            (
+                Value::String("colors".to_string()),
+                object(vec![(
+                    Value::U64(1),
+                    Value::Array(vec![Value::I64(-1), Value::I64(2)].into()),
+                )]),
+            ),
+        ]);
+        assert_eq!(
+            toml::to_string(&dynamic_to_toml(&value).unwrap().unwrap()).unwrap(),
+            "font_size = 12.0\n\n[colors]\n1 = [-1, 2]\n"
+        );
+    }
+
+    #[test]
+    fn dynamic_to_toml_errors() {
+        let value = object(vec![(
+            Value::String("keys".to_string()),
+            Value::Array(vec![Value::Bool(true), Value::Null].into()),
+        )]);
+        assert_eq!(
+            format!("{:#}", dynamic_to_toml(&value).unwrap_err()),
+            "key keys: array index 1 is null, which cannot be represented in TOML"
+        );
+
+        let value = object(vec![(Value::Bool(true), Value::U64(1))]);
+        assert!(dynamic_to_toml(&value).is_err());
+    }
+}
@@ -91,6 +91,12 @@ pub struct SshDomain {
 
     #[dynamic(default)]
     pub assume_shell: Shell,
+
+    /// If true, and `term` is set to "wezterm", install the wezterm
+    /// terminfo entry into `~/.terminfo` on the remote host when
+    /// connecting, if it isn't already present there
+    #[dynamic(default)]
+    pub install_terminfo: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -67,6 +67,10 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         self.configuration().enable_kitty_graphics
     }
 
+    fn normalize_output_to_unicode_nfc(&self) -> bool {
+        self.configuration().normalize_output_to_unicode_nfc
+    }
+
     fn canonicalize_pasted_newlines(&self) -> wezterm_term::config::NewlineCanon {
         match self.configuration().canonicalize_pasted_newlines {
             None => wezterm_term::config::NewlineCanon::default(),
@@ -86,6 +90,7 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         UnicodeVersion {
             version: config.unicode_version,
             ambiguous_are_wide: config.treat_east_asian_ambiguous_width_as_wide,
+            prefer_emoji_presentation: config.prefer_emoji_presentation,
         }
     }
 
@@ -93,6 +98,10 @@ impl wezterm_term::TerminalConfiguration for TermConfig {
         self.configuration().debug_key_events
     }
 
+    fn max_download_size(&self) -> usize {
+        self.configuration().max_download_size
+    }
+
     fn bidi_mode(&self) -> BidiMode {
         let config = self.configuration();
         BidiMode {
@@ -133,7 +133,12 @@ impl termwiz::render::RenderTty for FormatTarget {
 }
 
 pub fn format_as_escapes(items: Vec<FormatItem>) -> anyhow::Result<String> {
-    let mut changes: Vec<Change> = items.into_iter().map(Into::into).collect();
+    changes_as_escapes(items.into_iter().map(Into::into).collect())
+}
+
+/// Render `changes` as text with embedded escape sequences.
+/// The attributes are reset to the defaults at the end.
+pub fn changes_as_escapes(mut changes: Vec<Change>) -> anyhow::Result<String> {
     changes.push(Change::AllAttributes(CellAttributes::default()).into());
     let mut renderer = new_wezterm_terminfo_renderer();
     let mut target = FormatTarget { target: vec![] };
@@ -163,6 +163,18 @@ pub trait Domain: Downcast {
     /// This allows the domain the opportunity to eg: detach/hide
     /// its tabs/panes rather than actually killing them off
     fn local_window_is_closing(&self, _window_id: WindowId) {}
+
+    /// Called to advise the domain that the title of a local tab
+    /// that holds its panes has been explicitly assigned.
+    /// This allows the domain to propagate the title to its
+    /// own representation of the tab.
+    fn local_tab_title_changed(&self, _tab_id: TabId, _title: &str) {}
+
+    /// Called to advise the domain that a local workspace has been
+    /// renamed.  This allows the domain to rename the workspace in its
+    /// own representation, so that windows that are not mirrored
+    /// locally, and other clients, see the new name too.
+    fn local_workspace_renamed(&self, _old_name: &str, _new_name: &str) {}
 }
 impl_downcast!(Domain);
 
@@ -266,12 +278,8 @@ impl LocalDomain {
         command_dir: Option<String>,
     ) -> anyhow::Result<CommandBuilder> {
         let config = configuration();
-        let mut cmd = match command {
-            Some(mut cmd) => {
-                config.apply_cmd_defaults(&mut cmd, config.default_cwd.as_ref());
-                cmd
-            }
-            None => config.build_prog(
+        let default_prog = || {
+            config.build_prog(
                 None,
                 self.wsl
                     .as_ref()
@@ -281,11 +289,36 @@ impl LocalDomain {
                     .as_ref()
                     .map(|wsl| wsl.default_cwd.as_ref())
                     .unwrap_or(config.default_cwd.as_ref()),
-            )?,
+            )
+        };
+        let mut cmd = match command {
+            Some(mut cmd) if !cmd.is_default_prog() => {
+                config.apply_cmd_defaults(&mut cmd, config.default_cwd.as_ref());
+                cmd
+            }
+            Some(extra) => {
+                // The caller only wants to adjust the environment or
+                // cwd of the default program
+                let mut cmd = default_prog()?;
+                for (key, value) in extra.iter_extra_env_as_str() {
+                    cmd.env(key, value);
+                }
+                if let Some(cwd) = extra.get_cwd() {
+                    cmd.cwd(cwd);
+                }
+                cmd
+            }
+            None => default_prog()?,
         };
         if let Some(dir) = command_dir {
             cmd.cwd(dir);
         }
+        #[cfg(unix)]
+        crate::shell_integration::apply_shell_options(
+            &mut cmd,
+            config.login_shell,
+            config.shell_integration,
+        );
         self.fixup_command(&mut cmd);
         Ok(cmd)
     }
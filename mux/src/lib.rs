@@ -1,4 +1,5 @@
 use crate::client::{ClientId, ClientInfo};
+use crate::localpane::LocalPane;
 use crate::pane::{Pane, PaneId};
 use crate::tab::{SplitRequest, Tab, TabId};
 use crate::window::{Window, WindowId};
@@ -18,7 +19,7 @@ use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Weak};
 use std::thread;
 use std::time::Instant;
 use termwiz::escape::csi::{DecPrivateMode, DecPrivateModeCode, Device, Mode};
@@ -35,6 +36,8 @@ pub mod domain;
 pub mod localpane;
 pub mod pane;
 pub mod renderable;
+#[cfg(unix)]
+mod shell_integration;
 pub mod ssh;
 pub mod tab;
 pub mod termwiztermtab;
@@ -68,6 +71,7 @@ pub enum MuxNotification {
         clipboard: Option<String>,
     },
     SaveToDownloads {
+        pane_id: PaneId,
         name: Option<String>,
         data: Arc<Vec<u8>>,
     },
@@ -87,23 +91,129 @@ pub struct Mux {
     clients: RefCell<HashMap<ClientId, ClientInfo>>,
     identity: RefCell<Option<Arc<ClientId>>>,
     num_panes_by_workspace: RefCell<HashMap<String, usize>>,
+    output_gates: RefCell<HashMap<PaneId, Weak<OutputGate>>>,
 }
 
 const BUFSIZE: usize = 1024 * 1024;
 
+/// Sits between the thread that reads the pty of a pane and the thread
+/// that parses its output, and allows that output to be paused.
+/// While paused, the bytes read from the pty are held here rather than
+/// being passed on to the parser; the parser and the mux keep running
+/// normally.  If more than `max_paused_output_bytes` accumulate, the
+/// pane is automatically resumed.
+struct OutputGate {
+    pane_id: PaneId,
+    paused: AtomicBool,
+    /// The sending half of the socket read by the parser thread.
+    /// The lock is held for every write, and while `held` is
+    /// drained, so that output is passed on in order.
+    tx: Mutex<FileDescriptor>,
+    held: Mutex<Vec<u8>>,
+}
+
+impl OutputGate {
+    /// Creates the gate, returning it along with the receiving half
+    /// of the socket to be read by the parser thread
+    fn new(pane_id: PaneId) -> anyhow::Result<(Self, FileDescriptor)> {
+        let (mut tx, mut rx) = socketpair()?;
+        set_socket_buffer(&mut tx, SO_SNDBUF, BUFSIZE)?;
+        set_socket_buffer(&mut rx, SO_RCVBUF, BUFSIZE)?;
+        Ok((
+            Self {
+                pane_id,
+                paused: AtomicBool::new(false),
+                tx: Mutex::new(tx),
+                held: Mutex::new(vec![]),
+            },
+            rx,
+        ))
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /// Resumes the output and passes on anything that was held back.
+    /// Writing to the parser can block, so this is done from a separate
+    /// thread rather than the main thread.
+    fn resume(self: &Arc<Self>) {
+        self.paused.store(false, Ordering::SeqCst);
+        let gate = Arc::clone(self);
+        thread::spawn(move || {
+            let mut tx = gate.tx.lock().unwrap();
+            if let Err(err) = gate.drain_held(&mut tx) {
+                error!(
+                    "failed to pass held output to parser: pane {} {:?}",
+                    gate.pane_id, err
+                );
+            }
+        });
+    }
+
+    fn drain_held(&self, tx: &mut FileDescriptor) -> std::io::Result<()> {
+        let held = std::mem::take(&mut *self.held.lock().unwrap());
+        if held.is_empty() {
+            Ok(())
+        } else {
+            tx.write_all(&held)
+        }
+    }
+
+    /// Passes output read from the pty on to the parser,
+    /// or holds onto it while paused
+    fn write(&self, data: &[u8]) -> std::io::Result<()> {
+        let mut tx = self.tx.lock().unwrap();
+        if self.is_paused() {
+            let mut held = self.held.lock().unwrap();
+            held.extend_from_slice(data);
+            if held.len() <= configuration().max_paused_output_bytes {
+                return Ok(());
+            }
+            drop(held);
+
+            log::warn!(
+                "pane {} exceeded max_paused_output_bytes; resuming output",
+                self.pane_id
+            );
+            self.paused.store(false, Ordering::SeqCst);
+            let pane_id = self.pane_id;
+            promise::spawn::spawn_into_main_thread(async move {
+                let mux = Mux::get().unwrap();
+                mux.notify(MuxNotification::PaneOutput(pane_id));
+                mux.notify(MuxNotification::Alert {
+                    pane_id,
+                    alert: wezterm_term::Alert::TitleMaybeChanged,
+                });
+            })
+            .detach();
+            return self.drain_held(&mut tx);
+        }
+        self.drain_held(&mut tx)?;
+        tx.write_all(data)
+    }
+}
+
 /// This function bounces parsed actions over to the main thread to feed to
 /// the pty in the mux.
 /// It blocks until the mux has finished consuming the data, which provides
 /// some back-pressure so that eg: ctrl-c can remain responsive.
-fn send_actions_to_mux(pane_id: PaneId, dead: &Arc<AtomicBool>, actions: Vec<Action>) {
+/// The actions are drained by the pane and the now-empty vec is
+/// handed back so that its allocation is reused for the next batch.
+fn send_actions_to_mux(pane_id: PaneId, dead: &Arc<AtomicBool>, actions: &mut Vec<Action>) {
     let start = Instant::now();
-    promise::spawn::block_on(promise::spawn::spawn_into_main_thread({
+    let mut batch = std::mem::take(actions);
+    *actions = promise::spawn::block_on(promise::spawn::spawn_into_main_thread({
         let dead = Arc::clone(&dead);
         async move {
             let mux = Mux::get().unwrap();
             if let Some(pane) = mux.get_pane(pane_id) {
                 let start = Instant::now();
-                pane.perform_actions(actions);
+                pane.perform_actions(&mut batch);
                 histogram!(
                     "send_actions_to_mux.perform_actions.latency",
                     start.elapsed()
@@ -115,13 +225,22 @@ fn send_actions_to_mux(pane_id: PaneId, dead: &Arc<AtomicBool>, actions: Vec<Act
                 // trying to process it in read_from_pane_pty.
                 dead.store(true, Ordering::Relaxed);
             }
+            // Not every pane consumes the actions
+            batch.clear();
+            batch
         }
     }));
     histogram!("send_actions_to_mux.latency", start.elapsed());
+    histogram!(
+        "send_actions_to_mux.reused_capacity",
+        actions.capacity() as f64
+    );
     histogram!("send_actions_to_mux.rate", 1.);
 }
 
 fn parse_buffered_data(pane_id: PaneId, dead: &Arc<AtomicBool>, mut rx: FileDescriptor) {
+    // The buffer is owned by this thread for the lifetime of the pane;
+    // we read directly into it and parse the bytes in place.
     let mut buf = vec![0; configuration().mux_output_parser_buffer_size];
     let mut parser = termwiz::escape::parser::Parser::new();
     let mut actions = vec![];
@@ -148,7 +267,7 @@ fn parse_buffered_data(pane_id: PaneId, dead: &Arc<AtomicBool>, mut rx: FileDesc
 
                             // Flush prior actions
                             if !actions.is_empty() {
-                                send_actions_to_mux(pane_id, dead, std::mem::take(&mut actions));
+                                send_actions_to_mux(pane_id, dead, &mut actions);
                             }
                         }
                         Action::CSI(CSI::Mode(Mode::ResetDecPrivateMode(
@@ -166,17 +285,54 @@ fn parse_buffered_data(pane_id: PaneId, dead: &Arc<AtomicBool>, mut rx: FileDesc
                     actions.push(action);
 
                     if flush && !actions.is_empty() {
-                        send_actions_to_mux(pane_id, dead, std::mem::take(&mut actions));
+                        send_actions_to_mux(pane_id, dead, &mut actions);
                     }
                 });
                 if !actions.is_empty() && !hold {
-                    send_actions_to_mux(pane_id, dead, std::mem::take(&mut actions));
+                    send_actions_to_mux(pane_id, dead, &mut actions);
                 }
 
-                buf.resize(configuration().mux_output_parser_buffer_size, 0);
+                // Only reallocate if the configured size was changed
+                // by a config reload; otherwise keep reusing the buffer.
+                let wanted = configuration().mux_output_parser_buffer_size;
+                if buf.len() != wanted {
+                    buf.resize(wanted, 0);
+                    buf.shrink_to_fit();
+                }
             }
         }
     }
+
+    // Don't lose output that was held back by synchronized output
+    // if the child exited before resetting that mode
+    if !actions.is_empty() {
+        send_actions_to_mux(pane_id, dead, &mut actions);
+    }
+
+    // All of the output has now been applied to the pane, so it is
+    // safe to let the exit status take effect.  Doing this from the
+    // reader thread would race with the actions that are still
+    // being parsed here.
+    promise::spawn::spawn_into_main_thread(async move {
+        let mux = Mux::get().unwrap();
+        if let Some(pane) = mux.get_pane(pane_id) {
+            if let Some(local) = pane.downcast_ref::<LocalPane>() {
+                local.set_output_drained();
+            }
+        }
+        match configuration().exit_behavior {
+            ExitBehavior::Hold | ExitBehavior::CloseOnCleanExit => {
+                // We don't know if we can unilaterally close
+                // this pane right now, so don't!
+                log::trace!("checking for dead windows after EOF on pane {}", pane_id);
+                mux.prune_dead_windows();
+            }
+            ExitBehavior::Close => {
+                mux.remove_pane(pane_id);
+            }
+        }
+    })
+    .detach();
 }
 
 fn set_socket_buffer(fd: &mut FileDescriptor, option: i32, size: usize) -> anyhow::Result<()> {
@@ -201,24 +357,30 @@ fn set_socket_buffer(fd: &mut FileDescriptor, option: i32, size: usize) -> anyho
 /// blocking reads from the pty (non-blocking reads are not portable to
 /// all platforms and pty/tty types), parse the escape sequences and
 /// relay the actions to the mux thread to apply them to the pane.
-fn read_from_pane_pty(pane_id: PaneId, banner: Option<String>, mut reader: Box<dyn std::io::Read>) {
+/// The terminal model itself is owned by the main thread, because the
+/// Pane/Tab/Mux model is Rc based; the GUI learns about the changes via
+/// PaneOutput notifications, which it coalesces per pane.
+/// Output from the pty passes through `gate`, which allows it to be paused.
+fn read_from_pane_pty(
+    pane_id: PaneId,
+    banner: Option<String>,
+    gate: Arc<OutputGate>,
+    rx: FileDescriptor,
+    mut reader: Box<dyn std::io::Read>,
+) {
     let mut buf = vec![0; BUFSIZE];
 
     // This is used to signal that an error occurred either in this thread,
     // or in the main mux thread.  If `true`, this thread will terminate.
     let dead = Arc::new(AtomicBool::new(false));
 
-    let (mut tx, mut rx) = socketpair().unwrap();
-    set_socket_buffer(&mut tx, SO_SNDBUF, BUFSIZE).unwrap();
-    set_socket_buffer(&mut rx, SO_RCVBUF, BUFSIZE).unwrap();
-
     std::thread::spawn({
         let dead = Arc::clone(&dead);
         move || parse_buffered_data(pane_id, &dead, rx)
     });
 
     if let Some(banner) = banner {
-        tx.write_all(banner.as_bytes()).ok();
+        gate.write(banner.as_bytes()).ok();
     }
 
     while !dead.load(Ordering::Relaxed) {
@@ -233,7 +395,7 @@ fn read_from_pane_pty(pane_id: PaneId, banner: Option<String>, mut reader: Box<d
             }
             Ok(size) => {
                 histogram!("read_from_pane_pty.bytes.rate", size as f64);
-                if let Err(err) = tx.write_all(&buf[..size]) {
+                if let Err(err) = gate.write(&buf[..size]) {
                     error!(
                         "read_pty failed to write to parser: pane {} {:?}",
                         pane_id, err
@@ -244,26 +406,14 @@ fn read_from_pane_pty(pane_id: PaneId, banner: Option<String>, mut reader: Box<d
         }
     }
 
-    match configuration().exit_behavior {
-        ExitBehavior::Hold | ExitBehavior::CloseOnCleanExit => {
-            // We don't know if we can unilaterally close
-            // this pane right now, so don't!
-            promise::spawn::spawn_into_main_thread(async move {
-                let mux = Mux::get().unwrap();
-                log::trace!("checking for dead windows after EOF on pane {}", pane_id);
-                mux.prune_dead_windows();
-            })
-            .detach();
-        }
-        ExitBehavior::Close => {
-            promise::spawn::spawn_into_main_thread(async move {
-                let mux = Mux::get().unwrap();
-                mux.remove_pane(pane_id);
-            })
-            .detach();
-        }
-    }
+    // Don't lose output that was held back when the program exits
+    // while paused
+    gate.paused.store(false, Ordering::SeqCst);
+    gate.drain_held(&mut gate.tx.lock().unwrap()).ok();
 
+    // Dropping the gate (the mux only holds a weak reference) on
+    // return closes `tx`, which signals EOF to the parser thread, which
+    // takes care of the exit_behavior once it has caught up
     dead.store(true, Ordering::Relaxed);
 }
 
@@ -343,6 +493,7 @@ impl Mux {
             clients: RefCell::new(HashMap::new()),
             identity: RefCell::new(None),
             num_panes_by_workspace: RefCell::new(HashMap::new()),
+            output_gates: RefCell::new(HashMap::new()),
         }
     }
 
@@ -466,6 +617,44 @@ impl Mux {
         }
     }
 
+    /// Renames the workspace `old_name` to `new_name`, moving all of
+    /// its windows and updating any clients that have it active.
+    /// Fails if there is already a workspace named `new_name`, as
+    /// renaming would otherwise silently merge the two.
+    pub fn rename_workspace(&self, old_name: &str, new_name: &str) -> anyhow::Result<()> {
+        if old_name == new_name {
+            return Ok(());
+        }
+        if self.iter_workspaces().iter().any(|name| name == new_name) {
+            anyhow::bail!("there is already a workspace named {}", new_name);
+        }
+
+        let mut changed_windows = vec![];
+        for (window_id, window) in self.windows.borrow_mut().iter_mut() {
+            if window.get_workspace() == old_name && window.assign_workspace(new_name) {
+                changed_windows.push(*window_id);
+            }
+        }
+
+        let mut changed_clients = vec![];
+        for info in self.clients.borrow_mut().values_mut() {
+            if info.active_workspace.as_deref() == Some(old_name) {
+                info.active_workspace.replace(new_name.to_string());
+                changed_clients.push(info.client_id.clone());
+            }
+        }
+
+        // Notify only after the borrows above have been released,
+        // as subscribers may want to look at the windows and clients
+        for window_id in changed_windows {
+            self.notify(MuxNotification::WindowWorkspaceChanged(window_id));
+        }
+        for ident in changed_clients {
+            self.notify(MuxNotification::ActiveWorkspaceChanged(ident));
+        }
+        Ok(())
+    }
+
     /// Assigns the active workspace name for the current identity
     pub fn set_active_workspace(&self, workspace: &str) {
         if let Some(ident) = self.identity.borrow().clone() {
@@ -567,6 +756,45 @@ impl Mux {
         self.panes.borrow().get(&pane_id).map(Rc::clone)
     }
 
+    /// Returns true if output processing has been paused for the pane
+    pub fn is_pane_output_paused(&self, pane_id: PaneId) -> bool {
+        if let Some(gate) = self.output_gate(pane_id) {
+            return gate.is_paused();
+        }
+        self.get_pane(pane_id)
+            .map(|pane| pane.is_output_paused())
+            .unwrap_or(false)
+    }
+
+    fn output_gate(&self, pane_id: PaneId) -> Option<Arc<OutputGate>> {
+        self.output_gates
+            .borrow()
+            .get(&pane_id)
+            .and_then(Weak::upgrade)
+    }
+
+    /// Pause or resume output processing for the pane.
+    /// While paused, the output read from the pane is held back rather
+    /// than being parsed, up to `max_paused_output_bytes`, beyond which
+    /// the pane is automatically resumed.
+    /// Panes whose output is not read by this mux, such as those in
+    /// a remote mux, are asked to pause themselves.
+    pub fn set_pane_output_paused(&self, pane_id: PaneId, paused: bool) {
+        match self.output_gate(pane_id) {
+            Some(gate) if paused => gate.pause(),
+            Some(gate) => gate.resume(),
+            None => match self.get_pane(pane_id) {
+                Some(pane) => pane.set_output_paused(paused),
+                None => return,
+            },
+        }
+        self.notify(MuxNotification::PaneOutput(pane_id));
+        self.notify(MuxNotification::Alert {
+            pane_id,
+            alert: wezterm_term::Alert::TitleMaybeChanged,
+        });
+    }
+
     pub fn get_tab(&self, tab_id: TabId) -> Option<Rc<Tab>> {
         self.tabs.borrow().get(&tab_id).map(Rc::clone)
     }
@@ -581,7 +809,9 @@ impl Mux {
         });
         pane.set_clipboard(&clipboard);
 
-        let downloader: Arc<dyn DownloadHandler> = Arc::new(MuxDownloader {});
+        let downloader: Arc<dyn DownloadHandler> = Arc::new(MuxDownloader {
+            pane_id: pane.pane_id(),
+        });
         pane.set_download_handler(&downloader);
 
         self.panes
@@ -590,7 +820,12 @@ impl Mux {
         let pane_id = pane.pane_id();
         if let Some(reader) = pane.reader()? {
             let banner = self.banner.borrow().clone();
-            thread::spawn(move || read_from_pane_pty(pane_id, banner, reader));
+            let (gate, rx) = OutputGate::new(pane_id)?;
+            let gate = Arc::new(gate);
+            self.output_gates
+                .borrow_mut()
+                .insert(pane_id, Arc::downgrade(&gate));
+            thread::spawn(move || read_from_pane_pty(pane_id, banner, gate, rx, reader));
         }
         self.recompute_pane_count();
         self.notify(MuxNotification::PaneAdded(pane_id));
@@ -612,6 +847,7 @@ impl Mux {
 
     fn remove_pane_internal(&self, pane_id: PaneId) {
         log::debug!("removing pane {}", pane_id);
+        self.output_gates.borrow_mut().remove(&pane_id);
         if let Some(pane) = self.panes.borrow_mut().remove(&pane_id).clone() {
             log::debug!("killing pane {}", pane_id);
             pane.kill();
@@ -772,6 +1008,54 @@ impl Mux {
         Ok(())
     }
 
+    /// Move a tab out of the window that currently contains it and
+    /// into `window_id`, where it becomes the active tab.
+    /// The panes in the tab are preserved.
+    pub fn move_tab_to_window(&self, tab_id: TabId, window_id: WindowId) -> anyhow::Result<()> {
+        let tab = self
+            .get_tab(tab_id)
+            .ok_or_else(|| anyhow!("move_tab_to_window: no such tab {}", tab_id))?;
+        let src_window_id = self
+            .window_containing_tab(tab_id)
+            .ok_or_else(|| anyhow!("move_tab_to_window: tab {} has no window", tab_id))?;
+        if src_window_id == window_id {
+            return Ok(());
+        }
+        if self.get_window(window_id).is_none() {
+            anyhow::bail!("move_tab_to_window: no such window_id {}", window_id);
+        }
+
+        if let Some(mut src) = self.get_window_mut(src_window_id) {
+            if let Some(idx) = src.idx_by_id(tab_id) {
+                src.remove_by_idx(idx);
+            }
+        }
+        if let Some(mut dest) = self.get_window_mut(window_id) {
+            dest.push(&tab);
+            let idx = dest.len() - 1;
+            dest.save_and_then_set_active(idx);
+        }
+
+        self.recompute_pane_count();
+        self.prune_dead_windows();
+        Ok(())
+    }
+
+    /// Move a tab out of the window that currently contains it and
+    /// into a newly created window in the same workspace.
+    /// Returns the id of the new window.
+    pub fn move_tab_to_new_window(&self, tab_id: TabId) -> anyhow::Result<WindowId> {
+        let workspace = self
+            .window_containing_tab(tab_id)
+            .and_then(|window_id| self.get_window(window_id))
+            .map(|window| window.get_workspace().to_string())
+            .ok_or_else(|| anyhow!("move_tab_to_new_window: tab {} has no window", tab_id))?;
+        let window_builder = self.new_empty_window(Some(workspace));
+        let window_id = *window_builder;
+        self.move_tab_to_window(tab_id, window_id)?;
+        Ok(window_id)
+    }
+
     pub fn window_containing_tab(&self, tab_id: TabId) -> Option<WindowId> {
         for w in self.windows.borrow().values() {
             for t in w.iter() {
@@ -937,6 +1221,44 @@ impl Mux {
         })
     }
 
+    /// Copies the variables listed in `inherit_environment_variables`
+    /// from the environment of the foreground process in `pane` into
+    /// the command that is about to be spawned.  Variables that were
+    /// explicitly set on the command take precedence.
+    fn resolve_env(
+        &self,
+        command: Option<CommandBuilder>,
+        pane: Option<&Rc<dyn Pane>>,
+    ) -> Option<CommandBuilder> {
+        let names = configuration().inherit_environment_variables.clone();
+        let pane = match pane {
+            Some(pane) if !names.is_empty() => pane,
+            _ => return command,
+        };
+        let env = match pane
+            .get_foreground_process_info()
+            .and_then(|info| procinfo::LocalProcessInfo::environment(info.pid))
+        {
+            Some(env) => env,
+            None => return command,
+        };
+
+        let mut command = command.unwrap_or_else(CommandBuilder::new_default_prog);
+        let explicit: Vec<String> = command
+            .iter_extra_env_as_str()
+            .map(|(key, _)| key.to_string())
+            .collect();
+        for name in &names {
+            if explicit.contains(name) {
+                continue;
+            }
+            if let Some(value) = env.get(name) {
+                command.env(name, value);
+            }
+        }
+        Some(command)
+    }
+
     pub async fn split_pane(
         &self,
         // TODO: disambiguate with TabId
@@ -967,7 +1289,7 @@ impl Mux {
                 command,
                 command_dir,
             } => SplitSource::Spawn {
-                command,
+                command: self.resolve_env(command, Some(&current_pane)),
                 command_dir: self.resolve_cwd(command_dir, Some(Rc::clone(&current_pane))),
             },
             other => other,
@@ -1081,24 +1403,24 @@ impl Mux {
             domain.attach(Some(window_id)).await?;
         }
 
-        let cwd = self.resolve_cwd(
-            command_dir,
-            match current_pane_id {
-                Some(id) => {
-                    // Only use the cwd from the current pane if the domain
-                    // is the same as the one we are spawning into
-                    let (current_domain_id, _, _) = self
-                        .resolve_pane_id(id)
-                        .ok_or_else(|| anyhow!("pane_id {} invalid", id))?;
-                    if current_domain_id == domain.domain_id() {
-                        self.get_pane(id)
-                    } else {
-                        None
-                    }
+        let current_pane = match current_pane_id {
+            Some(id) => {
+                // Only use the cwd and environment from the current pane
+                // if the domain is the same as the one we are spawning into
+                let (current_domain_id, _, _) = self
+                    .resolve_pane_id(id)
+                    .ok_or_else(|| anyhow!("pane_id {} invalid", id))?;
+                if current_domain_id == domain.domain_id() {
+                    self.get_pane(id)
+                } else {
+                    None
                 }
-                None => None,
-            },
-        );
+            }
+            None => None,
+        };
+
+        let command = self.resolve_env(command, current_pane.as_ref());
+        let cwd = self.resolve_cwd(command_dir, current_pane);
 
         let tab = domain.spawn(size, command, cwd, window_id).await?;
 
@@ -1176,12 +1498,15 @@ impl Clipboard for MuxClipboard {
     }
 }
 
-struct MuxDownloader {}
+struct MuxDownloader {
+    pane_id: PaneId,
+}
 
 impl wezterm_term::DownloadHandler for MuxDownloader {
     fn save_to_downloads(&self, name: Option<String>, data: Vec<u8>) {
         if let Some(mux) = Mux::get() {
             mux.notify(MuxNotification::SaveToDownloads {
+                pane_id: self.pane_id,
                 name,
                 data: Arc::new(data),
             });
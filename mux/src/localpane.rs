@@ -11,7 +11,7 @@ use portable_pty::{Child, ChildKiller, ExitStatus, MasterPty, PtySize};
 use procinfo::LocalProcessInfo;
 use rangeset::RangeSet;
 use smol::channel::{bounded, Receiver, TryRecvError};
-use std::cell::{RefCell, RefMut};
+use std::cell::{Cell, RefCell, RefMut};
 use std::collections::{HashMap, HashSet};
 use std::io::Result as IoResult;
 use std::ops::Range;
@@ -23,8 +23,8 @@ use termwiz::surface::{Line, SequenceNo, SEQ_ZERO};
 use url::Url;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::{
-    Alert, AlertHandler, CellAttributes, Clipboard, DownloadHandler, KeyCode, KeyModifiers,
-    MouseEvent, SemanticZone, StableRowIndex, Terminal, TerminalConfiguration,
+    Alert, AlertHandler, CellAttributes, Clipboard, CommandHistoryEntry, DownloadHandler, KeyCode,
+    KeyModifiers, MouseEvent, SemanticZone, StableRowIndex, Terminal, TerminalConfiguration,
 };
 
 #[derive(Debug)]
@@ -35,6 +35,9 @@ enum ProcessState {
         signaller: Box<dyn ChildKiller>,
         // Whether we've explicitly killed the child
         killed: bool,
+        // The exit status and when we noticed it, if the child
+        // has exited but its output has not yet been drained
+        exited: Option<(ExitStatus, Instant)>,
     },
     DeadPendingClose {
         killed: bool,
@@ -57,8 +60,15 @@ pub struct LocalPane {
     tmux_domain: RefCell<Option<Arc<TmuxDomainState>>>,
     proc_list: RefCell<Option<CachedProcInfo>>,
     command_description: String,
+    output_drained: Cell<bool>,
 }
 
+/// How long to wait for the pty to reach EOF after the child has
+/// exited before giving up on reading the rest of its output.
+/// The pty may be held open indefinitely by a background process
+/// that was spawned by the child.
+const EXIT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
 #[async_trait(?Send)]
 impl Pane for LocalPane {
     fn pane_id(&self) -> PaneId {
@@ -152,12 +162,44 @@ impl Pane for LocalPane {
             ProcessState::Running {
                 child_waiter,
                 killed,
+                exited,
                 ..
             } => {
-                let status = match child_waiter.try_recv() {
-                    Ok(Ok(s)) => Some(s),
-                    Err(TryRecvError::Empty) => None,
-                    _ => Some(ExitStatus::with_exit_code(1)),
+                if exited.is_none() {
+                    *exited = match child_waiter.try_recv() {
+                        Ok(Ok(s)) => Some((s, Instant::now())),
+                        Err(TryRecvError::Empty) => None,
+                        _ => Some((ExitStatus::with_exit_code(1), Instant::now())),
+                    };
+
+                    if exited.is_some() && !self.output_drained.get() {
+                        // Make sure that we get to re-evaluate the state
+                        // even if the pty is never closed
+                        let pane_id = self.pane_id;
+                        promise::spawn::spawn_into_main_thread(async move {
+                            smol::Timer::after(EXIT_DRAIN_TIMEOUT).await;
+                            log::trace!("pty drain timed out for pane {}", pane_id);
+                            let mux = Mux::get().unwrap();
+                            mux.prune_dead_windows();
+                        })
+                        .detach();
+                    }
+                }
+
+                let status = match exited {
+                    // The child has gone, but there may still be output
+                    // (such as a panic message) in the pty that we haven't
+                    // applied yet.  Hold off on changing state until the
+                    // reader has reached EOF, which will prune again.
+                    Some((_, when))
+                        if !*killed
+                            && !self.output_drained.get()
+                            && when.elapsed() < EXIT_DRAIN_TIMEOUT =>
+                    {
+                        None
+                    }
+                    Some((status, _)) => Some(status.clone()),
+                    None => None,
                 };
 
                 if let Some(status) = status {
@@ -218,7 +260,7 @@ impl Pane for LocalPane {
                     let mut parser = termwiz::escape::parser::Parser::new();
                     let mut actions = vec![];
                     parser.parse(notify.as_bytes(), |action| actions.push(action));
-                    pane.perform_actions(actions);
+                    pane.perform_actions(&mut actions);
                     mux.notify(MuxNotification::PaneOutput(pane_id));
                 }
             })
@@ -248,8 +290,10 @@ impl Pane for LocalPane {
         Some(self.terminal.borrow().get_config())
     }
 
-    fn perform_actions(&self, actions: Vec<termwiz::escape::Action>) {
-        self.terminal.borrow_mut().perform_actions(actions)
+    fn perform_actions(&self, actions: &mut Vec<termwiz::escape::Action>) {
+        self.terminal
+            .borrow_mut()
+            .perform_actions(actions.drain(..))
     }
 
     fn mouse_event(&self, event: MouseEvent) -> Result<(), Error> {
@@ -470,6 +514,14 @@ impl Pane for LocalPane {
         term.get_semantic_zones()
     }
 
+    fn get_hyperlink_rows(&self, id: &str) -> Option<Vec<StableRowIndex>> {
+        Some(self.terminal.borrow_mut().get_hyperlink_rows(id))
+    }
+
+    fn get_command_history(&self) -> Vec<CommandHistoryEntry> {
+        self.terminal.borrow().get_command_history()
+    }
+
     async fn search(&self, mut pattern: Pattern) -> anyhow::Result<Vec<SearchResult>> {
         let term = self.terminal.borrow();
         let screen = term.screen();
@@ -799,15 +851,24 @@ impl LocalPane {
                 pid,
                 signaller,
                 killed: false,
+                exited: None,
             }),
             pty: RefCell::new(pty),
             domain_id,
             tmux_domain: RefCell::new(None),
             proc_list: RefCell::new(None),
             command_description,
+            output_drained: Cell::new(false),
         }
     }
 
+    /// Called once the output from the pty has been read through to
+    /// EOF and applied to the terminal, allowing the exit status of
+    /// the child to take effect.
+    pub(crate) fn set_output_drained(&self) {
+        self.output_drained.set(true);
+    }
+
     fn divine_current_working_dir(&self) -> Option<Url> {
         #[cfg(unix)]
         if let Some(pid) = self.pty.borrow().process_group_leader() {
@@ -17,8 +17,8 @@ use termwiz::surface::{Line, SequenceNo};
 use url::Url;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::{
-    Clipboard, DownloadHandler, KeyCode, KeyModifiers, MouseEvent, SemanticZone, StableRowIndex,
-    TerminalConfiguration,
+    Clipboard, CommandHistoryEntry, DownloadHandler, KeyCode, KeyModifiers, MouseEvent,
+    SemanticZone, StableRowIndex, TerminalConfiguration,
 };
 
 static PANE_ID: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
@@ -206,6 +206,10 @@ pub trait Pane: Downcast {
     /// the visible screen
     fn get_cursor_position(&self) -> StableCursorPosition;
 
+    /// Returns the sequence number of the most recent change to the pane.
+    /// A renderer remembers this value after it has rendered, and passes
+    /// it to get_changed_since to learn which lines need to be redrawn;
+    /// there is no separate dirty state to clear.
     fn get_current_seqno(&self) -> SequenceNo;
 
     /// Given a range of lines, return the subset of those lines that
@@ -357,13 +361,22 @@ pub trait Pane: Downcast {
     /// Called as a hint that the pane is being resized as part of
     /// a zoom-to-fill-all-the-tab-space operation.
     fn set_zoomed(&self, _zoomed: bool) {}
+    /// Called to pause or resume the output of a pane whose output
+    /// is not read by the local mux, such as a pane in a remote mux.
+    /// Panes whose output is read locally are paused by the mux.
+    fn set_output_paused(&self, _paused: bool) {}
+    fn is_output_paused(&self) -> bool {
+        false
+    }
     fn key_down(&self, key: KeyCode, mods: KeyModifiers) -> anyhow::Result<()>;
     fn key_up(&self, key: KeyCode, mods: KeyModifiers) -> anyhow::Result<()>;
     fn perform_assignment(&self, _assignment: &KeyAssignment) -> bool {
         false
     }
     fn mouse_event(&self, event: MouseEvent) -> anyhow::Result<()>;
-    fn perform_actions(&self, _actions: Vec<termwiz::escape::Action>) {}
+    /// Applies the parsed actions to the pane, draining them from
+    /// `actions` so that the caller can reuse its allocation.
+    fn perform_actions(&self, _actions: &mut Vec<termwiz::escape::Action>) {}
     fn is_dead(&self) -> bool;
     fn kill(&self) {}
     fn palette(&self) -> ColorPalette;
@@ -407,6 +420,19 @@ pub trait Pane: Downcast {
         Ok(vec![])
     }
 
+    /// Returns the rows that hold cells belonging to the explicit
+    /// hyperlink with the given `id`, or None if the pane doesn't
+    /// index its hyperlinks, in which case every row must be checked
+    fn get_hyperlink_rows(&self, _id: &str) -> Option<Vec<StableRowIndex>> {
+        None
+    }
+
+    /// Retrieve the commands that were entered at the shell prompt,
+    /// oldest first
+    fn get_command_history(&self) -> Vec<CommandHistoryEntry> {
+        vec![]
+    }
+
     /// Returns true if the terminal has grabbed the mouse and wants to
     /// give the embedded application a chance to process events.
     /// In practice this controls whether the gui will perform local
@@ -0,0 +1,257 @@
+//! Arranges for shells spawned in the local domain to run as login
+//! shells and to load the wezterm shell integration, without requiring
+//! the user to modify their shell startup files.
+use config::RUNTIME_DIR;
+use portable_pty::CommandBuilder;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+const WEZTERM_SH: &str = include_str!("../../assets/shell-integration/wezterm.sh");
+const WEZTERM_FISH: &str = include_str!("../../assets/shell-integration/wezterm.fish");
+
+/// Shells that accept `-l` to start as a login shell
+const LOGIN_SHELLS: &[&str] = &["bash", "dash", "fish", "ksh", "mksh", "sh", "zsh"];
+
+/// The default locations used by fish when XDG_DATA_DIRS is not set
+const DEFAULT_XDG_DATA_DIRS: &str = "/usr/local/share:/usr/share";
+
+/// Adjusts `cmd` according to the `login_shell` and `shell_integration`
+/// configuration options.  Commands that don't run a recognized shell
+/// are left alone.
+pub fn apply_shell_options(cmd: &mut CommandBuilder, login_shell: bool, shell_integration: bool) {
+    let shell = if cmd.is_default_prog() {
+        match cmd.get_shell() {
+            Ok(shell) => shell,
+            Err(_) => return,
+        }
+    } else {
+        cmd.get_argv()[0].to_string_lossy().into_owned()
+    };
+    let name = match shell_name(&shell) {
+        Some(name) => name,
+        None => return,
+    };
+
+    // The default program is always started as a login shell.
+    // Commands passed via -c and scripts are run as they were
+    // specified, so that they don't pick up the side effects of
+    // the login startup files.
+    if login_shell
+        && !cmd.is_default_prog()
+        && !has_arg(cmd, &["-l", "--login", "-c"])
+        && !runs_script(cmd)
+    {
+        cmd.get_argv_mut().insert(1, "-l".into());
+    }
+
+    if !shell_integration {
+        return;
+    }
+
+    let dir = match write_integration_files() {
+        Ok(dir) => dir,
+        Err(err) => {
+            log::error!("Unable to set up shell integration: {:#}", err);
+            return;
+        }
+    };
+
+    match name {
+        "bash" => {
+            // bash has no environment variable that is consulted by
+            // interactive shells outside of posix mode, so we start it
+            // in posix mode and have $ENV leave that mode again and
+            // load the startup files that would otherwise have been used
+            if has_arg(cmd, &["--posix", "-c"]) || runs_script(cmd) {
+                return;
+            }
+            if cmd.is_default_prog() {
+                let argv = cmd.get_argv_mut();
+                argv.push(shell.into());
+                argv.push("-l".into());
+            }
+            cmd.get_argv_mut().insert(1, "--posix".into());
+            cmd.env("ENV", dir.join("bash").join("wezterm-inject.bash"));
+        }
+        "zsh" => {
+            // zsh reads .zshenv from $ZDOTDIR first; our .zshenv
+            // restores the original value before continuing
+            if let Some(zdotdir) = cmd.get_env("ZDOTDIR").map(OsStr::to_owned) {
+                cmd.env("WEZTERM_ZDOTDIR", zdotdir);
+            }
+            cmd.env("ZDOTDIR", dir.join("zsh"));
+        }
+        "fish" => {
+            // fish loads vendor_conf.d from each of the XDG_DATA_DIRS
+            let orig = cmd
+                .get_env("XDG_DATA_DIRS")
+                .map(OsStr::to_owned)
+                .unwrap_or_default();
+            let mut data_dirs = OsString::from(dir.as_os_str());
+            data_dirs.push(":");
+            if orig.is_empty() {
+                data_dirs.push(DEFAULT_XDG_DATA_DIRS);
+            } else {
+                data_dirs.push(&orig);
+            }
+            cmd.env("WEZTERM_XDG_DATA_DIRS", orig);
+            cmd.env("XDG_DATA_DIRS", data_dirs);
+        }
+        _ => {}
+    }
+}
+
+fn shell_name(shell: &str) -> Option<&'static str> {
+    let base = Path::new(shell).file_name()?.to_str()?;
+    let base = base.trim_start_matches('-');
+    LOGIN_SHELLS.iter().find(|&&name| name == base).copied()
+}
+
+fn has_arg(cmd: &CommandBuilder, wanted: &[&str]) -> bool {
+    cmd.get_argv()
+        .iter()
+        .skip(1)
+        .any(|arg| wanted.iter().any(|w| arg.as_os_str() == OsStr::new(w)))
+}
+
+/// Returns true if the shell has been asked to run a script rather
+/// than to start an interactive session
+fn runs_script(cmd: &CommandBuilder) -> bool {
+    cmd.get_argv()
+        .iter()
+        .skip(1)
+        .any(|arg| !arg.to_string_lossy().starts_with('-'))
+}
+
+/// Writes the integration scripts into the runtime directory,
+/// returning the directory that holds them
+fn write_integration_files() -> anyhow::Result<PathBuf> {
+    let dir = RUNTIME_DIR.join("shell-integration");
+    let wezterm_sh = dir.join("wezterm.sh");
+    let quoted_sh = shell_words::quote(&wezterm_sh.to_string_lossy()).into_owned();
+
+    let bash = format!(
+        "# Loaded via $ENV by a bash that wezterm started with --posix.\n\
+         # Leave posix mode, load the usual startup files and then\n\
+         # load the shell integration.\n\
+         set +o posix\n\
+         unset ENV\n\
+         if shopt -q login_shell ; then\n\
+         \x20 [ -r /etc/profile ] && . /etc/profile\n\
+         \x20 for __wezterm_rc in ~/.bash_profile ~/.bash_login ~/.profile ; do\n\
+         \x20   if [ -r \"$__wezterm_rc\" ] ; then . \"$__wezterm_rc\" ; break ; fi\n\
+         \x20 done\n\
+         \x20 unset __wezterm_rc\n\
+         elif [ -r ~/.bashrc ] ; then\n\
+         \x20 . ~/.bashrc\n\
+         fi\n\
+         . {}\n",
+        quoted_sh
+    );
+
+    let zshenv = format!(
+        "# Loaded because wezterm pointed ZDOTDIR here.\n\
+         # Restore the original ZDOTDIR, load the user's .zshenv and\n\
+         # then load the shell integration.\n\
+         if [[ -n \"${{WEZTERM_ZDOTDIR+set}}\" ]] ; then\n\
+         \x20 ZDOTDIR=\"$WEZTERM_ZDOTDIR\"\n\
+         \x20 unset WEZTERM_ZDOTDIR\n\
+         else\n\
+         \x20 unset ZDOTDIR\n\
+         fi\n\
+         [[ -r \"${{ZDOTDIR:-$HOME}}/.zshenv\" ]] && source \"${{ZDOTDIR:-$HOME}}/.zshenv\"\n\
+         [[ -o interactive ]] && source {}\n",
+        quoted_sh
+    );
+
+    write_if_changed(&wezterm_sh, WEZTERM_SH)?;
+    write_if_changed(&dir.join("bash").join("wezterm-inject.bash"), &bash)?;
+    write_if_changed(&dir.join("zsh").join(".zshenv"), &zshenv)?;
+    write_if_changed(
+        &dir.join("fish").join("vendor_conf.d").join("wezterm.fish"),
+        WEZTERM_FISH,
+    )?;
+
+    Ok(dir)
+}
+
+/// Avoid rewriting files that may be in the middle of being
+/// read by a shell that was started moments ago
+fn write_if_changed(path: &Path, content: &str) -> anyhow::Result<()> {
+    if std::fs::read_to_string(path).ok().as_deref() == Some(content) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        config::create_user_owned_dirs(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn command(argv: &[&str]) -> CommandBuilder {
+        let mut cmd = CommandBuilder::new(argv[0]);
+        cmd.args(&argv[1..]);
+        cmd
+    }
+
+    fn argv(cmd: &CommandBuilder) -> Vec<String> {
+        cmd.get_argv()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(shell_name("/bin/bash"), Some("bash"));
+        assert_eq!(shell_name("-zsh"), Some("zsh"));
+        assert_eq!(shell_name("/usr/local/bin/fish"), Some("fish"));
+        assert_eq!(shell_name("/usr/bin/python3"), None);
+        assert_eq!(shell_name("/"), None);
+    }
+
+    #[test]
+    fn args() {
+        let cmd = command(&["bash", "-l", "script.sh"]);
+        assert!(has_arg(&cmd, &["--login", "-l"]));
+        assert!(!has_arg(&cmd, &["-c"]));
+        assert!(runs_script(&cmd));
+
+        // The program itself is not considered to be an argument
+        let cmd = command(&["-c", "-i"]);
+        assert!(!has_arg(&cmd, &["-c"]));
+        assert!(!runs_script(&cmd));
+    }
+
+    #[test]
+    fn login_shell() {
+        let mut cmd = command(&["zsh"]);
+        apply_shell_options(&mut cmd, true, false);
+        assert_eq!(argv(&cmd), vec!["zsh", "-l"]);
+
+        // Don't add a redundant -l
+        let mut cmd = command(&["bash", "--login", "-i"]);
+        apply_shell_options(&mut cmd, true, false);
+        assert_eq!(argv(&cmd), vec!["bash", "--login", "-i"]);
+
+        let mut cmd = command(&["bash", "-c", "echo hello"]);
+        apply_shell_options(&mut cmd, true, false);
+        assert_eq!(argv(&cmd), vec!["bash", "-c", "echo hello"]);
+
+        let mut cmd = command(&["sh", "script.sh"]);
+        apply_shell_options(&mut cmd, true, false);
+        assert_eq!(argv(&cmd), vec!["sh", "script.sh"]);
+
+        let mut cmd = command(&["top"]);
+        apply_shell_options(&mut cmd, true, false);
+        assert_eq!(argv(&cmd), vec!["top"]);
+
+        let mut cmd = command(&["zsh"]);
+        apply_shell_options(&mut cmd, false, false);
+        assert_eq!(argv(&cmd), vec!["zsh"]);
+    }
+}
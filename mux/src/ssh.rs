@@ -13,6 +13,7 @@ use smol::channel::{bounded, Receiver as AsyncReceiver};
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::io::{BufWriter, Read, Write};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
 use std::sync::{Arc, Mutex};
@@ -23,7 +24,7 @@ use termwiz::lineedit::*;
 use termwiz::render::terminfo::TerminfoRenderer;
 use termwiz::surface::Change;
 use termwiz::terminal::{ScreenSize, Terminal, TerminalWaker};
-use wezterm_ssh::{ConfigMap, Session, SessionEvent, SshChildProcess, SshPty};
+use wezterm_ssh::{ConfigMap, Session, SessionEvent, SshChildProcess, SshPty, Utf8PathBuf};
 
 #[derive(Default)]
 struct PasswordPromptHost {
@@ -195,6 +196,47 @@ impl RemoteSshDomain {
         ssh_domain_to_ssh_config(&self.dom)
     }
 
+    /// Uploads the local file `local_path` to the remote host using
+    /// sftp, placing it in `remote_dir`, or in the home directory of
+    /// the remote user if `remote_dir` is None.
+    /// Returns the path of the uploaded file on the remote host.
+    pub async fn upload_file(
+        &self,
+        local_path: &Path,
+        remote_dir: Option<&str>,
+    ) -> anyhow::Result<String> {
+        use smol::io::AsyncWriteExt;
+
+        let session = self
+            .session
+            .borrow()
+            .as_ref()
+            .map(|s| s.clone())
+            .ok_or_else(|| anyhow!("domain {} is not connected", self.name))?;
+        let file_name = local_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("{} has no usable file name", local_path.display()))?;
+
+        let sftp = session.sftp();
+        let remote_dir = match remote_dir {
+            Some(dir) => Utf8PathBuf::from(dir),
+            None => sftp.canonicalize(".").await?,
+        };
+        let remote_path = remote_dir.join(file_name);
+
+        let data = smol::fs::read(local_path)
+            .await
+            .with_context(|| format!("reading {}", local_path.display()))?;
+        let mut file = sftp.create(remote_path.clone()).await?;
+        file.write_all(&data)
+            .await
+            .with_context(|| format!("writing {}", remote_path))?;
+        file.close().await?;
+
+        Ok(remote_path.into_string())
+    }
+
     fn build_command(
         &self,
         pane_id: PaneId,
@@ -258,6 +300,33 @@ impl RemoteSshDomain {
     }
 }
 
+/// Compile and install the wezterm terminfo entry into `~/.terminfo`
+/// on the remote host, unless the remote host already has it.
+pub fn install_wezterm_terminfo(session: &Session) -> anyhow::Result<()> {
+    let script = "infocmp wezterm >/dev/null 2>&1 && exit 0; \
+                  t=$(mktemp) || exit 1; \
+                  cat > \"$t\"; \
+                  mkdir -p ~/.terminfo && tic -x -o ~/.terminfo \"$t\"; \
+                  rc=$?; rm -f \"$t\"; exit $rc";
+    let cmd = format!("sh -c {}", shell_words::quote(script));
+    let exec = smol::block_on(session.exec(&cmd, None))
+        .context("executing terminfo install on remote host")?;
+
+    let mut stdin = exec.stdin;
+    stdin.write_all(termwiz::caps::WEZTERM_TERMINFO_SOURCE.as_bytes())?;
+    drop(stdin);
+
+    let mut child = exec.child;
+    let status = smol::block_on(child.async_wait())?;
+    if !status.success() {
+        let mut stderr = exec.stderr;
+        let mut err = String::new();
+        let _ = stderr.read_to_string(&mut err);
+        bail!("{}", err.trim());
+    }
+    Ok(())
+}
+
 /// Carry out the authentication process and create the initial pty.
 fn connect_ssh_session(
     session: Session,
@@ -271,6 +340,7 @@ fn connect_ssh_session(
     size: Arc<Mutex<PtySize>>,
     command_line: Option<String>,
     env: HashMap<String, String>,
+    install_terminfo: bool,
 ) -> anyhow::Result<()> {
     struct StdoutShim<'a> {
         size: Arc<Mutex<PtySize>>,
@@ -484,6 +554,15 @@ fn connect_ssh_session(
                 shim.output_line(&format!("Error: {}", err))?;
             }
             SessionEvent::Authenticated => {
+                if install_terminfo && config::configuration().term == "wezterm" {
+                    if let Err(err) = install_wezterm_terminfo(&session) {
+                        shim.output_line(&format!(
+                            "Failed to install the wezterm terminfo entry: {:#}",
+                            err
+                        ))?;
+                    }
+                }
+
                 // Our session has been authenticated: we can now
                 // set up the real pty for the pane
                 match smol::block_on(session.request_pty(
@@ -623,6 +702,7 @@ impl Domain for RemoteSshDomain {
             // to perform the blocking (from its perspective) terminal
             // UI to carry out any authentication.
             let mut stdout_write = BufWriter::new(stdout_write);
+            let install_terminfo = self.dom.install_terminfo;
             std::thread::spawn(move || {
                 if let Err(err) = connect_ssh_session(
                     session,
@@ -636,6 +716,7 @@ impl Domain for RemoteSshDomain {
                     size,
                     command_line,
                     env,
+                    install_terminfo,
                 ) {
                     let _ = write!(stdout_write, "{:#}", err);
                     log::error!("Failed to connect ssh: {:#}", err);
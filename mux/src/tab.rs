@@ -25,6 +25,10 @@ pub struct Tab {
     size: RefCell<PtySize>,
     active: RefCell<usize>,
     zoomed: RefCell<Option<Rc<dyn Pane>>>,
+    title: RefCell<String>,
+    /// The number of rows at the top of each pane that are
+    /// reserved for the pane header
+    pane_header_rows: RefCell<usize>,
 }
 
 #[derive(Clone)]
@@ -415,7 +419,7 @@ fn adjust_y_size(tree: &mut Tree, mut y_adjust: isize, cell_dimensions: &PtySize
     }
 }
 
-fn apply_sizes_from_splits(tree: &Tree, size: &PtySize) {
+fn apply_sizes_from_splits(tree: &Tree, size: &PtySize, header_rows: usize) {
     match tree {
         Tree::Empty => return,
         Tree::Node { data: None, .. } => return,
@@ -424,15 +428,34 @@ fn apply_sizes_from_splits(tree: &Tree, size: &PtySize) {
             right,
             data: Some(data),
         } => {
-            apply_sizes_from_splits(&*left, &data.first);
-            apply_sizes_from_splits(&*right, &data.second);
+            apply_sizes_from_splits(&*left, &data.first, header_rows);
+            apply_sizes_from_splits(&*right, &data.second, header_rows);
         }
         Tree::Leaf(pane) => {
-            pane.resize(*size).ok();
+            resize_pane_content(pane, *size, header_rows).ok();
         }
     }
 }
 
+/// Resize a pane to fit into the space allotted to it by the layout,
+/// leaving `header_rows` rows at the top for the pane header
+fn resize_pane_content(
+    pane: &Rc<dyn Pane>,
+    size: PtySize,
+    header_rows: usize,
+) -> anyhow::Result<()> {
+    let header_rows = header_rows as u16;
+    if header_rows == 0 || size.rows <= header_rows {
+        return pane.resize(size);
+    }
+    let cell_height = size.pixel_height / size.rows;
+    pane.resize(PtySize {
+        rows: size.rows - header_rows,
+        pixel_height: size.pixel_height - (header_rows * cell_height),
+        ..size
+    })
+}
+
 fn cell_dimensions(size: &PtySize) -> PtySize {
     PtySize {
         rows: 1,
@@ -450,6 +473,8 @@ impl Tab {
             size: RefCell::new(*size),
             active: RefCell::new(0),
             zoomed: RefCell::new(None),
+            title: RefCell::new(String::new()),
+            pane_header_rows: RefCell::new(0),
         }
     }
 
@@ -591,13 +616,13 @@ impl Tab {
             }
 
             let mut root = self.pane.borrow_mut();
-            apply_sizes_from_splits(root.as_mut().unwrap(), &size);
+            apply_sizes_from_splits(root.as_mut().unwrap(), &size, self.get_pane_header_rows());
         } else {
             // We weren't zoomed, but now we want to zoom.
             // Locate the active pane
             if let Some(pane) = self.get_active_pane() {
                 pane.set_zoomed(true);
-                pane.resize(size).ok();
+                resize_pane_content(&pane, size, self.get_pane_header_rows()).ok();
                 self.zoomed.borrow_mut().replace(pane);
             }
         }
@@ -654,7 +679,11 @@ impl Tab {
                 Err(c) => {
                     root.replace(c.tree());
                     let size = *self.size.borrow();
-                    apply_sizes_from_splits(root.as_mut().unwrap(), &size);
+                    apply_sizes_from_splits(
+                        root.as_mut().unwrap(),
+                        &size,
+                        self.get_pane_header_rows(),
+                    );
                     break;
                 }
             }
@@ -686,7 +715,11 @@ impl Tab {
                 Err(c) => {
                     root.replace(c.tree());
                     let size = *self.size.borrow();
-                    apply_sizes_from_splits(root.as_mut().unwrap(), &size);
+                    apply_sizes_from_splits(
+                        root.as_mut().unwrap(),
+                        &size,
+                        self.get_pane_header_rows(),
+                    );
                     break;
                 }
             }
@@ -696,19 +729,25 @@ impl Tab {
     fn iter_panes_impl(&self, respect_zoom_state: bool) -> Vec<PositionedPane> {
         let mut panes = vec![];
 
+        // The reported position and size are those of the pane content,
+        // which sits below the pane header
+        let header_rows = self.get_pane_header_rows();
+
         if respect_zoom_state {
             if let Some(zoomed) = self.zoomed.borrow().as_ref() {
                 let size = *self.size.borrow();
+                let header_rows = header_rows.min(size.rows.saturating_sub(1) as usize);
+                let cell_height = size.pixel_height as usize / (size.rows as usize).max(1);
                 panes.push(PositionedPane {
                     index: 0,
                     is_active: true,
                     is_zoomed: true,
                     left: 0,
-                    top: 0,
+                    top: header_rows,
                     width: size.cols.into(),
                     pixel_width: size.pixel_width.into(),
-                    height: size.rows.into(),
-                    pixel_height: size.pixel_height.into(),
+                    height: size.rows as usize - header_rows,
+                    pixel_height: size.pixel_height as usize - (header_rows * cell_height),
                     pane: Rc::clone(zoomed),
                 });
                 return panes;
@@ -743,17 +782,19 @@ impl Tab {
 
                 let pane = Rc::clone(cursor.leaf_mut().unwrap());
                 let dims = parent_size.unwrap_or_else(|| *self.size.borrow());
+                let header_rows = header_rows.min(dims.rows.saturating_sub(1) as usize);
+                let cell_height = dims.pixel_height as usize / (dims.rows as usize).max(1);
 
                 panes.push(PositionedPane {
                     index,
                     is_active: index == active_idx,
                     is_zoomed: false,
                     left,
-                    top,
+                    top: top + header_rows,
                     width: dims.cols as _,
-                    height: dims.rows as _,
+                    height: dims.rows as usize - header_rows,
                     pixel_width: dims.pixel_width as _,
-                    pixel_height: dims.pixel_height as _,
+                    pixel_height: dims.pixel_height as usize - (header_rows * cell_height),
                     pane,
                 });
             }
@@ -829,6 +870,24 @@ impl Tab {
         self.id
     }
 
+    /// Returns the explicitly assigned title of the tab,
+    /// or an empty string if none has been assigned.
+    pub fn get_title(&self) -> String {
+        self.title.borrow().clone()
+    }
+
+    /// Assign an explicit title to the tab, overriding the title
+    /// derived from its active pane.  An empty title clears the
+    /// override.
+    pub fn set_title(&self, title: &str) {
+        *self.title.borrow_mut() = title.to_string();
+        if let Some(mux) = Mux::get() {
+            if let Some(window_id) = mux.window_containing_tab(self.id) {
+                mux.notify(crate::MuxNotification::WindowInvalidated(window_id));
+            }
+        }
+    }
+
     pub fn get_size(&self) -> PtySize {
         *self.size.borrow()
     }
@@ -882,7 +941,7 @@ impl Tab {
                 *self.size.borrow_mut() = size;
 
                 // And then resize the individual panes to match
-                apply_sizes_from_splits(root.as_mut().unwrap(), &size);
+                apply_sizes_from_splits(root.as_mut().unwrap(), &size, self.get_pane_header_rows());
             }
         }
 
@@ -890,6 +949,33 @@ impl Tab {
         self.set_zoomed(was_zoomed);
     }
 
+    /// Returns the number of rows at the top of each pane that are
+    /// reserved for the pane header
+    pub fn get_pane_header_rows(&self) -> usize {
+        *self.pane_header_rows.borrow()
+    }
+
+    /// Set the number of rows at the top of each pane that are
+    /// reserved for the pane header.  This is controlled by the
+    /// GUI; if the number has changed, the layout is re-applied
+    /// to the panes to make room for, or reclaim the space used by,
+    /// the header.
+    pub fn set_pane_header_rows(&self, rows: usize) {
+        if *self.pane_header_rows.borrow() == rows {
+            return;
+        }
+        *self.pane_header_rows.borrow_mut() = rows;
+
+        let size = *self.size.borrow();
+        {
+            let mut root = self.pane.borrow_mut();
+            apply_sizes_from_splits(root.as_mut().unwrap(), &size, rows);
+        }
+        if let Some(zoomed) = self.zoomed.borrow().as_ref() {
+            resize_pane_content(zoomed, size, rows).ok();
+        }
+    }
+
     fn apply_pane_size(&self, pane_size: PtySize, cursor: &mut Cursor) {
         let cell_width = pane_size.pixel_width / pane_size.cols;
         let cell_height = pane_size.pixel_height / pane_size.rows;
@@ -1079,7 +1165,9 @@ impl Tab {
 
             if cursor.is_leaf() {
                 // Apply our size to the tty
-                cursor.leaf_mut().map(|pane| pane.resize(pane_size));
+                cursor
+                    .leaf_mut()
+                    .map(|pane| resize_pane_content(pane, pane_size, self.get_pane_header_rows()));
             } else {
                 self.apply_pane_size(pane_size, &mut cursor);
             }
@@ -1099,6 +1187,10 @@ impl Tab {
         if self.zoomed.borrow().is_some() {
             return;
         }
+        if matches!(direction, PaneDirection::Next | PaneDirection::Prev) {
+            log::error!("Invalid direction {:?} for AdjustPaneSize", direction);
+            return;
+        }
         let active_index = *self.active.borrow();
         let mut root = self.pane.borrow_mut();
         let mut cursor = root.take().unwrap().cursor();
@@ -1216,6 +1308,9 @@ impl Tab {
             .count()
         }
 
+        // Vertically adjacent panes are also separated by the header
+        let header_rows = self.get_pane_header_rows();
+
         for pane in &panes {
             let score = match direction {
                 PaneDirection::Right => {
@@ -1233,14 +1328,14 @@ impl Tab {
                     }
                 }
                 PaneDirection::Up => {
-                    if pane.top + pane.height + 1 == active.top {
+                    if pane.top + pane.height + 1 + header_rows == active.top {
                         compute_score(active.left, active.width, pane.left, pane.width)
                     } else {
                         0
                     }
                 }
                 PaneDirection::Down => {
-                    if active.top + active.height + 1 == pane.top {
+                    if active.top + active.height + 1 + header_rows == pane.top {
                         compute_score(active.left, active.width, pane.left, pane.width)
                     } else {
                         0
@@ -1359,13 +1454,13 @@ impl Tab {
                         };
 
                         if let Some(unsplit) = cursor.leaf_mut() {
-                            unsplit.resize(size).ok();
+                            resize_pane_content(unsplit, size, self.get_pane_header_rows()).ok();
                         } else {
                             self.apply_pane_size(size, &mut cursor);
                         }
                     } else if !dead_panes.is_empty() {
                         // Apply our revised size to the tty
-                        pane.resize(pane_size).ok();
+                        resize_pane_content(&pane, pane_size, self.get_pane_header_rows()).ok();
                     }
 
                     pane_index += 1;
@@ -1530,7 +1625,7 @@ impl Tab {
 
             // Advise the panes of their new sizes
             let size = *self.size.borrow();
-            apply_sizes_from_splits(root.as_mut().unwrap(), &size);
+            apply_sizes_from_splits(root.as_mut().unwrap(), &size, self.get_pane_header_rows());
         }
 
         // And update focus
@@ -1734,8 +1829,12 @@ impl Tab {
                 (pane, existing_pane)
             };
 
-            pane1.resize(split_info.first)?;
-            pane2.resize(split_info.second.clone())?;
+            resize_pane_content(&pane1, split_info.first, self.get_pane_header_rows())?;
+            resize_pane_content(
+                &pane2,
+                split_info.second.clone(),
+                self.get_pane_header_rows(),
+            )?;
 
             *cursor.leaf_mut().unwrap() = pane1;
 
@@ -1962,6 +2061,39 @@ mod test {
         }
     }
 
+    #[test]
+    fn adjust_pane_size() {
+        let size = PtySize {
+            rows: 24,
+            cols: 80,
+            pixel_width: 800,
+            pixel_height: 600,
+        };
+
+        let tab = Tab::new(&size);
+        tab.assign_pane(&FakePane::new(1, size));
+        let request = SplitRequest {
+            direction: SplitDirection::Horizontal,
+            ..Default::default()
+        };
+        let horz_size = tab.compute_split_size(0, request).unwrap();
+        tab.split_and_insert(0, request, FakePane::new(2, horz_size.second))
+            .unwrap();
+
+        tab.adjust_pane_size(PaneDirection::Left, 5);
+        let panes = tab.iter_panes();
+        assert_eq!(34, panes[0].width);
+        assert_eq!(35, panes[1].left);
+        assert_eq!(45, panes[1].width);
+
+        // Next and Prev have no meaning for resizing and are ignored
+        tab.adjust_pane_size(PaneDirection::Next, 5);
+        tab.adjust_pane_size(PaneDirection::Prev, 5);
+        let panes = tab.iter_panes();
+        assert_eq!(34, panes[0].width);
+        assert_eq!(45, panes[1].width);
+    }
+
     #[test]
     fn tab_splitting() {
         let size = PtySize {
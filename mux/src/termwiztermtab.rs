@@ -114,6 +114,23 @@ impl TermWizTerminalPane {
             dead: RefCell::new(false),
         }
     }
+
+    fn set_background(&self, background: Vec<Line>) {
+        let mut terminal = self.terminal.borrow_mut();
+        let seqno = terminal.current_seqno();
+        let screen = terminal.screen_mut();
+        let cols = screen.physical_cols;
+        for (row, mut line) in background
+            .into_iter()
+            .take(screen.physical_rows)
+            .enumerate()
+        {
+            line.resize(cols, seqno);
+            line.update_last_change_seqno(seqno);
+            let phys = screen.phys_row(row as _);
+            *screen.line_mut(phys) = line;
+        }
+    }
 }
 
 impl Pane for TermWizTerminalPane {
@@ -222,8 +239,10 @@ impl Pane for TermWizTerminalPane {
         Ok(())
     }
 
-    fn perform_actions(&self, actions: Vec<termwiz::escape::Action>) {
-        self.terminal.borrow_mut().perform_actions(actions)
+    fn perform_actions(&self, actions: &mut Vec<termwiz::escape::Action>) {
+        self.terminal
+            .borrow_mut()
+            .perform_actions(actions.drain(..))
     }
 
     fn kill(&self) {
@@ -405,6 +424,17 @@ impl termwiz::terminal::Terminal for TermWizTerminal {
 }
 
 pub fn allocate(size: PtySize) -> (TermWizTerminal, Rc<dyn Pane>) {
+    allocate_with_background(size, vec![])
+}
+
+/// Like `allocate`, except that the screen of the returned pane starts
+/// out showing `background`, one line per row from the top, rather than
+/// being blank.  This allows an overlay to draw over a snapshot of the
+/// pane that it covers, leaving the parts that it doesn't touch visible.
+pub fn allocate_with_background(
+    size: PtySize,
+    background: Vec<Line>,
+) -> (TermWizTerminal, Rc<dyn Pane>) {
     let render_pipe = Pipe::new().expect("Pipe creation not to fail");
 
     let (input_tx, input_rx) = channel();
@@ -428,6 +458,7 @@ pub fn allocate(size: PtySize) -> (TermWizTerminal, Rc<dyn Pane>) {
 
     let domain_id = 0;
     let pane = TermWizTerminalPane::new(domain_id, size, input_tx, render_pipe.read);
+    pane.set_background(background);
 
     // Add the tab to the mux so that the output is processed
     let pane: Rc<dyn Pane> = Rc::new(pane);
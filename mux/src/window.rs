@@ -1,5 +1,6 @@
 use crate::pane::CloseReason;
 use crate::{Mux, MuxNotification, Tab, TabId};
+use config::configuration;
 use std::rc::Rc;
 
 static WIN_ID: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
@@ -33,15 +34,24 @@ impl Window {
     }
 
     pub fn set_workspace(&mut self, workspace: &str) {
-        if workspace == self.workspace {
+        if !self.assign_workspace(workspace) {
             return;
         }
-        self.workspace = workspace.to_string();
         if let Some(mux) = Mux::get() {
             mux.notify(MuxNotification::WindowWorkspaceChanged(self.id));
         }
     }
 
+    /// Changes the workspace without notifying subscribers.
+    /// Returns true if the workspace was changed.
+    pub(crate) fn assign_workspace(&mut self, workspace: &str) -> bool {
+        if workspace == self.workspace {
+            return false;
+        }
+        self.workspace = workspace.to_string();
+        true
+    }
+
     pub fn window_id(&self) -> WindowId {
         self.id
     }
@@ -108,6 +118,14 @@ impl Window {
                     return;
                 }
             }
+
+            // The active tab was the one that was removed
+            if configuration().switch_to_last_active_tab_when_closing_tab {
+                if let Some(idx) = self.get_last_active_idx() {
+                    self.set_active_without_saving(idx);
+                    return;
+                }
+            }
         }
 
         if len > 0 && self.active >= len {
@@ -231,3 +249,51 @@ impl Window {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use portable_pty::PtySize;
+
+    fn window_with_three_tabs() -> (Window, Vec<Rc<Tab>>) {
+        let size = PtySize::default();
+        let mut window = Window::new(Some("default".to_string()));
+        let tabs: Vec<Rc<Tab>> = (0..3).map(|_| Rc::new(Tab::new(&size))).collect();
+        for tab in &tabs {
+            window.push(tab);
+        }
+        // Activate the last tab, remembering the first as last active
+        window.save_and_then_set_active(2);
+        (window, tabs)
+    }
+
+    #[test]
+    fn close_active_tab() {
+        Mux::set_mux(&Rc::new(Mux::new(None)));
+
+        // Both cases live in the one test because the configuration
+        // is process-global and tests run concurrently
+        let mut test_config = config::Config::default_config();
+        test_config.switch_to_last_active_tab_when_closing_tab = true;
+        config::use_this_configuration(test_config.clone());
+
+        let (mut window, tabs) = window_with_three_tabs();
+        window.remove_by_idx(2);
+        assert_eq!(
+            window.get_active().map(|tab| tab.tab_id()),
+            Some(tabs[0].tab_id())
+        );
+
+        test_config.switch_to_last_active_tab_when_closing_tab = false;
+        config::use_this_configuration(test_config);
+
+        let (mut window, tabs) = window_with_three_tabs();
+        window.remove_by_idx(2);
+        assert_eq!(
+            window.get_active().map(|tab| tab.tab_id()),
+            Some(tabs[1].tab_id())
+        );
+
+        Mux::shutdown();
+    }
+}
@@ -91,4 +91,9 @@ impl LocalProcessInfo {
     pub fn executable_path(_pid: u32) -> Option<PathBuf> {
         None
     }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn environment(_pid: u32) -> Option<HashMap<String, String>> {
+        None
+    }
 }
@@ -28,6 +28,21 @@ impl LocalProcessInfo {
         std::fs::read_link(format!("/proc/{}/exe", pid)).ok()
     }
 
+    /// Returns the environment of the process.
+    /// Note that this is the environment that the process was started
+    /// with; changes made by the process itself are not reflected.
+    pub fn environment(pid: u32) -> Option<HashMap<String, String>> {
+        let data = std::fs::read(format!("/proc/{}/environ", pid)).ok()?;
+        let mut env = HashMap::new();
+        for entry in data.split(|&c| c == 0) {
+            let entry = String::from_utf8_lossy(entry);
+            if let Some((key, value)) = entry.split_once('=') {
+                env.insert(key.to_string(), value.to_string());
+            }
+        }
+        Some(env)
+    }
+
     pub fn with_root_pid(pid: u32) -> Option<Self> {
         use libc::pid_t;
 
@@ -257,7 +257,7 @@ impl CommandBuilder {
         self.envs.clear();
     }
 
-    fn get_env<K>(&self, key: K) -> Option<&OsStr>
+    pub fn get_env<K>(&self, key: K) -> Option<&OsStr>
     where
         K: AsRef<OsStr>,
     {
@@ -16,10 +16,7 @@ impl PtySystem for ConPtySystem {
         let stdout = Pipe::new()?;
 
         let con = PsuedoCon::new(
-            COORD {
-                X: size.cols as i16,
-                Y: size.rows as i16,
-            },
+            size_to_coord(size.rows, size.cols),
             stdin.read,
             stdout.write,
         )?;
@@ -44,6 +41,17 @@ impl PtySystem for ConPtySystem {
     }
 }
 
+/// ConPTY rejects a zero sized console, and the dimensions are
+/// signed 16 bit values, so clamp the size into the range that
+/// it will accept rather than letting it wrap around.
+fn size_to_coord(rows: u16, cols: u16) -> COORD {
+    let clamp = |v: u16| v.max(1).min(i16::max_value() as u16) as i16;
+    COORD {
+        X: clamp(cols),
+        Y: clamp(rows),
+    }
+}
+
 struct Inner {
     con: PsuedoCon,
     readable: FileDescriptor,
@@ -59,10 +67,7 @@ impl Inner {
         pixel_width: u16,
         pixel_height: u16,
     ) -> Result<(), Error> {
-        self.con.resize(COORD {
-            X: num_cols as i16,
-            Y: num_rows as i16,
-        })?;
+        self.con.resize(size_to_coord(num_rows, num_cols))?;
         self.size = PtySize {
             rows: num_rows,
             cols: num_cols,
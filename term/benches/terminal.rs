@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::sync::Arc;
+use wezterm_term::color::ColorPalette;
+use wezterm_term::{Terminal, TerminalConfiguration, TerminalSize};
+
+mod corpus;
+
+#[derive(Debug)]
+struct BenchConfig;
+impl TerminalConfiguration for BenchConfig {
+    fn scrollback_size(&self) -> usize {
+        3500
+    }
+
+    fn color_palette(&self) -> ColorPalette {
+        ColorPalette::default()
+    }
+}
+
+fn new_terminal() -> Terminal {
+    Terminal::new(
+        TerminalSize {
+            physical_rows: 24,
+            physical_cols: 80,
+            pixel_width: 80 * 8,
+            pixel_height: 24 * 16,
+        },
+        Arc::new(BenchConfig),
+        "WezTerm",
+        "bench",
+        Box::new(std::io::sink()),
+    )
+}
+
+/// Measures parsing plus applying the resulting actions to the
+/// screen model, which is the cost paid per chunk of pty output.
+fn advance_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("terminal");
+    for (name, corpus) in corpus::all() {
+        group.throughput(Throughput::Bytes(corpus.len() as u64));
+        group.bench_function(name, |b| {
+            let mut term = new_terminal();
+            b.iter(|| term.advance_bytes(black_box(&corpus)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, advance_bytes);
+criterion_main!(benches);
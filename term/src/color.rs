@@ -87,6 +87,15 @@ impl ColorPalette {
     }
 }
 
+impl termwiz::html::ColorResolver for ColorPalette {
+    fn resolve_fg(&self, color: ColorAttribute) -> SrgbaTuple {
+        ColorPalette::resolve_fg(self, color)
+    }
+    fn resolve_bg(&self, color: ColorAttribute) -> SrgbaTuple {
+        ColorPalette::resolve_bg(self, color)
+    }
+}
+
 lazy_static::lazy_static! {
     static ref DEFAULT_PALETTE: ColorPalette = ColorPalette::compute_default();
 }
@@ -163,6 +163,13 @@ pub trait TerminalConfiguration: std::fmt::Debug {
         NewlineCanon::default()
     }
 
+    /// When true, text that is pasted or composed by an input method
+    /// is converted to Unicode Normalization Form C before it is sent
+    /// to the application.
+    fn normalize_output_to_unicode_nfc(&self) -> bool {
+        false
+    }
+
     fn alternate_buffer_wheel_scroll_speed(&self) -> u8 {
         3
     }
@@ -186,6 +193,7 @@ pub trait TerminalConfiguration: std::fmt::Debug {
         UnicodeVersion {
             version: 9,
             ambiguous_are_wide: false,
+            prefer_emoji_presentation: false,
         }
     }
 
@@ -193,6 +201,14 @@ pub trait TerminalConfiguration: std::fmt::Debug {
         false
     }
 
+    /// The largest file, in bytes, that will be passed on to the
+    /// download handler when received via the iTerm2 file transfer
+    /// protocol.
+    // Coupled with config/src/config.rs:default_max_download_size
+    fn max_download_size(&self) -> usize {
+        100 * 1024 * 1024
+    }
+
     /// Returns (bidi_enabled, direction hint) that should be used
     /// unless an escape sequence has changed the default mode
     fn bidi_mode(&self) -> BidiMode {
@@ -21,6 +21,7 @@ use serde::{Deserialize, Serialize};
 use std::ops::{Deref, DerefMut, Range};
 use std::str;
 use termwiz::surface::SequenceNo;
+use wezterm_dynamic::ToDynamic;
 
 pub mod config;
 pub use config::TerminalConfiguration;
@@ -124,6 +125,24 @@ pub struct SemanticZone {
     pub semantic_type: SemanticType,
 }
 
+/// A command that was entered at a shell prompt, as delimited by
+/// the OSC 133 semantic prompt escapes
+#[cfg_attr(feature = "use_serde", derive(Deserialize, Serialize))]
+#[derive(Debug, Clone, Eq, PartialEq, ToDynamic)]
+pub struct CommandHistoryEntry {
+    /// The text of the input zone that was submitted
+    pub command: String,
+    /// The exit status reported via OSC 133;D, or None if the
+    /// shell didn't report one before the next prompt
+    pub exit_status: Option<i32>,
+    /// The row on which the command input started
+    pub start_y: StableRowIndex,
+    /// The first row of the command output
+    pub output_start_y: StableRowIndex,
+    /// The row following the command output
+    pub output_end_y: StableRowIndex,
+}
+
 pub mod color;
 
 #[cfg(test)]
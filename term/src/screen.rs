@@ -2,7 +2,7 @@
 use super::*;
 use crate::config::BidiMode;
 use log::debug;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::sync::Arc;
 use termwiz::surface::SequenceNo;
 
@@ -38,6 +38,57 @@ pub struct Screen {
     pub physical_rows: usize,
     /// Physical, visible width of the screen
     pub physical_cols: usize,
+
+    /// Index of the rows containing explicit hyperlinks, by link id
+    hyperlinks: HyperlinkIndex,
+}
+
+/// Maps the `id` of explicit hyperlinks to the rows that hold cells
+/// linked with that id, so that all of the cells of a link can be found
+/// even when they are spread over several lines.
+/// It is brought up to date lazily by `Screen::hyperlink_rows`, which
+/// only re-indexes the lines that changed since it was last called.
+#[derive(Debug, Clone, Default)]
+struct HyperlinkIndex {
+    /// Lines that changed after this seqno need to be re-indexed
+    seqno: SequenceNo,
+    rows_by_id: HashMap<String, BTreeSet<StableRowIndex>>,
+    ids_by_row: HashMap<StableRowIndex, Vec<String>>,
+}
+
+impl HyperlinkIndex {
+    fn remove_row(&mut self, row: StableRowIndex) {
+        for id in self.ids_by_row.remove(&row).unwrap_or_default() {
+            if let Some(rows) = self.rows_by_id.get_mut(&id) {
+                rows.remove(&row);
+                if rows.is_empty() {
+                    self.rows_by_id.remove(&id);
+                }
+            }
+        }
+    }
+
+    fn index_row(&mut self, row: StableRowIndex, line: &Line) {
+        self.remove_row(row);
+        if !line.has_hyperlink() {
+            return;
+        }
+
+        let mut ids: Vec<String> = vec![];
+        for cell in line.cells() {
+            if let Some(id) = cell.attrs().hyperlink().and_then(|link| link.id()) {
+                if !ids.iter().any(|existing| existing == id) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        for id in &ids {
+            self.rows_by_id.entry(id.clone()).or_default().insert(row);
+        }
+        if !ids.is_empty() {
+            self.ids_by_row.insert(row, ids);
+        }
+    }
 }
 
 fn scrollback_size(config: &Arc<dyn TerminalConfiguration>, allow_scrollback: bool) -> usize {
@@ -78,6 +129,7 @@ impl Screen {
             physical_rows,
             physical_cols,
             stable_row_index_offset: 0,
+            hyperlinks: HyperlinkIndex::default(),
         }
     }
 
@@ -85,6 +137,18 @@ impl Screen {
         scrollback_size(&self.config, self.allow_scrollback)
     }
 
+    /// Adopt a new configuration.  If it reduces the scrollback size,
+    /// the oldest lines beyond the new limit are discarded right away
+    /// rather than waiting for output to scroll them off.
+    pub fn set_config(&mut self, config: Arc<dyn TerminalConfiguration>) {
+        self.config = config;
+        let capacity = self.physical_rows + self.scrollback_size();
+        while self.lines.len() > capacity {
+            self.lines.pop_front();
+            self.stable_row_index_offset += 1;
+        }
+    }
+
     fn rewrap_lines(
         &mut self,
         physical_cols: usize,
@@ -175,6 +239,9 @@ impl Screen {
         }
         log::debug!("resize screen to {}x{}", physical_cols, physical_rows);
 
+        // Rewrapping moves content between rows without stamping them
+        self.hyperlinks = HyperlinkIndex::default();
+
         // pre-prune blank lines that range from the cursor position to the end of the display;
         // this avoids growing the scrollback size when rapidly switching between normal and
         // maximized states.
@@ -611,19 +678,34 @@ impl Screen {
         // changed by the scroll operation.  For normal newline at the bottom
         // of the screen based scrolling, the StableRowIndex does not change,
         // so we use the scroll region bounds to gate the invalidation.
+        // Lines that merely move within the region still need a new seqno:
+        // mux clients cache lines by StableRowIndex and only refetch those
+        // that changed since their last fetch, so without it they would
+        // keep showing the old content at the moved lines' new positions.
+        // The lines revealed at the bottom are stamped when they are cleared.
         if scroll_region.start != 0 || scroll_region.end as usize != self.physical_rows {
-            for y in phys_scroll.clone() {
+            for y in phys_scroll.start + num_rows..phys_scroll.end {
                 self.line_mut(y).update_last_change_seqno(seqno);
             }
         }
 
+        if scroll_region.start > 0 {
+            // No scrollback available for these; the lines scrolled
+            // off the top of the region are cleared and re-used as
+            // the lines revealed at the bottom.
+            self.rotate_lines_left(phys_scroll.clone(), num_rows);
+            self.clear_lines(
+                phys_scroll.end - num_rows..phys_scroll.end,
+                seqno,
+                blank_attr,
+                bidi_mode,
+            );
+            return;
+        }
+
         // if we're going to remove lines due to lack of scrollback capacity,
         // remember how many so that we can adjust our insertion point later.
-        let lines_removed = if scroll_region.start > 0 {
-            // No scrollback available for these;
-            // Remove the scrolled lines
-            num_rows
-        } else {
+        let lines_removed = {
             let max_allowed = self.physical_rows + self.scrollback_size();
             if self.lines.len() + num_rows >= max_allowed {
                 (self.lines.len() + num_rows) - max_allowed
@@ -632,21 +714,15 @@ impl Screen {
             }
         };
 
-        let remove_idx = if scroll_region.start == 0 {
-            0
-        } else {
-            phys_scroll.start
-        };
-
         // To avoid thrashing the heap, prefer to move lines that were
         // scrolled off the top and re-use them at the bottom.
         let to_move = lines_removed.min(num_rows);
         let (to_remove, to_add) = {
             for _ in 0..to_move {
-                let mut line = self.lines.remove(remove_idx).unwrap();
+                let mut line = self.lines.pop_front().unwrap();
                 // Make the line like a new one of the appropriate width
                 line.resize_and_clear(self.physical_cols, seqno, blank_attr.clone());
-                line.update_last_change_seqno(seqno);
+                bidi_mode.apply_to_line(&mut line, seqno);
                 if scroll_region.end as usize == self.physical_rows {
                     self.lines.push_back(line);
                 } else {
@@ -660,12 +736,10 @@ impl Screen {
 
         // Perform the removal
         for _ in 0..to_remove {
-            self.lines.remove(remove_idx);
+            self.lines.pop_front();
         }
 
-        if remove_idx == 0 {
-            self.stable_row_index_offset += lines_removed;
-        }
+        self.stable_row_index_offset += lines_removed;
 
         // It's cheaper to push() than it is insert() at the end
         let push = scroll_region.end as usize == self.physical_rows;
@@ -719,23 +793,45 @@ impl Screen {
 
         let middle = phys_scroll.end - num_rows;
 
-        // dirty the rows in the region
+        // dirty the rows that move down within the region; see the
+        // comment in scroll_up for why moving a line dirties it
         for y in phys_scroll.start..middle {
             self.line_mut(y).update_last_change_seqno(seqno);
         }
 
-        for _ in 0..num_rows {
-            self.lines.remove(middle);
-        }
+        // The lines scrolled off the bottom of the region are cleared
+        // and re-used as the lines revealed at the top
+        self.rotate_lines_left(phys_scroll.clone(), middle - phys_scroll.start);
+        self.clear_lines(
+            phys_scroll.start..phys_scroll.start + num_rows,
+            seqno,
+            blank_attr,
+            bidi_mode,
+        );
+    }
 
-        for _ in 0..num_rows {
-            let mut line = Line::with_width_and_cell(
-                self.physical_cols,
-                Cell::blank_with_attrs(blank_attr.clone()),
-                seqno,
-            );
-            bidi_mode.apply_to_line(&mut line, seqno);
-            self.lines.insert(phys_scroll.start, line);
+    /// Rotates the lines in `phys_range` so that the line `mid` lines
+    /// into the range becomes its first line.  Only the line references
+    /// are moved, which avoids shifting the rest of the screen and
+    /// scrollback once per scrolled line.
+    fn rotate_lines_left(&mut self, phys_range: Range<PhysRowIndex>, mid: usize) {
+        self.lines.make_contiguous()[phys_range].rotate_left(mid);
+    }
+
+    /// Blanks out the lines in `phys_range` so that they are like
+    /// freshly created lines of the current width
+    fn clear_lines(
+        &mut self,
+        phys_range: Range<PhysRowIndex>,
+        seqno: SequenceNo,
+        blank_attr: CellAttributes,
+        bidi_mode: BidiMode,
+    ) {
+        let physical_cols = self.physical_cols;
+        for y in phys_range {
+            let line = self.line_mut(y);
+            line.resize_and_clear(physical_cols, seqno, blank_attr.clone());
+            bidi_mode.apply_to_line(line, seqno);
         }
     }
 
@@ -825,6 +921,10 @@ impl Screen {
             .collect()
     }
 
+    /// Returns the rows in `stable_lines` that have changed since `seqno`.
+    /// Rows whose content is affected by a change, including when
+    /// scrolling moves other content to that StableRowIndex, are
+    /// stamped with the terminal's sequence number at that time.
     pub fn get_changed_stable_rows(
         &self,
         stable_lines: Range<StableRowIndex>,
@@ -846,6 +946,44 @@ impl Screen {
         set
     }
 
+    /// Returns the rows that hold cells belonging to the explicit
+    /// hyperlink with the given `id`, in ascending order.  The cells
+    /// still need to be compared with the link itself, as links with
+    /// different uris may share an id.
+    /// `seqno` is the current seqno of the terminal.
+    pub fn hyperlink_rows(&mut self, id: &str, seqno: SequenceNo) -> Vec<StableRowIndex> {
+        let first_row = self.phys_to_stable_row_index(0);
+        let end_row = self.phys_to_stable_row_index(self.lines.len());
+        let offset = self.stable_row_index_offset;
+        let index = &mut self.hyperlinks;
+
+        // Forget rows that have been scrolled out of the scrollback
+        let gone: Vec<StableRowIndex> = index
+            .ids_by_row
+            .keys()
+            .filter(|row| **row < first_row || **row >= end_row)
+            .copied()
+            .collect();
+        for row in gone {
+            index.remove_row(row);
+        }
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.changed_since(index.seqno) {
+                index.index_row((idx + offset) as StableRowIndex, line);
+            }
+        }
+        // Lines may still be changed with the current seqno after we
+        // return, so look at those again next time
+        index.seqno = seqno.saturating_sub(1);
+
+        index
+            .rows_by_id
+            .get(id)
+            .map(|rows| rows.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
     pub fn for_each_phys_line<F>(&self, mut f: F)
     where
         F: FnMut(usize, &Line),
@@ -58,6 +58,9 @@ pub enum Alert {
     /// When something bumps the seqno in the terminal model and
     /// the terminal is not focused
     OutputSinceFocusLost,
+    /// The application requested that the window draw the
+    /// user's attention
+    RequestAttention,
 }
 
 pub trait AlertHandler {
@@ -146,7 +149,7 @@ impl Terminal {
         self.trigger_unseen_output_notif();
     }
 
-    pub fn perform_actions(&mut self, actions: Vec<termwiz::escape::Action>) {
+    pub fn perform_actions<I: IntoIterator<Item = termwiz::escape::Action>>(&mut self, actions: I) {
         self.state.increment_seqno();
         {
             let mut performer = Performer::new(&mut self.state);
@@ -226,16 +226,30 @@ impl TerminalState {
     }
 
     /// cache recent images and avoid assigning a new id for repeated data!
+    /// The cache is keyed by the hash of the data as it was received,
+    /// so that repeatedly sending the same encoded file doesn't decode
+    /// it again, as well as by the hash of the decoded frames, so that
+    /// the same pixels arriving via different encodings share an id.
     pub(crate) fn raw_image_to_image_data(&mut self, data: ImageDataType) -> Arc<ImageData> {
+        let raw_key = data.compute_hash();
+        if let Some(item) = self.image_cache.get(&raw_key) {
+            return Arc::clone(item);
+        }
+
         let data = data.decode();
         let key = data.compute_hash();
-        if let Some(item) = self.image_cache.get(&key) {
-            Arc::clone(item)
-        } else {
-            let image_data = Arc::new(ImageData::with_data(data));
-            self.image_cache.put(key, Arc::clone(&image_data));
-            image_data
+        let image_data = match self.image_cache.get(&key) {
+            Some(item) => Arc::clone(item),
+            None => {
+                let image_data = Arc::new(ImageData::with_data(data));
+                self.image_cache.put(key, Arc::clone(&image_data));
+                image_data
+            }
+        };
+        if raw_key != key {
+            self.image_cache.put(raw_key, Arc::clone(&image_data));
         }
+        image_data
     }
 }
 
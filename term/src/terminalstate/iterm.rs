@@ -9,6 +9,16 @@ use termwiz::image::ImageDataType;
 impl TerminalState {
     pub(crate) fn set_image(&mut self, image: ITermFileData) {
         if !image.inline {
+            let max_size = self.config.max_download_size();
+            let size = image.size.unwrap_or(0).max(image.data.len());
+            if size > max_size {
+                error!(
+                    "Ignoring file download request name={:?} size={} \
+                     as it is larger than max_download_size={}",
+                    image.name, size, max_size
+                );
+                return;
+            }
             if let Some(handler) = &self.download_handler {
                 handler.save_to_downloads(image.name, image.data);
             } else {
@@ -95,7 +105,10 @@ impl TerminalState {
 
         let downscaled = (width < info.width as usize) || (height < info.height as usize);
         let data = match (downscaled, info.format) {
-            (true, ImageFormat::Gif) | (true, ImageFormat::Png) | (false, _) => {
+            (true, ImageFormat::Gif)
+            | (true, ImageFormat::Png)
+            | (true, ImageFormat::WebP)
+            | (false, _) => {
                 // Don't resample things that might be animations,
                 // or things that don't need resampling
                 ImageDataType::EncodedFile(image.data)
@@ -30,6 +30,7 @@ impl TerminalState {
                 encoding,
                 newline_mode: self.newline_mode,
                 application_cursor_keys: self.application_cursor_keys,
+                application_keypad: self.application_keypad,
             },
             is_down,
         )?;
@@ -6,20 +6,22 @@ use crate::color::{ColorPalette, RgbColor};
 use crate::config::{BidiMode, NewlineCanon};
 use log::debug;
 use num_traits::ToPrimitive;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::Arc;
 use terminfo::{Database, Value};
 use termwiz::cell::UnicodeVersion;
 use termwiz::escape::csi::{
-    Cursor, CursorStyle, DecPrivateMode, DecPrivateModeCode, Device, Edit, EraseInDisplay,
-    EraseInLine, Mode, Sgr, TabulationClear, TerminalMode, TerminalModeCode, Window, XtSmGraphics,
-    XtSmGraphicsAction, XtSmGraphicsItem, XtSmGraphicsStatus,
+    Cursor, CursorStyle, CursorTabulationControl, DecPrivateMode, DecPrivateModeCode, Device, Edit,
+    EraseInDisplay, EraseInLine, Mode, RectangularArea, Sgr, TabulationClear, TerminalMode,
+    TerminalModeCode, Window, XtSmGraphics, XtSmGraphicsAction, XtSmGraphicsItem,
+    XtSmGraphicsStatus,
 };
 use termwiz::escape::{OneBased, OperatingSystemCommand, CSI};
 use termwiz::image::ImageData;
 use termwiz::input::KeyboardEncoding;
 use termwiz::surface::{CursorShape, CursorVisibility, SequenceNo};
+use unicode_normalization::UnicodeNormalization;
 use url::Url;
 use wezterm_bidi::ParagraphDirectionHint;
 
@@ -40,6 +42,9 @@ lazy_static::lazy_static! {
     };
 }
 
+/// How many entries to retain in the command history
+const MAX_COMMAND_HISTORY: usize = 1000;
+
 pub(crate) struct TabStop {
     tabs: Vec<bool>,
     tab_width: usize,
@@ -52,6 +57,72 @@ pub(crate) enum CharSet {
     DecLineDrawing,
 }
 
+/// The initial G0 through G3 designations
+pub(crate) const DEFAULT_CHARSETS: [CharSet; 4] = [
+    CharSet::Ascii,
+    CharSet::DecLineDrawing,
+    CharSet::Ascii,
+    CharSet::Ascii,
+];
+
+impl CharSet {
+    /// Map a grapheme from the GL range of this character set
+    /// to its unicode equivalent
+    pub(crate) fn map<'a>(self, g: &'a str) -> &'a str {
+        match self {
+            CharSet::Ascii => g,
+            CharSet::Uk => match g {
+                "#" => "£",
+                _ => g,
+            },
+            CharSet::DecLineDrawing => match g {
+                "`" => "◆",
+                "a" => "▒",
+                "b" => "␉",
+                "c" => "␌",
+                "d" => "␍",
+                "e" => "␊",
+                "f" => "°",
+                "g" => "±",
+                "h" => "␤",
+                "i" => "␋",
+                "j" => "┘",
+                "k" => "┐",
+                "l" => "┌",
+                "m" => "└",
+                "n" => "┼",
+                "o" => "⎺",
+                "p" => "⎻",
+                "q" => "─",
+                "r" => "⎼",
+                "s" => "⎽",
+                "t" => "├",
+                "u" => "┤",
+                "v" => "┴",
+                "w" => "┬",
+                "x" => "│",
+                "y" => "≤",
+                "z" => "≥",
+                "{" => "π",
+                "|" => "≠",
+                "}" => "£",
+                "~" => "·",
+                _ => g,
+            },
+        }
+    }
+}
+
+/// The status values reported by DECRPM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModeStatus {
+    NotRecognized = 0,
+    Set = 1,
+    Reset = 2,
+    PermanentlySet = 3,
+    PermanentlyReset = 4,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum MouseEncoding {
     X10,
@@ -70,7 +141,22 @@ impl TabStop {
     }
 
     fn set_tab_stop(&mut self, col: usize) {
-        self.tabs[col] = true;
+        if let Some(t) = self.tabs.get_mut(col) {
+            *t = true;
+        }
+    }
+
+    /// Replace the current tab stops with the default stops
+    fn reset(&mut self) {
+        for (i, t) in self.tabs.iter_mut().enumerate() {
+            *t = (i % self.tab_width) == 0;
+        }
+    }
+
+    fn clear_all(&mut self) {
+        for t in &mut self.tabs {
+            *t = false;
+        }
     }
 
     fn find_prev_tab_stop(&self, col: usize) -> Option<usize> {
@@ -112,11 +198,11 @@ impl TabStop {
             }
             // If we want to exactly match VT100/xterm behavior, then
             // we cannot honor ClearCharacterTabStopsAtActiveLine.
-            TabulationClear::ClearAllCharacterTabStops => {
+            // We don't have line tab stops, so clearing all tab stops
+            // is the same as clearing all character tab stops.
+            TabulationClear::ClearAllCharacterTabStops | TabulationClear::ClearAllTabStops => {
                 // | TabulationClear::ClearCharacterTabStopsAtActiveLine
-                for t in &mut self.tabs {
-                    *t = false;
-                }
+                self.clear_all();
             }
             _ => log::warn!("unhandled TabulationClear {:?}", to_clear),
         }
@@ -129,8 +215,8 @@ struct SavedCursor {
     wrap_next: bool,
     pen: CellAttributes,
     dec_origin_mode: bool,
-    g0_charset: CharSet,
-    g1_charset: CharSet,
+    charsets: [CharSet; 4],
+    gl_charset: usize,
     // TODO: selective_erase when supported
 }
 
@@ -212,6 +298,11 @@ impl ScreenOrAlt {
         (cursor_main, cursor_alt)
     }
 
+    pub fn set_config(&mut self, config: &Arc<dyn TerminalConfiguration>) {
+        self.screen.set_config(Arc::clone(config));
+        self.alt_screen.set_config(Arc::clone(config));
+    }
+
     pub fn activate_alt_screen(&mut self, seqno: SequenceNo) {
         self.alt_screen_is_active = true;
         self.dirty_top_phys_rows(seqno);
@@ -246,6 +337,12 @@ impl ScreenOrAlt {
             &mut self.saved_cursor
         }
     }
+
+    /// Forget the DECSC state for both the primary and alternate screens
+    pub fn clear_saved_cursors(&mut self) {
+        self.saved_cursor.take();
+        self.alt_saved_cursor.take();
+    }
 }
 
 /// Manages the state for the terminal
@@ -266,6 +363,12 @@ pub struct TerminalState {
 
     clear_semantic_attribute_on_newline: bool,
 
+    /// Commands entered at the shell prompt, oldest first
+    command_history: VecDeque<CommandHistoryEntry>,
+    /// The command whose output is in progress; it is moved to
+    /// command_history when its status is reported or a new prompt starts
+    pending_command: Option<CommandHistoryEntry>,
+
     /// If true, writing a character inserts a new cell
     insert: bool,
 
@@ -326,10 +429,15 @@ pub struct TerminalState {
     cursor_visible: bool,
 
     keyboard_encoding: KeyboardEncoding,
-    /// Support for US, UK, and DEC Special Graphics
-    g0_charset: CharSet,
-    g1_charset: CharSet,
-    shift_out: bool,
+    /// Support for US, UK, and DEC Special Graphics.
+    /// Holds the designations for G0 through G3.
+    charsets: [CharSet; 4],
+    /// The index into `charsets` that is currently invoked into GL,
+    /// as changed by the locking shifts SI, SO, LS2 and LS3.
+    gl_charset: usize,
+    /// A pending SS2 or SS3 that applies to the next graphic
+    /// character only.
+    single_shift: Option<usize>,
 
     newline_mode: bool,
 
@@ -355,7 +463,7 @@ pub struct TerminalState {
     term_program: String,
     term_version: String,
 
-    writer: Box<dyn std::io::Write>,
+    writer: std::io::BufWriter<ThreadedWriter>,
 
     image_cache: lru::LruCache<[u8; 32], Arc<ImageData>>,
     sixel_scrolls_right: bool,
@@ -367,6 +475,9 @@ pub struct TerminalState {
 
     /// The unicode version that is in effect
     unicode_version: UnicodeVersion,
+    /// The unicode version specified by the config when it was
+    /// last applied, so that a change can be detected on reload
+    config_unicode_version: UnicodeVersion,
     unicode_version_stack: Vec<UnicodeVersionStackEntry>,
 
     enable_conpty_quirks: bool,
@@ -443,29 +554,53 @@ fn default_color_map() -> HashMap<u16, RgbColor> {
 /// and we're in control of the write side, which represents
 /// input from the interactive user, or pastes.
 struct ThreadedWriter {
-    sender: Sender<Vec<u8>>,
+    sender: Sender<WriterMessage>,
+}
+
+enum WriterMessage {
+    Data(Vec<u8>),
+    /// Acknowledged once everything sent before it has been written
+    #[allow(dead_code)]
+    Sync(Sender<()>),
 }
 
 impl ThreadedWriter {
     fn new(mut writer: Box<dyn std::io::Write + Send>) -> Self {
-        let (sender, receiver) = channel::<Vec<u8>>();
+        let (sender, receiver) = channel::<WriterMessage>();
 
         std::thread::spawn(move || {
-            while let Ok(buf) = receiver.recv() {
-                if writer.write(&buf).is_err() {
-                    break;
+            while let Ok(msg) = receiver.recv() {
+                match msg {
+                    WriterMessage::Data(buf) => {
+                        if writer.write(&buf).is_err() {
+                            break;
+                        }
+                    }
+                    WriterMessage::Sync(done) => {
+                        done.send(()).ok();
+                    }
                 }
             }
         });
 
         Self { sender }
     }
+
+    /// Blocks until the writer thread has written all of the data
+    /// that was sent to it prior to this call.
+    #[cfg(test)]
+    fn wait_for_pending_writes(&self) {
+        let (done, wait) = channel();
+        if self.sender.send(WriterMessage::Sync(done)).is_ok() {
+            wait.recv().ok();
+        }
+    }
 }
 
 impl std::io::Write for ThreadedWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         self.sender
-            .send(buf.to_vec())
+            .send(WriterMessage::Data(buf.to_vec()))
             .map_err(|err| std::io::Error::new(std::io::ErrorKind::BrokenPipe, err))?;
         Ok(buf.len())
     }
@@ -485,7 +620,6 @@ impl TerminalState {
         term_version: &str,
         writer: Box<dyn std::io::Write + Send>,
     ) -> TerminalState {
-        let writer = Box::new(ThreadedWriter::new(writer));
         let seqno = 1;
         let screen = ScreenOrAlt::new(
             size.physical_rows,
@@ -509,6 +643,8 @@ impl TerminalState {
             left_and_right_margin_mode: false,
             wrap_next: false,
             clear_semantic_attribute_on_newline: false,
+            command_history: VecDeque::new(),
+            pending_command: None,
             // We default auto wrap to true even though the default for
             // a dec terminal is false, because it is more useful this way.
             dec_auto_wrap: true,
@@ -532,9 +668,9 @@ impl TerminalState {
             mouse_tracking: false,
             last_mouse_move: None,
             cursor_visible: true,
-            g0_charset: CharSet::Ascii,
-            g1_charset: CharSet::DecLineDrawing,
-            shift_out: false,
+            charsets: DEFAULT_CHARSETS,
+            gl_charset: 0,
+            single_shift: None,
             newline_mode: false,
             current_mouse_buttons: vec![],
             tabs: TabStop::new(size.physical_cols, 8),
@@ -550,12 +686,13 @@ impl TerminalState {
             current_dir: None,
             term_program: term_program.to_string(),
             term_version: term_version.to_string(),
-            writer: Box::new(std::io::BufWriter::new(writer)),
+            writer: std::io::BufWriter::new(ThreadedWriter::new(writer)),
             image_cache: lru::LruCache::new(16),
             user_vars: HashMap::new(),
             kitty_img: Default::default(),
             seqno,
             unicode_version,
+            config_unicode_version: unicode_version,
             unicode_version_stack: vec![],
             suppress_initial_title_change: false,
             enable_conpty_quirks: false,
@@ -572,6 +709,9 @@ impl TerminalState {
         self.suppress_initial_title_change = true;
     }
 
+    /// Returns the sequence number of the most recent batch of actions
+    /// applied to the terminal; lines changed by that batch carry this
+    /// value.  See Screen::get_changed_stable_rows
     pub fn current_seqno(&self) -> SequenceNo {
         self.seqno
     }
@@ -581,6 +721,18 @@ impl TerminalState {
     }
 
     pub fn set_config(&mut self, config: Arc<dyn TerminalConfiguration>) {
+        // Adopt a changed unicode_version from the config, unless the
+        // application running in the terminal has selected its own.
+        // The prior config may reflect the new values already, so
+        // compare against the version that it specified when applied.
+        let config_unicode_version = config.unicode_version();
+        if self.unicode_version_stack.is_empty()
+            && self.unicode_version == self.config_unicode_version
+        {
+            self.unicode_version = config_unicode_version;
+        }
+        self.config_unicode_version = config_unicode_version;
+        self.screen.set_config(&config);
         self.config = config;
     }
 
@@ -588,6 +740,14 @@ impl TerminalState {
         Arc::clone(&self.config)
     }
 
+    /// Waits for the responses that have been generated so far to be
+    /// written out by the writer thread
+    #[cfg(test)]
+    pub(crate) fn wait_for_pending_writes(&mut self) {
+        self.writer.flush().ok();
+        self.writer.get_ref().wait_for_pending_writes();
+    }
+
     pub fn set_clipboard(&mut self, clipboard: &Arc<dyn Clipboard>) {
         self.clipboard.replace(Arc::clone(clipboard));
     }
@@ -780,7 +940,11 @@ impl TerminalState {
         };
 
         let canon = canon.canonicalize(text);
-        buf.push_str(&canon);
+        if self.config.normalize_output_to_unicode_nfc() {
+            buf.extend(canon.nfc());
+        } else {
+            buf.push_str(&canon);
+        }
 
         if self.bracketed_paste {
             buf.push_str("\x1b[201~");
@@ -1022,7 +1186,12 @@ impl TerminalState {
         } else {
             y + 1
         };
-        self.set_cursor_pos(&Position::Absolute(x as i64), &Position::Absolute(y as i64));
+        // x and y are already screen coordinates, so we must not use
+        // set_cursor_pos here as that would apply the origin mode offset
+        // a second time.  We still need to clamp to the screen in case
+        // the cursor was below the bottom margin.
+        let y = y.min(self.screen().physical_rows as VisibleRowIndex - 1);
+        self.set_cursor_position_absolute(x, y);
     }
 
     /// Moves the cursor down one line in the same column.
@@ -1175,22 +1344,26 @@ impl TerminalState {
         match dev {
             Device::DeviceAttributes(a) => log::warn!("unhandled: {:?}", a),
             Device::SoftReset => {
-                // TODO: see https://vt100.net/docs/vt510-rm/DECSTR.html
+                // https://vt100.net/docs/vt510-rm/DECSTR.html
+                // Unlike RIS, this leaves the screen contents, scrollback
+                // and the active screen alone.
                 self.pen = CellAttributes::default();
+                self.wrap_next = false;
                 self.insert = false;
                 self.dec_origin_mode = false;
                 // Note that xterm deviates from the documented DECSTR
                 // setting for dec_auto_wrap, so we do too
                 self.dec_auto_wrap = true;
+                self.cursor_visible = true;
                 self.application_cursor_keys = false;
                 self.application_keypad = false;
+                self.charsets = DEFAULT_CHARSETS;
+                self.gl_charset = 0;
+                self.single_shift = None;
                 self.top_and_bottom_margins = 0..self.screen().physical_rows as i64;
                 self.left_and_right_margins = 0..self.screen().physical_cols;
                 self.left_and_right_margin_mode = false;
-                self.screen.activate_alt_screen(self.seqno);
-                self.screen.saved_cursor().take();
-                self.screen.activate_primary_screen(self.seqno);
-                self.screen.saved_cursor().take();
+                self.screen.clear_saved_cursors();
                 self.kitty_remove_all_placements(true);
 
                 self.reverse_wraparound_mode = false;
@@ -1199,15 +1372,7 @@ impl TerminalState {
                 self.bidi_hint.take();
             }
             Device::RequestPrimaryDeviceAttributes => {
-                let mut ident = "\x1b[?65".to_string(); // Vt500
-                ident.push_str(";4"); // Sixel graphics
-                ident.push_str(";6"); // Selective erase
-                ident.push_str(";18"); // windowing extensions
-                ident.push_str(";22"); // ANSI color, vt525
-                ident.push('c');
-
-                self.writer.write(ident.as_bytes()).ok();
-                self.writer.flush().ok();
+                self.send_primary_device_attributes();
             }
             Device::RequestSecondaryDeviceAttributes => {
                 // Response is: Pp ; Pv ; Pc
@@ -1294,16 +1459,57 @@ impl TerminalState {
         }
     }
 
-    fn decqrm_response(&mut self, mode: Mode, mut recognized: bool, enabled: bool) {
+    /// Respond to DA1 and its obsolete DECID equivalent.
+    /// <https://vt100.net/docs/vt510-rm/DA1.html>
+    pub(crate) fn send_primary_device_attributes(&mut self) {
+        let mut ident = "\x1b[?65".to_string(); // Vt500
+        ident.push_str(";4"); // Sixel graphics
+        ident.push_str(";6"); // Selective erase
+        ident.push_str(";18"); // windowing extensions
+        ident.push_str(";22"); // ANSI color, vt525
+        ident.push_str(";28"); // Rectangular editing
+        ident.push_str(";52"); // Clipboard access via OSC 52
+        ident.push('c');
+
+        self.writer.write(ident.as_bytes()).ok();
+        self.writer.flush().ok();
+    }
+
+    fn decqrm_response(&mut self, mode: Mode, recognized: bool, enabled: bool) {
+        let status = if !recognized {
+            ModeStatus::NotRecognized
+        } else if enabled {
+            ModeStatus::Set
+        } else {
+            ModeStatus::Reset
+        };
+        self.decrpm(mode, status);
+    }
+
+    /// Respond to a DECRQM query for a mode that cannot be changed
+    fn decqrm_response_permanent(&mut self, mode: Mode, enabled: bool) {
+        self.decrpm(
+            mode,
+            if enabled {
+                ModeStatus::PermanentlySet
+            } else {
+                ModeStatus::PermanentlyReset
+            },
+        );
+    }
+
+    /// Send a DECRPM report for the queried mode.
+    /// <https://vt100.net/docs/vt510-rm/DECRPM.html>
+    fn decrpm(&mut self, mode: Mode, mut status: ModeStatus) {
         let (is_dec, number) = match &mode {
             Mode::QueryDecPrivateMode(DecPrivateMode::Code(code)) => (true, code.to_u16().unwrap()),
             Mode::QueryDecPrivateMode(DecPrivateMode::Unspecified(code)) => {
-                recognized = false;
+                status = ModeStatus::NotRecognized;
                 (true, *code)
             }
             Mode::QueryMode(TerminalMode::Code(code)) => (false, code.to_u16().unwrap()),
             Mode::QueryMode(TerminalMode::Unspecified(code)) => {
-                recognized = false;
+                status = ModeStatus::NotRecognized;
                 (false, *code)
             }
             _ => unreachable!(),
@@ -1311,18 +1517,8 @@ impl TerminalState {
 
         let prefix = if is_dec { "?" } else { "" };
 
-        let status = if recognized {
-            if enabled {
-                1 // set
-            } else {
-                2 // reset
-            }
-        } else {
-            0
-        };
-
-        log::trace!("{:?} -> recognized={} status={}", mode, recognized, status);
-        write!(self.writer, "\x1b[{}{};{}$y", prefix, number, status).ok();
+        log::trace!("{:?} -> status={:?}", mode, status);
+        write!(self.writer, "\x1b[{}{};{}$y", prefix, number, status as u8).ok();
         self.writer.flush().ok();
     }
 
@@ -1474,6 +1670,9 @@ impl TerminalState {
             | Mode::ResetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::SmoothScroll)) => {
                 // We always output at our "best" rate
             }
+            Mode::QueryDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::SmoothScroll)) => {
+                self.decqrm_response_permanent(mode, false);
+            }
 
             Mode::SetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::ReverseVideo)) => {
                 // Turn on reverse video for all of the lines on the
@@ -1486,6 +1685,9 @@ impl TerminalState {
                 // display.
                 self.reverse_video_mode = false;
             }
+            Mode::QueryDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::ReverseVideo)) => {
+                self.decqrm_response(mode, true, self.reverse_video_mode);
+            }
 
             Mode::SetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::Select132Columns))
             | Mode::ResetDecPrivateMode(DecPrivateMode::Code(
@@ -1561,6 +1763,7 @@ impl TerminalState {
                 if !self.screen.is_alt_screen_active() {
                     self.screen.activate_alt_screen(self.seqno);
                     self.pen = CellAttributes::default();
+                    self.wrap_next = false;
                 }
             }
             Mode::ResetDecPrivateMode(DecPrivateMode::Code(
@@ -1570,6 +1773,7 @@ impl TerminalState {
                     self.pen = CellAttributes::default();
                     self.erase_in_display(EraseInDisplay::EraseDisplay);
                     self.screen.activate_primary_screen(self.seqno);
+                    self.wrap_next = false;
                 }
             }
 
@@ -1579,6 +1783,7 @@ impl TerminalState {
                 if self.screen.is_alt_screen_active() {
                     self.screen.activate_primary_screen(self.seqno);
                     self.pen = CellAttributes::default();
+                    self.wrap_next = false;
                 }
             }
 
@@ -1637,6 +1842,9 @@ impl TerminalState {
             Mode::ResetMode(TerminalMode::Code(TerminalModeCode::ShowCursor)) => {
                 self.cursor_visible = false;
             }
+            Mode::QueryMode(TerminalMode::Code(TerminalModeCode::ShowCursor)) => {
+                self.decqrm_response(mode, true, self.cursor_visible);
+            }
 
             Mode::SetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::MouseTracking)) => {
                 self.mouse_tracking = true;
@@ -1656,6 +1864,11 @@ impl TerminalState {
             | Mode::ResetDecPrivateMode(DecPrivateMode::Code(
                 DecPrivateModeCode::HighlightMouseTracking,
             )) => {}
+            Mode::QueryDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::HighlightMouseTracking,
+            )) => {
+                self.decqrm_response_permanent(mode, false);
+            }
 
             Mode::SetDecPrivateMode(DecPrivateMode::Code(DecPrivateModeCode::ButtonEventMouse)) => {
                 self.button_event_mouse = true;
@@ -1769,6 +1982,18 @@ impl TerminalState {
                     self.dec_restore_cursor();
                 }
             }
+            Mode::QueryDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::ClearAndEnableAlternateScreen,
+            ))
+            | Mode::QueryDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::OptEnableAlternateScreen,
+            ))
+            | Mode::QueryDecPrivateMode(DecPrivateMode::Code(
+                DecPrivateModeCode::EnableAlternateScreen,
+            )) => {
+                let active = self.screen.is_alt_screen_active();
+                self.decqrm_response(mode, true, active);
+            }
             Mode::SaveDecPrivateMode(DecPrivateMode::Code(n))
             | Mode::RestoreDecPrivateMode(DecPrivateMode::Code(n)) => {
                 log::warn!("save/restore dec mode {:?} unimplemented", n)
@@ -2120,6 +2345,111 @@ impl TerminalState {
                 self.cursor.x = x;
                 self.cursor.y = y;
             }
+            Edit::CopyRectangularArea {
+                src,
+                dest_top,
+                dest_left,
+                ..
+            } => {
+                // We only have a single page, so the page numbers are ignored
+                let dest = RectangularArea {
+                    top: dest_top,
+                    left: dest_left,
+                    bottom: OneBased::new(u32::max_value()),
+                    right: OneBased::new(u32::max_value()),
+                };
+                if let (Some((rows, cols)), Some((dest_rows, dest_cols))) = (
+                    self.resolve_rectangular_area(&src),
+                    self.resolve_rectangular_area(&dest),
+                ) {
+                    let screen = self.screen_mut();
+                    // Take a copy first, as the source and destination may overlap
+                    let cells: Vec<Vec<Cell>> = rows
+                        .map(|y| {
+                            cols.clone()
+                                .map(|x| screen.get_cell(x, y).cloned().unwrap_or_else(Cell::blank))
+                                .collect()
+                        })
+                        .collect();
+                    for (row, y) in cells.into_iter().zip(dest_rows) {
+                        for (cell, x) in row.into_iter().zip(dest_cols.clone()) {
+                            screen.set_cell(x, y, &cell, seqno);
+                        }
+                    }
+                }
+            }
+            Edit::FillRectangularArea { ch, area } => {
+                // Only the printable characters from GL and GR are permitted
+                let ch = match char::from_u32(ch) {
+                    Some(c) if (32..=126).contains(&ch) || (160..=255).contains(&ch) => c,
+                    _ => return,
+                };
+                if let Some((rows, cols)) = self.resolve_rectangular_area(&area) {
+                    let cell = Cell::new(ch, self.pen.clone_sgr_only());
+                    let screen = self.screen_mut();
+                    for y in rows {
+                        for x in cols.clone() {
+                            screen.set_cell(x, y, &cell, seqno);
+                        }
+                    }
+                }
+            }
+            // We don't support DECSCA, so there are no protected cells
+            // and DECSERA is equivalent to DECERA
+            Edit::EraseRectangularArea(area) | Edit::SelectiveEraseRectangularArea(area) => {
+                if let Some((rows, cols)) = self.resolve_rectangular_area(&area) {
+                    let blank = Cell::blank_with_attrs(self.pen.clone_sgr_only());
+                    let screen = self.screen_mut();
+                    for y in rows {
+                        for x in cols.clone() {
+                            screen.set_cell(x, y, &blank, seqno);
+                        }
+                    }
+                }
+            }
+            Edit::SelectAttributeChangeExtent(_) => {
+                // This only influences DECCARA and DECRARA, which
+                // we don't implement
+            }
+        }
+    }
+
+    /// Resolve the area used by the DEC rectangular area operations
+    /// to screen coordinates, taking origin mode into account and
+    /// clipping to the page (or margins).  Returns None if the
+    /// resulting area is empty.
+    fn resolve_rectangular_area(
+        &self,
+        area: &RectangularArea,
+    ) -> Option<(Range<VisibleRowIndex>, Range<usize>)> {
+        let (rows, cols) = if self.dec_origin_mode {
+            (
+                self.top_and_bottom_margins.clone(),
+                self.left_and_right_margins.clone(),
+            )
+        } else {
+            (
+                0..self.screen().physical_rows as VisibleRowIndex,
+                0..self.screen().physical_cols,
+            )
+        };
+
+        let top = (rows.start + area.top.as_zero_based() as VisibleRowIndex).min(rows.end);
+        let bottom =
+            (rows.start + area.bottom.as_zero_based() as VisibleRowIndex + 1).min(rows.end);
+        let left = cols
+            .start
+            .saturating_add(area.left.as_zero_based() as usize)
+            .min(cols.end);
+        let right = cols
+            .start
+            .saturating_add(area.right.as_zero_based() as usize + 1)
+            .min(cols.end);
+
+        if top >= bottom || left >= right {
+            None
+        } else {
+            Some((top..bottom, left..right))
         }
     }
 
@@ -2128,9 +2458,8 @@ impl TerminalState {
         // The terminal only recognizes this control function if vertical split
         // screen mode (DECLRMM) is set.
         if self.left_and_right_margin_mode {
-            let rows = self.screen().physical_rows as u32;
             let cols = self.screen().physical_cols as u32;
-            let left = left.as_zero_based().min(rows - 1).max(0) as usize;
+            let left = left.as_zero_based().min(cols - 1).max(0) as usize;
             let right = right.as_zero_based().min(cols - 1).max(0) as usize;
 
             // The value of the left margin (Pl) must be less than the right margin (Pr).
@@ -2153,6 +2482,23 @@ impl TerminalState {
         }
     }
 
+    /// Returns the cursor position as reported by CPR and DECXCPR;
+    /// when DECOM is enabled the position is relative to the margins.
+    fn cursor_position_for_report(&self) -> (OneBased, OneBased) {
+        let (top, left) = if self.dec_origin_mode {
+            (
+                self.top_and_bottom_margins.start,
+                self.left_and_right_margins.start,
+            )
+        } else {
+            (0, 0)
+        };
+        (
+            OneBased::from_zero_based(self.cursor.y.saturating_sub(top) as u32),
+            OneBased::from_zero_based(self.cursor.x.saturating_sub(left) as u32),
+        )
+    }
+
     fn perform_csi_cursor(&mut self, cursor: Cursor) {
         let seqno = self.seqno;
         match cursor {
@@ -2197,7 +2543,20 @@ impl TerminalState {
                 self.tabs.clear(to_clear, self.cursor.x);
             }
 
-            Cursor::TabulationControl(_) => {}
+            Cursor::TabulationControl(control) => match control {
+                CursorTabulationControl::SetCharacterTabStopAtActivePosition => self.c1_hts(),
+                CursorTabulationControl::ClearCharacterTabStopAtActivePosition => self.tabs.clear(
+                    TabulationClear::ClearCharacterTabStopAtActivePosition,
+                    self.cursor.x,
+                ),
+                CursorTabulationControl::ClearAllCharacterTabStopsAtActiveLine
+                | CursorTabulationControl::ClearAllCharacterTabStops => self.tabs.clear_all(),
+                // We don't support line tab stops
+                CursorTabulationControl::SetLineTabStopAtActiveLine
+                | CursorTabulationControl::ClearLineTabstopAtActiveLine
+                | CursorTabulationControl::ClearAllLineTabStops => {}
+            },
+            Cursor::SetTabStopsEvery8Columns => self.tabs.reset(),
             Cursor::LineTabulation(_) => {}
 
             Cursor::Left(_n) => {
@@ -2342,29 +2701,26 @@ impl TerminalState {
                 self.wrap_next = false;
             }
 
-            Cursor::ActivePositionReport { .. } => {
+            Cursor::ActivePositionReport { .. } | Cursor::ExtendedActivePositionReport { .. } => {
                 // This is really a response from the terminal, and
                 // we don't need to process it as a terminal command
             }
             Cursor::RequestActivePositionReport => {
-                let line = OneBased::from_zero_based(
-                    (self.cursor.y.saturating_sub(if self.dec_origin_mode {
-                        self.top_and_bottom_margins.start
-                    } else {
-                        0
-                    })) as u32,
-                );
-                let col = OneBased::from_zero_based(
-                    (self.cursor.x.saturating_sub(if self.dec_origin_mode {
-                        self.left_and_right_margins.start
-                    } else {
-                        0
-                    })) as u32,
-                );
+                let (line, col) = self.cursor_position_for_report();
                 let report = CSI::Cursor(Cursor::ActivePositionReport { line, col });
                 write!(self.writer, "{}", report).ok();
                 self.writer.flush().ok();
             }
+            Cursor::RequestExtendedActivePositionReport => {
+                let (line, col) = self.cursor_position_for_report();
+                let report = CSI::Cursor(Cursor::ExtendedActivePositionReport {
+                    line,
+                    col,
+                    page: OneBased::new(1),
+                });
+                write!(self.writer, "{}", report).ok();
+                self.writer.flush().ok();
+            }
             Cursor::SaveCursor => {
                 // The `CSI s` SaveCursor sequence is ambiguous with DECSLRM
                 // with default parameters.  To resolve the ambiguity, DECSLRM
@@ -2403,8 +2759,8 @@ impl TerminalState {
             wrap_next: self.wrap_next,
             pen: self.pen.clone(),
             dec_origin_mode: self.dec_origin_mode,
-            g0_charset: self.g0_charset,
-            g1_charset: self.g1_charset,
+            charsets: self.charsets,
+            gl_charset: self.gl_charset,
         };
         debug!(
             "saving cursor {:?} is_alt={}",
@@ -2425,8 +2781,8 @@ impl TerminalState {
                 wrap_next: false,
                 pen: Default::default(),
                 dec_origin_mode: false,
-                g0_charset: CharSet::Ascii,
-                g1_charset: CharSet::DecLineDrawing,
+                charsets: DEFAULT_CHARSETS,
+                gl_charset: 0,
             });
         debug!(
             "restore cursor {:?} is_alt={}",
@@ -2442,9 +2798,9 @@ impl TerminalState {
         self.wrap_next = saved.wrap_next;
         self.pen = saved.pen;
         self.dec_origin_mode = saved.dec_origin_mode;
-        self.g0_charset = saved.g0_charset;
-        self.g1_charset = saved.g1_charset;
-        self.shift_out = false;
+        self.charsets = saved.charsets;
+        self.gl_charset = saved.gl_charset;
+        self.single_shift = None;
         self.newline_mode = false;
     }
 
@@ -2496,6 +2852,13 @@ impl TerminalState {
     }
 
     /// Computes the set of `SemanticZone`s for the current terminal screen.
+    /// Returns the rows of the current screen that hold cells belonging
+    /// to the explicit hyperlink with the given `id`
+    pub fn get_hyperlink_rows(&mut self, id: &str) -> Vec<StableRowIndex> {
+        let seqno = self.seqno;
+        self.screen_mut().hyperlink_rows(id, seqno)
+    }
+
     /// Semantic zones are contiguous runs of cells that have the same
     /// `SemanticType` (Prompt, Input, Output).
     /// Due to the way that the terminal clears the screen, the raw, literal
@@ -2547,6 +2910,108 @@ impl TerminalState {
         Ok(zones)
     }
 
+    /// Returns the commands that were entered at the shell prompt,
+    /// oldest first.  This relies on the shell using OSC 133 escapes
+    /// to mark up its prompt, input and output.
+    pub fn get_command_history(&self) -> Vec<CommandHistoryEntry> {
+        self.command_history.iter().cloned().collect()
+    }
+
+    /// Called when the shell marks the start of command output.
+    /// Captures the text of the input zone that precedes the cursor
+    /// as the pending command.
+    fn begin_command(&mut self) {
+        self.end_command(None);
+
+        let cursor_row = self.screen().phys_row(self.cursor.y);
+        let output_start_y = self.cursor_stable_row();
+        let screen = self.screen_mut();
+
+        // Walk back from the cursor to the row holding the prompt,
+        // gathering the rows that hold input
+        let mut rows = vec![];
+        for phys in (0..=cursor_row).rev() {
+            let line = screen.line_mut(phys);
+            let zones = line.semantic_zone_ranges().to_vec();
+            let mut text = String::new();
+            let mut saw_prompt = false;
+            for zone in zones {
+                match zone.semantic_type {
+                    SemanticType::Input => {
+                        // The end of a zone range is inclusive
+                        let range = zone.range.start as usize..=zone.range.end as usize;
+                        for (idx, cell) in line.visible_cells() {
+                            if range.contains(&idx) {
+                                text.push_str(cell.str());
+                            }
+                        }
+                    }
+                    SemanticType::Prompt => saw_prompt = true,
+                    SemanticType::Output => {}
+                }
+            }
+
+            if !text.is_empty() {
+                rows.push((phys, text, line.last_cell_was_wrapped()));
+            } else if !rows.is_empty() {
+                break;
+            }
+            if saw_prompt {
+                break;
+            }
+        }
+
+        let start_y = match rows.last() {
+            Some((phys, _, _)) => screen.phys_to_stable_row_index(*phys),
+            None => return,
+        };
+
+        let mut command = String::new();
+        for (_, text, wrapped) in rows.into_iter().rev() {
+            if wrapped {
+                command.push_str(&text);
+            } else {
+                command.push_str(text.trim_end());
+                command.push('\n');
+            }
+        }
+        let command = command.trim().to_string();
+
+        if !command.is_empty() {
+            self.pending_command.replace(CommandHistoryEntry {
+                command,
+                exit_status: None,
+                start_y,
+                output_start_y,
+                output_end_y: output_start_y,
+            });
+        }
+    }
+
+    fn cursor_stable_row(&self) -> StableRowIndex {
+        self.screen().visible_row_to_stable_row(self.cursor.y)
+    }
+
+    /// Called when the shell reports the exit status of a command, or
+    /// starts a new prompt; records the pending command, if any.
+    fn end_command(&mut self, exit_status: Option<i32>) {
+        if let Some(mut entry) = self.pending_command.take() {
+            entry.exit_status = exit_status;
+            // Include the cursor row if output has been written to it
+            let cursor_row = self.cursor_stable_row();
+            entry.output_end_y = if self.cursor.x > 0 {
+                cursor_row + 1
+            } else {
+                cursor_row
+            }
+            .max(entry.output_start_y);
+            self.command_history.push_back(entry);
+            while self.command_history.len() > MAX_COMMAND_HISTORY {
+                self.command_history.pop_front();
+            }
+        }
+    }
+
     #[inline]
     pub fn get_reverse_video(&self) -> bool {
         self.reverse_video_mode
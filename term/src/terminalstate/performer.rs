@@ -1,6 +1,6 @@
 use crate::terminal::Alert;
 use crate::terminalstate::{
-    default_color_map, CharSet, MouseEncoding, TabStop, UnicodeVersionStackEntry,
+    default_color_map, CharSet, MouseEncoding, TabStop, UnicodeVersionStackEntry, DEFAULT_CHARSETS,
 };
 use crate::{ClipboardSelection, Position, TerminalState, VisibleRowIndex, DCS, ST};
 use log::{debug, error};
@@ -64,53 +64,8 @@ impl<'a> Performer<'a> {
         let mut p = std::mem::take(&mut self.print);
 
         for g in unicode_segmentation::UnicodeSegmentation::graphemes(p.as_str(), true) {
-            let g = if (self.shift_out && self.g1_charset == CharSet::DecLineDrawing)
-                || (!self.shift_out && self.g0_charset == CharSet::DecLineDrawing)
-            {
-                match g {
-                    "`" => "◆",
-                    "a" => "▒",
-                    "b" => "␉",
-                    "c" => "␌",
-                    "d" => "␍",
-                    "e" => "␊",
-                    "f" => "°",
-                    "g" => "±",
-                    "h" => "␤",
-                    "i" => "␋",
-                    "j" => "┘",
-                    "k" => "┐",
-                    "l" => "┌",
-                    "m" => "└",
-                    "n" => "┼",
-                    "o" => "⎺",
-                    "p" => "⎻",
-                    "q" => "─",
-                    "r" => "⎼",
-                    "s" => "⎽",
-                    "t" => "├",
-                    "u" => "┤",
-                    "v" => "┴",
-                    "w" => "┬",
-                    "x" => "│",
-                    "y" => "≤",
-                    "z" => "≥",
-                    "{" => "π",
-                    "|" => "≠",
-                    "}" => "£",
-                    "~" => "·",
-                    _ => g,
-                }
-            } else if (self.shift_out && self.g1_charset == CharSet::Uk)
-                || (!self.shift_out && self.g0_charset == CharSet::Uk)
-            {
-                match g {
-                    "#" => "£",
-                    _ => g,
-                }
-            } else {
-                g
-            };
+            let charset_idx = self.single_shift.take().unwrap_or(self.gl_charset);
+            let g = self.charsets[charset_idx].map(g);
 
             let print_width = grapheme_column_width(g, Some(self.unicode_version));
             if print_width == 0 {
@@ -377,10 +332,10 @@ impl<'a> Performer<'a> {
             // selected by ASCII Shift Out (0x0E, ^N) or by setting G0
             // via ESC ( 0 .
             ControlCode::ShiftIn => {
-                self.shift_out = false;
+                self.gl_charset = 0;
             }
             ControlCode::ShiftOut => {
-                self.shift_out = true;
+                self.gl_charset = 1;
             }
 
             ControlCode::Enquiry => {
@@ -461,23 +416,54 @@ impl<'a> Performer<'a> {
             Esc::Code(EscCode::NextLine) => self.c1_nel(),
             Esc::Code(EscCode::HorizontalTabSet) => self.c1_hts(),
             Esc::Code(EscCode::DecLineDrawingG0) => {
-                self.g0_charset = CharSet::DecLineDrawing;
+                self.charsets[0] = CharSet::DecLineDrawing;
             }
             Esc::Code(EscCode::AsciiCharacterSetG0) => {
-                self.g0_charset = CharSet::Ascii;
+                self.charsets[0] = CharSet::Ascii;
             }
             Esc::Code(EscCode::UkCharacterSetG0) => {
-                self.g0_charset = CharSet::Uk;
+                self.charsets[0] = CharSet::Uk;
             }
             Esc::Code(EscCode::DecLineDrawingG1) => {
-                self.g1_charset = CharSet::DecLineDrawing;
+                self.charsets[1] = CharSet::DecLineDrawing;
             }
             Esc::Code(EscCode::AsciiCharacterSetG1) => {
-                self.g1_charset = CharSet::Ascii;
+                self.charsets[1] = CharSet::Ascii;
             }
             Esc::Code(EscCode::UkCharacterSetG1) => {
-                self.g1_charset = CharSet::Uk;
+                self.charsets[1] = CharSet::Uk;
+            }
+            Esc::Code(EscCode::DecLineDrawingG2) => {
+                self.charsets[2] = CharSet::DecLineDrawing;
+            }
+            Esc::Code(EscCode::AsciiCharacterSetG2) => {
+                self.charsets[2] = CharSet::Ascii;
+            }
+            Esc::Code(EscCode::UkCharacterSetG2) => {
+                self.charsets[2] = CharSet::Uk;
+            }
+            Esc::Code(EscCode::DecLineDrawingG3) => {
+                self.charsets[3] = CharSet::DecLineDrawing;
+            }
+            Esc::Code(EscCode::AsciiCharacterSetG3) => {
+                self.charsets[3] = CharSet::Ascii;
+            }
+            Esc::Code(EscCode::UkCharacterSetG3) => {
+                self.charsets[3] = CharSet::Uk;
+            }
+            Esc::Code(EscCode::LockingShiftG2) => {
+                self.gl_charset = 2;
             }
+            Esc::Code(EscCode::LockingShiftG3) => {
+                self.gl_charset = 3;
+            }
+            Esc::Code(EscCode::SingleShiftG2) => {
+                self.single_shift = Some(2);
+            }
+            Esc::Code(EscCode::SingleShiftG3) => {
+                self.single_shift = Some(3);
+            }
+            Esc::Code(EscCode::ReturnTerminalId) => self.send_primary_device_attributes(),
             Esc::Code(EscCode::DecSaveCursorPosition) => self.dec_save_cursor(),
             Esc::Code(EscCode::DecRestoreCursorPosition) => self.dec_restore_cursor(),
 
@@ -547,24 +533,31 @@ impl<'a> Performer<'a> {
                 self.mouse_encoding = MouseEncoding::X10;
                 self.keyboard_encoding = KeyboardEncoding::Xterm;
                 self.sixel_scrolls_right = false;
+                self.mouse_tracking = false;
                 self.any_event_mouse = false;
                 self.button_event_mouse = false;
                 self.current_mouse_buttons.clear();
+                self.last_mouse_move.take();
                 self.cursor_visible = true;
-                self.g0_charset = CharSet::Ascii;
-                self.g1_charset = CharSet::DecLineDrawing;
-                self.shift_out = false;
+                self.charsets = DEFAULT_CHARSETS;
+                self.gl_charset = 0;
+                self.single_shift = None;
                 self.newline_mode = false;
                 self.tabs = TabStop::new(self.screen().physical_cols, 8);
                 self.palette.take();
                 self.top_and_bottom_margins = 0..self.screen().physical_rows as VisibleRowIndex;
                 self.left_and_right_margins = 0..self.screen().physical_cols;
+                self.left_and_right_margin_mode = false;
+                self.bidi_enabled.take();
+                self.bidi_hint.take();
                 self.unicode_version = self.config.unicode_version();
                 self.unicode_version_stack.clear();
                 self.suppress_initial_title_change = false;
                 self.accumulating_title.take();
 
                 self.screen.activate_primary_screen(seqno);
+                self.screen.clear_saved_cursors();
+                self.kitty_remove_all_placements(true);
                 self.erase_in_display(EraseInDisplay::EraseScrollback);
                 self.erase_in_display(EraseInDisplay::EraseDisplay);
                 if let Some(handler) = self.alert_handler.as_mut() {
@@ -637,6 +630,11 @@ impl<'a> Performer<'a> {
                         handler.alert(Alert::SetUserVar { name, value });
                     }
                 }
+                ITermProprietary::RequestAttention(true) => {
+                    if let Some(handler) = self.alert_handler.as_mut() {
+                        handler.alert(Alert::RequestAttention);
+                    }
+                }
                 ITermProprietary::UnicodeVersion(ITermUnicodeVersionOp::Set(n)) => {
                     self.unicode_version.version = n;
                 }
@@ -667,17 +665,20 @@ impl<'a> Performer<'a> {
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::FreshLineAndStartPrompt { .. },
             ) => {
+                self.end_command(None);
                 self.fresh_line();
                 self.pen.set_semantic_type(SemanticType::Prompt);
             }
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::StartPrompt(_),
             ) => {
+                self.end_command(None);
                 self.pen.set_semantic_type(SemanticType::Prompt);
             }
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::MarkEndOfCommandWithFreshLine { .. },
             ) => {
+                self.end_command(None);
                 self.fresh_line();
                 self.pen.set_semantic_type(SemanticType::Prompt);
             }
@@ -695,12 +696,15 @@ impl<'a> Performer<'a> {
             OperatingSystemCommand::FinalTermSemanticPrompt(
                 FinalTermSemanticPrompt::MarkEndOfInputAndStartOfOutput { .. },
             ) => {
+                self.begin_command();
                 self.pen.set_semantic_type(SemanticType::Output);
             }
 
             OperatingSystemCommand::FinalTermSemanticPrompt(
-                FinalTermSemanticPrompt::CommandStatus { .. },
-            ) => {}
+                FinalTermSemanticPrompt::CommandStatus { status, .. },
+            ) => {
+                self.end_command(Some(status));
+            }
 
             OperatingSystemCommand::SystemNotification(message) => {
                 if let Some(handler) = self.alert_handler.as_mut() {
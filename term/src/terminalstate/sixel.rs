@@ -38,7 +38,12 @@ impl TerminalState {
         let mut y = 0;
         let mut foreground_color = RgbColor::new_8bpc(0, 0xff, 0);
 
+        // The raster attributes may declare a size that is smaller than
+        // the sixel data; anything that falls outside of it is clipped.
         let mut emit_sixel = |d: &u8, foreground_color: &RgbColor, x: u32, y: u32| {
+            if x >= width {
+                return;
+            }
             let (red, green, blue) = foreground_color.to_tuple_rgb8();
             for bitno in 0..6 {
                 if y + bitno >= height {
@@ -55,20 +60,21 @@ impl TerminalState {
             match d {
                 SixelData::Data(d) => {
                     emit_sixel(d, &foreground_color, x, y);
-                    x += 1;
+                    x = x.saturating_add(1);
                 }
 
                 SixelData::Repeat { repeat_count, data } => {
-                    for _ in 0..*repeat_count {
+                    let end = x.saturating_add(*repeat_count);
+                    for x in x..end.min(width) {
                         emit_sixel(data, &foreground_color, x, y);
-                        x += 1;
                     }
+                    x = end;
                 }
 
                 SixelData::CarriageReturn => x = 0,
                 SixelData::NewLine => {
                     x = 0;
-                    y += 6;
+                    y = y.saturating_add(6);
                 }
 
                 SixelData::DefineColorMapRGB { color_number, rgb } => {
@@ -48,3 +48,24 @@ fn test_tab() {
     term.print("\t");
     term.assert_cursor_pos(24, 0, None, None);
 }
+
+#[test]
+fn test_tab_stops() {
+    let mut term = TestTerm::new(3, 25, 0);
+    // TBC 3: clear all, then HTS at column 3 and CTC 0 at column 10
+    term.print("\x1b[3g\x1b[4G\x1bH\x1b[11G\x1b[0W\r\t");
+    term.assert_cursor_pos(3, 0, None, None);
+    term.print("\t");
+    term.assert_cursor_pos(10, 0, None, None);
+    // CBT moves back to the prior stop
+    term.print("\x1b[Z");
+    term.assert_cursor_pos(3, 0, None, None);
+
+    // CTC 2 clears the stop at the cursor
+    term.print("\x1b[2W\r\t");
+    term.assert_cursor_pos(10, 0, None, None);
+
+    // DECST8C restores the default stops
+    term.print("\x1b[?5W\r\x1b[2I");
+    term.assert_cursor_pos(16, 0, None, None);
+}
@@ -222,3 +222,76 @@ fn test_ed_erase_scrollback() {
     term.print("b");
     assert_all_contents(&term, file!(), line!(), &["111", "222", "ab "]);
 }
+
+#[test]
+fn test_rectangular_area_ops() {
+    let mut term = TestTerm::new(4, 5, 0);
+    term.print("abcde\r\nfghij\r\nklmno\r\npqrst");
+
+    // DECFRA: fill rows 2-3, cols 2-4 with 'x'
+    term.print("\x1b[120;2;2;3;4$x");
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["abcde", "fxxxj", "kxxxo", "pqrst"],
+    );
+
+    // DECCRA: copy the top left 2x2 block to row 3, col 4;
+    // the copy is clipped to the right edge of the screen
+    term.print("\x1b[1;1;2;2;1;3;4;1$v");
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["abcde", "fxxxj", "kxxab", "pqrfx"],
+    );
+
+    // DECERA: erase from row 2, col 3 to the bottom right
+    term.print("\x1b[2;3$z");
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["abcde", "fx   ", "kx   ", "pq   "],
+    );
+}
+
+#[test]
+fn test_decrqm() {
+    let mut term = TestTerm::new(2, 10, 0);
+
+    // DECAWM is enabled by TestTerm::new
+    term.print("\x1b[?7$p");
+    assert_eq!(term.take_responses(), "\x1b[?7;1$y");
+
+    // DECSCNM is reported as reset, then as set once enabled
+    term.print("\x1b[?5$p");
+    assert_eq!(term.take_responses(), "\x1b[?5;2$y");
+    term.print("\x1b[?5h\x1b[?5$p");
+    assert_eq!(term.take_responses(), "\x1b[?5;1$y");
+
+    // An unrecognized mode
+    term.print("\x1b[?9999$p");
+    assert_eq!(term.take_responses(), "\x1b[?9999;0$y");
+
+    // The ANSI form of the show cursor mode
+    term.print("\x1b[25$p");
+    assert_eq!(term.take_responses(), "\x1b[25;1$y");
+}
+
+#[test]
+fn test_decrqm_permanent() {
+    let mut term = TestTerm::new(2, 10, 0);
+
+    // DECSCLM (smooth scroll) is permanently reset, even after
+    // an attempt to enable it
+    term.print("\x1b[?4$p");
+    assert_eq!(term.take_responses(), "\x1b[?4;4$y");
+    term.print("\x1b[?4h\x1b[?4$p");
+    assert_eq!(term.take_responses(), "\x1b[?4;4$y");
+
+    // Highlight mouse tracking is not supported
+    term.print("\x1b[?1001$p");
+    assert_eq!(term.take_responses(), "\x1b[?1001;4$y");
+}
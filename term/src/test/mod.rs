@@ -10,7 +10,8 @@ mod csi;
 use crate::color::ColorPalette;
 use k9::assert_equal as assert_eq;
 use std::cell::RefCell;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use termwiz::color::ColorAttribute;
 use termwiz::escape::csi::{Edit, EraseInDisplay, EraseInLine};
 use termwiz::escape::{OneBased, OperatingSystemCommand, CSI};
 use termwiz::surface::{CursorShape, CursorVisibility, SequenceNo, SEQ_ZERO};
@@ -39,19 +40,62 @@ impl Clipboard for LocalClip {
     }
 }
 
+/// Records the files that the terminal asks to be downloaded
+#[derive(Default)]
+struct LocalDownloads {
+    files: Mutex<Vec<(Option<String>, Vec<u8>)>>,
+}
+
+impl DownloadHandler for LocalDownloads {
+    fn save_to_downloads(&self, name: Option<String>, data: Vec<u8>) {
+        self.files.lock().unwrap().push((name, data));
+    }
+}
+
+/// Captures the responses that the terminal sends back to the application
+#[derive(Clone, Default)]
+struct ResponseWriter {
+    buf: Arc<Mutex<Vec<u8>>>,
+}
+
+impl std::io::Write for ResponseWriter {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.lock().unwrap().extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 struct TestTerm {
     term: Terminal,
+    responses: ResponseWriter,
 }
 
 #[derive(Debug)]
 struct TestTermConfig {
     scrollback: usize,
+    unicode_version: u8,
 }
 impl TerminalConfiguration for TestTermConfig {
     fn scrollback_size(&self) -> usize {
         self.scrollback
     }
 
+    fn max_download_size(&self) -> usize {
+        16
+    }
+
+    fn unicode_version(&self) -> UnicodeVersion {
+        UnicodeVersion {
+            version: self.unicode_version,
+            ambiguous_are_wide: false,
+            prefer_emoji_presentation: false,
+        }
+    }
+
     fn color_palette(&self) -> ColorPalette {
         ColorPalette::default()
     }
@@ -64,6 +108,7 @@ impl TestTerm {
             .filter_level(log::LevelFilter::Trace)
             .try_init();
 
+        let responses = ResponseWriter::default();
         let mut term = Terminal::new(
             TerminalSize {
                 physical_rows: height,
@@ -71,15 +116,18 @@ impl TestTerm {
                 pixel_width: width * 8,
                 pixel_height: height * 16,
             },
-            Arc::new(TestTermConfig { scrollback }),
+            Arc::new(TestTermConfig {
+                scrollback,
+                unicode_version: 9,
+            }),
             "WezTerm",
             "O_o",
-            Box::new(Vec::new()),
+            Box::new(responses.clone()),
         );
         let clip: Arc<dyn Clipboard> = Arc::new(LocalClip::new());
         term.set_clipboard(&clip);
 
-        let mut term = Self { term };
+        let mut term = Self { term, responses };
 
         term.set_auto_wrap(true);
 
@@ -90,6 +138,14 @@ impl TestTerm {
         self.term.advance_bytes(bytes);
     }
 
+    /// Returns and clears the data that the terminal has written
+    /// back in response to queries
+    fn take_responses(&mut self) -> String {
+        self.term.wait_for_pending_writes();
+        let data = std::mem::take(&mut *self.responses.buf.lock().unwrap());
+        String::from_utf8(data).unwrap()
+    }
+
     fn set_mode(&mut self, mode: &str, enable: bool) {
         self.print(CSI);
         self.print(mode);
@@ -507,6 +563,47 @@ fn test_semantic() {
     );
 }
 
+#[test]
+fn test_sixel_larger_than_raster_attributes() {
+    let mut term = TestTerm::new(5, 10, 0);
+    // The raster attributes declare a 2x6 image, but the data
+    // is 4 pixels wide and 12 pixels tall
+    term.print("\x1bPq\"1;1;2;6#0;2;100;0;0#0~~~~-!4~\x1b\\");
+
+    let cell = term.screen().get_cell(0, 0).cloned().unwrap();
+    assert!(cell.attrs().images().is_some());
+}
+
+#[test]
+fn test_command_history() {
+    let mut term = TestTerm::new(5, 10, 0);
+    term.print("\x1b]133;A\x1b\\$ \x1b]133;B\x1b\\ls -l\r\n");
+    term.print("\x1b]133;C\x1b\\output\r\n\x1b]133;D;1\x1b\\");
+    term.print("\x1b]133;A\x1b\\$ \x1b]133;B\x1b\\echo hello\r\n");
+    term.print("\x1b]133;C\x1b\\hello\r\n");
+    term.print("\x1b]133;A\x1b\\$ \x1b]133;B\x1b\\");
+
+    assert_eq!(
+        term.get_command_history(),
+        vec![
+            CommandHistoryEntry {
+                command: "ls -l".to_string(),
+                exit_status: Some(1),
+                start_y: 0,
+                output_start_y: 1,
+                output_end_y: 2,
+            },
+            CommandHistoryEntry {
+                command: "echo hello".to_string(),
+                exit_status: None,
+                start_y: 2,
+                output_start_y: 4,
+                output_end_y: 5,
+            },
+        ]
+    );
+}
+
 #[test]
 fn issue_1161() {
     let mut term = TestTerm::new(1, 5, 0);
@@ -828,6 +925,169 @@ fn test_dec_special_graphics() {
     );
 }
 
+#[test]
+fn test_xtgettcap() {
+    let mut term = TestTerm::new(1, 10, 0);
+
+    // TN
+    term.print("\x1bP+q544E\x1b\\");
+    assert_eq!(term.take_responses(), "\x1bP1+r544E=57657A5465726D\x1b\\");
+
+    // RGB, with an unknown name in the same request
+    term.print("\x1bP+q524742;78797A\x1b\\");
+    assert_eq!(
+        term.take_responses(),
+        "\x1bP1+r524742=382F382F38\x1b\\\x1bP0+r78797A\x1b\\"
+    );
+
+    // Sync is looked up from the compiled terminfo
+    term.print("\x1bP+q53796E63\x1b\\");
+    assert!(term.take_responses().starts_with("\x1bP1+r53796E63="));
+
+    // Ms advertises OSC 52 clipboard support to eg: tmux and vim
+    term.print("\x1bP+q4D73\x1b\\");
+    assert_eq!(
+        term.take_responses(),
+        format!(
+            "\x1bP1+r4D73={}\x1b\\",
+            hex::encode_upper("\x1b]52;%p1%s;%p2%s\x07")
+        )
+    );
+}
+
+#[test]
+fn test_primary_device_attributes() {
+    let mut term = TestTerm::new(1, 10, 0);
+    term.print("\x1b[c");
+    assert_eq!(term.take_responses(), "\x1b[?65;4;6;18;22;28;52c");
+
+    // DECID is answered in the same way
+    term.print("\x1bZ");
+    assert_eq!(term.take_responses(), "\x1b[?65;4;6;18;22;28;52c");
+}
+
+#[test]
+fn test_cursor_position_report() {
+    let mut term = TestTerm::new(10, 20, 0);
+    term.print("\x1b[4;7H\x1b[6n");
+    assert_eq!(term.take_responses(), "\x1b[4;7R");
+    term.print("\x1b[?6n");
+    assert_eq!(term.take_responses(), "\x1b[?4;7;1R");
+
+    // With DECOM set, the report is relative to the margins
+    term.print("\x1b[3;8r\x1b[?69h\x1b[5;15s\x1b[?6h\x1b[2;3H\x1b[6n");
+    assert_eq!(term.take_responses(), "\x1b[2;3R");
+    term.print("\x1b[?6n");
+    assert_eq!(term.take_responses(), "\x1b[?2;3;1R");
+
+    term.print("\x1b[?6l\x1b[6n");
+    assert_eq!(term.take_responses(), "\x1b[1;1R");
+
+    // Without DECOM the margins don't affect the report
+    term.print("\x1b[5;9H\x1b[?6n");
+    assert_eq!(term.take_responses(), "\x1b[?5;9;1R");
+}
+
+/// 1049 saves the cursor and SGR state, then switches to a cleared
+/// alternate screen; resetting it restores both on the primary screen
+#[test]
+fn test_alt_screen_1049() {
+    let mut term = TestTerm::new(3, 10, 0);
+    term.print("\x1b[31mhello");
+    term.set_mode("?1049", true);
+    assert!(term.is_alt_screen_active());
+    term.assert_cursor_pos(0, 0, Some("alt screen starts at home"), None);
+    term.print("vim");
+    assert_eq!(
+        term.screen().visible_lines()[0].cells()[0]
+            .attrs()
+            .foreground(),
+        ColorAttribute::Default
+    );
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["vim       ", "          ", "          "],
+    );
+
+    term.set_mode("?1049", false);
+    assert!(!term.is_alt_screen_active());
+    term.assert_cursor_pos(5, 0, Some("cursor restored"), None);
+    term.print("!");
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["hello!    ", "          ", "          "],
+    );
+    assert_eq!(
+        term.screen().visible_lines()[0].cells()[5]
+            .attrs()
+            .foreground(),
+        ColorAttribute::PaletteIndex(1)
+    );
+
+    // The alternate screen is cleared each time it is entered
+    term.set_mode("?1049", true);
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["          ", "          ", "          "],
+    );
+}
+
+/// 1047 switches screens without saving the cursor and clears the
+/// alternate screen on the way out; 1048 saves and restores the cursor
+#[test]
+fn test_alt_screen_1047_1048() {
+    let mut term = TestTerm::new(3, 5, 0);
+    term.print("abcde");
+    term.set_mode("?1048", true);
+    term.set_mode("?1047", true);
+    assert!(term.is_alt_screen_active());
+    // The pending wrap from the primary screen must not carry over
+    term.print("x");
+    assert_visible_contents(&term, file!(), line!(), &["    x", "     ", "     "]);
+
+    term.set_mode("?1047", false);
+    term.set_mode("?1048", false);
+    assert!(!term.is_alt_screen_active());
+    assert_visible_contents(&term, file!(), line!(), &["abcde", "     ", "     "]);
+    term.assert_cursor_pos(4, 0, Some("cursor restored"), None);
+
+    // 47 doesn't clear the alternate screen, but 1047 already did
+    term.set_mode("?47", true);
+    assert_visible_contents(&term, file!(), line!(), &["     ", "     ", "     "]);
+}
+
+/// DECSTR resets modes but must not switch screens or erase anything
+#[test]
+fn test_soft_reset_keeps_screen() {
+    let mut term = TestTerm::new(2, 5, 0);
+    term.set_mode("?1049", true);
+    term.print("\x1b(0\x1b[?25lq");
+    term.soft_reset();
+    term.print("q");
+
+    assert!(term.is_alt_screen_active());
+    assert_eq!(term.cursor_pos().visibility, CursorVisibility::Visible);
+    assert_visible_contents(&term, file!(), line!(), &["─q   ", "     "]);
+}
+
+/// Test designating and shifting the G2 and G3 character sets.
+#[test]
+fn test_g2_g3_charsets() {
+    let mut term = TestTerm::new(2, 10, 0);
+
+    // LS2 locks G2 into GL until SI returns to G0
+    term.print("\u{1b}*0\u{1b}nqx\u{0f}qx\r\n");
+    // SS3 only affects the next character
+    term.print("\u{1b}+0\u{1b}Oqq");
+    assert_visible_contents(&term, file!(), line!(), &["─│qx      ", "─q        "]);
+}
+
 /// Test double-width / double-height sequences.
 #[test]
 fn test_dec_double_width() {
@@ -1053,6 +1313,90 @@ fn test_scroll_margins() {
     );
 }
 
+/// Scrolling within a region re-uses the lines that scroll out of the
+/// region, and leaves the lines outside of the region untouched
+#[test]
+fn test_scroll_region_multiple_rows() {
+    let mut term = TestTerm::new(5, 1, 10);
+    term.print("a\r\nb\r\nc\r\nd\r\ne");
+    term.set_scroll_region(1, 3);
+
+    let seqno = term.current_seqno();
+    term.print("\x1b[2S");
+    assert_all_contents(&term, file!(), line!(), &["a", "d", " ", " ", "e"]);
+    term.assert_dirty_lines(seqno, &[1, 2, 3], None);
+
+    term.print("\x1b[2;1Hx\x1b[2T");
+    assert_all_contents(&term, file!(), line!(), &["a", " ", " ", "x", "e"]);
+}
+
+/// Reducing the scrollback size in a new config trims the existing
+/// scrollback, and the new size is used for subsequent output
+#[test]
+fn test_set_config_scrollback() {
+    let mut term = TestTerm::new(2, 1, 4);
+    term.print("1\r\n2\r\n3\r\n4\r\n5");
+    assert_all_contents(&term, file!(), line!(), &["1", "2", "3", "4", "5"]);
+
+    term.set_config(Arc::new(TestTermConfig {
+        scrollback: 1,
+        unicode_version: 9,
+    }));
+    assert_all_contents(&term, file!(), line!(), &["3", "4", "5"]);
+
+    term.print("\r\n6");
+    assert_all_contents(&term, file!(), line!(), &["4", "5", "6"]);
+}
+
+/// A changed unicode_version is adopted when the config is reloaded,
+/// unless the application has selected its own version
+#[test]
+fn test_set_config_unicode_version() {
+    // VICTORY HAND with an emoji presentation selector is only
+    // double width from unicode version 14 onwards
+    let victory = "\u{270c}\u{fe0f}";
+
+    let mut term = TestTerm::new(2, 10, 0);
+    term.print(victory);
+    term.assert_cursor_pos(1, 0, None, None);
+
+    term.set_config(Arc::new(TestTermConfig {
+        scrollback: 0,
+        unicode_version: 14,
+    }));
+    term.print(format!("\r{}", victory));
+    term.assert_cursor_pos(2, 0, None, None);
+
+    term.print(format!("\x1b]1337;UnicodeVersion=9\x07\r{}", victory));
+    term.assert_cursor_pos(1, 0, None, None);
+
+    term.set_config(Arc::new(TestTermConfig {
+        scrollback: 0,
+        unicode_version: 14,
+    }));
+    term.print(format!("\r{}", victory));
+    term.assert_cursor_pos(1, 0, None, None);
+}
+
+/// The left margin must be clamped to the width of the screen,
+/// not its height
+#[test]
+fn test_left_margin_wider_than_tall() {
+    let mut term = TestTerm::new(2, 20, 0);
+    term.set_mode("?69", true);
+    term.set_left_and_right_margins(10, 14);
+    // With origin mode, CUP is relative to the margins
+    term.set_mode("?6", true);
+    term.cup(0, 0);
+    term.print("hello!");
+    assert_visible_contents(
+        &term,
+        file!(),
+        line!(),
+        &["          hello     ", "          !         "],
+    );
+}
+
 #[test]
 fn test_emoji_with_modifier() {
     let waving_hand = "\u{1f44b}";
@@ -1183,3 +1527,47 @@ fn test_hyperlinks() {
         Compare::TEXT | Compare::ATTRS,
     );
 }
+
+#[test]
+fn test_hyperlink_rows() {
+    let mut term = TestTerm::new(4, 5, 0);
+    let link = Arc::new(Hyperlink::new_with_id("http://example.com", "a"));
+    let other = Arc::new(Hyperlink::new_with_id("http://example.com", "b"));
+
+    term.hyperlink(&link);
+    term.print("hello\r\n");
+    term.hyperlink(&other);
+    term.print("there\r\n");
+    term.hyperlink(&link);
+    term.print("again");
+    term.hyperlink_off();
+
+    assert_eq!(term.get_hyperlink_rows("a"), vec![0, 2]);
+    assert_eq!(term.get_hyperlink_rows("b"), vec![1]);
+    assert_eq!(term.get_hyperlink_rows("c"), Vec::<StableRowIndex>::new());
+
+    // Overwriting the link removes the row from the index
+    term.cup(0, 0);
+    term.print("plain");
+    assert_eq!(term.get_hyperlink_rows("a"), vec![2]);
+}
+
+#[test]
+fn test_download_size_limit() {
+    let mut term = TestTerm::new(2, 10, 0);
+    let downloads = Arc::new(LocalDownloads::default());
+    let handler: Arc<dyn DownloadHandler> = downloads.clone();
+    term.term.set_download_handler(&handler);
+
+    // "small.txt" containing "hello"
+    term.print("\x1b]1337;File=name=c21hbGwudHh0:aGVsbG8=\x07");
+    // "big.txt" containing 17 bytes
+    term.print("\x1b]1337;File=name=YmlnLnR4dA==:eHh4eHh4eHh4eHh4eHh4eHg=\x07");
+    // The declared size is also checked
+    term.print("\x1b]1337;File=name=YmlnLnR4dA==;size=1000:aGVsbG8=\x07");
+
+    assert_eq!(
+        *downloads.files.lock().unwrap(),
+        vec![(Some("small.txt".to_string()), b"hello".to_vec())]
+    );
+}
@@ -0,0 +1,49 @@
+//! Input data for the parser benchmarks.
+//! The terminal benchmarks in the wezterm-term crate generate the same
+//! data (see term/benches/corpus) so that their throughput numbers can
+//! be compared directly; keep the two in sync.
+
+/// Plain text scrolling through the terminal, similar to the
+/// `dense_cells`/`scrolling` cases in vtebench.
+pub fn scroll_corpus() -> Vec<u8> {
+    let mut data = vec![];
+    for line in 0..2000 {
+        data.extend_from_slice(
+            format!("{} the quick brown fox jumps over the lazy dog\r\n", line).as_bytes(),
+        );
+    }
+    data
+}
+
+/// Text heavy in multi-codepoint graphemes and wide characters
+pub fn emoji_corpus() -> Vec<u8> {
+    let mut data = vec![];
+    for _ in 0..1000 {
+        data.extend_from_slice("👨‍👩‍👧‍👦 ❤️ 🇺🇸 漢字 カタカナ e\u{301}\r\n".as_bytes());
+    }
+    data
+}
+
+/// Output that changes the graphic rendition for almost every cell
+pub fn sgr_corpus() -> Vec<u8> {
+    let mut data = vec![];
+    for line in 0..500 {
+        for col in 0..80 {
+            let idx = (line + col) % 256;
+            data.extend_from_slice(
+                format!("\x1b[1;38;5;{};48;2;{};{};{}mx", idx, idx, col, line % 256).as_bytes(),
+            );
+        }
+        data.extend_from_slice(b"\x1b[0m\r\n");
+    }
+    data
+}
+
+/// All of the corpora, labelled with the name of the benchmark
+pub fn all() -> [(&'static str, Vec<u8>); 3] {
+    [
+        ("scroll", scroll_corpus()),
+        ("emoji", emoji_corpus()),
+        ("sgr", sgr_corpus()),
+    ]
+}
@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use termwiz::escape::parser::Parser;
+
+mod corpus;
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parser");
+    for (name, corpus) in corpus::all() {
+        group.throughput(Throughput::Bytes(corpus.len() as u64));
+        group.bench_function(name, |b| {
+            let mut parser = Parser::new();
+            b.iter(|| {
+                let mut count = 0;
+                parser.parse(black_box(&corpus), |action| {
+                    black_box(action);
+                    count += 1;
+                });
+                count
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
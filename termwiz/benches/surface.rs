@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use termwiz::cell::AttributeChange;
+use termwiz::color::{AnsiColor, ColorAttribute};
+use termwiz::surface::{Change, Position, Surface};
+
+/// Populate a surface with a screenful of colorful text
+fn fill(surface: &mut Surface, seed: usize) {
+    let (cols, rows) = surface.dimensions();
+    for row in 0..rows {
+        surface.add_change(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(row),
+        });
+        for col in 0..cols {
+            let color = ((row + col + seed) % 16) as u8;
+            surface.add_change(Change::Attribute(AttributeChange::Foreground(
+                ColorAttribute::PaletteIndex(color),
+            )));
+            surface.add_change(Change::Text(
+                ((b'a' + (col % 26) as u8) as char).to_string(),
+            ));
+        }
+    }
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        AnsiColor::Default.into(),
+    )));
+}
+
+fn frame_assembly(c: &mut Criterion) {
+    let mut group = c.benchmark_group("surface");
+
+    group.bench_function("fill_80x24", |b| {
+        b.iter(|| {
+            let mut surface = Surface::new(80, 24);
+            fill(&mut surface, 0);
+            black_box(surface.get_changes(0).1.len())
+        })
+    });
+
+    group.bench_function("diff_screens_80x24", |b| {
+        let mut a = Surface::new(80, 24);
+        fill(&mut a, 0);
+        let mut other = Surface::new(80, 24);
+        fill(&mut other, 3);
+        b.iter(|| black_box(a.diff_screens(&other)))
+    });
+
+    group.bench_function("screen_chars_to_string_80x24", |b| {
+        let mut surface = Surface::new(80, 24);
+        fill(&mut surface, 0);
+        b.iter(|| black_box(surface.screen_chars_to_string()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, frame_assembly);
+criterion_main!(benches);
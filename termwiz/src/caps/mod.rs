@@ -59,6 +59,10 @@ use semver::Version;
 use std::env::var;
 use terminfo::{self, capability as cap};
 
+/// The source of the `wezterm` terminfo entry, suitable for compiling
+/// with `tic -x`; useful for installing it on systems that don't have it.
+pub const WEZTERM_TERMINFO_SOURCE: &str = include_str!("../../data/wezterm.terminfo");
+
 builder! {
     /// Use the `ProbeHints` to configure an instance of
     /// the `ProbeHints` struct.  `ProbeHints` are passed to the `Capabilities`
@@ -107,6 +111,14 @@ builder! {
 
         /// Whether mouse support is present and should be used
         mouse_reporting: Option<bool>,
+
+        /// Whether the italic attribute is supported.
+        /// The default is to assume yes.
+        italic: Option<bool>,
+
+        /// Whether the strikethrough attribute is supported.
+        /// The default is to assume yes.
+        strikethrough: Option<bool>,
     }
 }
 
@@ -121,8 +133,9 @@ impl ProbeHints {
     }
 }
 
-/// Describes the level of color support available
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Describes the level of color support available.
+/// The variants are ordered from least to most capable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ColorLevel {
     /// Basic ANSI colors; 8 colors + bright versions
     Sixteen,
@@ -154,6 +167,8 @@ pub struct Capabilities {
     terminfo_db: Option<terminfo::Database>,
     bracketed_paste: bool,
     mouse_reporting: bool,
+    italic: bool,
+    strikethrough: bool,
 }
 
 impl Capabilities {
@@ -281,6 +296,8 @@ impl Capabilities {
 
         let bracketed_paste = hints.bracketed_paste.unwrap_or(true);
         let mouse_reporting = hints.mouse_reporting.unwrap_or(true);
+        let italic = hints.italic.unwrap_or(true);
+        let strikethrough = hints.strikethrough.unwrap_or(true);
 
         Ok(Self {
             color_level,
@@ -291,9 +308,32 @@ impl Capabilities {
             terminfo_db,
             bracketed_paste,
             mouse_reporting,
+            italic,
+            strikethrough,
         })
     }
 
+    /// Reduce the capabilities to match those of a terminal that
+    /// identified itself via the supplied secondary device attributes.
+    /// This only ever removes capabilities; it is intended to catch
+    /// the case where the environment claims more than an older
+    /// terminal can actually render.
+    pub fn apply_secondary_device_attributes(mut self, da: &SecondaryDeviceAttributes) -> Self {
+        let (color_level, italic, strikethrough) = match da.terminal_type {
+            // GNU screen; truecolor arrived in 5.0
+            83 if da.version < 50000 => (ColorLevel::TwoFiftySix, false, true),
+            // xterm; see its changelog for the patch numbers
+            41 if da.version < 282 => (ColorLevel::Sixteen, false, false),
+            41 if da.version < 305 => (ColorLevel::TwoFiftySix, false, false),
+            41 if da.version < 331 => (ColorLevel::TwoFiftySix, true, true),
+            _ => return self,
+        };
+        self.color_level = self.color_level.min(color_level);
+        self.italic &= italic;
+        self.strikethrough &= strikethrough;
+        self
+    }
+
     /// Indicates how many colors are supported
     pub fn color_level(&self) -> ColorLevel {
         self.color_level
@@ -336,6 +376,50 @@ impl Capabilities {
     pub fn mouse_reporting(&self) -> bool {
         self.mouse_reporting
     }
+
+    /// Whether the italic attribute is supported
+    pub fn italic(&self) -> bool {
+        self.italic
+    }
+
+    /// Whether the strikethrough attribute is supported
+    pub fn strikethrough(&self) -> bool {
+        self.strikethrough
+    }
+}
+
+/// The response to a secondary device attributes query (`CSI > c`),
+/// which has the form `CSI > Pp ; Pv ; Pc c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecondaryDeviceAttributes {
+    /// `Pp`: identifies the terminal type; xterm reports 41,
+    /// GNU screen reports 83.
+    pub terminal_type: u32,
+    /// `Pv`: the firmware or patch version
+    pub version: u32,
+    /// `Pc`: the ROM cartridge registration number; usually 0
+    pub rom: u32,
+}
+
+impl SecondaryDeviceAttributes {
+    /// Locate and parse a secondary device attributes response
+    /// within `data`, which may also hold other input.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let start = data.windows(3).position(|w| w == b"\x1b[>")? + 3;
+        let data = &data[start..];
+        let end = data.iter().position(|&b| b == b'c')?;
+        let params = std::str::from_utf8(&data[..end]).ok()?;
+
+        let mut params = params.split(';').map(|p| p.parse::<u32>().ok());
+        let terminal_type = params.next()??;
+        let version = params.next().unwrap_or(Some(0))?;
+        let rom = params.next().unwrap_or(Some(0))?;
+        Some(Self {
+            terminal_type,
+            version,
+            rom,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -470,4 +554,66 @@ mod test {
         .unwrap();
         assert_eq!(caps.iterm2_image(), true);
     }
+
+    #[test]
+    fn secondary_device_attributes() {
+        assert_eq!(
+            SecondaryDeviceAttributes::parse(b"\x1b[>41;330;0c\x1b[?1;2c"),
+            Some(SecondaryDeviceAttributes {
+                terminal_type: 41,
+                version: 330,
+                rom: 0
+            })
+        );
+        assert_eq!(
+            SecondaryDeviceAttributes::parse(b"\x1b[>83;40800c"),
+            Some(SecondaryDeviceAttributes {
+                terminal_type: 83,
+                version: 40800,
+                rom: 0
+            })
+        );
+        assert_eq!(SecondaryDeviceAttributes::parse(b"\x1b[?1;2c"), None);
+        assert_eq!(SecondaryDeviceAttributes::parse(b"\x1b[>41;3"), None);
+    }
+
+    #[test]
+    fn apply_secondary_device_attributes() {
+        let truecolor = || {
+            Capabilities::new_with_hints(
+                ProbeHints::default().color_level(Some(ColorLevel::TrueColor)),
+            )
+            .unwrap()
+        };
+
+        let caps = truecolor().apply_secondary_device_attributes(
+            &SecondaryDeviceAttributes::parse(b"\x1b[>83;40800;0c").unwrap(),
+        );
+        assert_eq!(caps.color_level(), ColorLevel::TwoFiftySix);
+        assert_eq!(caps.italic(), false);
+        assert_eq!(caps.strikethrough(), true);
+
+        let caps = truecolor().apply_secondary_device_attributes(
+            &SecondaryDeviceAttributes::parse(b"\x1b[>41;279;0c").unwrap(),
+        );
+        assert_eq!(caps.color_level(), ColorLevel::Sixteen);
+        assert_eq!(caps.italic(), false);
+
+        // A modern xterm keeps whatever the environment told us
+        let caps = truecolor().apply_secondary_device_attributes(
+            &SecondaryDeviceAttributes::parse(b"\x1b[>41;370;0c").unwrap(),
+        );
+        assert_eq!(caps.color_level(), ColorLevel::TrueColor);
+        assert_eq!(caps.italic(), true);
+
+        // Never upgrades
+        let caps = Capabilities::new_with_hints(
+            ProbeHints::default().color_level(Some(ColorLevel::Sixteen)),
+        )
+        .unwrap()
+        .apply_secondary_device_attributes(
+            &SecondaryDeviceAttributes::parse(b"\x1b[>41;300;0c").unwrap(),
+        );
+        assert_eq!(caps.color_level(), ColorLevel::Sixteen);
+    }
 }
@@ -785,6 +785,17 @@ impl Cell {
     pub fn presentation(&self) -> Presentation {
         match Presentation::for_grapheme(self.str()) {
             (_, Some(variation)) => variation,
+            // If the cell was made wider than its text presentation
+            // would be, then it was assigned emoji presentation
+            // via UnicodeVersion::prefer_emoji_presentation
+            (Presentation::Text, None)
+                if self.width() == 2
+                    && Presentation::is_ambiguous(self.str())
+                    && grapheme_column_width(self.str(), Some(AMBIGUOUS_WIDE_TEXT_VERSION))
+                        == 1 =>
+            {
+                Presentation::Emoji
+            }
             (presentation, None) => presentation,
         }
     }
@@ -841,6 +852,11 @@ impl Cell {
 pub struct UnicodeVersion {
     pub version: u8,
     pub ambiguous_are_wide: bool,
+    /// When true, characters that default to text presentation but
+    /// that also have an emoji presentation are treated as emoji
+    /// (and thus double width) when no variation selector is present.
+    /// Only applies to unicode version 14 and later.
+    pub prefer_emoji_presentation: bool,
 }
 
 impl UnicodeVersion {
@@ -848,6 +864,7 @@ impl UnicodeVersion {
         Self {
             version,
             ambiguous_are_wide: false,
+            prefer_emoji_presentation: false,
         }
     }
 }
@@ -855,6 +872,15 @@ impl UnicodeVersion {
 pub const LATEST_UNICODE_VERSION: UnicodeVersion = UnicodeVersion {
     version: 14,
     ambiguous_are_wide: false,
+    prefer_emoji_presentation: false,
+};
+
+/// Used to determine the width of a grapheme with text presentation,
+/// taking the widest reasonable interpretation.
+const AMBIGUOUS_WIDE_TEXT_VERSION: UnicodeVersion = UnicodeVersion {
+    version: 14,
+    ambiguous_are_wide: true,
+    prefer_emoji_presentation: false,
 };
 
 /// Returns the number of cells visually occupied by a sequence
@@ -901,6 +927,7 @@ pub fn unicode_column_width(s: &str, version: Option<UnicodeVersion>) -> usize {
 pub fn grapheme_column_width(s: &str, version: Option<UnicodeVersion>) -> usize {
     let version = version.unwrap_or(LATEST_UNICODE_VERSION);
     let ambiguous_are_wide = version.ambiguous_are_wide;
+    let prefer_emoji_presentation = version.prefer_emoji_presentation;
     let version = version.version;
 
     let width: usize = s
@@ -929,6 +956,11 @@ pub fn grapheme_column_width(s: &str, version: Option<UnicodeVersion>) -> usize
             (_, Some(Presentation::Emoji)) => 2,
             (_, Some(Presentation::Text)) => 1,
             (Presentation::Emoji, None) => 2,
+            (Presentation::Text, None)
+                if prefer_emoji_presentation && Presentation::is_ambiguous(s) =>
+            {
+                2
+            }
             (Presentation::Text, None) => width.min(2),
         }
     } else {
@@ -1064,6 +1096,32 @@ mod test {
         assert_eq!(unicode_column_width(england_flag, None), 2);
     }
 
+    #[test]
+    fn prefer_emoji_presentation() {
+        let heart = "\u{2764}";
+        let prefer_emoji = UnicodeVersion {
+            prefer_emoji_presentation: true,
+            ..LATEST_UNICODE_VERSION
+        };
+        assert!(Presentation::is_ambiguous(heart));
+        assert_eq!(grapheme_column_width(heart, None), 1);
+        assert_eq!(grapheme_column_width(heart, Some(prefer_emoji)), 2);
+        // An explicit variation selector is always respected
+        assert_eq!(
+            grapheme_column_width("\u{2764}\u{fe0e}", Some(prefer_emoji)),
+            1
+        );
+
+        let attrs = CellAttributes::default();
+        let cell = Cell::new_grapheme(heart, attrs.clone(), None);
+        assert_eq!(cell.presentation(), Presentation::Text);
+        let cell = Cell::new_grapheme(heart, attrs, Some(prefer_emoji));
+        assert_eq!(cell.presentation(), Presentation::Emoji);
+
+        // Characters that are always emoji are unaffected
+        assert!(!Presentation::is_ambiguous("\u{1f600}"));
+    }
+
     #[test]
     fn issue_1161() {
         let x_ideographic_space_x = "x\u{3000}x";
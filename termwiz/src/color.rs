@@ -161,6 +161,93 @@ impl RgbColor {
     pub fn from_named_or_rgb_string(s: &str) -> Option<Self> {
         RgbColor::from_rgb_str(&s).or_else(|| RgbColor::from_named(&s))
     }
+
+    /// Returns the index of the closest color in the xterm 256 color
+    /// palette, considering only the 6x6x6 color cube and the grey
+    /// ramp, as the first 16 colors vary between terminals.
+    pub fn nearest_palette_index(self) -> PaletteIndex {
+        fn nearest_ramp_idx(v: u8) -> usize {
+            (0..RAMP6.len())
+                .min_by_key(|&idx| (RAMP6[idx] as i32 - v as i32).abs())
+                .unwrap()
+        }
+
+        let rgb = self.to_tuple_rgb8();
+
+        let (r, g, b) = (
+            nearest_ramp_idx(rgb.0),
+            nearest_ramp_idx(rgb.1),
+            nearest_ramp_idx(rgb.2),
+        );
+        let cube_idx = 16 + 36 * r + 6 * g + b;
+        let cube_distance = distance(rgb, (RAMP6[r], RAMP6[g], RAMP6[b]));
+
+        // The grey ramp runs from 0x08 to 0xee in steps of 10
+        let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+        let grey = (average.saturating_sub(3) / 10).min(23) as usize;
+        let level = (8 + grey * 10) as u8;
+        let grey_distance = distance(rgb, (level, level, level));
+
+        if grey_distance < cube_distance {
+            (232 + grey) as PaletteIndex
+        } else {
+            cube_idx as PaletteIndex
+        }
+    }
+
+    /// Returns the index of the closest of the 16 ANSI colors,
+    /// judged against the stock xterm values for those colors.
+    pub fn nearest_ansi_index(self) -> PaletteIndex {
+        let rgb = self.to_tuple_rgb8();
+        (0..XTERM_ANSI.len())
+            .min_by_key(|&idx| distance(rgb, XTERM_ANSI[idx]))
+            .unwrap() as PaletteIndex
+    }
+
+    /// Returns the color that xterm uses for the specified
+    /// palette index by default.
+    pub fn from_xterm_palette_index(idx: PaletteIndex) -> Self {
+        let (r, g, b) = match idx as usize {
+            idx @ 0..=15 => XTERM_ANSI[idx],
+            idx @ 16..=231 => {
+                let idx = idx - 16;
+                (RAMP6[idx / 36], RAMP6[idx / 6 % 6], RAMP6[idx % 6])
+            }
+            idx => {
+                let level = (8 + (idx - 232) * 10) as u8;
+                (level, level, level)
+            }
+        };
+        Self::new_8bpc(r, g, b)
+    }
+}
+
+/// The levels used by each channel of the xterm 6x6x6 color cube
+const RAMP6: [u8; 6] = [0, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// The stock xterm values for the 16 ANSI colors
+const XTERM_ANSI: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
 }
 
 impl From<SrgbaTuple> for RgbColor {
@@ -299,6 +386,23 @@ mod tests {
         assert_eq!(foo.to_rgb_string(), "#0015ff");
     }
 
+    #[test]
+    fn nearest_palette_index() {
+        assert_eq!(RgbColor::new_8bpc(0, 0, 0).nearest_palette_index(), 16);
+        assert_eq!(
+            RgbColor::new_8bpc(255, 255, 255).nearest_palette_index(),
+            231
+        );
+        assert_eq!(
+            RgbColor::new_8bpc(0x80, 0x80, 0x80).nearest_palette_index(),
+            244
+        );
+        assert_eq!(
+            RgbColor::new_8bpc(255, 128, 64).nearest_palette_index(),
+            209
+        );
+    }
+
     #[test]
     fn from_rgb() {
         assert!(RgbColor::from_rgb_str("").is_none());
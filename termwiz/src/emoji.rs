@@ -26,6 +26,20 @@ impl Presentation {
         (presentation, VARIATION_MAP.get(s).copied())
     }
 
+    /// Returns true if `s` is a single character whose default
+    /// presentation is text, but which also has an emoji
+    /// presentation that can be selected by a variation selector.
+    pub fn is_ambiguous(s: &str) -> bool {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                Self::for_char(c) == Self::Text
+                    && VARIATION_MAP.contains_key(format!("{}\u{FE0F}", c).as_str())
+            }
+            _ => false,
+        }
+    }
+
     pub fn for_char(c: char) -> Self {
         if crate::emoji_presentation::EMOJI_PRESENTATION.contains_u32(c as u32) {
             Self::Emoji
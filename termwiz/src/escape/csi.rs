@@ -894,6 +894,20 @@ pub enum Cursor {
     /// The terminal will respond with ActivePositionReport.
     RequestActivePositionReport,
 
+    /// DECXCPR - Extended Cursor Position Report.
+    /// This is the response to RequestExtendedActivePositionReport,
+    /// and additionally reports the page number, which is always 1
+    /// for terminals that don't implement multiple pages.
+    ExtendedActivePositionReport {
+        line: OneBased,
+        col: OneBased,
+        page: OneBased,
+    },
+
+    /// DECXCPR: this is the request from the client.
+    /// The terminal will respond with ExtendedActivePositionReport.
+    RequestExtendedActivePositionReport,
+
     /// SCP - Save Cursor Position.
     /// Only works when DECLRMM is disabled
     SaveCursor,
@@ -906,6 +920,11 @@ pub enum Cursor {
     /// depends on the setting of the TABULATION STOP MODE (TSM).
     TabulationControl(CursorTabulationControl),
 
+    /// DECST8C - Set Tab at Every 8 Columns
+    /// Replaces all of the current tab stops with stops at every
+    /// eighth column, starting with column 9.
+    SetTabStopsEvery8Columns,
+
     /// CUB - Cursor Left
     /// Moves cursor to the left Ps columns. The default value of Ps is 1.
     Left(u32),
@@ -1053,6 +1072,69 @@ pub enum Edit {
 
     /// REP - Repeat the preceding character n times
     Repeat(u32),
+
+    /// DECCRA - Copy Rectangular Area
+    /// <https://vt100.net/docs/vt510-rm/DECCRA.html>
+    CopyRectangularArea {
+        src: RectangularArea,
+        src_page: u32,
+        dest_top: OneBased,
+        dest_left: OneBased,
+        dest_page: u32,
+    },
+
+    /// DECFRA - Fill Rectangular Area with the character `ch`,
+    /// using the current graphic rendition.
+    /// <https://vt100.net/docs/vt510-rm/DECFRA.html>
+    FillRectangularArea { ch: u32, area: RectangularArea },
+
+    /// DECERA - Erase Rectangular Area
+    /// <https://vt100.net/docs/vt510-rm/DECERA.html>
+    EraseRectangularArea(RectangularArea),
+
+    /// DECSERA - Selective Erase Rectangular Area.
+    /// Erases only the characters that are not protected by DECSCA.
+    /// <https://vt100.net/docs/vt510-rm/DECSERA.html>
+    SelectiveEraseRectangularArea(RectangularArea),
+
+    /// DECSACE - Select Attribute Change Extent
+    /// <https://vt100.net/docs/vt510-rm/DECSACE.html>
+    SelectAttributeChangeExtent(AttributeChangeExtent),
+}
+
+/// The area affected by the DEC rectangular area operations.
+/// The coordinates are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RectangularArea {
+    pub top: OneBased,
+    pub left: OneBased,
+    pub bottom: OneBased,
+    pub right: OneBased,
+}
+
+impl Display for RectangularArea {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(
+            f,
+            "{};{};{};{}",
+            self.top, self.left, self.bottom, self.right
+        )
+    }
+}
+
+/// Controls whether DECCARA and DECRARA apply to the stream of
+/// characters between the start and end positions, or to the
+/// rectangle that they describe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum AttributeChangeExtent {
+    Stream = 1,
+    Rectangle = 2,
+}
+
+impl ParamEnum for AttributeChangeExtent {
+    fn default() -> Self {
+        AttributeChangeExtent::Stream
+    }
 }
 
 trait EncodeCSIParam {
@@ -1103,6 +1185,21 @@ impl Display for Edit {
             Edit::ScrollUp(n) => n.write_csi(f, "S")?,
             Edit::EraseInDisplay(n) => n.write_csi(f, "J")?,
             Edit::Repeat(n) => n.write_csi(f, "b")?,
+            Edit::CopyRectangularArea {
+                src,
+                src_page,
+                dest_top,
+                dest_left,
+                dest_page,
+            } => write!(
+                f,
+                "{};{};{};{};{}$v",
+                src, src_page, dest_top, dest_left, dest_page
+            )?,
+            Edit::FillRectangularArea { ch, area } => write!(f, "{};{}$x", ch, area)?,
+            Edit::EraseRectangularArea(area) => write!(f, "{}$z", area)?,
+            Edit::SelectiveEraseRectangularArea(area) => write!(f, "{}${{", area)?,
+            Edit::SelectAttributeChangeExtent(n) => n.write_csi(f, "*x")?,
         }
         Ok(())
     }
@@ -1124,6 +1221,7 @@ impl Display for Cursor {
             Cursor::Position { line, col } => write!(f, "{};{}H", line, col)?,
             Cursor::LineTabulation(n) => n.write_csi(f, "Y")?,
             Cursor::TabulationControl(n) => n.write_csi(f, "W")?,
+            Cursor::SetTabStopsEvery8Columns => write!(f, "?5W")?,
             Cursor::TabulationClear(n) => n.write_csi(f, "g")?,
             Cursor::CharacterPositionAbsolute(n) => n.write_csi(f, "`")?,
             Cursor::CharacterPositionBackward(n) => n.write_csi(f, "j")?,
@@ -1147,6 +1245,10 @@ impl Display for Cursor {
                 }
             }
             Cursor::RequestActivePositionReport => write!(f, "6n")?,
+            Cursor::ExtendedActivePositionReport { line, col, page } => {
+                write!(f, "?{};{};{}R", line, col, page)?
+            }
+            Cursor::RequestExtendedActivePositionReport => write!(f, "?6n")?,
             Cursor::SaveCursor => write!(f, "s")?,
             Cursor::RestoreCursor => write!(f, "u")?,
             Cursor::CursorStyle(style) => write!(f, "{} q", *style as u8)?,
@@ -1606,6 +1708,17 @@ impl<'a> CSIParser<'a> {
             ('k', [.., CsiParam::P(b' ')]) => self.select_character_path(params),
             ('q', [.., CsiParam::P(b' ')]) => self.cursor_style(params),
             ('y', [.., CsiParam::P(b'*')]) => self.checksum_area(params),
+            ('x', [.., CsiParam::P(b'*')]) => self.attribute_change_extent(params),
+            ('v', [.., CsiParam::P(b'$')]) => self.copy_rectangular_area(params),
+            ('x', [.., CsiParam::P(b'$')]) => self.fill_rectangular_area(params),
+            ('z', [.., CsiParam::P(b'$')]) => {
+                let area = self.rectangular_area(params, 0)?;
+                Ok(CSI::Edit(Edit::EraseRectangularArea(area)))
+            }
+            ('{', [.., CsiParam::P(b'$')]) => {
+                let area = self.rectangular_area(params, 0)?;
+                Ok(CSI::Edit(Edit::SelectiveEraseRectangularArea(area)))
+            }
 
             ('c', [CsiParam::P(b'='), ..]) => self
                 .req_tertiary_device_attributes(params)
@@ -1623,6 +1736,15 @@ impl<'a> CSIParser<'a> {
                 .map(|dev| CSI::Device(Box::new(dev))),
 
             ('S', [CsiParam::P(b'?'), ..]) => XtSmGraphics::parse(params),
+            ('n', [CsiParam::P(b'?'), CsiParam::Integer(6)]) => Ok(self.advance_by(
+                2,
+                params,
+                CSI::Cursor(Cursor::RequestExtendedActivePositionReport),
+            )),
+            ('R', [CsiParam::P(b'?'), ..]) => self.extended_active_position_report(params),
+            ('W', [CsiParam::P(b'?'), CsiParam::Integer(5)]) => {
+                Ok(self.advance_by(2, params, CSI::Cursor(Cursor::SetTabStopsEvery8Columns)))
+            }
             ('p', [CsiParam::Integer(_), CsiParam::P(b'$')])
             | ('p', [CsiParam::P(b'?'), CsiParam::Integer(_), CsiParam::P(b'$')]) => {
                 self.decrqm(params)
@@ -1789,6 +1911,68 @@ impl<'a> CSIParser<'a> {
         }))
     }
 
+    /// Parses the `Pt;Pl;Pb;Pr` parameters used by the rectangular area
+    /// operations, starting at parameter index `idx`.
+    /// Omitted bottom and right values default to the bottom right of the page.
+    fn rectangular_area(&self, params: &'a [CsiParam], idx: usize) -> Result<RectangularArea, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let or_max = |p: Option<&CsiParam>| match p {
+            Some(p) => OneBased::from_esc_param_with_big_default(p),
+            None => Ok(OneBased::new(u32::max_value())),
+        };
+        Ok(RectangularArea {
+            top: OneBased::from_optional_esc_param(params.get(idx))?,
+            left: OneBased::from_optional_esc_param(params.get(idx + 1))?,
+            bottom: or_max(params.get(idx + 2))?,
+            right: or_max(params.get(idx + 3))?,
+        })
+    }
+
+    fn copy_rectangular_area(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let src = self.rectangular_area(params, 0)?;
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let page = |idx: usize| params.opt_int(idx).unwrap_or(1).max(1) as u32;
+        Ok(CSI::Edit(Edit::CopyRectangularArea {
+            src,
+            src_page: page(4),
+            dest_top: OneBased::from_optional_esc_param(params.get(5))?,
+            dest_left: OneBased::from_optional_esc_param(params.get(6))?,
+            dest_page: page(7),
+        }))
+    }
+
+    fn fill_rectangular_area(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let area = self.rectangular_area(params, 1)?;
+        let ch = Cracked::parse(&params[..params.len() - 1])?.int(0)?;
+        Ok(CSI::Edit(Edit::FillRectangularArea {
+            ch: ch.to_u32().ok_or(())?,
+            area,
+        }))
+    }
+
+    fn attribute_change_extent(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let params = Cracked::parse(&params[..params.len() - 1])?;
+        let extent = match params.opt_int(0).unwrap_or(0) {
+            0 | 1 => AttributeChangeExtent::Stream,
+            2 => AttributeChangeExtent::Rectangle,
+            _ => return Err(()),
+        };
+        Ok(CSI::Edit(Edit::SelectAttributeChangeExtent(extent)))
+    }
+
+    fn extended_active_position_report(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
+        let (line, col, page) = match params {
+            [_, line, CsiParam::P(b';'), col] => (line, col, None),
+            [_, line, CsiParam::P(b';'), col, CsiParam::P(b';'), page] => (line, col, Some(page)),
+            _ => return Err(()),
+        };
+        Ok(CSI::Cursor(Cursor::ExtendedActivePositionReport {
+            line: OneBased::from_esc_param(line)?,
+            col: OneBased::from_esc_param(col)?,
+            page: OneBased::from_optional_esc_param(page)?,
+        }))
+    }
+
     fn dsr(&mut self, params: &'a [CsiParam]) -> Result<CSI, ()> {
         match params {
             [CsiParam::Integer(5)] => {
@@ -2749,6 +2933,52 @@ mod test {
         );
     }
 
+    #[test]
+    fn cursor_position_report() {
+        assert_eq!(
+            parse('n', &[6], "\x1b[6n"),
+            vec![CSI::Cursor(Cursor::RequestActivePositionReport)]
+        );
+        assert_eq!(
+            parse('R', &[3, 4], "\x1b[3;4R"),
+            vec![CSI::Cursor(Cursor::ActivePositionReport {
+                line: OneBased::new(3),
+                col: OneBased::new(4),
+            })]
+        );
+
+        let res: Vec<_> =
+            CSI::parse(&[CsiParam::P(b'?'), CsiParam::Integer(6)], false, 'n').collect();
+        assert_eq!(
+            res,
+            vec![CSI::Cursor(Cursor::RequestExtendedActivePositionReport)]
+        );
+        assert_eq!(encode(&res), "\x1b[?6n");
+
+        let res: Vec<_> = CSI::parse(
+            &[
+                CsiParam::P(b'?'),
+                CsiParam::Integer(3),
+                CsiParam::P(b';'),
+                CsiParam::Integer(4),
+                CsiParam::P(b';'),
+                CsiParam::Integer(1),
+            ],
+            false,
+            'R',
+        )
+        .collect();
+        assert_eq!(
+            res,
+            vec![CSI::Cursor(Cursor::ExtendedActivePositionReport {
+                line: OneBased::new(3),
+                col: OneBased::new(4),
+                page: OneBased::new(1),
+            })]
+        );
+        assert_eq!(encode(&res), "\x1b[?3;4;1R");
+    }
+
     #[test]
     fn ansiset() {
         assert_eq!(
@@ -2808,6 +3038,90 @@ mod test {
         );
     }
 
+    #[test]
+    fn rectangular_area() {
+        let area = RectangularArea {
+            top: OneBased::new(2),
+            left: OneBased::new(3),
+            bottom: OneBased::new(4),
+            right: OneBased::new(5),
+        };
+        let params = |nums: &[i64]| {
+            let mut res = vec![];
+            for &n in nums {
+                if !res.is_empty() {
+                    res.push(CsiParam::P(b';'));
+                }
+                res.push(CsiParam::Integer(n));
+            }
+            res.push(CsiParam::P(b'$'));
+            res
+        };
+
+        let res: Vec<_> = CSI::parse(&params(&[2, 3, 4, 5]), false, 'z').collect();
+        assert_eq!(encode(&res), "\x1b[2;3;4;5$z");
+        assert_eq!(res, vec![CSI::Edit(Edit::EraseRectangularArea(area))]);
+
+        let res: Vec<_> = CSI::parse(&params(&[120, 2, 3, 4, 5]), false, 'x').collect();
+        assert_eq!(encode(&res), "\x1b[120;2;3;4;5$x");
+        assert_eq!(
+            res,
+            vec![CSI::Edit(Edit::FillRectangularArea { ch: 120, area })]
+        );
+
+        let res: Vec<_> = CSI::parse(&params(&[2, 3, 4, 5, 1, 7, 8, 1]), false, 'v').collect();
+        assert_eq!(encode(&res), "\x1b[2;3;4;5;1;7;8;1$v");
+        assert_eq!(
+            res,
+            vec![CSI::Edit(Edit::CopyRectangularArea {
+                src: area,
+                src_page: 1,
+                dest_top: OneBased::new(7),
+                dest_left: OneBased::new(8),
+                dest_page: 1,
+            })]
+        );
+
+        // Omitted bottom/right extend to the edge of the page
+        let res: Vec<_> = CSI::parse(&[CsiParam::P(b'$')], false, '{').collect();
+        assert_eq!(
+            res,
+            vec![CSI::Edit(Edit::SelectiveEraseRectangularArea(
+                RectangularArea {
+                    top: OneBased::new(1),
+                    left: OneBased::new(1),
+                    bottom: OneBased::new(u32::max_value()),
+                    right: OneBased::new(u32::max_value()),
+                }
+            ))]
+        );
+
+        let res: Vec<_> =
+            CSI::parse(&[CsiParam::Integer(2), CsiParam::P(b'*')], false, 'x').collect();
+        assert_eq!(encode(&res), "\x1b[2*x");
+        assert_eq!(
+            res,
+            vec![CSI::Edit(Edit::SelectAttributeChangeExtent(
+                AttributeChangeExtent::Rectangle
+            ))]
+        );
+    }
+
+    #[test]
+    fn tab_stops() {
+        let res: Vec<_> =
+            CSI::parse(&[CsiParam::P(b'?'), CsiParam::Integer(5)], false, 'W').collect();
+        assert_eq!(encode(&res), "\x1b[?5W");
+        assert_eq!(res, vec![CSI::Cursor(Cursor::SetTabStopsEvery8Columns)]);
+
+        assert_eq!(
+            parse('W', &[2], "\x1b[2W"),
+            vec![CSI::Cursor(Cursor::TabulationControl(
+                CursorTabulationControl::ClearCharacterTabStopAtActivePosition
+            ))]
+        );
+    }
+
     #[test]
     fn soft_reset() {
         let res: Vec<_> = CSI::parse(&[CsiParam::P(b'!')], false, 'p').collect();
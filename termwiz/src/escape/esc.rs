@@ -84,6 +84,25 @@ pub enum EscCode {
     /// Designate G1 Character Set – US ASCII
     AsciiCharacterSetG1 = esc!(')', 'B'),
 
+    /// Designate G2 Character Set – DEC Line Drawing
+    DecLineDrawingG2 = esc!('*', '0'),
+    /// Designate G2 Character Set - UK
+    UkCharacterSetG2 = esc!('*', 'A'),
+    /// Designate G2 Character Set – US ASCII
+    AsciiCharacterSetG2 = esc!('*', 'B'),
+
+    /// Designate G3 Character Set – DEC Line Drawing
+    DecLineDrawingG3 = esc!('+', '0'),
+    /// Designate G3 Character Set - UK
+    UkCharacterSetG3 = esc!('+', 'A'),
+    /// Designate G3 Character Set – US ASCII
+    AsciiCharacterSetG3 = esc!('+', 'B'),
+
+    /// LS2 - Invoke the G2 Character Set into GL
+    LockingShiftG2 = esc!('n'),
+    /// LS3 - Invoke the G3 Character Set into GL
+    LockingShiftG3 = esc!('o'),
+
     /// https://vt100.net/docs/vt510-rm/DECALN.html
     DecScreenAlignmentDisplay = esc!('#', '8'),
 
@@ -194,6 +213,10 @@ mod test {
         assert_eq!(parse("(B"), Esc::Code(EscCode::AsciiCharacterSetG0));
         assert_eq!(parse(")0"), Esc::Code(EscCode::DecLineDrawingG1));
         assert_eq!(parse(")B"), Esc::Code(EscCode::AsciiCharacterSetG1));
+        assert_eq!(parse("*0"), Esc::Code(EscCode::DecLineDrawingG2));
+        assert_eq!(parse("+B"), Esc::Code(EscCode::AsciiCharacterSetG3));
+        assert_eq!(parse("n"), Esc::Code(EscCode::LockingShiftG2));
+        assert_eq!(parse("O"), Esc::Code(EscCode::SingleShiftG3));
         assert_eq!(parse("#3"), Esc::Code(EscCode::DecDoubleHeightTopHalfLine));
         assert_eq!(
             parse("#4"),
@@ -796,6 +796,10 @@ pub enum ITermProprietary {
     SetMark,
     /// To bring iTerm2 to the foreground
     StealFocus,
+    /// Request the user's attention, for example by bouncing the dock icon.
+    /// The parameter is true for "yes", "once" and "fireworks", and false
+    /// for "no", which cancels a prior request.
+    RequestAttention(bool),
     /// To erase the scrollback history
     ClearScrollback,
     /// To inform iTerm2 of the current directory to help semantic history
@@ -1121,6 +1125,10 @@ impl ITermProprietary {
         single!(RequestCellSize, "ReportCellSize");
         const_arg!(HighlightCursorLine, "HighlightCursorLine", "yes", true);
         const_arg!(HighlightCursorLine, "HighlightCursorLine", "no", false);
+        const_arg!(RequestAttention, "RequestAttention", "yes", true);
+        const_arg!(RequestAttention, "RequestAttention", "once", true);
+        const_arg!(RequestAttention, "RequestAttention", "fireworks", true);
+        const_arg!(RequestAttention, "RequestAttention", "no", false);
         one_str!(CurrentDir, "CurrentDir");
         one_str!(SetProfile, "SetProfile");
         one_str!(CopyToClipboard, "CopyToClipboard");
@@ -1206,6 +1214,9 @@ impl Display for ITermProprietary {
         match self {
             SetMark => write!(f, "SetMark")?,
             StealFocus => write!(f, "StealFocus")?,
+            RequestAttention(yes) => {
+                write!(f, "RequestAttention={}", if *yes { "yes" } else { "no" })?
+            }
             ClearScrollback => write!(f, "ClearScrollback")?,
             CurrentDir(s) => write!(f, "CurrentDir={}", s)?,
             SetProfile(s) => write!(f, "SetProfile={}", s)?,
@@ -1583,6 +1594,14 @@ mod test {
             OperatingSystemCommand::ITermProprietary(ITermProprietary::HighlightCursorLine(true))
         );
 
+        assert_eq!(
+            parse(
+                &["1337", "RequestAttention=yes"],
+                "\x1b]1337;RequestAttention=yes\x1b\\"
+            ),
+            OperatingSystemCommand::ITermProprietary(ITermProprietary::RequestAttention(true))
+        );
+
         assert_eq!(
             parse(
                 &["1337", "Copy=", "aGVsbG8="],
@@ -0,0 +1,271 @@
+//! Export of `Line`s as styled HTML
+use crate::cell::{CellAttributes, Intensity, Underline};
+use crate::color::{ColorAttribute, SrgbaTuple};
+use crate::surface::Line;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// Maps the colors held in cell attributes to concrete colors.
+/// Palette indices and default colors depend on the color scheme
+/// of the terminal that is being exported, so the embedding
+/// application provides that mapping.
+pub trait ColorResolver {
+    fn resolve_fg(&self, color: ColorAttribute) -> SrgbaTuple;
+    fn resolve_bg(&self, color: ColorAttribute) -> SrgbaTuple;
+}
+
+/// Builds up an HTML fragment from a sequence of lines.
+/// The colors, intensity, italic, underline and strikethrough
+/// attributes are expressed as inline styles, and hyperlinks
+/// are preserved as anchors.
+/// Invisible (concealed) text is rendered as spaces unless
+/// `include_concealed` is used to opt in to including it.
+pub struct HtmlBuilder<'a> {
+    resolver: &'a dyn ColorResolver,
+    body: String,
+    include_concealed: bool,
+}
+
+#[derive(PartialEq)]
+struct Run<'a> {
+    style: String,
+    link: Option<&'a str>,
+}
+
+impl<'a> HtmlBuilder<'a> {
+    pub fn new(resolver: &'a dyn ColorResolver) -> Self {
+        Self {
+            resolver,
+            body: String::new(),
+            include_concealed: false,
+        }
+    }
+
+    /// Specify whether the text of invisible cells should be included
+    pub fn include_concealed(mut self, include: bool) -> Self {
+        self.include_concealed = include;
+        self
+    }
+
+    /// Returns true if nothing has been added to the builder yet
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Start a new line in the output
+    pub fn push_newline(&mut self) {
+        self.body.push('\n');
+    }
+
+    /// Append the cells in the `cols` range of `line`.
+    /// If `trim_end` is true, trailing whitespace is omitted.
+    pub fn push_line(&mut self, line: &Line, cols: Range<usize>, trim_end: bool) {
+        let mut cells: Vec<_> = line
+            .visible_cells()
+            .filter(|(idx, _cell)| cols.contains(idx))
+            .map(|(_idx, cell)| cell)
+            .collect();
+        if trim_end {
+            while let Some(cell) = cells.last() {
+                if cell.str().trim().is_empty() {
+                    cells.pop();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        let mut current: Option<Run> = None;
+        for cell in cells {
+            let attrs = cell.attrs();
+            let run = Run {
+                style: self.style_for(attrs),
+                link: attrs.hyperlink().map(|link| link.uri()),
+            };
+            if current.as_ref() != Some(&run) {
+                if let Some(prior) = current.take() {
+                    self.close_run(&prior);
+                }
+                self.open_run(&run);
+                current = Some(run);
+            }
+            if attrs.invisible() && !self.include_concealed {
+                for _ in 0..cell.width() {
+                    self.body.push(' ');
+                }
+            } else {
+                push_escaped(&mut self.body, cell.str());
+            }
+        }
+        if let Some(prior) = current.take() {
+            self.close_run(&prior);
+        }
+    }
+
+    /// Returns the accumulated lines wrapped in a `pre` element
+    /// that carries the default colors.
+    pub fn finish(self) -> String {
+        format!(
+            "<pre style=\"color:{};background-color:{};\">{}</pre>",
+            self.resolver
+                .resolve_fg(ColorAttribute::Default)
+                .to_rgb_string(),
+            self.resolver
+                .resolve_bg(ColorAttribute::Default)
+                .to_rgb_string(),
+            self.body
+        )
+    }
+
+    fn open_run(&mut self, run: &Run) {
+        if let Some(link) = run.link {
+            self.body.push_str("<a href=\"");
+            push_escaped(&mut self.body, link);
+            self.body.push_str("\">");
+        }
+        if !run.style.is_empty() {
+            write!(self.body, "<span style=\"{}\">", run.style).ok();
+        }
+    }
+
+    fn close_run(&mut self, run: &Run) {
+        if !run.style.is_empty() {
+            self.body.push_str("</span>");
+        }
+        if run.link.is_some() {
+            self.body.push_str("</a>");
+        }
+    }
+
+    fn style_for(&self, attrs: &CellAttributes) -> String {
+        let mut style = String::new();
+        let reverse = attrs.reverse();
+
+        if attrs.foreground() != ColorAttribute::Default || reverse {
+            let fg = if reverse {
+                self.resolver.resolve_bg(attrs.background())
+            } else {
+                self.resolver.resolve_fg(attrs.foreground())
+            };
+            write!(style, "color:{};", fg.to_rgb_string()).ok();
+        }
+        if attrs.background() != ColorAttribute::Default || reverse {
+            let bg = if reverse {
+                self.resolver.resolve_fg(attrs.foreground())
+            } else {
+                self.resolver.resolve_bg(attrs.background())
+            };
+            write!(style, "background-color:{};", bg.to_rgb_string()).ok();
+        }
+
+        match attrs.intensity() {
+            Intensity::Bold => style.push_str("font-weight:bold;"),
+            Intensity::Half => style.push_str("font-weight:lighter;"),
+            Intensity::Normal => {}
+        }
+        if attrs.italic() {
+            style.push_str("font-style:italic;");
+        }
+
+        let underline = attrs.underline() != Underline::None;
+        match (underline, attrs.strikethrough()) {
+            (true, true) => style.push_str("text-decoration:underline line-through;"),
+            (true, false) => style.push_str("text-decoration:underline;"),
+            (false, true) => style.push_str("text-decoration:line-through;"),
+            (false, false) => {}
+        }
+
+        style
+    }
+}
+
+fn push_escaped(target: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => target.push_str("&amp;"),
+            '<' => target.push_str("&lt;"),
+            '>' => target.push_str("&gt;"),
+            '"' => target.push_str("&quot;"),
+            '\'' => target.push_str("&#39;"),
+            c => target.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::color::AnsiColor;
+    use crate::hyperlink::Hyperlink;
+    use std::sync::Arc;
+
+    struct Palette;
+
+    impl ColorResolver for Palette {
+        fn resolve_fg(&self, color: ColorAttribute) -> SrgbaTuple {
+            match color {
+                ColorAttribute::Default => SrgbaTuple(1., 1., 1., 1.),
+                _ => SrgbaTuple(1., 0., 0., 1.),
+            }
+        }
+        fn resolve_bg(&self, color: ColorAttribute) -> SrgbaTuple {
+            match color {
+                ColorAttribute::Default => SrgbaTuple(0., 0., 0., 1.),
+                _ => SrgbaTuple(0., 0., 1., 1.),
+            }
+        }
+    }
+
+    #[test]
+    fn styled_line() {
+        let mut line = Line::with_width(8, 0);
+        let red_bold = CellAttributes::default()
+            .set_foreground(AnsiColor::Maroon)
+            .set_intensity(Intensity::Bold)
+            .clone();
+        line.set_cell(0, Cell::new('<', red_bold.clone()), 0);
+        line.set_cell(1, Cell::new('b', red_bold), 0);
+        line.set_cell(2, Cell::new('c', CellAttributes::default()), 0);
+
+        let link = Arc::new(Hyperlink::new("https://example.com/?a&b"));
+        let linked = CellAttributes::default()
+            .set_hyperlink(Some(link))
+            .set_italic(true)
+            .clone();
+        line.set_cell(3, Cell::new('d', linked), 0);
+
+        let mut builder = HtmlBuilder::new(&Palette);
+        builder.push_line(&line, 0..8, true);
+        assert_eq!(
+            builder.finish(),
+            "<pre style=\"color:#ffffff;background-color:#000000;\">\
+             <span style=\"color:#ff0000;font-weight:bold;\">&lt;b</span>\
+             c\
+             <a href=\"https://example.com/?a&amp;b\">\
+             <span style=\"font-style:italic;\">d</span></a>\
+             </pre>"
+        );
+    }
+
+    #[test]
+    fn reverse_and_concealed() {
+        let mut line = Line::with_width(4, 0);
+        let reverse = CellAttributes::default().set_reverse(true).clone();
+        line.set_cell(0, Cell::new('r', reverse), 0);
+        let concealed = CellAttributes::default().set_invisible(true).clone();
+        line.set_cell(1, Cell::new('x', concealed), 0);
+        line.set_cell(2, Cell::new('z', CellAttributes::default()), 0);
+
+        let mut builder = HtmlBuilder::new(&Palette);
+        builder.push_line(&line, 0..2, false);
+        builder.push_newline();
+        builder.push_line(&line, 2..4, false);
+        assert_eq!(
+            builder.finish(),
+            "<pre style=\"color:#ffffff;background-color:#000000;\">\
+             <span style=\"color:#000000;background-color:#ffffff;\">r</span> \n\
+             z </pre>"
+        );
+    }
+}
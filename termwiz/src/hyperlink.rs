@@ -33,6 +33,25 @@ impl Hyperlink {
         &self.params
     }
 
+    /// Returns the `id` parameter of the link, if any
+    pub fn id(&self) -> Option<&str> {
+        self.params.get("id").map(String::as_str)
+    }
+
+    /// Returns true if `other` refers to the same link as `self`.
+    /// Links that carry an `id` are considered to be the same
+    /// link when both their `id` and uri match, even if their other
+    /// parameters differ; this is how an application tells us that
+    /// cells scattered across the screen (for example, a link that
+    /// was broken across lines by the application itself) are all
+    /// part of a single link.
+    pub fn is_same_link(&self, other: &Self) -> bool {
+        match (self.id(), other.id()) {
+            (Some(a), Some(b)) => a == b && self.uri == other.uri,
+            _ => self == other,
+        }
+    }
+
     pub fn new<S: Into<String>>(uri: S) -> Self {
         Self {
             uri: uri.into(),
@@ -318,4 +337,25 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn same_link() {
+        let a = Hyperlink::new_with_id("http://example.com", "1");
+        let mut params = HashMap::new();
+        params.insert("id".to_string(), "1".to_string());
+        params.insert("foo".to_string(), "bar".to_string());
+        let b = Hyperlink::new_with_params("http://example.com", params);
+        assert!(a.is_same_link(&b));
+        assert_ne!(a, b);
+
+        let c = Hyperlink::new_with_id("http://example.com", "2");
+        assert!(!a.is_same_link(&c));
+
+        let d = Hyperlink::new_with_id("http://example.org", "1");
+        assert!(!a.is_same_link(&d));
+
+        let e = Hyperlink::new("http://example.com");
+        assert!(!a.is_same_link(&e));
+        assert!(e.is_same_link(&Hyperlink::new("http://example.com")));
+    }
 }
@@ -11,9 +11,12 @@
 //! protocol appears to track the images out of band as attachments with
 //! z-order.
 
+use crate::color::RgbColor;
+use crate::escape::{Sixel, SixelData, SixelValue};
 use ordered_float::NotNan;
 #[cfg(feature = "use_serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 
@@ -167,6 +170,13 @@ impl ImageCell {
         self.z_index
     }
 
+    /// Returns true if this image should be drawn beneath the
+    /// background color of the cells that it occupies, rather
+    /// than between the background and the text.
+    pub fn is_below_background(&self) -> bool {
+        self.z_index < i32::MIN / 2
+    }
+
     /// Returns padding (left, top, right, bottom)
     pub fn padding(&self) -> (u16, u16, u16, u16) {
         (
@@ -305,6 +315,8 @@ impl ImageDataType {
     /// Decode an encoded file into either an Rgba8 or AnimRgba8 variant
     /// if we recognize the file format, otherwise the EncodedFile data
     /// is preserved as is.
+    /// Animated GIF, APNG and WebP files produce an AnimRgba8 holding
+    /// each frame along with its delay.
     #[cfg(feature = "use_image")]
     pub fn decode(self) -> Self {
         use image::{AnimationDecoder, ImageFormat};
@@ -343,6 +355,20 @@ impl ImageDataType {
                             Self::decode_single(data)
                         }
                     }
+                    ImageFormat::WebP => {
+                        let decoder = match image::codecs::webp::WebPDecoder::new(&*data) {
+                            Ok(d) => d,
+                            _ => return Self::EncodedFile(data),
+                        };
+                        if decoder.has_animation() {
+                            match decoder.into_frames().collect_frames() {
+                                Ok(frames) => Self::decode_frames(frames),
+                                _ => Self::EncodedFile(data),
+                            }
+                        } else {
+                            Self::decode_single(data)
+                        }
+                    }
                     _ => Self::decode_single(data),
                 }
             }
@@ -466,3 +492,365 @@ impl ImageData {
         self.hash
     }
 }
+
+/// Controls how `encode_sixel` maps an image to the sixel palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SixelEncoderOptions {
+    /// The maximum number of palette entries to use.
+    /// Most sixel capable terminals support at least 256.
+    pub max_colors: usize,
+    /// When true, Floyd-Steinberg error diffusion is used to
+    /// reduce banding when the image has more colors than
+    /// can fit in the palette.
+    pub dither: bool,
+}
+
+impl Default for SixelEncoderOptions {
+    fn default() -> Self {
+        Self {
+            max_colors: 256,
+            dither: true,
+        }
+    }
+}
+
+/// Encode `width` x `height` pixels of RGBA8 data as a `Sixel`.
+/// The colors are quantized to at most `options.max_colors` palette
+/// entries using median cut.  Pixels that are more than half transparent
+/// are left unpainted, as are any pixels missing from the end of
+/// a `rgba` buffer that is shorter than `width * height * 4` bytes.
+/// Formatting the returned `Sixel` yields the DCS sequence; it must be
+/// followed by the string terminator `ESC \` when sent to a terminal.
+pub fn encode_sixel(width: u32, height: u32, rgba: &[u8], options: &SixelEncoderOptions) -> Sixel {
+    let width = width as usize;
+    let height = height as usize;
+    let pixels: Vec<[u8; 4]> = rgba
+        .chunks_exact(4)
+        .map(|p| [p[0], p[1], p[2], p[3]])
+        .chain(std::iter::repeat([0, 0, 0, 0]))
+        .take(width * height)
+        .collect();
+
+    let opaque: Vec<[u8; 3]> = pixels
+        .iter()
+        .filter(|p| p[3] >= 128)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let palette = median_cut(&opaque, options.max_colors.max(1).min(256));
+    let indices = map_to_palette(&pixels, width, &palette, options.dither);
+    let background_is_transparent = opaque.len() < width * height;
+
+    let mut data: Vec<SixelData> = palette
+        .iter()
+        .enumerate()
+        .map(|(idx, color)| SixelData::DefineColorMapRGB {
+            color_number: idx as u16,
+            rgb: RgbColor::new_8bpc(color[0], color[1], color[2]),
+        })
+        .collect();
+
+    let bands = (height + 5) / 6;
+    for band in 0..bands {
+        let y0 = band * 6;
+        let rows = y0..(y0 + 6).min(height);
+
+        let mut colors: Vec<u16> = indices[rows.start * width..rows.end * width]
+            .iter()
+            .filter_map(|&idx| idx)
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (n, &color) in colors.iter().enumerate() {
+            if n > 0 {
+                data.push(SixelData::CarriageReturn);
+            }
+            data.push(SixelData::SelectColorMapEntry(color));
+
+            let values: Vec<SixelValue> = (0..width)
+                .map(|x| {
+                    rows.clone()
+                        .filter(|&y| indices[y * width + x] == Some(color))
+                        .fold(0, |value, y| value | 1 << (y - y0))
+                })
+                .collect();
+            // There's no need to emit the empty sixels at the end of the line
+            let len = values
+                .iter()
+                .rposition(|&v| v != 0)
+                .map(|p| p + 1)
+                .unwrap_or(0);
+            push_sixel_runs(&mut data, &values[..len]);
+        }
+
+        if band + 1 < bands {
+            data.push(SixelData::NewLine);
+        }
+    }
+
+    Sixel {
+        pan: 1,
+        pad: 1,
+        pixel_width: Some(width as u32),
+        pixel_height: Some(height as u32),
+        background_is_transparent,
+        horizontal_grid_size: None,
+        data,
+    }
+}
+
+/// Append `values` to `data`, using run-length encoding where
+/// that is shorter than repeating the value
+fn push_sixel_runs(data: &mut Vec<SixelData>, values: &[SixelValue]) {
+    let mut iter = values.iter().peekable();
+    while let Some(&value) = iter.next() {
+        let mut count = 1;
+        while iter.peek() == Some(&&value) {
+            iter.next();
+            count += 1;
+        }
+        if count > 3 {
+            data.push(SixelData::Repeat {
+                repeat_count: count,
+                data: value,
+            });
+        } else {
+            for _ in 0..count {
+                data.push(SixelData::Data(value));
+            }
+        }
+    }
+}
+
+/// Reduce `pixels` to at most `max_colors` representative colors
+/// using the median cut algorithm.
+fn median_cut(pixels: &[[u8; 3]], max_colors: usize) -> Vec<[u8; 3]> {
+    let mut counts: HashMap<[u8; 3], usize> = HashMap::new();
+    for p in pixels {
+        *counts.entry(*p).or_insert(0) += 1;
+    }
+    let mut colors: Vec<([u8; 3], usize)> = counts.into_iter().collect();
+    if colors.len() <= max_colors {
+        colors.sort_unstable();
+        return colors.into_iter().map(|(color, _)| color).collect();
+    }
+
+    fn widest_channel(colors: &[([u8; 3], usize)]) -> (usize, u8) {
+        (0..3)
+            .map(|c| {
+                let min = colors.iter().map(|(p, _)| p[c]).min().unwrap_or(0);
+                let max = colors.iter().map(|(p, _)| p[c]).max().unwrap_or(0);
+                (c, max - min)
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap()
+    }
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max_colors {
+        // Split the box with the widest range of values along any channel
+        let (idx, channel, range) = boxes
+            .iter()
+            .enumerate()
+            .map(|(idx, b)| {
+                let (channel, range) = widest_channel(b);
+                (idx, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range)
+            .unwrap();
+        if range == 0 {
+            break;
+        }
+        let mut b = boxes.swap_remove(idx);
+        b.sort_unstable_by_key(|(p, _)| p[channel]);
+        let upper = b.split_off(b.len() / 2);
+        boxes.push(b);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|b| {
+            let total: usize = b.iter().map(|(_, n)| n).sum();
+            let mut avg = [0u8; 3];
+            for c in 0..3 {
+                let sum: usize = b.iter().map(|(p, n)| p[c] as usize * n).sum();
+                avg[c] = (sum / total.max(1)) as u8;
+            }
+            avg
+        })
+        .collect()
+}
+
+fn nearest_color(palette: &[[u8; 3]], color: [u8; 3]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, p)| {
+            (0..3)
+                .map(|c| {
+                    let d = p[c] as i32 - color[c] as i32;
+                    d * d
+                })
+                .sum::<i32>()
+        })
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
+/// Map each pixel to the index of its palette entry, or `None`
+/// for transparent pixels, optionally diffusing the quantization
+/// error to the neighboring pixels.
+fn map_to_palette(
+    pixels: &[[u8; 4]],
+    width: usize,
+    palette: &[[u8; 3]],
+    dither: bool,
+) -> Vec<Option<u16>> {
+    let mut cache: HashMap<[u8; 3], usize> = HashMap::new();
+    let mut lookup = |color: [u8; 3]| {
+        *cache
+            .entry(color)
+            .or_insert_with(|| nearest_color(palette, color))
+    };
+
+    if !dither {
+        return pixels
+            .iter()
+            .map(|p| {
+                if p[3] < 128 {
+                    None
+                } else {
+                    Some(lookup([p[0], p[1], p[2]]) as u16)
+                }
+            })
+            .collect();
+    }
+
+    let mut work: Vec<[f32; 3]> = pixels
+        .iter()
+        .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+        .collect();
+    let mut result = Vec::with_capacity(pixels.len());
+    for (i, p) in pixels.iter().enumerate() {
+        if p[3] < 128 {
+            result.push(None);
+            continue;
+        }
+        let wanted = work[i];
+        let color = [
+            wanted[0].max(0.).min(255.) as u8,
+            wanted[1].max(0.).min(255.) as u8,
+            wanted[2].max(0.).min(255.) as u8,
+        ];
+        let idx = lookup(color);
+        result.push(Some(idx as u16));
+
+        let x = i % width;
+        let chosen = palette[idx];
+        let error = [
+            wanted[0] - chosen[0] as f32,
+            wanted[1] - chosen[1] as f32,
+            wanted[2] - chosen[2] as f32,
+        ];
+        let mut diffuse = |target: usize, factor: f32| {
+            if let Some(w) = work.get_mut(target) {
+                for c in 0..3 {
+                    w[c] += error[c] * factor;
+                }
+            }
+        };
+        if x + 1 < width {
+            diffuse(i + 1, 7. / 16.);
+        }
+        if x > 0 {
+            diffuse(i + width - 1, 3. / 16.);
+        }
+        diffuse(i + width, 5. / 16.);
+        if x + 1 < width {
+            diffuse(i + width + 1, 1. / 16.);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::escape::parser::Parser;
+    use crate::escape::Action;
+
+    #[test]
+    fn encode_sixel_two_colors() {
+        // A 5x7 image: a red top row, a blue left column and
+        // transparent everywhere else
+        let (width, height) = (5, 7);
+        let mut rgba = vec![0u8; width * height * 4];
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = &mut rgba[(y * width + x) * 4..][..4];
+                if y == 0 {
+                    pixel.copy_from_slice(&[255, 0, 0, 255]);
+                } else if x == 0 {
+                    pixel.copy_from_slice(&[0, 0, 255, 255]);
+                }
+            }
+        }
+
+        let sixel = encode_sixel(
+            width as u32,
+            height as u32,
+            &rgba,
+            &SixelEncoderOptions::default(),
+        );
+        assert_eq!(sixel.dimensions(), (5, 7));
+        assert!(sixel.background_is_transparent);
+        assert_eq!(
+            format!("{}", sixel),
+            "\x1bP;1q\"1;1;5;7\
+             #0;2;0;0;100#1;2;100;0;0\
+             #0}$#1!5@-\
+             #0@"
+        );
+
+        // Parsing the output yields the same sixel data
+        let mut p = Parser::new();
+        let actions = p.parse_as_vec(format!("{}\x1b\\", sixel).as_bytes());
+        match &actions[0] {
+            Action::Sixel(parsed) => {
+                assert_eq!(parsed.data, sixel.data);
+                assert_eq!(parsed.dimensions(), (5, 7));
+            }
+            wat => panic!("unexpected {:?}", wat),
+        }
+    }
+
+    #[test]
+    fn encode_sixel_short_buffer() {
+        // Only the first row of a 3x2 image is present; the
+        // remainder is treated as transparent
+        let rgba = [255, 0, 0, 255].repeat(3);
+        let sixel = encode_sixel(3, 2, &rgba, &SixelEncoderOptions::default());
+        assert_eq!(sixel.dimensions(), (3, 2));
+        assert!(sixel.background_is_transparent);
+        assert_eq!(format!("{}", sixel), "\x1bP;1q\"1;1;3;2#0;2;100;0;0#0@@@");
+    }
+
+    #[test]
+    fn median_cut_reduces_palette() {
+        let pixels: Vec<[u8; 3]> = (0..=255u8).map(|v| [v, v, v]).collect();
+        let palette = median_cut(&pixels, 16);
+        assert_eq!(palette.len(), 16);
+
+        let indices = map_to_palette(
+            &pixels
+                .iter()
+                .map(|p| [p[0], p[1], p[2], 255])
+                .collect::<Vec<_>>(),
+            16,
+            &palette,
+            true,
+        );
+        assert!(indices.iter().all(|idx| idx.map(|i| i < 16) == Some(true)));
+    }
+}
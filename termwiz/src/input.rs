@@ -31,6 +31,9 @@ pub struct KeyCodeEncodeModes {
     pub encoding: KeyboardEncoding,
     pub application_cursor_keys: bool,
     pub newline_mode: bool,
+    /// Set by DECKPAM; when true, the numeric keypad
+    /// emits application mode (SS3) sequences
+    pub application_keypad: bool,
 }
 
 #[cfg(windows)]
@@ -294,8 +297,6 @@ impl KeyCode {
 
         let mut buf = String::new();
 
-        // TODO: also respect self.application_keypad
-
         match key {
             Char(c)
                 if is_ambiguous_ascii_ctrl(c)
@@ -486,9 +487,37 @@ impl KeyCode {
                 }
             }
 
-            // TODO: emit numpad sequences
             Numpad0 | Numpad1 | Numpad2 | Numpad3 | Numpad4 | Numpad5 | Numpad6 | Numpad7
-            | Numpad8 | Numpad9 | Multiply | Add | Separator | Subtract | Decimal | Divide => {}
+            | Numpad8 | Numpad9 | Multiply | Add | Separator | Subtract | Decimal | Divide => {
+                let (c, app) = match key {
+                    Numpad0 => ('0', 'p'),
+                    Numpad1 => ('1', 'q'),
+                    Numpad2 => ('2', 'r'),
+                    Numpad3 => ('3', 's'),
+                    Numpad4 => ('4', 't'),
+                    Numpad5 => ('5', 'u'),
+                    Numpad6 => ('6', 'v'),
+                    Numpad7 => ('7', 'w'),
+                    Numpad8 => ('8', 'x'),
+                    Numpad9 => ('9', 'y'),
+                    Multiply => ('*', 'j'),
+                    Add => ('+', 'k'),
+                    Separator => (',', 'l'),
+                    Subtract => ('-', 'm'),
+                    Decimal => ('.', 'n'),
+                    Divide => ('/', 'o'),
+                    _ => unreachable!(),
+                };
+
+                if modes.application_keypad && mods.is_empty() {
+                    // DECKPAM: use SS3 in application keypad mode
+                    write!(buf, "{}{}", SS3, app)?;
+                } else if mods.is_empty() {
+                    buf.push(c);
+                } else {
+                    csi_u_encode(&mut buf, c, mods, modes.encoding)?;
+                }
+            }
 
             // Modifier keys pressed on their own don't expand to anything
             Control | LeftControl | RightControl | Alt | LeftAlt | RightAlt | Menu | LeftMenu
@@ -1564,6 +1593,7 @@ mod test {
             encoding: KeyboardEncoding::Xterm,
             newline_mode: false,
             application_cursor_keys: false,
+            application_keypad: false,
         };
 
         assert_eq!(
@@ -1604,6 +1634,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn encode_application_modes() {
+        let mut mode = KeyCodeEncodeModes {
+            encoding: KeyboardEncoding::Xterm,
+            newline_mode: false,
+            application_cursor_keys: false,
+            application_keypad: false,
+        };
+
+        assert_eq!(
+            KeyCode::UpArrow.encode(Modifiers::NONE, mode).unwrap(),
+            "\x1b[A".to_string()
+        );
+        assert_eq!(
+            KeyCode::Numpad5.encode(Modifiers::NONE, mode).unwrap(),
+            "5".to_string()
+        );
+        assert_eq!(
+            KeyCode::Add.encode(Modifiers::NONE, mode).unwrap(),
+            "+".to_string()
+        );
+
+        mode.application_cursor_keys = true;
+        mode.application_keypad = true;
+
+        assert_eq!(
+            KeyCode::UpArrow.encode(Modifiers::NONE, mode).unwrap(),
+            "\x1bOA".to_string()
+        );
+        assert_eq!(
+            KeyCode::UpArrow.encode(Modifiers::CTRL, mode).unwrap(),
+            "\x1b[1;5A".to_string()
+        );
+        assert_eq!(
+            KeyCode::Numpad5.encode(Modifiers::NONE, mode).unwrap(),
+            "\x1bOu".to_string()
+        );
+        assert_eq!(
+            KeyCode::Add.encode(Modifiers::NONE, mode).unwrap(),
+            "\x1bOk".to_string()
+        );
+        assert_eq!(
+            KeyCode::Divide.encode(Modifiers::NONE, mode).unwrap(),
+            "\x1bOo".to_string()
+        );
+    }
+
     #[test]
     fn partial_bracketed_paste() {
         let mut p = InputParser::new();
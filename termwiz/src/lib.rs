@@ -39,6 +39,11 @@
 //!
 //! * `widgets` - enables the widget layout and related traits
 //! * `use_serde` - makes a number of structs serde serializable
+//! * `use_async_io` - adds `Terminal::poll_input_async` and
+//!   `Terminal::render_async` for reading input events and writing output
+//!   from async code.  It uses the `async-io` reactor, which runs
+//!   independently of the executor, so it can be used with tokio,
+//!   async-std or any other executor without a runtime specific feature.
 
 mod emoji;
 mod emoji_presentation;
@@ -51,6 +56,7 @@ pub mod cellcluster;
 pub mod color;
 pub mod error;
 pub mod escape;
+pub mod html;
 pub mod hyperlink;
 pub mod image;
 pub mod input;
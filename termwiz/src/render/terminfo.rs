@@ -1,7 +1,9 @@
 //! Rendering of Changes using terminfo
 use crate::caps::{Capabilities, ColorLevel};
-use crate::cell::{AttributeChange, Blink, CellAttributes, Intensity, Underline};
-use crate::color::{ColorAttribute, ColorSpec};
+use crate::cell::{
+    unicode_column_width, AttributeChange, Blink, CellAttributes, Intensity, Underline,
+};
+use crate::color::{ColorAttribute, ColorSpec, RgbColor};
 use crate::escape::csi::{Cursor, Edit, EraseInDisplay, EraseInLine, Sgr, CSI};
 use crate::escape::osc::{ITermDimension, ITermFileData, ITermProprietary, OperatingSystemCommand};
 use crate::escape::OneBased;
@@ -16,8 +18,71 @@ pub struct TerminfoRenderer {
     caps: Capabilities,
     current_attr: CellAttributes,
     pending_attr: Option<CellAttributes>,
-    /* TODO: we should record cursor position, shape and color here
-     * so that we can optimize updating them on screen. */
+    /// The (x, y) position of the cursor on the terminal, if known.
+    /// This is used to pick the cheapest way to move the cursor.
+    /// It is only trusted within a single render call; see
+    /// `invalidate_terminal_state`.
+    cursor: Option<(usize, usize)>,
+    /// true once we have emitted a full SGR reset, so that we know
+    /// that `current_attr` reflects the state of the terminal and
+    /// can switch on additional attributes without another reset.
+    attributes_known: bool,
+}
+
+/// Returns true if the attributes that are reflected in the output are
+/// the same for both `a` and `b`.  Bits such as `wrapped` and
+/// `semantic_type` are only meaningful to the model and changing them
+/// shouldn't cause us to emit any SGR sequences.
+fn rendered_attributes_equal(a: &CellAttributes, b: &CellAttributes) -> bool {
+    a.intensity() == b.intensity()
+        && a.underline() == b.underline()
+        && a.blink() == b.blink()
+        && a.italic() == b.italic()
+        && a.reverse() == b.reverse()
+        && a.strikethrough() == b.strikethrough()
+        && a.invisible() == b.invisible()
+}
+
+/// Returns true if `new` can be reached from `current` purely by
+/// enabling additional attributes, in which case there is no need
+/// to reset the terminal attributes and start over.
+fn only_enables_attributes(current: &CellAttributes, new: &CellAttributes) -> bool {
+    (current.intensity() == Intensity::Normal || current.intensity() == new.intensity())
+        && (current.underline() == Underline::None || current.underline() == new.underline())
+        && (current.blink() == Blink::None || current.blink() == new.blink())
+        && (!current.italic() || new.italic())
+        && (!current.reverse() || new.reverse())
+        && (!current.strikethrough() || new.strikethrough())
+        && (!current.invisible() || new.invisible())
+}
+
+/// Returns the size of the terminal, only querying it the first time
+/// that it is needed during a render, as that is a syscall for a tty.
+fn size_in_cells<W: RenderTty>(
+    size: &mut Option<(usize, usize)>,
+    out: &mut W,
+) -> Result<(usize, usize)> {
+    match size {
+        Some(size) => Ok(*size),
+        None => {
+            let cells = out.get_size_in_cells()?;
+            size.replace(cells);
+            Ok(cells)
+        }
+    }
+}
+
+/// Resolve `pos` to an absolute coordinate along an axis with `limit`
+/// cells, given the current coordinate along that axis, if known.
+fn resolve_position(current: Option<usize>, pos: &Position, limit: usize) -> Option<usize> {
+    let max = limit.saturating_sub(1);
+    match pos {
+        Position::Absolute(n) => Some((*n).min(max)),
+        Position::EndRelative(n) => Some(max.saturating_sub(*n)),
+        Position::Relative(n) => {
+            current.map(|c| ((c as isize).saturating_add(*n).max(0) as usize).min(max))
+        }
+    }
 }
 
 impl TerminfoRenderer {
@@ -26,13 +91,63 @@ impl TerminfoRenderer {
             caps,
             current_attr: CellAttributes::default(),
             pending_attr: None,
+            cursor: None,
+            attributes_known: false,
         }
     }
 
+    /// Forget what we know about the cursor position and the terminal
+    /// attributes.  Between renders the cursor may be moved by things
+    /// that we don't see, such as switching to the alternate screen,
+    /// the terminal being resized or the application writing escape
+    /// sequences directly, so this is called at the start of each render,
+    /// as well as by the terminal implementations when they do any of those.
+    pub fn invalidate_terminal_state(&mut self) {
+        self.cursor = None;
+        self.attributes_known = false;
+    }
+
     fn get_capability<'a, T: TermInfoCapability<'a>>(&'a self) -> Option<T> {
         self.caps.terminfo_db().and_then(|db| db.get::<T>())
     }
 
+    /// When the terminal supports 256 colors but not truecolor, map a
+    /// truecolor that has no palette fallback to the nearest palette
+    /// entry rather than losing it to the default color.
+    fn downgrade_color(&self, color: ColorAttribute) -> ColorAttribute {
+        match (self.caps.color_level(), color) {
+            (ColorLevel::TwoFiftySix, ColorAttribute::TrueColorWithDefaultFallback(tc)) => {
+                ColorAttribute::PaletteIndex(tc.nearest_palette_index())
+            }
+            (ColorLevel::Sixteen, ColorAttribute::TrueColorWithPaletteFallback(_, idx))
+                if idx < 16 =>
+            {
+                ColorAttribute::PaletteIndex(idx)
+            }
+            (ColorLevel::Sixteen, ColorAttribute::TrueColorWithPaletteFallback(tc, _))
+            | (ColorLevel::Sixteen, ColorAttribute::TrueColorWithDefaultFallback(tc)) => {
+                ColorAttribute::PaletteIndex(tc.nearest_ansi_index())
+            }
+            (ColorLevel::Sixteen, ColorAttribute::PaletteIndex(idx)) if idx >= 16 => {
+                ColorAttribute::PaletteIndex(
+                    RgbColor::from_xterm_palette_index(idx).nearest_ansi_index(),
+                )
+            }
+            _ => color,
+        }
+    }
+
+    /// Clear any attributes that the terminal is known not to support,
+    /// so that they are neither emitted nor cause needless SGR updates.
+    fn strip_unsupported_attributes(&self, attr: &mut CellAttributes) {
+        if !self.caps.italic() {
+            attr.set_italic(false);
+        }
+        if !self.caps.strikethrough() {
+            attr.set_strikethrough(false);
+        }
+    }
+
     fn attr_apply<F: FnOnce(&mut CellAttributes)>(&mut self, func: F) {
         self.pending_attr = Some(match self.pending_attr.take() {
             Some(mut attr) => {
@@ -62,14 +177,60 @@ impl TerminfoRenderer {
             };
         }
 
-        if let Some(attr) = self.pending_attr.take() {
+        if let Some(mut attr) = self.pending_attr.take() {
+            self.strip_unsupported_attributes(&mut attr);
             let mut current_foreground = self.current_attr.foreground();
             let mut current_background = self.current_attr.background();
 
-            if !attr.attribute_bits_equal(&self.current_attr) {
+            if rendered_attributes_equal(&attr, &self.current_attr) {
+                // Nothing visible changed
+            } else if self.attributes_known && only_enables_attributes(&self.current_attr, &attr) {
+                // We only need to switch on the new attributes; there is
+                // nothing to turn off, so we can avoid resetting everything
+                // and re-sending the colors.
+                let current = &self.current_attr;
+
+                if attr.intensity() != current.intensity() {
+                    match attr.intensity() {
+                        Intensity::Bold => attr_on!(EnterBoldMode, Sgr::Intensity(Intensity::Bold)),
+                        Intensity::Half => attr_on!(EnterDimMode, Sgr::Intensity(Intensity::Half)),
+                        Intensity::Normal => {}
+                    }
+                }
+
+                if attr.underline() != current.underline() {
+                    match attr.underline() {
+                        Underline::Single | Underline::Double => {
+                            attr_on!(Sgr::Underline(attr.underline()));
+                        }
+                        _ => {}
+                    }
+                }
+
+                if attr.blink() != current.blink() && attr.blink() != Blink::None {
+                    attr_on!(Sgr::Blink(attr.blink()));
+                }
+
+                if attr.reverse() && !current.reverse() {
+                    attr_on!(EnterReverseMode, Sgr::Inverse(true));
+                }
+
+                if attr.invisible() && !current.invisible() {
+                    attr_on!(Sgr::Invisible(true));
+                }
+
+                if attr.italic() && !current.italic() {
+                    attr_on!(EnterItalicsMode, Sgr::Italic(true));
+                }
+
+                if attr.strikethrough() && !current.strikethrough() {
+                    attr_on!(Sgr::StrikeThrough(true));
+                }
+            } else {
                 // Updating the attribute bits also resets the colors.
                 current_foreground = ColorAttribute::Default;
                 current_background = ColorAttribute::Default;
+                self.attributes_known = true;
 
                 // The SetAttributes capability can only handle single underline and slow blink.
                 if let Some(sgr) = self.get_capability::<cap::SetAttributes>() {
@@ -131,7 +292,7 @@ impl TerminfoRenderer {
             };
 
             if attr.foreground() != current_foreground {
-                match (has_true_color, attr.foreground()) {
+                match (has_true_color, self.downgrade_color(attr.foreground())) {
                     (true, ColorAttribute::TrueColorWithPaletteFallback(tc, _))
                     | (true, ColorAttribute::TrueColorWithDefaultFallback(tc)) => {
                         write!(
@@ -165,7 +326,7 @@ impl TerminfoRenderer {
             }
 
             if attr.background() != current_background {
-                match (has_true_color, attr.background()) {
+                match (has_true_color, self.downgrade_color(attr.background())) {
                     (true, ColorAttribute::TrueColorWithPaletteFallback(tc, _))
                     | (true, ColorAttribute::TrueColorWithDefaultFallback(tc)) => {
                         write!(
@@ -215,7 +376,7 @@ impl TerminfoRenderer {
         Ok(())
     }
 
-    fn cursor_up<W: RenderTty + Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
+    fn cursor_up<W: Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
         if n > 0 {
             if let Some(attr) = self.get_capability::<cap::ParmUpCursor>() {
                 attr.expand().count(n).to(out.by_ref())?;
@@ -226,7 +387,7 @@ impl TerminfoRenderer {
         Ok(())
     }
 
-    fn cursor_down<W: RenderTty + Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
+    fn cursor_down<W: Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
         if n > 0 {
             if let Some(attr) = self.get_capability::<cap::ParmDownCursor>() {
                 attr.expand().count(n).to(out.by_ref())?;
@@ -237,7 +398,7 @@ impl TerminfoRenderer {
         Ok(())
     }
 
-    fn cursor_y_relative<W: RenderTty + Write>(&mut self, y: isize, out: &mut W) -> Result<()> {
+    fn cursor_y_relative<W: Write>(&mut self, y: isize, out: &mut W) -> Result<()> {
         if y > 0 {
             self.cursor_down(y as u32, out)
         } else {
@@ -245,7 +406,7 @@ impl TerminfoRenderer {
         }
     }
 
-    fn cursor_left<W: RenderTty + Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
+    fn cursor_left<W: Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
         if n > 0 {
             if let Some(attr) = self.get_capability::<cap::ParmLeftCursor>() {
                 attr.expand().count(n).to(out.by_ref())?;
@@ -256,7 +417,7 @@ impl TerminfoRenderer {
         Ok(())
     }
 
-    fn cursor_right<W: RenderTty + Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
+    fn cursor_right<W: Write>(&mut self, n: u32, out: &mut W) -> Result<()> {
         if n > 0 {
             if let Some(attr) = self.get_capability::<cap::ParmRightCursor>() {
                 attr.expand().count(n).to(out.by_ref())?;
@@ -267,7 +428,7 @@ impl TerminfoRenderer {
         Ok(())
     }
 
-    fn cursor_x_relative<W: RenderTty + Write>(&mut self, x: isize, out: &mut W) -> Result<()> {
+    fn cursor_x_relative<W: Write>(&mut self, x: isize, out: &mut W) -> Result<()> {
         if x > 0 {
             self.cursor_right(x as u32, out)
         } else {
@@ -275,12 +436,7 @@ impl TerminfoRenderer {
         }
     }
 
-    fn move_cursor_absolute<W: RenderTty + Write>(
-        &mut self,
-        x: u32,
-        y: u32,
-        out: &mut W,
-    ) -> Result<()> {
+    fn move_cursor_absolute<W: Write>(&mut self, x: u32, y: u32, out: &mut W) -> Result<()> {
         if x == 0 && y == 0 {
             if let Some(attr) = self.get_capability::<cap::CursorHome>() {
                 attr.expand().to(out.by_ref())?;
@@ -299,14 +455,53 @@ impl TerminfoRenderer {
                 out,
                 "{}",
                 CSI::Cursor(Cursor::Position {
-                    line: OneBased::from_zero_based(x),
-                    col: OneBased::from_zero_based(y),
+                    line: OneBased::from_zero_based(y),
+                    col: OneBased::from_zero_based(x),
                 })
             )?;
         }
         Ok(())
     }
 
+    /// Move the cursor from `from` to `to`, picking whichever of an
+    /// absolute move, a relative move or a carriage return followed by
+    /// a relative move produces the fewest bytes.
+    /// We don't consider line feeds here because they would scroll the
+    /// display if the cursor happens to be at the bottom of a scroll region.
+    fn move_cursor_cheapest<W: Write>(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+        out: &mut W,
+    ) -> Result<()> {
+        if from == to {
+            return Ok(());
+        }
+        let (from_x, from_y) = from;
+        let (x, y) = to;
+        let dy = y as isize - from_y as isize;
+
+        let mut best = vec![];
+        self.move_cursor_absolute(x as u32, y as u32, &mut best)?;
+
+        let mut relative = vec![];
+        self.cursor_y_relative(dy, &mut relative)?;
+        self.cursor_x_relative(x as isize - from_x as isize, &mut relative)?;
+        if relative.len() < best.len() {
+            best = relative;
+        }
+
+        let mut carriage_return = b"\r".to_vec();
+        self.cursor_y_relative(dy, &mut carriage_return)?;
+        self.cursor_right(x as u32, &mut carriage_return)?;
+        if carriage_return.len() < best.len() {
+            best = carriage_return;
+        }
+
+        out.write_all(&best)?;
+        Ok(())
+    }
+
     #[cfg_attr(
         feature = "cargo-clippy",
         allow(clippy::cyclomatic_complexity, clippy::cognitive_complexity)
@@ -324,6 +519,9 @@ impl TerminfoRenderer {
             };
         }
 
+        self.invalidate_terminal_state();
+        let mut screen_size = None;
+
         for change in changes {
             match change {
                 Change::ClearScreen(color) => {
@@ -350,16 +548,20 @@ impl TerminfoRenderer {
                                 CSI::Edit(Edit::EraseInDisplay(EraseInDisplay::EraseDisplay))
                             )?;
                         }
+                        self.cursor = Some((0, 0));
                     } else {
                         // We're setting the background to a specific color, so we get to
                         // paint the whole thing.
                         self.move_cursor_absolute(0, 0, out)?;
 
-                        let (cols, rows) = out.get_size_in_cells()?;
+                        let (cols, rows) = size_in_cells(&mut screen_size, out)?;
                         let num_spaces = cols * rows;
                         let mut buf = Vec::with_capacity(num_spaces);
                         buf.resize(num_spaces, b' ');
                         out.write_all(buf.as_slice())?;
+                        // The cursor is left in the pending-wrap state
+                        // at the end of the screen
+                        self.cursor = None;
                     }
                 }
                 Change::ClearToEndOfLine(color) => {
@@ -448,76 +650,103 @@ impl TerminfoRenderer {
                 Change::Text(text) => {
                     self.flush_pending_attr(out)?;
                     out.by_ref().write_all(text.as_bytes())?;
+
+                    // Advance our idea of the cursor position, unless the
+                    // text contains controls or reaches the right margin,
+                    // in which case we can't be sure where the cursor is.
+                    self.cursor = match self.cursor {
+                        Some((x, y)) if !text.chars().any(char::is_control) => {
+                            let (cols, _rows) = size_in_cells(&mut screen_size, out)?;
+                            let x = x + unicode_column_width(text, None);
+                            if x < cols {
+                                Some((x, y))
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    };
                 }
 
                 Change::CursorPosition { x, y } => {
-                    // Note: we use `cursor_up(screen_height)` to move the cursor all the way to
-                    // the top of the screen when we need to absolutely position only y.
-
-                    match (x, y) {
-                        (Position::Relative(0), Position::Relative(0)) => {}
-                        (Position::Absolute(0), Position::Relative(1)) => {
-                            out.by_ref().write_all(b"\r\n")?;
-                        }
-                        (Position::Absolute(x), Position::Relative(y)) => {
-                            self.cursor_y_relative(*y, out)?;
-                            out.by_ref().write_all(b"\r")?;
-                            self.cursor_right(*x as u32, out)?;
-                        }
+                    let (cols, rows) = size_in_cells(&mut screen_size, out)?;
+                    let target_x = resolve_position(self.cursor.map(|c| c.0), x, cols);
+                    let target_y = resolve_position(self.cursor.map(|c| c.1), y, rows);
+
+                    if let (Position::Absolute(0), Position::Relative(1)) = (x, y) {
+                        // This is a newline; it must remain a line feed so that
+                        // the display scrolls if we're on the bottom line.
+                        out.by_ref().write_all(b"\r\n")?;
+                    } else if let (Some(from), Some(x), Some(y)) = (self.cursor, target_x, target_y)
+                    {
+                        // We know where the cursor is, so we can pick the
+                        // cheapest way to get it to where it needs to be.
+                        self.move_cursor_cheapest(from, (x, y), out)?;
+                    } else {
+                        // Note: we use `cursor_up(screen_height)` to move the cursor all the way to
+                        // the top of the screen when we need to absolutely position only y.
+
+                        match (x, y) {
+                            (Position::Relative(0), Position::Relative(0)) => {}
+                            (Position::Absolute(x), Position::Relative(y)) => {
+                                self.cursor_y_relative(*y, out)?;
+                                out.by_ref().write_all(b"\r")?;
+                                self.cursor_right(*x as u32, out)?;
+                            }
 
-                        (Position::Relative(x), Position::EndRelative(y)) => {
-                            let (_cols, rows) = out.get_size_in_cells()?;
-                            self.cursor_up(rows as u32, out)?;
-                            self.cursor_down(rows.saturating_sub(y + 1) as u32, out)?;
-                            self.cursor_x_relative(*x, out)?;
-                        }
-                        (Position::Relative(x), Position::Relative(y)) => {
-                            self.cursor_y_relative(*y, out)?;
-                            self.cursor_x_relative(*x, out)?;
-                        }
-                        (Position::EndRelative(x), Position::Relative(y)) => {
-                            self.cursor_y_relative(*y, out)?;
-                            let (cols, _rows) = out.get_size_in_cells()?;
-                            out.by_ref().write_all(b"\r")?;
-                            self.cursor_right(cols.saturating_sub(x + 1) as u32, out)?;
-                        }
+                            (Position::Relative(x), Position::EndRelative(y)) => {
+                                self.cursor_up(rows as u32, out)?;
+                                self.cursor_down(rows.saturating_sub(y + 1) as u32, out)?;
+                                self.cursor_x_relative(*x, out)?;
+                            }
+                            (Position::Relative(x), Position::Relative(y)) => {
+                                self.cursor_y_relative(*y, out)?;
+                                self.cursor_x_relative(*x, out)?;
+                            }
+                            (Position::EndRelative(x), Position::Relative(y)) => {
+                                self.cursor_y_relative(*y, out)?;
+                                out.by_ref().write_all(b"\r")?;
+                                self.cursor_right(cols.saturating_sub(x + 1) as u32, out)?;
+                            }
 
-                        (Position::Absolute(x), Position::Absolute(y)) => {
-                            self.move_cursor_absolute(*x as u32, *y as u32, out)?;
-                        }
-                        (Position::Absolute(x), Position::EndRelative(y)) => {
-                            let (_cols, rows) = out.get_size_in_cells()?;
-                            self.move_cursor_absolute(
-                                *x as u32,
-                                rows.saturating_sub(y + 1) as u32,
-                                out,
-                            )?;
-                        }
-                        (Position::EndRelative(x), Position::EndRelative(y)) => {
-                            let (cols, rows) = out.get_size_in_cells()?;
-                            self.move_cursor_absolute(
-                                cols.saturating_sub(x + 1) as u32,
-                                rows.saturating_sub(y + 1) as u32,
-                                out,
-                            )?;
-                        }
+                            (Position::Absolute(x), Position::Absolute(y)) => {
+                                self.move_cursor_absolute(*x as u32, *y as u32, out)?;
+                            }
+                            (Position::Absolute(x), Position::EndRelative(y)) => {
+                                self.move_cursor_absolute(
+                                    *x as u32,
+                                    rows.saturating_sub(y + 1) as u32,
+                                    out,
+                                )?;
+                            }
+                            (Position::EndRelative(x), Position::EndRelative(y)) => {
+                                self.move_cursor_absolute(
+                                    cols.saturating_sub(x + 1) as u32,
+                                    rows.saturating_sub(y + 1) as u32,
+                                    out,
+                                )?;
+                            }
 
-                        (Position::Relative(x), Position::Absolute(y)) => {
-                            let (_cols, rows) = out.get_size_in_cells()?;
-                            self.cursor_up(rows as u32, out)?;
-                            self.cursor_down(*y as u32, out)?;
-                            self.cursor_x_relative(*x, out)?;
-                        }
+                            (Position::Relative(x), Position::Absolute(y)) => {
+                                self.cursor_up(rows as u32, out)?;
+                                self.cursor_down(*y as u32, out)?;
+                                self.cursor_x_relative(*x, out)?;
+                            }
 
-                        (Position::EndRelative(x), Position::Absolute(y)) => {
-                            let (cols, _rows) = out.get_size_in_cells()?;
-                            self.move_cursor_absolute(
-                                cols.saturating_sub(x + 1) as u32,
-                                *y as u32,
-                                out,
-                            )?;
+                            (Position::EndRelative(x), Position::Absolute(y)) => {
+                                self.move_cursor_absolute(
+                                    cols.saturating_sub(x + 1) as u32,
+                                    *y as u32,
+                                    out,
+                                )?;
+                            }
                         }
                     }
+
+                    self.cursor = match (target_x, target_y) {
+                        (Some(x), Some(y)) => Some((x, y)),
+                        _ => None,
+                    };
                 }
 
                 Change::CursorColor(_color) => {
@@ -611,6 +840,7 @@ impl TerminfoRenderer {
                         }
                         self.cursor_up(image.height as u32, out)?;
                     }
+                    self.cursor = None;
                 }
                 Change::ScrollRegionUp {
                     first_row,
@@ -641,6 +871,9 @@ impl TerminfoRenderer {
                             }
                         }
                     }
+                    // Changing the scroll region homes the cursor on
+                    // some terminals but not on others
+                    self.cursor = None;
                 }
                 Change::ScrollRegionDown {
                     first_row,
@@ -671,6 +904,7 @@ impl TerminfoRenderer {
                             }
                         }
                     }
+                    self.cursor = None;
                 }
 
                 Change::Title(text) => {
@@ -693,7 +927,7 @@ mod test {
     use crate::caps::ProbeHints;
     use crate::color::{AnsiColor, ColorAttribute, RgbColor};
     use crate::escape::parser::Parser;
-    use crate::escape::{Action, Esc, EscCode};
+    use crate::escape::{Action, ControlCode, Esc, EscCode};
     use crate::input::InputEvent;
     use crate::terminal::unix::{Purge, SetAttributeWhen, UnixTty};
     use crate::terminal::{cast, ScreenSize, Terminal, TerminalWaker};
@@ -715,6 +949,18 @@ mod test {
         .unwrap()
     }
 
+    fn no_terminfo_256_colors() -> Capabilities {
+        Capabilities::new_with_hints(
+            ProbeHints::default().color_level(Some(ColorLevel::TwoFiftySix)),
+        )
+        .unwrap()
+    }
+
+    fn no_terminfo_16_colors() -> Capabilities {
+        Capabilities::new_with_hints(ProbeHints::default().color_level(Some(ColorLevel::Sixteen)))
+            .unwrap()
+    }
+
     fn no_terminfo_all_enabled() -> Capabilities {
         Capabilities::new_with_hints(ProbeHints::default().color_level(Some(ColorLevel::TrueColor)))
             .unwrap()
@@ -1064,6 +1310,46 @@ mod test {
         );
     }
 
+    #[test]
+    fn enable_additional_attribute() {
+        let mut out = FakeTerm::new(no_terminfo_all_enabled());
+        out.render(&[
+            Change::Attribute(AttributeChange::Intensity(Intensity::Bold)),
+            Change::Text("a".into()),
+            Change::Attribute(AttributeChange::Italic(true)),
+            Change::Text("b".into()),
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                // We don't know the initial state of the terminal
+                Action::CSI(CSI::Sgr(Sgr::Reset)),
+                Action::CSI(CSI::Sgr(Sgr::Intensity(Intensity::Bold))),
+                Action::Print('a'),
+                // but now we do, and we only need to turn on italics
+                Action::CSI(CSI::Sgr(Sgr::Italic(true))),
+                Action::Print('b'),
+            ]
+        );
+    }
+
+    #[test]
+    fn model_only_attribute_change() {
+        let mut out = FakeTerm::new(xterm_terminfo());
+        out.render(&[
+            Change::AllAttributes(CellAttributes::default().set_wrapped(true).clone()),
+            Change::Text("a".into()),
+        ])
+        .unwrap();
+
+        // The wrapped bit isn't something that the terminal knows about,
+        // so changing it must not produce any SGR sequences
+        assert_eq!("a", String::from_utf8(out.write.buf).unwrap());
+    }
+
     #[test]
     fn red_bold_text() {
         let mut out = FakeTerm::new(xterm_terminfo());
@@ -1175,6 +1461,48 @@ mod test {
         );
     }
 
+    #[test]
+    fn cursor_motion() {
+        let mut out = FakeTerm::new(xterm_terminfo());
+        out.render(&[
+            Change::CursorPosition {
+                x: Position::Absolute(10),
+                y: Position::Absolute(5),
+            },
+            Change::Text("ab".into()),
+            // A short relative move is cheaper than addressing the cell
+            Change::CursorPosition {
+                x: Position::Absolute(14),
+                y: Position::Absolute(5),
+            },
+            Change::Text("c".into()),
+            // and returning to the start of the line only needs a CR
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(5),
+            },
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                // We don't know where the cursor is initially
+                Action::CSI(CSI::Cursor(Cursor::Position {
+                    line: OneBased::new(6),
+                    col: OneBased::new(11)
+                })),
+                Action::Print('a'),
+                Action::Print('b'),
+                Action::CSI(CSI::Cursor(Cursor::Right(2))),
+                Action::Print('c'),
+                Action::Control(ControlCode::CarriageReturn),
+            ]
+        );
+        assert_eq!(out.renderer.cursor, Some((0, 5)));
+    }
+
     #[test]
     fn truecolor() {
         let mut out = FakeTerm::new(xterm_terminfo());
@@ -1220,4 +1548,105 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn truecolor_downgraded_to_256_colors() {
+        let mut out = FakeTerm::new(no_terminfo_256_colors());
+        out.render(&[
+            Change::Attribute(AttributeChange::Foreground(
+                ColorSpec::TrueColor(RgbColor::new_8bpc(255, 128, 64)).into(),
+            )),
+            Change::Attribute(AttributeChange::Background(
+                ColorAttribute::TrueColorWithPaletteFallback(
+                    RgbColor::new_8bpc(0, 0, 0),
+                    AnsiColor::Navy.into(),
+                ),
+            )),
+            Change::Text("A".into()),
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                Action::CSI(CSI::Sgr(Sgr::Foreground(ColorSpec::PaletteIndex(209)))),
+                Action::CSI(CSI::Sgr(Sgr::Background(ColorSpec::PaletteIndex(
+                    AnsiColor::Navy.into()
+                )))),
+                Action::Print('A'),
+            ]
+        );
+    }
+
+    #[test]
+    fn colors_downgraded_to_16_colors() {
+        let mut out = FakeTerm::new(no_terminfo_16_colors());
+        out.render(&[
+            Change::Attribute(AttributeChange::Foreground(
+                ColorSpec::TrueColor(RgbColor::new_8bpc(255, 128, 64)).into(),
+            )),
+            Change::Attribute(AttributeChange::Background(ColorAttribute::PaletteIndex(
+                196,
+            ))),
+            Change::Text("A".into()),
+            Change::Attribute(AttributeChange::Background(
+                ColorAttribute::TrueColorWithPaletteFallback(
+                    RgbColor::new_8bpc(0, 0, 0),
+                    AnsiColor::Navy.into(),
+                ),
+            )),
+            Change::Text("B".into()),
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                Action::CSI(CSI::Sgr(Sgr::Foreground(ColorSpec::PaletteIndex(
+                    AnsiColor::Olive.into()
+                )))),
+                Action::CSI(CSI::Sgr(Sgr::Background(ColorSpec::PaletteIndex(
+                    AnsiColor::Red.into()
+                )))),
+                Action::Print('A'),
+                Action::CSI(CSI::Sgr(Sgr::Background(ColorSpec::PaletteIndex(
+                    AnsiColor::Navy.into()
+                )))),
+                Action::Print('B'),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_attributes_are_not_emitted() {
+        let caps = Capabilities::new_with_hints(
+            ProbeHints::default()
+                .color_level(Some(ColorLevel::TrueColor))
+                .italic(Some(false))
+                .strikethrough(Some(false)),
+        )
+        .unwrap();
+        let mut out = FakeTerm::new(caps);
+        out.render(&[
+            Change::Attribute(AttributeChange::Italic(true)),
+            Change::Attribute(AttributeChange::StrikeThrough(true)),
+            Change::Text("A".into()),
+            Change::Attribute(AttributeChange::Intensity(Intensity::Bold)),
+            Change::Text("B".into()),
+        ])
+        .unwrap();
+
+        let result = out.parse();
+        assert_eq!(
+            result,
+            vec![
+                Action::Print('A'),
+                Action::CSI(CSI::Sgr(Sgr::Reset)),
+                Action::CSI(CSI::Sgr(Sgr::Intensity(Intensity::Bold))),
+                Action::Print('B'),
+            ]
+        );
+    }
 }
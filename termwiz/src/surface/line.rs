@@ -175,34 +175,55 @@ impl Line {
     }
 
     pub fn resize(&mut self, width: usize, seqno: SequenceNo) {
+        if width < self.cells.len() {
+            // Don't leave half of a wide grapheme at the end
+            self.invalidate_grapheme_at_or_before(width);
+        }
         self.cells.resize_with(width, Cell::blank);
         self.update_last_change_seqno(seqno);
         self.invalidate_zones();
     }
 
     /// Wrap the line so that it fits within the provided width.
-    /// Returns the list of resultant line(s)
+    /// Returns the list of resultant line(s).
+    /// A wide grapheme is never split from its spacer cells; if it
+    /// doesn't fit at the end of a line, that line is padded with
+    /// blanks and the grapheme moves to the start of the next one.
     pub fn wrap(mut self, width: usize, seqno: SequenceNo) -> Vec<Self> {
         if let Some(end_idx) = self.cells.iter().rposition(|c| c.str() != " ") {
             self.cells.resize_with(end_idx + 1, Cell::blank);
 
-            let mut lines: Vec<_> = self
-                .cells
-                .chunks_mut(width)
-                .map(|chunk| {
-                    let mut line = Line {
-                        cells: chunk.to_vec(),
-                        bits: LineBits::NONE,
-                        seqno: seqno,
-                        zones: vec![],
-                    };
-                    if line.cells.len() == width {
-                        // Ensure that we don't forget that we wrapped
-                        line.set_last_cell_was_wrapped(true, seqno);
-                    }
-                    line
-                })
-                .collect();
+            let make_line = |cells: Vec<Cell>| {
+                let mut line = Line {
+                    cells,
+                    bits: LineBits::NONE,
+                    seqno: seqno,
+                    zones: vec![],
+                };
+                if line.cells.len() >= width {
+                    // Ensure that we don't forget that we wrapped
+                    line.set_last_cell_was_wrapped(true, seqno);
+                }
+                line
+            };
+
+            let mut lines = vec![];
+            let mut current = Vec::with_capacity(width);
+            let mut idx = 0;
+            while idx < self.cells.len() {
+                let group_len = self.cells[idx].width().max(1).min(self.cells.len() - idx);
+                if !current.is_empty() && current.len() + group_len > width {
+                    current.resize_with(width, Cell::blank);
+                    lines.push(make_line(std::mem::replace(
+                        &mut current,
+                        Vec::with_capacity(width),
+                    )));
+                }
+                current.extend_from_slice(&self.cells[idx..idx + group_len]);
+                idx += group_len;
+            }
+            lines.push(make_line(current));
+
             // The last of the chunks wasn't actually wrapped
             if let Some(line) = lines.last_mut() {
                 line.set_last_cell_was_wrapped(false, seqno);
@@ -219,6 +240,7 @@ impl Line {
         self.seqno == SEQ_ZERO || self.seqno > seqno
     }
 
+    /// Returns the seqno of the most recent change to the line
     pub fn current_seqno(&self) -> SequenceNo {
         self.seqno
     }
@@ -531,6 +553,10 @@ impl Line {
     }
 
     pub fn split_off(&mut self, idx: usize, seqno: SequenceNo) -> Self {
+        if idx < self.cells.len() {
+            // Don't separate a wide grapheme from its spacer
+            self.invalidate_grapheme_at_or_before(idx);
+        }
         let cells = self.cells.split_off(idx);
         Self {
             bits: self.bits,
@@ -685,7 +711,7 @@ impl Line {
             let width = self.cells[prior].width();
             if width > 1 {
                 let attrs = self.cells[prior].attrs().clone();
-                for nerf in prior..prior + width {
+                for nerf in prior..(prior + width).min(self.cells.len()) {
                     self.cells[nerf] = Cell::blank_with_attrs(attrs.clone());
                 }
             }
@@ -695,7 +721,13 @@ impl Line {
     pub fn insert_cell(&mut self, x: usize, cell: Cell, right_margin: usize, seqno: SequenceNo) {
         self.invalidate_implicit_hyperlinks(seqno);
 
+        // Inserting between a wide grapheme and its spacer, or pushing
+        // a spacer out past the margin, would orphan the grapheme
+        if x < self.cells.len() {
+            self.invalidate_grapheme_at_or_before(x);
+        }
         if right_margin <= self.cells.len() {
+            self.invalidate_grapheme_at_or_before(right_margin - 1);
             self.cells.remove(right_margin - 1);
         }
 
@@ -756,6 +788,9 @@ impl Line {
         if right_margin <= self.cells.len() + 1
         /* we just removed one */
         {
+            if right_margin - 1 < self.cells.len() {
+                self.invalidate_grapheme_at_or_before(right_margin - 1);
+            }
             self.cells
                 .insert(right_margin - 1, Cell::blank_with_attrs(blank_attr));
         }
@@ -784,6 +819,19 @@ impl Line {
         self.prune_trailing_blanks(seqno);
     }
 
+    /// Returns true if the cell at `idx` is a spacer; one of the blank
+    /// cells that follow a wide grapheme and are covered by it when
+    /// the line is rendered.  Spacers are skipped by `visible_cells`.
+    pub fn is_spacer(&self, idx: usize) -> bool {
+        idx < self.cells.len()
+            && self
+                .visible_cells()
+                .take_while(|(i, _)| *i <= idx)
+                .last()
+                .map(|(i, _)| i != idx)
+                .unwrap_or(false)
+    }
+
     /// Iterates the visible cells, respecting the width of the cell.
     /// For instance, a double-width cell overlaps the following (blank)
     /// cell, so that blank cell is omitted from the iterator results.
@@ -1021,4 +1069,46 @@ mod test {
         let r = line.compute_double_click_range(200, |_| true);
         assert_eq!(r, DoubleClickRange::Range(200..200));
     }
+
+    #[test]
+    fn spacers() {
+        let line: Line = "a😍b".into();
+        assert_eq!(line.cells().len(), 4);
+        assert!(!line.is_spacer(0));
+        assert!(!line.is_spacer(1));
+        assert!(line.is_spacer(2));
+        assert!(!line.is_spacer(3));
+        assert!(!line.is_spacer(4));
+    }
+
+    #[test]
+    fn wrap_keeps_wide_graphemes_together() {
+        let line: Line = "ab😍cd".into();
+        let lines = line.wrap(3, 0);
+        assert_eq!(
+            lines.iter().map(|l| l.as_str()).collect::<Vec<_>>(),
+            vec!["ab ".to_string(), "😍c".to_string(), "d".to_string()]
+        );
+        assert!(lines[0].last_cell_was_wrapped());
+        assert!(lines[1].last_cell_was_wrapped());
+        assert!(!lines[2].last_cell_was_wrapped());
+        assert!(lines[1].is_spacer(1));
+    }
+
+    #[test]
+    fn orphaned_spacers_are_blanked() {
+        let mut line: Line = "a😍b".into();
+        line.insert_cell(2, Cell::new('x', CellAttributes::default()), 5, 0);
+        assert_eq!(line.as_str(), "a x b");
+        assert!(!line.is_spacer(2));
+
+        let mut line: Line = "a😍b".into();
+        let tail = line.split_off(2, 0);
+        assert_eq!(line.as_str(), "a ");
+        assert_eq!(tail.as_str(), " b");
+
+        let mut line: Line = "a😍b".into();
+        line.resize(2, 0);
+        assert_eq!(line.as_str(), "a ");
+    }
 }
@@ -100,6 +100,14 @@ pub const SEQ_ZERO: SequenceNo = 0;
 /// difference between the updated screen and apply those changes to
 /// the render target, and then use `get_changes` to render those without
 /// repainting the world on each update.
+///
+/// When the `use_serde` feature is enabled, a `Surface` can be serialized
+/// to snapshot its contents, cursor and title.  The change log is not
+/// included in the snapshot, so the first call to `get_changes` on a
+/// deserialized `Surface` will produce a full repaint.  The `Change`
+/// streams returned by `get_changes` and `diff_screens` are themselves
+/// serializable and can be replayed into another `Surface` via `add_changes`.
+#[cfg_attr(feature = "use_serde", derive(Serialize, Deserialize))]
 #[derive(Default)]
 pub struct Surface {
     width: usize,
@@ -109,6 +117,7 @@ pub struct Surface {
     xpos: usize,
     ypos: usize,
     seqno: SequenceNo,
+    #[cfg_attr(feature = "use_serde", serde(skip))]
     changes: Vec<Change>,
     cursor_shape: Option<CursorShape>,
     cursor_visibility: CursorVisibility,
@@ -1436,6 +1445,66 @@ mod test {
         }
     }
 
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn roundtrip_snapshot() {
+        let mut s = Surface::new(4, 3);
+        s.add_change(Change::Attribute(AttributeChange::Intensity(
+            Intensity::Bold,
+        )));
+        s.add_change("hello");
+        s.add_change(Change::Title("the title".into()));
+
+        let data = varbincode::serialize(&s).unwrap();
+        let decoded: Surface = varbincode::deserialize(data.as_slice()).unwrap();
+
+        assert_eq!(decoded.screen_chars_to_string(), s.screen_chars_to_string());
+        assert_eq!(decoded.screen_cells(), s.screen_cells());
+        assert_eq!(decoded.cursor_position(), s.cursor_position());
+        assert_eq!(decoded.title(), "the title");
+        assert_eq!(decoded.current_seqno(), s.current_seqno());
+
+        // The change log isn't part of the snapshot, so a renderer
+        // that is behind gets a full repaint
+        let (seq, changes) = decoded.get_changes(1);
+        assert_eq!(seq, s.current_seqno());
+        assert_eq!(changes.into_owned(), s.repaint_all());
+    }
+
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn replay_serialized_delta() {
+        let mut s = Surface::new(4, 3);
+        let mut replica = Surface::new(4, 3);
+
+        s.add_change("abc");
+        s.add_change(Change::CursorPosition {
+            x: Position::Absolute(1),
+            y: Position::Absolute(2),
+        });
+        s.add_change("XY");
+
+        let (_seq, changes) = s.get_changes(0);
+        let data = varbincode::serialize(&changes.into_owned()).unwrap();
+        let decoded: Vec<Change> = varbincode::deserialize(data.as_slice()).unwrap();
+        replica.add_changes(decoded);
+        assert_eq!(replica.screen_chars_to_string(), s.screen_chars_to_string());
+
+        // Deltas can be shipped too
+        let seq = s.current_seqno();
+        s.add_change("Z");
+        let (_seq, changes) = s.get_changes(seq);
+        let data = varbincode::serialize(&changes.into_owned()).unwrap();
+        let decoded: Vec<Change> = varbincode::deserialize(data.as_slice()).unwrap();
+        replica.add_changes(decoded);
+        assert_eq!(
+            replica.screen_chars_to_string(),
+            "abc \n\
+             \x20\x20\x20\x20\n\
+             \x20XYZ\n"
+        );
+    }
+
     #[test]
     fn draw_screens() {
         let mut s = Surface::new(4, 4);
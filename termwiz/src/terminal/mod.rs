@@ -40,6 +40,50 @@ pub struct ScreenSize {
     pub ypixel: usize,
 }
 
+/// The future returned by the async methods of `Terminal`
+#[cfg(feature = "use_async_io")]
+pub type TerminalFuture<'a, T> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Result<T>> + 'a>>;
+
+/// The future returned by `Terminal::poll_input_async`
+#[cfg(feature = "use_async_io")]
+pub type PollInputFuture<'a> = TerminalFuture<'a, InputEvent>;
+
+/// Collects rendered output in memory so that it can be written to
+/// the terminal by `Terminal::render_async` without blocking
+#[cfg(feature = "use_async_io")]
+pub(crate) struct RenderBuffer {
+    pub output: Vec<u8>,
+    size: (usize, usize),
+}
+
+#[cfg(feature = "use_async_io")]
+impl RenderBuffer {
+    /// Creates a buffer for a terminal that is `size` (cols, rows)
+    pub fn new(output: Vec<u8>, size: (usize, usize)) -> Self {
+        Self { output, size }
+    }
+}
+
+#[cfg(feature = "use_async_io")]
+impl std::io::Write for RenderBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "use_async_io")]
+impl crate::render::RenderTty for RenderBuffer {
+    fn get_size_in_cells(&mut self) -> Result<(usize, usize)> {
+        Ok(self.size)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Blocking {
     DoNotWait,
@@ -91,6 +135,48 @@ pub trait Terminal {
     /// the terminal is set to raw mode.
     fn poll_input(&mut self, wait: Option<Duration>) -> Result<Option<InputEvent>>;
 
+    /// Wait for the next parsed input event.
+    /// This is the async equivalent of `poll_input(None)`; rather than
+    /// blocking the calling thread, the returned future resolves once
+    /// an event is available.  Resizes are reported as
+    /// `InputEvent::Resized` and wakeups as `InputEvent::Wake`, just as
+    /// they are by `poll_input`.
+    ///
+    /// The future uses the `async-io` reactor rather than that of a
+    /// particular runtime, so it can be awaited from any executor,
+    /// including tokio and async-std, without enabling any of their
+    /// features.
+    ///
+    /// `UnixTerminal` and `WindowsTerminal` wait for their input to
+    /// become readable.  There is no way to do that for an arbitrary
+    /// `Terminal`, so the default implementation fails rather than
+    /// repeatedly polling for input.
+    #[cfg(feature = "use_async_io")]
+    fn poll_input_async(&mut self) -> PollInputFuture<'_> {
+        Box::pin(async move { crate::bail!("this Terminal does not support poll_input_async") })
+    }
+
+    /// Render a series of changes and flush them to the terminal output.
+    /// This is the async equivalent of `render` followed by `flush`;
+    /// the returned future resolves once the output has been written,
+    /// without blocking the calling thread while the terminal is slow
+    /// to accept it.
+    ///
+    /// The future must be awaited to completion before rendering again,
+    /// otherwise output may be interleaved.  If it is dropped early, the
+    /// output that was already rendered is still written.
+    ///
+    /// The default implementation calls `render` and `flush`, and so
+    /// blocks; `UnixTerminal` and `WindowsTerminal` write from a helper
+    /// thread instead.
+    #[cfg(feature = "use_async_io")]
+    fn render_async<'a>(&'a mut self, changes: &'a [Change]) -> TerminalFuture<'a, ()> {
+        Box::pin(async move {
+            self.render(changes)?;
+            self.flush()
+        })
+    }
+
     fn waker(&self) -> TerminalWaker;
 }
 
@@ -182,8 +182,21 @@ impl UnixTty for TtyWriteHandle {
     }
 }
 
+/// The terminal file descriptors, as registered with the `async-io`
+/// reactor by `poll_input_async`
+#[cfg(feature = "use_async_io")]
+struct AsyncInput {
+    tty: async_io::Async<std::os::unix::io::RawFd>,
+    sigwinch: async_io::Async<std::os::unix::io::RawFd>,
+    wake: async_io::Async<std::os::unix::io::RawFd>,
+}
+
 /// A unix style terminal
 pub struct UnixTerminal {
+    /// Registered on first use of `poll_input_async`.  This is declared
+    /// first so that it is deregistered before the descriptors are closed.
+    #[cfg(feature = "use_async_io")]
+    async_input: Option<AsyncInput>,
     read: TtyReadHandle,
     write: TtyWriteHandle,
     saved_termios: Termios,
@@ -230,6 +243,8 @@ impl UnixTerminal {
         read.set_blocking(Blocking::Wait)?;
 
         Ok(UnixTerminal {
+            #[cfg(feature = "use_async_io")]
+            async_input: None,
             caps,
             read,
             write,
@@ -276,6 +291,27 @@ impl UnixTerminal {
             Err(e) => bail!("failed to read sigwinch pipe {}", e),
         }
     }
+
+    /// Registers the tty, the SIGWINCH pipe and the wake pipe with the
+    /// `async-io` reactor, if that hasn't already been done
+    #[cfg(feature = "use_async_io")]
+    fn async_input(&mut self) -> Result<&AsyncInput> {
+        if self.async_input.is_none() {
+            let tty = async_io::Async::new(self.read.fd.as_raw_fd())?;
+            // Registering with the reactor switches the tty to non-blocking
+            // mode.  The write handle may share the same open file
+            // description, so switch it back to avoid partial writes when
+            // rendering; the reactor only needs the fd for readiness
+            // notifications, and reads only happen once it is readable.
+            self.read.set_blocking(Blocking::Wait)?;
+            self.async_input = Some(AsyncInput {
+                tty,
+                sigwinch: async_io::Async::new(self.sigwinch_pipe.as_raw_fd())?,
+                wake: async_io::Async::new(self.wake_pipe.as_raw_fd())?,
+            });
+        }
+        Ok(self.async_input.as_ref().unwrap())
+    }
 }
 
 #[derive(Clone)]
@@ -343,6 +379,7 @@ impl Terminal for UnixTerminal {
                 )))
             )?;
             self.in_alternate_screen = true;
+            self.renderer.invalidate_terminal_state();
         }
         Ok(())
     }
@@ -357,6 +394,7 @@ impl Terminal for UnixTerminal {
                 )))
             )?;
             self.in_alternate_screen = false;
+            self.renderer.invalidate_terminal_state();
         }
         Ok(())
     }
@@ -379,6 +417,7 @@ impl Terminal for UnixTerminal {
             ws_ypixel: cast(size.ypixel)?,
         };
 
+        self.renderer.invalidate_terminal_state();
         self.write.set_size(size)
     }
     fn render(&mut self, changes: &[Change]) -> Result<()> {
@@ -483,6 +522,55 @@ impl Terminal for UnixTerminal {
         Ok(None)
     }
 
+    #[cfg(feature = "use_async_io")]
+    fn render_async<'a>(
+        &'a mut self,
+        changes: &'a [Change],
+    ) -> crate::terminal::TerminalFuture<'a, ()> {
+        Box::pin(async move {
+            // Anything left over from an earlier `render` must go first
+            let pending = mem::take(&mut self.write.write_buffer);
+            let mut buffer =
+                crate::terminal::RenderBuffer::new(pending, self.write.get_size_in_cells()?);
+            self.renderer.render_to(changes, &mut buffer)?;
+            if buffer.output.is_empty() {
+                return Ok(());
+            }
+
+            // The tty is kept in blocking mode (see `async_input`), so
+            // rather than waiting for it to become writable, which only
+            // promises room for part of the output, write it from a
+            // helper thread.
+            let mut fd = self.write.fd.try_clone()?;
+            blocking::unblock(move || {
+                fd.write_all(&buffer.output)?;
+                fd.flush()
+            })
+            .await
+            .context("failed to write to terminal")
+        })
+    }
+
+    #[cfg(feature = "use_async_io")]
+    fn poll_input_async(&mut self) -> crate::terminal::PollInputFuture<'_> {
+        Box::pin(async move {
+            use futures_lite::future::or;
+
+            loop {
+                if let Some(event) = self.poll_input(Some(Duration::ZERO))? {
+                    return Ok(event);
+                }
+
+                let input = self.async_input()?;
+                or(
+                    input.tty.readable(),
+                    or(input.sigwinch.readable(), input.wake.readable()),
+                )
+                .await?;
+            }
+        })
+    }
+
     fn waker(&self) -> UnixTerminalWaker {
         UnixTerminalWaker {
             pipe: self.wake_pipe_write.clone(),
@@ -524,3 +612,87 @@ impl Drop for UnixTerminal {
             .expect("failed to restore original termios state");
     }
 }
+
+#[cfg(all(test, feature = "use_async_io"))]
+mod test {
+    use super::*;
+    use crate::caps::ProbeHints;
+    use crate::input::{KeyCode, KeyEvent, Modifiers};
+    use futures_lite::future::block_on;
+    use k9::assert_equal as assert_eq;
+
+    /// Returns the (master, slave) ends of a new pty
+    fn open_pty() -> (FileDescriptor, FileDescriptor) {
+        let mut master = -1;
+        let mut slave = -1;
+        let result = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert!(result == 0, "openpty failed: {}", IoError::last_os_error());
+        unsafe {
+            use std::os::unix::io::FromRawFd;
+            (
+                FileDescriptor::from_raw_fd(master),
+                FileDescriptor::from_raw_fd(slave),
+            )
+        }
+    }
+
+    #[test]
+    fn poll_input_async() {
+        let (mut master, slave) = open_pty();
+        let caps = Capabilities::new_with_hints(ProbeHints::default()).unwrap();
+        let mut term = UnixTerminal::new_with(caps, &slave, &slave).unwrap();
+        term.set_raw_mode().unwrap();
+
+        master.write_all(b"a").unwrap();
+        assert_eq!(
+            block_on(term.poll_input_async()).unwrap(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('a'),
+                modifiers: Modifiers::NONE,
+            })
+        );
+
+        // The descriptors stay registered for the next call
+        term.waker().wake().unwrap();
+        assert_eq!(block_on(term.poll_input_async()).unwrap(), InputEvent::Wake);
+        assert!(term.async_input.is_some());
+
+        master.write_all(b"b").unwrap();
+        assert_eq!(
+            block_on(term.poll_input_async()).unwrap(),
+            InputEvent::Key(KeyEvent {
+                key: KeyCode::Char('b'),
+                modifiers: Modifiers::NONE,
+            })
+        );
+    }
+
+    #[test]
+    fn render_async() {
+        let (mut master, slave) = open_pty();
+        let caps = Capabilities::new_with_hints(ProbeHints::default()).unwrap();
+        let mut term = UnixTerminal::new_with(caps, &slave, &slave).unwrap();
+        term.set_raw_mode().unwrap();
+
+        // Output buffered by `render` is written ahead of the new changes
+        term.write.write_all(b"a").unwrap();
+        block_on(term.render_async(&[Change::Text("bc".to_string())])).unwrap();
+        assert!(term.write.write_buffer.is_empty());
+
+        let mut output = vec![];
+        let mut buf = [0u8; 64];
+        while !output.ends_with(b"bc") {
+            let len = master.read(&mut buf).unwrap();
+            output.extend_from_slice(&buf[..len]);
+        }
+        assert!(output.starts_with(b"a"), "{:?}", output);
+    }
+}
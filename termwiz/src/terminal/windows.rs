@@ -212,6 +212,19 @@ impl EventHandle {
 // Handle created by `CreateEventW` is safe to be shared.
 unsafe impl Sync for EventHandle {}
 
+/// Signals its event when dropped.  `poll_input_async` holds one of
+/// these while its helper thread waits, so that dropping the future
+/// also ends the wait instead of leaving the thread blocked forever.
+#[cfg(feature = "use_async_io")]
+struct CancelOnDrop(Arc<EventHandle>);
+
+#[cfg(feature = "use_async_io")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.set().ok();
+    }
+}
+
 impl Write for OutputHandle {
     fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
         if self.write_buffer.len() + buf.len() > self.write_buffer.capacity() {
@@ -719,8 +732,9 @@ impl Terminal for WindowsTerminal {
     }
 
     fn enter_alternate_screen(&mut self) -> Result<()> {
-        if matches!(&self.renderer, Renderer::Terminfo(_)) {
+        if let Renderer::Terminfo(r) = &mut self.renderer {
             if !self.in_alternate_screen {
+                r.invalidate_terminal_state();
                 write!(
                     self.output_handle,
                     "{}",
@@ -739,8 +753,9 @@ impl Terminal for WindowsTerminal {
 
     fn exit_alternate_screen(&mut self) -> Result<()> {
         // TODO: Implement using SetConsoleActiveScreenBuffer.
-        if matches!(&self.renderer, Renderer::Terminfo(_)) {
+        if let Renderer::Terminfo(r) = &mut self.renderer {
             if self.in_alternate_screen {
+                r.invalidate_terminal_state();
                 write!(
                     self.output_handle,
                     "{}",
@@ -776,6 +791,9 @@ impl Terminal for WindowsTerminal {
             X: cast(size.cols)?,
             Y: cast(size.rows)?,
         };
+        if let Renderer::Terminfo(r) = &mut self.renderer {
+            r.invalidate_terminal_state();
+        }
         let handle = self.output_handle.handle.as_raw_handle();
         if unsafe { SetConsoleScreenBufferSize(handle as *mut _, size) } != 1 {
             bail!(
@@ -844,6 +862,85 @@ impl Terminal for WindowsTerminal {
         }
     }
 
+    #[cfg(feature = "use_async_io")]
+    fn render_async<'a>(
+        &'a mut self,
+        changes: &'a [Change],
+    ) -> crate::terminal::TerminalFuture<'a, ()> {
+        Box::pin(async move {
+            use crate::Context;
+
+            let renderer = match &mut self.renderer {
+                Renderer::Terminfo(r) => r,
+                // The console renderer drives the console APIs directly
+                // rather than producing output that can be written later
+                Renderer::Windows(r) => {
+                    r.render_to(changes, &mut self.output_handle)?;
+                    return self.output_handle.flush().context("flush failed");
+                }
+            };
+
+            // Anything left over from an earlier `render` must go first
+            let pending = mem::take(&mut self.output_handle.write_buffer);
+            let mut buffer = crate::terminal::RenderBuffer::new(
+                pending,
+                self.output_handle.get_size_in_cells()?,
+            );
+            renderer.render_to(changes, &mut buffer)?;
+            if buffer.output.is_empty() {
+                return Ok(());
+            }
+
+            let mut handle = self.output_handle.handle.try_clone()?;
+            blocking::unblock(move || {
+                handle.write_all(&buffer.output)?;
+                handle.flush()
+            })
+            .await
+            .context("failed to write to console")
+        })
+    }
+
+    #[cfg(feature = "use_async_io")]
+    fn poll_input_async(&mut self) -> crate::terminal::PollInputFuture<'_> {
+        Box::pin(async move {
+            use crate::Context;
+
+            loop {
+                if let Some(event) = self.poll_input(Some(Duration::ZERO))? {
+                    return Ok(event);
+                }
+
+                // Console handles cannot be registered with the reactor,
+                // so wait for them in a helper thread instead.  The handles
+                // are duplicated so that they remain valid for that thread
+                // even if this future is dropped while it is waiting, in
+                // which case `cancel` is signalled to end the wait.
+                let input = self.input_handle.handle.try_clone()?;
+                let waker = self.waker_handle.handle.try_clone()?;
+                let cancel = CancelOnDrop(Arc::new(EventHandle::new()?));
+                let cancel_event = Arc::clone(&cancel.0);
+                blocking::unblock(move || {
+                    let mut handles = [
+                        input.as_raw_handle() as *mut _,
+                        waker.as_raw_handle() as *mut _,
+                        cancel_event.handle.as_raw_handle() as *mut _,
+                    ];
+                    let result =
+                        unsafe { WaitForMultipleObjects(3, handles.as_mut_ptr(), 0, INFINITE) };
+                    if result == WAIT_FAILED {
+                        Err(IoError::last_os_error())
+                    } else {
+                        Ok(())
+                    }
+                })
+                .await
+                .context("failed to WaitForMultipleObjects")?;
+                drop(cancel);
+            }
+        })
+    }
+
     fn waker(&self) -> WindowsTerminalWaker {
         WindowsTerminalWaker {
             handle: self.waker_handle.clone(),
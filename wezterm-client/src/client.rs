@@ -44,6 +44,7 @@ enum ReaderMessage {
         pdu: Pdu,
         promise: Sender<anyhow::Result<Pdu>>,
     },
+    SubscribeUnilateral(Sender<Pdu>),
     Readable,
 }
 
@@ -277,6 +278,10 @@ async fn client_thread_async(
         map: HashMap::new(),
     };
 
+    // When there is no local domain to receive them, unilateral PDUs
+    // can be routed to a subscriber instead; see Client::subscribe_unilateral
+    let mut unilateral: Option<Sender<Pdu>> = None;
+
     let mut stream = reconnectable.take_stream().unwrap();
 
     loop {
@@ -296,11 +301,19 @@ async fn client_thread_async(
                     .context("encoding a PDU to send to the server")?;
                 stream.flush().await.context("flushing PDU to server")?;
             }
+            Ok(ReaderMessage::SubscribeUnilateral(sender)) => {
+                unilateral.replace(sender);
+            }
             Ok(ReaderMessage::Readable) => {
                 match Pdu::decode_async(&mut stream, Some(next_serial)).await {
                     Ok(decoded) => {
                         log::trace!("decoded serial {}", decoded.serial);
-                        if decoded.serial == 0 {
+                        if let (0, Some(sender)) = (decoded.serial, unilateral.as_ref()) {
+                            if sender.send(decoded.pdu).await.is_err() {
+                                // The subscriber went away; stop routing to it
+                                unilateral.take();
+                            }
+                        } else if decoded.serial == 0 {
                             process_unilateral(local_domain_id, decoded)
                                 .context("processing unilateral PDU from server")
                                 .map_err(|e| {
@@ -565,6 +578,18 @@ impl Reconnectable {
         let ssh_config = mux::ssh::ssh_domain_to_ssh_config(&ssh_dom)?;
 
         let sess = ssh_connect_with_ui(ssh_config, ui)?;
+
+        // Panes are spawned by the mux server on the remote host, so the
+        // entry needs to be there for them; it only needs installing once
+        if initial && ssh_dom.install_terminfo && configuration().term == "wezterm" {
+            if let Err(err) = mux::ssh::install_wezterm_terminfo(&sess) {
+                ui.output_str(&format!(
+                    "Failed to install the wezterm terminfo entry: {:#}\n",
+                    err
+                ));
+            }
+        }
+
         let proxy_bin = Self::wezterm_bin_path(&ssh_dom.remote_wezterm_path);
 
         let cmd = if initial {
@@ -1085,6 +1110,22 @@ impl Client {
         }
     }
 
+    /// For a client that isn't associated with a local domain, returns
+    /// a channel that receives the PDUs that the server sends unilaterally,
+    /// such as pane render changes, instead of discarding them.
+    pub async fn subscribe_unilateral(&self) -> anyhow::Result<Receiver<Pdu>> {
+        if self.local_domain_id.is_some() {
+            bail!("unilateral PDUs are already processed by the local domain");
+        }
+        let (tx, rx) = unbounded();
+        self.sender
+            .send(ReaderMessage::SubscribeUnilateral(tx))
+            .await
+            .map_err(|_| ChannelSendError)
+            .context("subscribing to unilateral PDUs")?;
+        Ok(rx)
+    }
+
     #[allow(dead_code)]
     pub fn local_domain_id(&self) -> Option<DomainId> {
         self.local_domain_id
@@ -1197,6 +1238,9 @@ impl Client {
     rpc!(mouse_event, SendMouseEvent, UnitResponse);
     rpc!(resize, Resize, UnitResponse);
     rpc!(set_zoomed, SetPaneZoomed, UnitResponse);
+    rpc!(set_output_paused, SetPaneOutputPaused, UnitResponse);
+    rpc!(set_tab_title, SetTabTitle, UnitResponse);
+    rpc!(rename_workspace, RenameWorkspace, UnitResponse);
     rpc!(
         get_pane_render_changes,
         GetPaneRenderChanges,
@@ -2,7 +2,7 @@ use crate::client::Client;
 use crate::pane::ClientPane;
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
-use codec::{ListPanesResponse, SpawnV2, SplitPane};
+use codec::{ListPanesResponse, RenameWorkspace, SetTabTitle, SpawnV2, SplitPane};
 use config::keyassignment::SpawnTabDomain;
 use config::{SshDomain, TlsDomainClient, UnixDomain};
 use mux::connui::{ConnectionUI, ConnectionUIParams};
@@ -99,6 +99,16 @@ impl ClientInner {
         );
     }
 
+    pub fn local_to_remote_tab_id(&self, local_tab_id: TabId) -> Option<TabId> {
+        let map = self.remote_to_local_tab.lock().unwrap();
+        for (remote, local) in map.iter() {
+            if *local == local_tab_id {
+                return Some(*remote);
+            }
+        }
+        None
+    }
+
     pub fn remote_to_local_tab_id(&self, remote_tab_id: TabId) -> Option<TabId> {
         let map = self.remote_to_local_tab.lock().unwrap();
         for (remote, local) in map.iter() {
@@ -328,7 +338,7 @@ impl ClientDomain {
         let mux = Mux::get().expect("to be called on main thread");
         log::debug!("ListPanes result {:#?}", panes);
 
-        for tabroot in panes.tabs {
+        for (tab_idx, tabroot) in panes.tabs.into_iter().enumerate() {
             let root_size = match tabroot.root_size() {
                 Some(size) => size,
                 None => continue,
@@ -356,6 +366,12 @@ impl ClientDomain {
                     inner.record_remote_to_local_tab_mapping(remote_tab_id, tab.tab_id());
                 }
 
+                if let Some(title) = panes.tab_titles.get(tab_idx) {
+                    if *title != tab.get_title() {
+                        tab.set_title(title);
+                    }
+                }
+
                 log::debug!("tree: {:#?}", tabroot);
                 let mut workspace = None;
                 tab.sync_with_pane_tree(root_size, tabroot, |entry| {
@@ -682,6 +698,54 @@ impl Domain for ClientDomain {
         }
     }
 
+    fn local_workspace_renamed(&self, old_name: &str, new_name: &str) {
+        let inner = match self.inner() {
+            Some(inner) => inner,
+            None => return,
+        };
+        // Only rename the remote workspace if it holds some of our windows
+        let mux = Mux::get().unwrap();
+        if !mux
+            .iter_windows_in_workspace(new_name)
+            .into_iter()
+            .any(|window_id| inner.local_to_remote_window(window_id).is_some())
+        {
+            return;
+        }
+        let request = RenameWorkspace {
+            old_workspace: old_name.to_string(),
+            new_workspace: new_name.to_string(),
+        };
+        promise::spawn::spawn(async move {
+            if let Err(err) = inner.client.rename_workspace(request).await {
+                log::error!("Failed to rename remote workspace: {:#}", err);
+            }
+        })
+        .detach();
+    }
+
+    fn local_tab_title_changed(&self, tab_id: TabId, title: &str) {
+        let inner = match self.inner() {
+            Some(inner) => inner,
+            None => return,
+        };
+        let remote_tab_id = match inner.local_to_remote_tab_id(tab_id) {
+            Some(id) => id,
+            None => return,
+        };
+        let title = title.to_string();
+        promise::spawn::spawn(async move {
+            inner
+                .client
+                .set_tab_title(SetTabTitle {
+                    tab_id: remote_tab_id,
+                    title,
+                })
+                .await
+        })
+        .detach();
+    }
+
     fn detach(&self) -> anyhow::Result<()> {
         self.perform_detach();
         Ok(())
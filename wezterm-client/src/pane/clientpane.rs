@@ -37,6 +37,7 @@ pub struct ClientPane {
     mouse_grabbed: RefCell<bool>,
     ignore_next_kill: RefCell<bool>,
     user_vars: RefCell<HashMap<String, String>>,
+    output_paused: RefCell<bool>,
 }
 
 impl ClientPane {
@@ -94,6 +95,7 @@ impl ClientPane {
             mouse_grabbed: RefCell::new(false),
             ignore_next_kill: RefCell::new(false),
             user_vars: RefCell::new(HashMap::new()),
+            output_paused: RefCell::new(false),
         }
     }
 
@@ -278,6 +280,26 @@ impl Pane for ClientPane {
         inner.update_last_send();
     }
 
+    fn set_output_paused(&self, paused: bool) {
+        *self.output_paused.borrow_mut() = paused;
+        let client = Arc::clone(&self.client);
+        let remote_pane_id = self.remote_pane_id;
+        promise::spawn::spawn(async move {
+            client
+                .client
+                .set_output_paused(SetPaneOutputPaused {
+                    pane_id: remote_pane_id,
+                    paused,
+                })
+                .await
+        })
+        .detach();
+    }
+
+    fn is_output_paused(&self) -> bool {
+        *self.output_paused.borrow()
+    }
+
     fn resize(&self, size: PtySize) -> anyhow::Result<()> {
         let render = self.renderable.borrow();
         let mut inner = render.inner.borrow_mut();
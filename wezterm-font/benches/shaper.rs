@@ -0,0 +1,50 @@
+use config::FontAttributes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wezterm_font::db::FontDatabase;
+use wezterm_font::shaper::harfbuzz::HarfbuzzShaper;
+use wezterm_font::shaper::{Direction, FontShaper};
+
+fn shape(c: &mut Criterion) {
+    let db = FontDatabase::with_built_in().unwrap();
+    let handle = db
+        .resolve(&FontAttributes::new("JetBrains Mono"), 14)
+        .unwrap()
+        .clone();
+    let config = config::configuration();
+    let shaper = HarfbuzzShaper::new(&config, &[handle]).unwrap();
+
+    let mut group = c.benchmark_group("shaper");
+    for (name, text) in [
+        (
+            "ascii",
+            "the quick brown fox jumps over the lazy dog ".repeat(4),
+        ),
+        (
+            "ligatures",
+            "a -> b => c != d <= e >= f :: g === h ".repeat(4),
+        ),
+        ("emoji", "👨‍👩‍👧‍👦 ❤️ 🇺🇸 e\u{301} ".repeat(8)),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut no_glyphs = vec![];
+                shaper
+                    .shape(
+                        black_box(&text),
+                        10.,
+                        72,
+                        &mut no_glyphs,
+                        None,
+                        Direction::LeftToRight,
+                        None,
+                        None,
+                    )
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, shape);
+criterion_main!(benches);
@@ -44,6 +44,36 @@ fn ft_result<T>(err: FT_Error, t: T) -> anyhow::Result<T> {
     }
 }
 
+/// Parses a variation specification like "wght=450" into the
+/// axis tag and its 16.16 fixed point design coordinate
+fn parse_variation(spec: &str) -> anyhow::Result<(FT_ULong, FT_Fixed)> {
+    let (tag, value) = spec
+        .split_once('=')
+        .ok_or_else(|| anyhow!("invalid variation {:?}; expected tag=value", spec))?;
+    let tag = tag.trim().as_bytes();
+    if tag.len() != 4 {
+        anyhow::bail!(
+            "invalid variation axis tag in {:?}; expected 4 characters",
+            spec
+        );
+    }
+    let tag = tag
+        .iter()
+        .fold(0 as FT_ULong, |acc, &b| (acc << 8) | b as FT_ULong);
+    let value: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid variation value in {:?}", spec))?;
+    Ok((tag, (value * 65536.0) as FT_Fixed))
+}
+
+fn tag_to_string(tag: FT_ULong) -> String {
+    (0..4)
+        .rev()
+        .map(|shift| ((tag >> (shift * 8)) & 0xff) as u8 as char)
+        .collect()
+}
+
 fn render_mode_to_load_target(render_mode: FT_Render_Mode) -> u32 {
     // enable FT_LOAD_TARGET bits.  There are no flags defined
     // for these in the bindings so we do some bit magic for
@@ -314,6 +344,16 @@ impl Face {
         }
     }
 
+    /// Returns the position of the top of the strikeout stroke,
+    /// relative to the baseline and in font units, if known
+    pub fn strikeout_position(&self) -> Option<i16> {
+        let os2 = self.get_os2_table()?;
+        if os2.yStrikeoutPosition == 0 {
+            return None;
+        }
+        Some(os2.yStrikeoutPosition)
+    }
+
     pub fn weight_and_width(&self) -> (u16, u16) {
         let (mut weight, mut width) = self
             .get_os2_table()
@@ -611,6 +651,60 @@ impl Face {
         ft_result(unsafe { FT_Select_Size(self.face, idx as i32) }, ()).context("FT_Select_Size")
     }
 
+    /// Applies design coordinates to the axes of a variable font.
+    /// Each entry is of the form "wght=450", naming the axis by its
+    /// four character tag.  Axes that are not mentioned keep their
+    /// current value, which reflects the named instance that was
+    /// selected when the face was loaded, and out of range values
+    /// are clamped.
+    pub fn set_variations(&mut self, variations: &[String]) -> anyhow::Result<()> {
+        let mut wanted = vec![];
+        for v in variations {
+            wanted.push(parse_variation(v)?);
+        }
+
+        let mut mm = std::ptr::null_mut();
+        unsafe {
+            ft_result(FT_Get_MM_Var(self.face, &mut mm), ())
+                .context("FT_Get_MM_Var: font has no variation axes")?;
+
+            let axes = std::slice::from_raw_parts((*mm).axis, (*mm).num_axis as usize);
+            let mut coords: Vec<FT_Fixed> = vec![0; axes.len()];
+            if FT_Get_Var_Design_Coordinates(
+                self.face,
+                coords.len() as FT_UInt,
+                coords.as_mut_ptr(),
+            ) != 0
+            {
+                coords = axes.iter().map(|axis| axis.def).collect();
+            }
+            for (tag, value) in &wanted {
+                match axes.iter().position(|axis| axis.tag == *tag) {
+                    Some(idx) => {
+                        coords[idx] = (*value).max(axes[idx].minimum).min(axes[idx].maximum);
+                    }
+                    None => log::warn!(
+                        "variation axis {} not present in {:?}",
+                        tag_to_string(*tag),
+                        self.source
+                    ),
+                }
+            }
+
+            FT_Done_MM_Var(self.lib, mm);
+
+            ft_result(
+                FT_Set_Var_Design_Coordinates(
+                    self.face,
+                    coords.len() as FT_UInt,
+                    coords.as_mut_ptr(),
+                ),
+                (),
+            )
+            .context("FT_Set_Var_Design_Coordinates")
+        }
+    }
+
     pub fn set_transform(&mut self, matrix: Option<FT_Matrix>) {
         let mut matrix = matrix.clone();
         unsafe {
@@ -15,7 +15,7 @@ use std::ops::Range;
 use std::rc::{Rc, Weak};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use termwiz::cell::Presentation;
 use thiserror::Error;
 use wezterm_bidi::Direction;
@@ -215,8 +215,15 @@ impl LoadedFont {
 
         if !no_glyphs.is_empty() {
             if let Some(font_config) = self.font_config.upgrade() {
+                let consulted = self
+                    .handles
+                    .borrow()
+                    .iter()
+                    .map(|p| p.names().full_name.clone())
+                    .collect();
                 font_config.schedule_fallback_resolve(
                     no_glyphs,
+                    consulted,
                     &self.pending_fallback,
                     completion,
                 );
@@ -247,6 +254,16 @@ impl LoadedFont {
         }
     }
 
+    /// Returns true if `font_idx` is the built-in Last Resort font,
+    /// which only supplies glyphs that no other font has
+    pub fn is_last_resort(&self, font_idx: usize) -> bool {
+        self.handles
+            .borrow()
+            .get(font_idx)
+            .map(|p| p.names().family == "Last Resort High-Efficiency")
+            .unwrap_or(false)
+    }
+
     pub fn rasterize_glyph(
         &self,
         glyph_pos: u32,
@@ -274,8 +291,36 @@ impl LoadedFont {
     }
 }
 
+lazy_static::lazy_static! {
+    /// When we last warned about each missing codepoint
+    static ref MISSING_GLYPH_WARNINGS: Mutex<HashMap<u32, Instant>> = Mutex::new(HashMap::new());
+}
+
+/// Returns those of the `missing` codepoints that haven't been warned
+/// about recently, and records that they are being warned about now.
+/// Each codepoint is warned about at most once per minute, so that a
+/// new missing glyph is reported even while an old one keeps showing up.
+fn missing_glyphs_to_warn_about(missing: &RangeSet<u32>) -> Vec<u32> {
+    const MIN_INTERVAL: Duration = Duration::from_secs(60);
+    let mut warnings = MISSING_GLYPH_WARNINGS.lock().unwrap();
+    let now = Instant::now();
+    missing
+        .iter_values()
+        .filter(|c| match warnings.get(c) {
+            Some(prior) if now.duration_since(*prior) < MIN_INTERVAL => false,
+            _ => {
+                warnings.insert(*c, now);
+                true
+            }
+        })
+        .collect()
+}
+
 struct FallbackResolveInfo {
     no_glyphs: Vec<char>,
+    /// The names of the fonts in the fallback chain that were
+    /// unable to supply the glyphs
+    consulted: Vec<String>,
     pending: Arc<Mutex<Vec<ParsedFont>>>,
     completion: Box<dyn FnOnce() + Send>,
     font_dirs: Arc<FontDatabase>,
@@ -380,16 +425,34 @@ impl FallbackResolveInfo {
                 .map(|c| std::char::from_u32(c).unwrap_or(' '))
                 .collect::<String>();
 
-            if self.config.warn_about_missing_glyphs {
+            let to_warn = if self.config.warn_about_missing_glyphs {
+                missing_glyphs_to_warn_about(&wanted)
+            } else {
+                vec![]
+            };
+
+            if !to_warn.is_empty() {
                 let url = "https://wezfurlong.org/wezterm/config/fonts.html";
+                let codepoints = to_warn
+                    .iter()
+                    .map(|c| format!("U+{:04X}", c))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let fallback_str = to_warn
+                    .iter()
+                    .map(|&c| std::char::from_u32(c).unwrap_or(' '))
+                    .collect::<String>();
                 log::warn!(
-                    "No fonts contain glyphs for these codepoints: {}.\n\
-                     Placeholder 'Last Resort' glyphs are being displayed instead.\n\
+                    "No fonts contain glyphs for these codepoints: {} ({}).\n\
+                     Fonts consulted: {}, plus any system fallback fonts.\n\
+                     Placeholder boxes showing the codepoints are being displayed instead.\n\
                      You may wish to install additional fonts, or adjust your\n\
                      configuration so that it can find them.\n\
                      {} has more information about configuring fonts.\n\
                      Set warn_about_missing_glyphs=false to suppress this message.",
+                    codepoints,
                     fallback_str.escape_unicode(),
+                    self.consulted.join(", "),
                     url,
                 );
 
@@ -410,8 +473,9 @@ impl FallbackResolveInfo {
                 .show();
             } else {
                 log::debug!(
-                    "No fonts contain glyphs for these codepoints: {}",
-                    fallback_str.escape_unicode()
+                    "No fonts contain glyphs for these codepoints: {}; consulted {:?}",
+                    fallback_str.escape_unicode(),
+                    self.consulted
                 );
             }
         }
@@ -424,7 +488,7 @@ struct FontConfigInner {
     dpi: RefCell<usize>,
     font_scale: RefCell<f64>,
     config: RefCell<ConfigHandle>,
-    locator: Arc<dyn FontLocator + Send + Sync>,
+    locator: RefCell<Arc<dyn FontLocator + Send + Sync>>,
     font_dirs: RefCell<Arc<FontDatabase>>,
     built_in: RefCell<Arc<FontDatabase>>,
     no_glyphs: RefCell<HashSet<char>>,
@@ -445,7 +509,7 @@ impl FontConfigInner {
         let locator = new_locator(config.font_locator);
         Ok(Self {
             fonts: RefCell::new(HashMap::new()),
-            locator,
+            locator: RefCell::new(locator),
             metrics: RefCell::new(None),
             title_font: RefCell::new(None),
             pane_select_font: RefCell::new(None),
@@ -461,6 +525,11 @@ impl FontConfigInner {
 
     fn config_changed(&self, config: &ConfigHandle) -> anyhow::Result<()> {
         let mut fonts = self.fonts.borrow_mut();
+        if self.config.borrow().font_locator != config.font_locator {
+            // Allow switching to a different locator without restarting,
+            // which is useful when working around platform specific issues
+            *self.locator.borrow_mut() = new_locator(config.font_locator);
+        }
         *self.config.borrow_mut() = config.clone();
         // Config was reloaded, invalidate our caches
         fonts.clear();
@@ -475,6 +544,7 @@ impl FontConfigInner {
     fn schedule_fallback_resolve<F: FnOnce() + Send + 'static>(
         &self,
         mut no_glyphs: Vec<char>,
+        consulted: Vec<String>,
         pending: &Arc<Mutex<Vec<ParsedFont>>>,
         completion: F,
     ) {
@@ -490,10 +560,11 @@ impl FontConfigInner {
         let info = FallbackResolveInfo {
             completion: Box::new(completion),
             no_glyphs,
+            consulted,
             pending: Arc::clone(pending),
             font_dirs: Arc::clone(&*self.font_dirs.borrow()),
             built_in: Arc::clone(&*self.built_in.borrow()),
-            locator: Arc::clone(&self.locator),
+            locator: Arc::clone(&*self.locator.borrow()),
             config: self.config.borrow().clone(),
         };
 
@@ -653,9 +724,10 @@ impl FontConfigInner {
             }
 
             let mut loaded_ignored = HashSet::new();
-            let located = self
-                .locator
-                .load_fonts(attrs, &mut loaded_ignored, pixel_size)?;
+            let located =
+                self.locator
+                    .borrow()
+                    .load_fonts(attrs, &mut loaded_ignored, pixel_size)?;
             for font in &located {
                 candidates.push(font);
             }
@@ -711,6 +783,12 @@ impl FontConfigInner {
             }
         }
 
+        let config = self.config.borrow();
+        for handle in &mut handles {
+            handle.synthesize_bold &= config.allow_synthetic_bold;
+            handle.synthesize_italic &= config.allow_synthetic_italic;
+        }
+
         Ok((handles, loaded))
     }
 
@@ -1005,7 +1083,7 @@ impl FontConfiguration {
     }
 
     pub fn list_system_fonts(&self) -> anyhow::Result<Vec<ParsedFont>> {
-        self.inner.locator.enumerate_all_fonts()
+        self.inner.locator.borrow().enumerate_all_fonts()
     }
 
     /// Apply the defined font_rules from the user configuration to
@@ -28,6 +28,7 @@ pub struct ParsedFont {
     pub pixel_sizes: Vec<u16>,
 
     pub harfbuzz_features: Option<Vec<String>>,
+    pub variations: Option<Vec<String>>,
     pub freetype_load_target: Option<FreeTypeLoadTarget>,
     pub freetype_render_target: Option<FreeTypeLoadTarget>,
     pub freetype_load_flags: Option<FreeTypeLoadFlags>,
@@ -49,6 +50,7 @@ impl std::fmt::Debug for ParsedFont {
             .field("assume_emoji_presentation", &self.assume_emoji_presentation)
             .field("pixel_sizes", &self.pixel_sizes)
             .field("harfbuzz_features", &self.harfbuzz_features)
+            .field("variations", &self.variations)
             .field("freetype_load_target", &self.freetype_load_target)
             .field("freetype_render_target", &self.freetype_render_target)
             .field("freetype_load_flags", &self.freetype_load_flags)
@@ -73,6 +75,7 @@ impl Clone for ParsedFont {
             coverage: Mutex::new(self.coverage.lock().unwrap().clone()),
             pixel_sizes: self.pixel_sizes.clone(),
             harfbuzz_features: self.harfbuzz_features.clone(),
+            variations: self.variations.clone(),
             freetype_load_target: self.freetype_load_target,
             freetype_render_target: self.freetype_render_target,
             freetype_load_flags: self.freetype_load_flags,
@@ -307,6 +310,7 @@ impl ParsedFont {
                 && p.freetype_load_target.is_none()
                 && p.freetype_load_flags.is_none()
                 && p.harfbuzz_features.is_none()
+                && p.variations.is_none()
                 && p.scale.is_none()
             {
                 code.push_str(&format!("  \"{}\",\n", p.names.family));
@@ -345,6 +349,18 @@ impl ParsedFont {
                     }
                     code.push('}');
                 }
+                if let Some(vars) = &p.variations {
+                    code.push_str(", variations={");
+                    for (idx, v) in vars.iter().enumerate() {
+                        if idx > 0 {
+                            code.push_str(", ");
+                        }
+                        code.push('"');
+                        code.push_str(v);
+                        code.push('"');
+                    }
+                    code.push('}');
+                }
                 code.push_str("},\n")
             }
             code.push_str("\n");
@@ -409,6 +425,7 @@ impl ParsedFont {
             cap_height,
             pixel_sizes,
             harfbuzz_features: None,
+            variations: None,
             freetype_render_target: None,
             freetype_load_target: None,
             freetype_load_flags: None,
@@ -650,6 +667,7 @@ impl ParsedFont {
     /// italic for this font.
     pub fn synthesize(mut self, attr: &FontAttributes) -> Self {
         self.harfbuzz_features = attr.harfbuzz_features.clone();
+        self.variations = attr.variations.clone();
         self.freetype_render_target = attr.freetype_render_target;
         self.freetype_load_target = attr.freetype_load_target;
         self.freetype_load_flags = attr.freetype_load_flags;
@@ -299,6 +299,11 @@ impl FreeTypeRasterizer {
         log::trace!("Rasterizier wants {:?}", parsed);
         let lib = ftwrap::Library::new()?;
         let mut face = lib.face_from_locator(&parsed.handle)?;
+        if let Some(variations) = &parsed.variations {
+            if let Err(err) = face.set_variations(variations) {
+                log::warn!("{:?}: {:#}", parsed.handle, err);
+            }
+        }
         let has_color = unsafe {
             (((*face.face).face_flags as u32) & (ftwrap::FT_FACE_FLAG_COLOR as u32)) != 0
         };
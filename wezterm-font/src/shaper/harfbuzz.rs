@@ -137,7 +137,12 @@ impl HarfbuzzShaper {
                 if opt_pair.is_none() {
                     let handle = &self.handles[font_idx];
                     log::trace!("shaper wants {} {:?}", font_idx, handle);
-                    let face = self.lib.face_from_locator(&handle.handle)?;
+                    let mut face = self.lib.face_from_locator(&handle.handle)?;
+                    if let Some(variations) = &handle.variations {
+                        if let Err(err) = face.set_variations(variations) {
+                            log::warn!("{:?}: {:#}", handle.handle, err);
+                        }
+                    }
                     let mut font = harfbuzz::Font::new(face.face);
                     let (load_flags, _) = ftwrap::compute_load_flags_from_config(
                         handle.freetype_load_flags,
@@ -593,6 +598,13 @@ impl FontShaper for HarfbuzzShaper {
             underline_position: PixelLength::new(
                 unsafe { (*pair.face.face).underline_position as f64 } * y_scale / 64.,
             ),
+            strikethrough_position: pair
+                .face
+                .strikeout_position()
+                .map(|pos| PixelLength::new(pos as f64 * y_scale / 64.)),
+            ascender: PixelLength::new(
+                unsafe { (*(*pair.face.face).size).metrics.ascender as f64 } / 64.0,
+            ),
             cap_height_ratio: selected_size.cap_height_to_height_ratio,
             cap_height: selected_size.cap_height.map(PixelLength::new),
             is_scaled: selected_size.is_scaled,
@@ -701,6 +713,7 @@ mod test {
                     freetype_load_target: None,
                     freetype_render_target: None,
                     harfbuzz_features: None,
+                    variations: None,
                     scale: None,
                 },
                 14,
@@ -61,6 +61,14 @@ pub struct FontMetrics {
     /// values are below the descender.
     pub underline_position: PixelLength,
 
+    /// Position of the top of the strikethrough relative to the
+    /// baseline, if specified by the font.  Positive values are
+    /// above the baseline.
+    pub strikethrough_position: Option<PixelLength>,
+
+    /// Distance from the baseline to the top of the tallest glyphs
+    pub ascender: PixelLength,
+
     /// Fraction of the EM square occupied by the cap height
     pub cap_height_ratio: Option<f64>,
     pub cap_height: Option<PixelLength>,
@@ -226,6 +226,13 @@ pub struct SshCommand {
     #[clap(short = 'v')]
     pub verbose: bool,
 
+    /// Install the wezterm terminfo entry into `~/.terminfo` on the
+    /// remote host, if it isn't already present there.
+    /// This only has an effect when `term = "wezterm"` is set in
+    /// your configuration.
+    #[clap(long = "install-terminfo")]
+    pub install_terminfo: bool,
+
     /// Override the default windowing system class.
     /// The default is "org.wezfurlong.wezterm".
     /// Under X11 and Windows this changes the window class.
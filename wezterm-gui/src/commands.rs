@@ -210,6 +210,20 @@ static DEFS: &[CommandDef] = &[
         keys: &[(Modifiers::SUPER, "c"), (Modifiers::NONE, "Copy")],
         args: &[ArgType::ActivePane],
     },
+    CommandDef {
+        brief: "Copy to clipboard as HTML",
+        doc: "Copies the selection to the clipboard as HTML, preserving colors and hyperlinks",
+        exp: |exp| exp.push(CopyAsHtmlTo(ClipboardCopyDestination::Clipboard)),
+        keys: &[],
+        args: &[ArgType::ActivePane],
+    },
+    CommandDef {
+        brief: "Copy to clipboard with escape sequences",
+        doc: "Copies the selection to the clipboard, including the escape sequences needed to reproduce its colors",
+        exp: |exp| exp.push(CopyWithEscapesTo(ClipboardCopyDestination::Clipboard)),
+        keys: &[],
+        args: &[ArgType::ActivePane],
+    },
     CommandDef {
         brief: "Paste from clipboard",
         doc: "Pastes text from the clipboard",
@@ -235,6 +249,15 @@ static DEFS: &[CommandDef] = &[
         keys: &[(Modifiers::SUPER, "m")],
         args: &[ArgType::ActiveWindow],
     },
+    CommandDef {
+        brief: "Request attention",
+        doc: "Asks the window manager to draw the user's attention to the window",
+        exp: |exp| {
+            exp.push(RequestAttention);
+        },
+        keys: &[],
+        args: &[ArgType::ActiveWindow],
+    },
     #[cfg(target_os = "macos")]
     CommandDef {
         brief: "Hide Application (macOS only)",
@@ -302,6 +325,24 @@ static DEFS: &[CommandDef] = &[
         keys: &[(Modifiers::CTRL.union(Modifiers::SHIFT), "Space")],
         args: &[ArgType::ActivePane],
     },
+    CommandDef {
+        brief: "Enter Emoji / Character selection mode",
+        doc: "Activates the character selection UI for the current pane",
+        exp: |exp| {
+            exp.push(CharSelect);
+        },
+        keys: &[(Modifiers::CTRL.union(Modifiers::SHIFT), "u")],
+        args: &[ArgType::ActivePane],
+    },
+    CommandDef {
+        brief: "Search command history",
+        doc: "Activates the command history UI for the current pane",
+        exp: |exp| {
+            exp.push(ShowCommandHistory);
+        },
+        keys: &[],
+        args: &[ArgType::ActivePane],
+    },
     CommandDef {
         brief: "Decrease font size",
         doc: "Scales the font size smaller by 10%",
@@ -430,6 +471,35 @@ static DEFS: &[CommandDef] = &[
         keys: &[(Modifiers::SUPER, "w")],
         args: &[ArgType::ActiveTab],
     },
+    CommandDef {
+        brief: "Close other tabs",
+        doc: "Closes all of the tabs in the window except for the current tab, \
+            terminating all the processes that are running in their panes.",
+        exp: |exp| {
+            exp.push(CloseOtherTabs { confirm: true });
+        },
+        keys: &[],
+        args: &[ArgType::ActiveTab],
+    },
+    CommandDef {
+        brief: "Rename workspace",
+        doc: "Prompts for a new name for the current workspace",
+        exp: |exp| {
+            exp.push(RenameWorkspace);
+        },
+        keys: &[],
+        args: &[ArgType::ActiveWindow],
+    },
+    CommandDef {
+        brief: "Rename tab",
+        doc: "Prompts for a new title for the current tab. \
+            Entering an empty title reverts to the default title.",
+        exp: |exp| {
+            exp.push(RenameTab);
+        },
+        keys: &[],
+        args: &[ArgType::ActiveTab],
+    },
     CommandDef {
         brief: "Activate the tab to the left",
         doc: "Activates the tab to the left. If this is the left-most \
@@ -649,6 +719,15 @@ static DEFS: &[CommandDef] = &[
         keys: &[(Modifiers::CTRL.union(Modifiers::SHIFT), "z")],
         args: &[ArgType::ActivePane],
     },
+    CommandDef {
+        brief: "Toggle Pane Output Pause",
+        doc: "Pauses or resumes processing of output for the current pane",
+        exp: |exp| {
+            exp.push(TogglePaneOutputPaused);
+        },
+        keys: &[],
+        args: &[ArgType::ActivePane],
+    },
     CommandDef {
         brief: "Activate the last active tab",
         doc: "If there was no prior active tab, has no effect.",
@@ -684,6 +763,14 @@ static DEFS: &[CommandDef] = &[
         keys: &[],
         args: &[ArgType::ActivePane],
     },
+    CommandDef {
+        brief: "Copy link address at mouse cursor",
+        doc: "Copies the URL of the link under the mouse cursor to the clipboard. \
+              If there is no link under the mouse cursor, has no effect.",
+        exp: |exp| exp.push(CopyLinkAtMouseCursor(ClipboardCopyDestination::Clipboard)),
+        keys: &[],
+        args: &[ArgType::ActivePane],
+    },
     CommandDef {
         brief: "Reset the window and font size",
         doc: "Restores the original window and font size",
@@ -159,6 +159,13 @@ pub enum BlockKey {
         underline_height: IntPixelLength,
         cell_size: Size,
     },
+
+    /// A box showing the hex value of a codepoint that no font
+    /// has a glyph for, spanning `cells` cells
+    Tofu {
+        codepoint: u32,
+        cells: u8,
+    },
 }
 
 /// Filled polygon used to describe the more complex shapes in
@@ -3697,7 +3704,17 @@ impl<T: Texture2d> GlyphCache<T> {
             return Ok(sprite.clone());
         }
 
-        let metrics = metrics.scale_cell_width(width as f64);
+        let mut metrics = metrics.scale_cell_width(width as f64);
+
+        // The cursor outline is stroked along the edges of the cell,
+        // so only half of the stroke width falls inside the cell
+        let outline = match metrics.cursor_thickness {
+            Some(thickness) => {
+                metrics.underline_height = thickness * 2;
+                PolyStyle::Outline
+            }
+            None => PolyStyle::OutlineHeavy,
+        };
 
         let mut buffer = Image::new(
             metrics.cell_size.width as usize,
@@ -3724,7 +3741,7 @@ impl<T: Texture2d> GlyphCache<T> {
                             PolyCommand::LineTo(BlockCoord::Zero, BlockCoord::Zero),
                         ],
                         intensity: BlockAlpha::Full,
-                        style: PolyStyle::OutlineHeavy,
+                        style: outline,
                     }],
                     &mut buffer,
                     PolyAA::AntiAlias,
@@ -3739,7 +3756,7 @@ impl<T: Texture2d> GlyphCache<T> {
                             PolyCommand::LineTo(BlockCoord::Zero, BlockCoord::One),
                         ],
                         intensity: BlockAlpha::Full,
-                        style: PolyStyle::OutlineHeavy,
+                        style: outline,
                     }],
                     &mut buffer,
                     PolyAA::AntiAlias,
@@ -3754,7 +3771,7 @@ impl<T: Texture2d> GlyphCache<T> {
                             PolyCommand::LineTo(BlockCoord::One, BlockCoord::One),
                         ],
                         intensity: BlockAlpha::Full,
-                        style: PolyStyle::OutlineHeavy,
+                        style: outline,
                     }],
                     &mut buffer,
                     PolyAA::AntiAlias,
@@ -3783,8 +3800,11 @@ impl<T: Texture2d> GlyphCache<T> {
                 descender_plus_two: 0,
                 underline_height: *underline_height,
                 strike_row: 0,
+                overline_row: 0,
                 cell_size: cell_size.clone(),
+                cursor_thickness: None,
             },
+            BlockKey::Tofu { cells, .. } => render_metrics.scale_cell_width(*cells as f64),
             _ => render_metrics.clone(),
         };
 
@@ -3954,6 +3974,9 @@ impl<T: Texture2d> GlyphCache<T> {
                     },
                 );
             }
+            BlockKey::Tofu { codepoint, .. } => {
+                draw_tofu(&mut buffer, &metrics, codepoint);
+            }
         }
 
         /*
@@ -3995,6 +4018,74 @@ fn fill_rect(buffer: &mut Image, x: Range<usize>, y: Range<usize>) {
     );
 }
 
+/// 3x5 pixel glyphs for the hex digits, one row per element with the
+/// most significant of the three bits being the leftmost pixel
+const HEX_DIGITS: [[u8; 5]; 16] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b010, 0b101, 0b111, 0b101, 0b101],
+    [0b110, 0b101, 0b110, 0b101, 0b110],
+    [0b011, 0b100, 0b100, 0b100, 0b011],
+    [0b110, 0b101, 0b101, 0b101, 0b110],
+    [0b111, 0b100, 0b111, 0b100, 0b111],
+    [0b111, 0b100, 0b111, 0b100, 0b100],
+];
+
+/// Draws an outlined box holding the hex digits of `codepoint` in two
+/// rows; four digits for the BMP and six beyond it.  If the cell is too
+/// small for the digits to be legible, only the box is drawn.
+fn draw_tofu(buffer: &mut Image, metrics: &RenderMetrics, codepoint: u32) {
+    let (width, height) = buffer.image_dimensions();
+    let line = (metrics.underline_height as usize).max(1);
+    if width <= line * 2 || height <= line * 2 {
+        fill_rect(buffer, 0..width, 0..height);
+        return;
+    }
+
+    fill_rect(buffer, 0..width, 0..line);
+    fill_rect(buffer, 0..width, height - line..height);
+    fill_rect(buffer, 0..line, 0..height);
+    fill_rect(buffer, width - line..width, 0..height);
+
+    let num_digits = if codepoint > 0xffff { 6 } else { 4 };
+    let digits = format!("{:0width$X}", codepoint, width = num_digits);
+    let cols = num_digits / 2;
+
+    let inner_width = width - line * 2;
+    let inner_height = height - line * 2;
+    let digit_width = inner_width / cols;
+    let digit_height = inner_height / 2;
+    if digit_width < 3 || digit_height < 5 {
+        return;
+    }
+    // Leave a pixel unit of spacing around the digits where possible
+    let px_width = (digit_width / 4).max(1);
+    let px_height = (digit_height / 6).max(1);
+
+    for (idx, digit) in digits.chars().enumerate() {
+        let glyph = &HEX_DIGITS[digit.to_digit(16).unwrap_or(0) as usize];
+        let left = line + (idx % cols) * digit_width + (digit_width - px_width * 3) / 2;
+        let top = line + (idx / cols) * digit_height + (digit_height - px_height * 5) / 2;
+        for (y, bits) in glyph.iter().enumerate() {
+            for x in 0..3 {
+                if bits & (0b100 >> x) != 0 {
+                    let x = left + x * px_width;
+                    let y = top + y * px_height;
+                    fill_rect(buffer, x..x + px_width, y..y + px_height);
+                }
+            }
+        }
+    }
+}
+
 fn scale(f: f32) -> usize {
     f.ceil().max(1.) as usize
 }
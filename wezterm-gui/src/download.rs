@@ -56,10 +56,40 @@ fn resolve_file_name(name: Option<&str>) -> anyhow::Result<(PathBuf, File)> {
     );
 }
 
-pub fn save_to_downloads(orig_name: Option<String>, data: &[u8]) -> anyhow::Result<()> {
+/// Formats a size in bytes for display to the user
+pub fn format_size(size: usize) -> String {
+    const UNITS: &[&str] = &["bytes", "KiB", "MiB", "GiB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024. && unit + 1 < UNITS.len() {
+        value /= 1024.;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Saves data to the download folder, calling `progress` with the
+/// number of bytes written so far after each chunk of the data
+/// has been written out.
+pub fn save_to_downloads<F: FnMut(usize)>(
+    orig_name: Option<String>,
+    data: &[u8],
+    mut progress: F,
+) -> anyhow::Result<()> {
+    const CHUNK_SIZE: usize = 1024 * 1024;
+
     let (name, mut file) = resolve_file_name(orig_name.as_deref())?;
-    file.write_all(data)
-        .with_context(|| format!("writing {} of data to {}", data.len(), name.display()))?;
+    let mut written = 0;
+    for chunk in data.chunks(CHUNK_SIZE) {
+        file.write_all(chunk)
+            .with_context(|| format!("writing {} of data to {}", data.len(), name.display()))?;
+        written += chunk.len();
+        progress(written);
+    }
 
     let url = format!("file://{}", name.display());
     wezterm_toast_notification::persistent_toast_notification_with_click_to_open_url(
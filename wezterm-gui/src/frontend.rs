@@ -2,6 +2,7 @@ use crate::termwindow::TermWindowNotif;
 use crate::TermWindow;
 use ::window::*;
 use anyhow::Error;
+use config::keyassignment::{KeyAssignment, LauncherActionArgs, LauncherFlags};
 pub use config::FrontEndSelection;
 use mux::client::ClientId;
 use mux::window::WindowId as MuxWindowId;
@@ -18,7 +19,11 @@ pub struct GuiFrontEnd {
     switching_workspaces: RefCell<bool>,
     spawned_mux_window: RefCell<HashSet<MuxWindowId>>,
     known_windows: RefCell<BTreeMap<Window, MuxWindowId>>,
+    /// The screen coordinates of the client area of those known
+    /// windows whose position is known to us
+    window_bounds: RefCell<BTreeMap<Window, ScreenRect>>,
     client_id: Arc<ClientId>,
+    pending_workspace_launcher: RefCell<bool>,
 }
 
 impl Drop for GuiFrontEnd {
@@ -39,7 +44,9 @@ impl GuiFrontEnd {
             switching_workspaces: RefCell::new(false),
             spawned_mux_window: RefCell::new(HashSet::new()),
             known_windows: RefCell::new(BTreeMap::new()),
+            window_bounds: RefCell::new(BTreeMap::new()),
             client_id: client_id.clone(),
+            pending_workspace_launcher: RefCell::new(false),
         });
         let fe = Rc::downgrade(&front_end);
         mux.subscribe(move |n| {
@@ -90,21 +97,40 @@ impl GuiFrontEnd {
                             | Alert::TitleMaybeChanged
                             | Alert::SetUserVar { .. },
                     } => {}
+                    MuxNotification::Alert {
+                        pane_id: _,
+                        alert: Alert::RequestAttention,
+                    } => {
+                        // Handled via TermWindowNotif; NOP it here.
+                    }
                     MuxNotification::Empty => {
                         if mux::activity::Activity::count() == 0 {
                             log::trace!("Mux is now empty, terminate gui");
                             Connection::get().unwrap().terminate_message_loop();
                         }
                     }
-                    MuxNotification::SaveToDownloads { name, data } => {
-                        if !config::configuration().allow_download_protocols {
-                            log::error!(
-                                "Ignoring download request for {:?}, \
-                                 as allow_download_protocols=false",
-                                name
+                    MuxNotification::SaveToDownloads { pane_id, name, .. } => {
+                        // Handled by the TermWindow that contains the pane;
+                        // if there isn't one, there is nowhere to prompt
+                        // for confirmation, so let the user know that
+                        // the file was not saved.
+                        let mux = Mux::get().expect("mux is calling us");
+                        let in_gui_window = match mux.resolve_pane_id(pane_id) {
+                            Some((_domain_id, window_id, _tab_id)) => fe
+                                .known_windows
+                                .borrow()
+                                .values()
+                                .any(|&id| id == window_id),
+                            None => false,
+                        };
+                        if !in_gui_window {
+                            let message = format!(
+                                "Ignoring download request for {:?} from pane {}, \
+                                 as it is not in a window",
+                                name, pane_id
                             );
-                        } else if let Err(err) = crate::download::save_to_downloads(name, &*data) {
-                            log::error!("save_to_downloads: {:#}", err);
+                            log::error!("{}", message);
+                            persistent_toast_notification("Download refused", &message);
                         }
                     }
                     MuxNotification::AssignClipboard {
@@ -259,10 +285,62 @@ impl GuiFrontEnd {
         if !self.is_switching_workspace() {
             self.reconcile_workspace();
         }
+        if self.pending_workspace_launcher.replace(false) {
+            self.show_workspace_launcher();
+        }
+    }
+
+    /// Shows the workspace launcher in one of our windows, so that
+    /// the user can pick the workspace they want to use.
+    /// If no windows have been created yet, the launcher will be
+    /// shown once the first of them is known.
+    pub fn show_workspace_launcher(&self) {
+        let mux = Mux::get().expect("mux started and running on main thread");
+        let known_windows = self.known_windows.borrow();
+        let pane_id = known_windows.iter().find_map(|(window, &mux_window_id)| {
+            let pane = mux
+                .get_active_tab_for_window(mux_window_id)?
+                .get_active_pane()?;
+            Some((window.clone(), pane.pane_id()))
+        });
+        match pane_id {
+            Some((window, pane_id)) => {
+                window.notify(TermWindowNotif::PerformAssignment {
+                    pane_id,
+                    assignment: KeyAssignment::ShowLauncherArgs(LauncherActionArgs {
+                        flags: LauncherFlags::WORKSPACES | LauncherFlags::FUZZY,
+                        title: Some("Choose a workspace".to_string()),
+                    }),
+                });
+            }
+            None => {
+                *self.pending_workspace_launcher.borrow_mut() = true;
+            }
+        }
+    }
+
+    /// Records the screen coordinates of the client area of `window`
+    pub fn record_window_bounds(&self, window: &Window, bounds: ScreenRect) {
+        self.window_bounds
+            .borrow_mut()
+            .insert(window.clone(), bounds);
+    }
+
+    /// Returns the mux window id of the known window whose client
+    /// area contains `point`, if any.
+    /// Windows whose position is not known to us are not considered.
+    pub fn mux_window_at_screen_point(&self, point: ScreenPoint) -> Option<MuxWindowId> {
+        let known_windows = self.known_windows.borrow();
+        self.window_bounds
+            .borrow()
+            .iter()
+            .find(|(_, bounds)| bounds.contains(point))
+            .and_then(|(window, _)| known_windows.get(window).copied())
     }
 
     pub fn forget_known_window(&self, window: &Window) {
         self.known_windows.borrow_mut().remove(window);
+        self.window_bounds.borrow_mut().remove(window);
         if !self.is_switching_workspace() {
             self.reconcile_workspace();
         }
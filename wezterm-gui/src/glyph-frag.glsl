@@ -114,7 +114,11 @@ void main() {
 
   color = apply_hsv(color, o_hsv);
 
+#ifndef LINEAR_BLENDING
   // We MUST output SRGB and tell glium that we do that (outputs_srgb),
   // otherwise something in glium over-gamma-corrects depending on the gl setup.
   color = to_srgb(color);
+#endif
+  // Otherwise, we output linear values and the sRGB framebuffer takes care
+  // of encoding them after blending.
 }
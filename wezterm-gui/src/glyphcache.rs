@@ -18,7 +18,7 @@ use std::collections::HashMap;
 use std::convert::TryInto;
 use std::rc::Rc;
 use std::sync::{Arc, MutexGuard};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use termwiz::color::RgbColor;
 use termwiz::image::{ImageData, ImageDataType};
 use termwiz::surface::CursorShape;
@@ -578,6 +578,8 @@ impl<T: Texture2d> GlyphCache<T> {
         atlas: &mut Atlas<T>,
         decoded: &mut DecodedImage,
         padding: Option<usize>,
+        min_frame_duration: Duration,
+        animate: bool,
     ) -> anyhow::Result<(Sprite<T>, Option<Instant>)> {
         let mut handle = DecodedImageHandle {
             h: decoded.image.data(),
@@ -599,10 +601,17 @@ impl<T: Texture2d> GlyphCache<T> {
                 durations,
                 ..
             } => {
+                // Frames are never shown for less time than is allowed
+                // by max_image_animation_fps
+                let frame_duration = |idx: usize| durations[idx].max(min_frame_duration);
                 let mut next = None;
-                if frames.len() > 1 {
+                if frames.len() > 1 && !animate {
+                    // Hold the current frame, and restart its duration
+                    // so that it is shown in full once animation resumes
+                    decoded.frame_start = Instant::now();
+                } else if frames.len() > 1 {
                     let now = Instant::now();
-                    let mut next_due = decoded.frame_start + durations[decoded.current_frame];
+                    let mut next_due = decoded.frame_start + frame_duration(decoded.current_frame);
                     if now >= next_due {
                         // Advance to next frame
                         decoded.current_frame += 1;
@@ -614,7 +623,7 @@ impl<T: Texture2d> GlyphCache<T> {
                             }
                         }
                         decoded.frame_start = now;
-                        next_due = decoded.frame_start + durations[decoded.current_frame];
+                        next_due = decoded.frame_start + frame_duration(decoded.current_frame);
                         handle.current_frame = decoded.current_frame;
                     }
 
@@ -631,24 +640,36 @@ impl<T: Texture2d> GlyphCache<T> {
 
                 frame_cache.insert(hash, sprite.clone());
 
-                return Ok((
-                    sprite,
-                    Some(decoded.frame_start + durations[decoded.current_frame]),
-                ));
+                return Ok((sprite, next));
             }
             ImageDataType::EncodedFile(_) => unreachable!(),
         }
     }
 
+    /// Returns the sprite for the current frame of the image, along
+    /// with the time at which the next frame is due, if it is animated.
+    /// When `animate` is false, the current frame is held.
     pub fn cached_image(
         &mut self,
         image_data: &Arc<ImageData>,
         padding: Option<usize>,
+        animate: bool,
     ) -> anyhow::Result<(Sprite<T>, Option<Instant>)> {
         let id = image_data.id();
+        let min_frame_duration = match self.fonts.config().max_image_animation_fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs_f32(1. / fps as f32),
+        };
 
         if let Some(decoded) = self.image_cache.get_mut(&id) {
-            Self::cached_image_impl(&mut self.frame_cache, &mut self.atlas, decoded, padding)
+            Self::cached_image_impl(
+                &mut self.frame_cache,
+                &mut self.atlas,
+                decoded,
+                padding,
+                min_frame_duration,
+                animate,
+            )
         } else {
             let mut decoded = DecodedImage::load(image_data);
             let res = Self::cached_image_impl(
@@ -656,6 +677,8 @@ impl<T: Texture2d> GlyphCache<T> {
                 &mut self.atlas,
                 &mut decoded,
                 padding,
+                min_frame_duration,
+                animate,
             )?;
             self.image_cache.put(id, decoded);
             Ok(res)
@@ -848,10 +871,13 @@ impl<T: Texture2d> GlyphCache<T> {
         let draw_overline = |buffer: &mut Image| {
             for row in 0..metrics.underline_height {
                 buffer.draw_line(
-                    Point::new(cell_rect.origin.x, cell_rect.origin.y + row),
+                    Point::new(
+                        cell_rect.origin.x,
+                        cell_rect.origin.y + metrics.overline_row + row,
+                    ),
                     Point::new(
                         cell_rect.origin.x + metrics.cell_size.width,
-                        cell_rect.origin.y + row,
+                        cell_rect.origin.y + metrics.overline_row + row,
                     ),
                     white,
                 );
@@ -45,6 +45,7 @@ mod tabbar;
 mod termwindow;
 mod update;
 mod utilsprites;
+mod windowsize;
 
 pub use selection::SelectionMode;
 pub use termwindow::{set_window_class, set_window_position, TermWindow, ICON_DATA};
@@ -122,6 +123,7 @@ async fn async_run_ssh(opts: SshCommand) -> anyhow::Result<()> {
         username: opts.user_at_host_and_port.username.clone(),
         multiplexing: SshMultiplexing::None,
         ssh_option,
+        install_terminfo: opts.install_terminfo,
         ..Default::default()
     };
 
@@ -252,7 +254,12 @@ async fn async_run_mux_client(opts: ConnectCommand) -> anyhow::Result<()> {
         None
     };
 
-    async_run_with_domain_as_default(domain, cmd).await
+    async_run_with_domain_as_default(domain, cmd).await?;
+
+    if config::configuration().show_workspace_launcher_on_connect {
+        crate::frontend::front_end().show_workspace_launcher();
+    }
+    Ok(())
 }
 
 fn run_mux_client(opts: ConnectCommand) -> anyhow::Result<()> {
@@ -302,7 +309,12 @@ async fn spawn_tab_in_default_domain_if_mux_is_empty(
     });
 
     let _tab = domain
-        .spawn(config.initial_size(), cmd, None, *window_id)
+        .spawn(
+            crate::windowsize::initial_size(&config),
+            cmd,
+            None,
+            *window_id,
+        )
         .await?;
     Ok(())
 }
@@ -703,6 +715,7 @@ pub fn run_ls_fonts(config: config::ConfigHandle, cmd: &LsFontsCommand) -> anyho
     let unicode_version = UnicodeVersion {
         version: config.unicode_version,
         ambiguous_are_wide: config.treat_east_asian_ambiguous_width_as_wide,
+        prefer_emoji_presentation: config.prefer_emoji_presentation,
     };
 
     if let Some(text) = &cmd.text {
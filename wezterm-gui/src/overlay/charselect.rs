@@ -0,0 +1,357 @@
+//! The character selector is an overlay that allows searching for
+//! unicode characters and emoji by name or by shortcode and inserting
+//! the selected character into the active pane.
+use crate::termwindow::TermWindowNotif;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use mux::pane::PaneId;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Mutex;
+use termwiz::cell::{AttributeChange, CellAttributes};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, Position};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+use window::WindowOps;
+
+/// How many recently used characters to remember
+const MAX_RECENT: usize = 32;
+
+const ROW_OVERHEAD: usize = 3;
+
+lazy_static::lazy_static! {
+    static ref ALL_CHARS: Vec<CharEntry> = build_char_list();
+    static ref RECENT: Mutex<Vec<&'static CharEntry>> = Mutex::new(vec![]);
+}
+
+#[derive(Clone, PartialEq)]
+struct CharEntry {
+    text: String,
+    label: String,
+}
+
+/// Emoji come first, with their shortcodes, followed by every named
+/// unicode character that wasn't already listed as an emoji.
+fn build_char_list() -> Vec<CharEntry> {
+    let mut entries = vec![];
+    let mut seen = HashSet::new();
+
+    for emoji in emojis::iter() {
+        let text = emoji.as_str();
+        let label = match emoji.shortcode() {
+            Some(code) => format!("{} {} :{}:", text, emoji.name(), code),
+            None => format!("{} {}", text, emoji.name()),
+        };
+        seen.insert(text.to_string());
+        entries.push(CharEntry {
+            text: text.to_string(),
+            label,
+        });
+    }
+
+    for c in (0..=0x10ffff).filter_map(char::from_u32) {
+        if c.is_control() {
+            continue;
+        }
+        let name = match unicode_names2::name(c) {
+            Some(name) => name.to_string().to_lowercase(),
+            None => continue,
+        };
+        let text = c.to_string();
+        if seen.contains(&text) {
+            continue;
+        }
+        entries.push(CharEntry {
+            label: format!("{} {} U+{:04X}", text, name, c as u32),
+            text,
+        });
+    }
+
+    entries
+}
+
+fn record_recent(entry: &'static CharEntry) {
+    let mut recent = RECENT.lock().unwrap();
+    recent.retain(|e| *e != entry);
+    recent.insert(0, entry);
+    recent.truncate(MAX_RECENT);
+}
+
+/// Returns the recently used characters followed by all of the others
+fn ordered_entries() -> Vec<&'static CharEntry> {
+    let recent = RECENT.lock().unwrap().clone();
+    let recent_text: HashSet<&str> = recent.iter().map(|entry| entry.text.as_str()).collect();
+    let mut entries = recent;
+    entries.extend(
+        ALL_CHARS
+            .iter()
+            .filter(|entry| !recent_text.contains(entry.text.as_str())),
+    );
+    entries
+}
+
+struct CharSelectState {
+    active_idx: usize,
+    max_items: usize,
+    top_row: usize,
+    filter_term: String,
+    all_entries: Vec<&'static CharEntry>,
+    /// The entries that matched `candidates_term`, in the order
+    /// of `all_entries`
+    candidates: Vec<&'static CharEntry>,
+    candidates_term: String,
+    filtered_entries: Vec<&'static CharEntry>,
+    pane_id: PaneId,
+    window: ::window::Window,
+}
+
+impl CharSelectState {
+    fn update_filter(&mut self) {
+        self.active_idx = 0;
+        self.top_row = 0;
+
+        if self.filter_term.is_empty() {
+            self.filtered_entries = self.all_entries.clone();
+            self.candidates.clear();
+            self.candidates_term.clear();
+            return;
+        }
+
+        // Anything that matches the new term also matched a prefix
+        // of it, so when the user is typing we only need to consider
+        // the entries that matched before
+        let pool = if !self.candidates_term.is_empty()
+            && self.filter_term.starts_with(&self.candidates_term)
+        {
+            std::mem::take(&mut self.candidates)
+        } else {
+            self.all_entries.clone()
+        };
+
+        let matcher = SkimMatcherV2::default();
+
+        struct MatchResult {
+            entry: &'static CharEntry,
+            score: i64,
+        }
+
+        let mut scores: Vec<MatchResult> = pool
+            .into_iter()
+            .filter_map(|entry| {
+                let score = matcher.fuzzy_match(&entry.label, &self.filter_term)?;
+                Some(MatchResult { entry, score })
+            })
+            .collect();
+
+        self.candidates = scores.iter().map(|result| result.entry).collect();
+        self.candidates_term = self.filter_term.clone();
+
+        // The sort is stable, so recently used characters are listed
+        // ahead of other matches with the same score
+        scores.sort_by(|a, b| a.score.cmp(&b.score).reverse());
+
+        self.filtered_entries = scores.into_iter().map(|result| result.entry).collect();
+    }
+
+    fn render(&mut self, term: &mut TermWizTerminal) -> termwiz::Result<()> {
+        let size = term.get_screen_size()?;
+        let max_width = size.cols.saturating_sub(6);
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            Change::Text(format!(
+                "{}\r\n",
+                truncate_right(&format!("Search: {}", self.filter_term), max_width)
+            )),
+            Change::Text(format!(
+                "{}\r\n",
+                truncate_right("Enter=insert  Esc=cancel", max_width)
+            )),
+            Change::AllAttributes(CellAttributes::default()),
+        ];
+
+        for (row_num, (entry_idx, entry)) in self
+            .filtered_entries
+            .iter()
+            .enumerate()
+            .skip(self.top_row)
+            .enumerate()
+        {
+            if row_num >= self.max_items {
+                break;
+            }
+            if entry_idx == self.active_idx {
+                changes.push(AttributeChange::Reverse(true).into());
+            }
+
+            changes.push(Change::Text(format!(
+                " {} \r\n",
+                truncate_right(&entry.label, max_width)
+            )));
+
+            if entry_idx == self.active_idx {
+                changes.push(AttributeChange::Reverse(false).into());
+            }
+        }
+
+        term.render(&changes)
+    }
+
+    fn insert(&self, active_idx: usize) {
+        let entry = match self.filtered_entries.get(active_idx).copied() {
+            Some(entry) => entry,
+            None => return,
+        };
+        record_recent(entry);
+        let pane_id = self.pane_id;
+        self.window
+            .notify(TermWindowNotif::Apply(Box::new(move |_term_window| {
+                let mux = Mux::get().unwrap();
+                if let Some(pane) = mux.get_pane(pane_id) {
+                    if let Err(err) = pane.writer().write_all(entry.text.as_bytes()) {
+                        log::error!("failed to insert {}: {:#}", entry.text, err);
+                    }
+                }
+            })));
+    }
+
+    fn move_up(&mut self) {
+        self.active_idx = self.active_idx.saturating_sub(1);
+        if self.active_idx < self.top_row {
+            self.top_row = self.active_idx;
+        }
+    }
+
+    fn move_down(&mut self) {
+        self.active_idx = (self.active_idx + 1).min(self.filtered_entries.len().saturating_sub(1));
+        if self.active_idx >= self.top_row + self.max_items {
+            self.top_row = (self.active_idx + 1).saturating_sub(self.max_items);
+        }
+    }
+
+    fn run_loop(&mut self, term: &mut TermWizTerminal) -> anyhow::Result<()> {
+        while let Ok(Some(event)) = term.poll_input(None) {
+            match event {
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('P'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::UpArrow,
+                    ..
+                }) => {
+                    self.move_up();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('N'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::DownArrow,
+                    ..
+                }) => {
+                    self.move_down();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('G'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape,
+                    ..
+                }) => {
+                    break;
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Backspace,
+                    ..
+                }) => {
+                    self.filter_term.pop();
+                    self.update_filter();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char(c),
+                    ..
+                }) => {
+                    self.filter_term.push(c);
+                    self.update_filter();
+                }
+                InputEvent::Mouse(MouseEvent { mouse_buttons, .. })
+                    if mouse_buttons.contains(MouseButtons::VERT_WHEEL) =>
+                {
+                    if mouse_buttons.contains(MouseButtons::WHEEL_POSITIVE) {
+                        self.move_up();
+                    } else {
+                        self.move_down();
+                    }
+                }
+                InputEvent::Mouse(MouseEvent {
+                    y, mouse_buttons, ..
+                }) => {
+                    let y = y as usize;
+                    if y >= 2 && self.top_row + y - 2 < self.filtered_entries.len() {
+                        self.active_idx = self.top_row + y - 2;
+
+                        if mouse_buttons == MouseButtons::LEFT {
+                            self.insert(self.active_idx);
+                            break;
+                        }
+                    }
+                    if mouse_buttons != MouseButtons::NONE {
+                        // Treat any other mouse button as cancel
+                        break;
+                    }
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Enter,
+                    ..
+                }) => {
+                    self.insert(self.active_idx);
+                    break;
+                }
+                InputEvent::Resized { rows, .. } => {
+                    self.max_items = rows.saturating_sub(ROW_OVERHEAD);
+                }
+                _ => {}
+            }
+            self.render(term)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn char_select(
+    pane_id: PaneId,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    let size = term.get_screen_size()?;
+    let max_items = size.rows.saturating_sub(ROW_OVERHEAD);
+    let mut state = CharSelectState {
+        active_idx: 0,
+        max_items,
+        top_row: 0,
+        filter_term: String::new(),
+        all_entries: ordered_entries(),
+        candidates: vec![],
+        candidates_term: String::new(),
+        filtered_entries: vec![],
+        pane_id,
+        window,
+    };
+
+    term.set_raw_mode()?;
+    term.render(&[Change::Title("Select Character".to_string())])?;
+    state.update_filter();
+    state.render(&mut term)?;
+    state.run_loop(&mut term)
+}
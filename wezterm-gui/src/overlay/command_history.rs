@@ -0,0 +1,313 @@
+//! The command history overlay allows fuzzy searching the commands
+//! that were previously entered at the shell prompt in a pane, as
+//! recorded via OSC 133 semantic prompt escapes, and re-running them.
+use crate::termwindow::TermWindowNotif;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use mux::pane::PaneId;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+use std::io::Write;
+use termwiz::cell::{AttributeChange, CellAttributes};
+use termwiz::color::ColorAttribute;
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers, MouseButtons, MouseEvent};
+use termwiz::surface::{Change, Position};
+use termwiz::terminal::Terminal;
+use termwiz_funcs::truncate_right;
+use wezterm_term::CommandHistoryEntry;
+use window::WindowOps;
+
+const ROW_OVERHEAD: usize = 3;
+
+#[derive(Clone, PartialEq)]
+struct HistoryEntry {
+    command: String,
+    label: String,
+}
+
+/// Produces the list of entries, most recent first, with only the
+/// most recent instance of any repeated command
+fn build_entries(history: Vec<CommandHistoryEntry>) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = vec![];
+
+    for item in history.into_iter().rev() {
+        if entries.iter().any(|e| e.command == item.command) {
+            continue;
+        }
+        let status = match item.exit_status {
+            Some(0) => "   ".to_string(),
+            Some(status) => format!("{:>3}", status),
+            None => "  ?".to_string(),
+        };
+        entries.push(HistoryEntry {
+            label: format!("{} {}", status, item.command.replace('\n', " ")),
+            command: item.command,
+        });
+    }
+
+    entries
+}
+
+struct CommandHistoryState {
+    active_idx: usize,
+    max_items: usize,
+    top_row: usize,
+    filter_term: String,
+    all_entries: Vec<HistoryEntry>,
+    filtered_entries: Vec<HistoryEntry>,
+    pane_id: PaneId,
+    window: ::window::Window,
+}
+
+impl CommandHistoryState {
+    fn update_filter(&mut self) {
+        self.active_idx = 0;
+        self.top_row = 0;
+
+        if self.filter_term.is_empty() {
+            self.filtered_entries = self.all_entries.clone();
+            return;
+        }
+
+        let matcher = SkimMatcherV2::default();
+
+        struct MatchResult<'a> {
+            entry: &'a HistoryEntry,
+            score: i64,
+        }
+
+        let mut scores: Vec<MatchResult> = self
+            .all_entries
+            .iter()
+            .filter_map(|entry| {
+                let score = matcher.fuzzy_match(&entry.command, &self.filter_term)?;
+                Some(MatchResult { entry, score })
+            })
+            .collect();
+
+        // The sort is stable, so more recent commands are listed
+        // ahead of other matches with the same score
+        scores.sort_by(|a, b| a.score.cmp(&b.score).reverse());
+
+        self.filtered_entries = scores
+            .into_iter()
+            .map(|result| result.entry.clone())
+            .collect();
+    }
+
+    fn render(&mut self, term: &mut TermWizTerminal) -> termwiz::Result<()> {
+        let size = term.get_screen_size()?;
+        let max_width = size.cols.saturating_sub(6);
+
+        let mut changes = vec![
+            Change::ClearScreen(ColorAttribute::Default),
+            Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(0),
+            },
+            Change::Text(format!(
+                "{}\r\n",
+                truncate_right(&format!("Search: {}", self.filter_term), max_width)
+            )),
+            Change::Text(format!(
+                "{}\r\n",
+                truncate_right("Enter=run  Tab=insert  Esc=cancel", max_width)
+            )),
+            Change::AllAttributes(CellAttributes::default()),
+        ];
+
+        if self.all_entries.is_empty() {
+            changes.push(Change::Text(truncate_right(
+                "No commands have been recorded in this pane; \
+                 shell integration is required to record them.",
+                max_width,
+            )));
+        }
+
+        for (row_num, (entry_idx, entry)) in self
+            .filtered_entries
+            .iter()
+            .enumerate()
+            .skip(self.top_row)
+            .enumerate()
+        {
+            if row_num >= self.max_items {
+                break;
+            }
+            if entry_idx == self.active_idx {
+                changes.push(AttributeChange::Reverse(true).into());
+            }
+
+            changes.push(Change::Text(format!(
+                " {} \r\n",
+                truncate_right(&entry.label, max_width)
+            )));
+
+            if entry_idx == self.active_idx {
+                changes.push(AttributeChange::Reverse(false).into());
+            }
+        }
+
+        term.render(&changes)
+    }
+
+    /// Sends the selected command to the pane, followed by
+    /// a carriage return if `run` is true
+    fn send(&self, active_idx: usize, run: bool) {
+        let entry = match self.filtered_entries.get(active_idx) {
+            Some(entry) => entry.clone(),
+            None => return,
+        };
+        let mut text = entry.command.replace('\n', "\r");
+        if run {
+            text.push('\r');
+        }
+        let pane_id = self.pane_id;
+        self.window
+            .notify(TermWindowNotif::Apply(Box::new(move |_term_window| {
+                let mux = Mux::get().unwrap();
+                if let Some(pane) = mux.get_pane(pane_id) {
+                    if let Err(err) = pane.writer().write_all(text.as_bytes()) {
+                        log::error!("failed to send {}: {:#}", entry.command, err);
+                    }
+                }
+            })));
+    }
+
+    fn move_up(&mut self) {
+        self.active_idx = self.active_idx.saturating_sub(1);
+        if self.active_idx < self.top_row {
+            self.top_row = self.active_idx;
+        }
+    }
+
+    fn move_down(&mut self) {
+        self.active_idx = (self.active_idx + 1).min(self.filtered_entries.len().saturating_sub(1));
+        if self.active_idx >= self.top_row + self.max_items {
+            self.top_row = (self.active_idx + 1).saturating_sub(self.max_items);
+        }
+    }
+
+    fn run_loop(&mut self, term: &mut TermWizTerminal) -> anyhow::Result<()> {
+        while let Ok(Some(event)) = term.poll_input(None) {
+            match event {
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('P'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::UpArrow,
+                    ..
+                }) => {
+                    self.move_up();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('N'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::DownArrow,
+                    ..
+                }) => {
+                    self.move_down();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char('G'),
+                    modifiers: Modifiers::CTRL,
+                })
+                | InputEvent::Key(KeyEvent {
+                    key: KeyCode::Escape,
+                    ..
+                }) => {
+                    break;
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Backspace,
+                    ..
+                }) => {
+                    self.filter_term.pop();
+                    self.update_filter();
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Tab, ..
+                }) => {
+                    self.send(self.active_idx, false);
+                    break;
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Char(c),
+                    ..
+                }) => {
+                    self.filter_term.push(c);
+                    self.update_filter();
+                }
+                InputEvent::Mouse(MouseEvent { mouse_buttons, .. })
+                    if mouse_buttons.contains(MouseButtons::VERT_WHEEL) =>
+                {
+                    if mouse_buttons.contains(MouseButtons::WHEEL_POSITIVE) {
+                        self.move_up();
+                    } else {
+                        self.move_down();
+                    }
+                }
+                InputEvent::Mouse(MouseEvent {
+                    y, mouse_buttons, ..
+                }) => {
+                    let y = y as usize;
+                    if y >= 2 && self.top_row + y - 2 < self.filtered_entries.len() {
+                        self.active_idx = self.top_row + y - 2;
+
+                        if mouse_buttons == MouseButtons::LEFT {
+                            self.send(self.active_idx, true);
+                            break;
+                        }
+                    }
+                    if mouse_buttons != MouseButtons::NONE {
+                        // Treat any other mouse button as cancel
+                        break;
+                    }
+                }
+                InputEvent::Key(KeyEvent {
+                    key: KeyCode::Enter,
+                    ..
+                }) => {
+                    self.send(self.active_idx, true);
+                    break;
+                }
+                InputEvent::Resized { rows, .. } => {
+                    self.max_items = rows.saturating_sub(ROW_OVERHEAD);
+                }
+                _ => {}
+            }
+            self.render(term)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn command_history(
+    pane_id: PaneId,
+    history: Vec<CommandHistoryEntry>,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    let size = term.get_screen_size()?;
+    let max_items = size.rows.saturating_sub(ROW_OVERHEAD);
+    let mut state = CommandHistoryState {
+        active_idx: 0,
+        max_items,
+        top_row: 0,
+        filter_term: String::new(),
+        all_entries: build_entries(history),
+        filtered_entries: vec![],
+        pane_id,
+        window,
+    };
+
+    term.set_raw_mode()?;
+    term.render(&[Change::Title("Command History".to_string())])?;
+    state.update_filter();
+    state.render(&mut term)?;
+    state.run_loop(&mut term)
+}
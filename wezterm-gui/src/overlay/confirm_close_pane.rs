@@ -4,6 +4,7 @@ use mux::tab::TabId;
 use mux::termwiztermtab::TermWizTerminal;
 use mux::window::WindowId;
 use mux::Mux;
+use std::sync::Arc;
 use termwiz::cell::AttributeChange;
 use termwiz::color::ColorAttribute;
 use termwiz::input::{InputEvent, KeyCode, KeyEvent, MouseButtons, MouseEvent};
@@ -60,20 +61,28 @@ fn run_confirmation_app(message: &str, term: &mut TermWizTerminal) -> anyhow::Re
         No,
     }
 
+    // Only the rows around the prompt are cleared, so that when the
+    // prompt overlays a single pane, the rest of the pane stays visible
+    let clear_rows = top_row.saturating_sub(1)..(button_row + 2).min(size.rows);
+
     let render = |term: &mut TermWizTerminal, active: ActiveButton| -> termwiz::Result<()> {
-        let mut changes = vec![
-            Change::ClearScreen(ColorAttribute::Default),
-            Change::CursorVisibility(CursorVisibility::Hidden),
-            /*
-            Change::Image(termwiz::surface::change::Image {
-                width: logo_width_cells,
-                height: logo_height_cells,
-                top_left: TextureCoordinate::new_f32(0., 0.),
-                bottom_right: TextureCoordinate::new_f32(1., 1.),
-                image: Arc::clone(&image_data),
-            }),
-            */
-        ];
+        let mut changes = vec![Change::CursorVisibility(CursorVisibility::Hidden)];
+        for y in clear_rows.clone() {
+            changes.push(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(y),
+            });
+            changes.push(Change::ClearToEndOfLine(ColorAttribute::Default));
+        }
+        /*
+        changes.push(Change::Image(termwiz::surface::change::Image {
+            width: logo_width_cells,
+            height: logo_height_cells,
+            top_left: TextureCoordinate::new_f32(0., 0.),
+            bottom_right: TextureCoordinate::new_f32(1., 1.),
+            image: Arc::clone(&image_data),
+        }));
+        */
 
         for (y, row) in wrapped.split("\n").enumerate() {
             let row = row.trim_end();
@@ -210,6 +219,29 @@ pub fn confirm_close_tab(
     Ok(())
 }
 
+pub fn confirm_close_other_tabs(
+    tab_id: TabId,
+    mut term: TermWizTerminal,
+    other_tab_ids: Vec<TabId>,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    if run_confirmation_app(
+        "🛑 Really kill the other tabs in this window and all contained panes?",
+        &mut term,
+    )? {
+        promise::spawn::spawn_into_main_thread(async move {
+            let mux = Mux::get().unwrap();
+            for tab_id in other_tab_ids {
+                mux.remove_tab(tab_id);
+            }
+        })
+        .detach();
+    }
+    TermWindow::schedule_cancel_overlay(window, tab_id, None);
+
+    Ok(())
+}
+
 pub fn confirm_close_window(
     mut term: TermWizTerminal,
     mux_window_id: WindowId,
@@ -231,6 +263,51 @@ pub fn confirm_close_window(
     Ok(())
 }
 
+pub fn confirm_download(
+    pane_id: PaneId,
+    name: Option<String>,
+    data: Arc<Vec<u8>>,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+) -> anyhow::Result<()> {
+    let message = format!(
+        "📥 The program running in this pane wants to save {} ({}) \
+         to your downloads folder. Allow it?",
+        match &name {
+            Some(name) => format!("\"{}\"", name),
+            None => "a file".to_string(),
+        },
+        crate::download::format_size(data.len())
+    );
+    if run_confirmation_app(&message, &mut term)? {
+        let total = data.len();
+        let result = crate::download::save_to_downloads(name, &data, |written| {
+            let percent = if total == 0 {
+                100
+            } else {
+                written * 100 / total
+            };
+            term.render(&[
+                Change::ClearScreen(ColorAttribute::Default),
+                Change::CursorVisibility(CursorVisibility::Hidden),
+                Change::Text(format!(
+                    "Saving... {}% of {}",
+                    percent,
+                    crate::download::format_size(total)
+                )),
+            ])
+            .ok();
+            term.flush().ok();
+        });
+        if let Err(err) = result {
+            log::error!("save_to_downloads: {:#}", err);
+        }
+    }
+    TermWindow::schedule_cancel_overlay_for_pane(window, pane_id);
+
+    Ok(())
+}
+
 pub fn confirm_quit_program(
     mut term: TermWizTerminal,
     window: ::window::Window,
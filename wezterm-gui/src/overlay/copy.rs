@@ -1,7 +1,8 @@
 use crate::selection::{SelectionCoordinate, SelectionRange};
 use crate::termwindow::{TermWindow, TermWindowNotif};
 use config::keyassignment::{
-    CopyModeAssignment, KeyAssignment, KeyTable, KeyTableEntry, ScrollbackEraseMode, SelectionMode,
+    ClipboardCopyDestination, CopyModeAssignment, KeyAssignment, KeyTable, KeyTableEntry,
+    ScrollbackEraseMode, SearchMatchType, SelectionMode,
 };
 use mux::domain::DomainId;
 use mux::pane::{Pane, PaneId, Pattern, SearchResult};
@@ -22,6 +23,7 @@ use url::Url;
 use wezterm_term::color::ColorPalette;
 use wezterm_term::{
     unicode_column_width, Clipboard, KeyCode, KeyModifiers, Line, MouseEvent, StableRowIndex,
+    UnicodeVersion,
 };
 use window::{KeyCode as WKeyCode, Modifiers, WindowOps};
 
@@ -166,6 +168,15 @@ impl CopyOverlay {
 }
 
 impl CopyRenderable {
+    /// Returns the unicode version used by the pane, so that word
+    /// motion agrees with the cell widths in the pane, for example
+    /// when ambiguous width characters are being treated as wide
+    fn unicode_version(&self) -> Option<UnicodeVersion> {
+        self.delegate
+            .get_config()
+            .map(|config| config.unicode_version())
+    }
+
     fn compute_search_row(&self) -> StableRowIndex {
         let dims = self.delegate.get_dimensions();
         let top = self.viewport.unwrap_or_else(|| dims.physical_top);
@@ -332,7 +343,15 @@ impl CopyRenderable {
                 y: self.cursor.y,
             };
 
-            self.adjust_selection(start, SelectionRange { start, end });
+            let range = if self.selection_mode == SelectionMode::Line {
+                let start_line = SelectionRange::line_around(start, &*self.delegate);
+                let end_line = SelectionRange::line_around(end, &*self.delegate);
+                start_line.extend_with(end_line)
+            } else {
+                SelectionRange { start, end }
+            };
+
+            self.adjust_selection(start, range);
         } else {
             self.adjust_viewport_for_cursor_position();
             self.window.invalidate();
@@ -485,8 +504,14 @@ impl CopyRenderable {
         self.editing_search = true;
     }
 
+    /// Stop editing the pattern and switch to copy mode, where
+    /// the matches can be traversed using the copy mode keys
     fn accept_pattern(&mut self) {
         self.editing_search = false;
+        self.window.notify(TermWindowNotif::PerformAssignment {
+            pane_id: self.delegate.pane_id(),
+            assignment: KeyAssignment::ActivateCopyMode,
+        });
     }
 
     fn cycle_match_type(&mut self) {
@@ -499,6 +524,23 @@ impl CopyRenderable {
         self.update_search();
     }
 
+    fn set_match_type(&mut self, match_type: SearchMatchType) {
+        let text = match &self.pattern {
+            Pattern::CaseSensitiveString(s)
+            | Pattern::CaseInSensitiveString(s)
+            | Pattern::Regex(s) => s.clone(),
+        };
+        let pattern = match match_type {
+            SearchMatchType::CaseSensitive => Pattern::CaseSensitiveString(text),
+            SearchMatchType::CaseInSensitive => Pattern::CaseInSensitiveString(text),
+            SearchMatchType::Regex => Pattern::Regex(text),
+        };
+        if pattern != self.pattern {
+            self.pattern = pattern;
+            self.update_search();
+        }
+    }
+
     fn move_to_viewport_middle(&mut self) {
         let dims = self.dimensions();
         self.cursor.y = dims.top + (dims.dims.viewport_rows as isize) / 2;
@@ -615,9 +657,10 @@ impl CopyRenderable {
             //  |     _
 
             let mut last_was_whitespace = false;
+            let unicode_version = self.unicode_version();
 
             for (idx, word) in s.split_word_bounds().rev().enumerate() {
-                let width = unicode_column_width(word, None);
+                let width = unicode_column_width(word, unicode_version);
 
                 if is_whitespace_word(word) {
                     self.cursor.x = self.cursor.x.saturating_sub(width);
@@ -654,15 +697,16 @@ impl CopyRenderable {
             let width = line.cells().len();
             let s = line.columns_as_str(self.cursor.x..width + 1);
             let mut words = s.split_word_bounds();
+            let unicode_version = self.unicode_version();
 
             if let Some(word) = words.next() {
-                self.cursor.x += unicode_column_width(word, None);
+                self.cursor.x += unicode_column_width(word, unicode_version);
                 if !is_whitespace_word(word) {
                     // We were part-way through a word, so look
                     // at the next word
                     if let Some(word) = words.next() {
                         if is_whitespace_word(word) {
-                            self.cursor.x += unicode_column_width(word, None);
+                            self.cursor.x += unicode_column_width(word, unicode_version);
                             // If we advance off the RHS, move to the start of the word on the
                             // next line, if any!
                             if self.cursor.x >= width {
@@ -685,7 +729,11 @@ impl CopyRenderable {
     }
 
     fn toggle_selection_by_cell(&mut self) {
-        if self.start.take().is_none() {
+        if self.start.is_some() && self.selection_mode != SelectionMode::Cell {
+            // Switch an existing line or block selection to cells
+            self.selection_mode = SelectionMode::Cell;
+            self.select_to_cursor_pos();
+        } else if self.start.take().is_none() {
             let coord = SelectionCoordinate {
                 x: self.cursor.x,
                 y: self.cursor.y,
@@ -701,6 +749,10 @@ impl CopyRenderable {
             None => {
                 self.start.take();
             }
+            Some(mode) if self.start.is_some() && self.selection_mode == *mode => {
+                // Selecting the current mode again toggles selection off
+                self.start.take();
+            }
             Some(mode) => {
                 if self.start.is_none() {
                     let coord = SelectionCoordinate {
@@ -708,9 +760,9 @@ impl CopyRenderable {
                         y: self.cursor.y,
                     };
                     self.start.replace(coord);
-                    self.select_to_cursor_pos();
                 }
                 self.selection_mode = *mode;
+                self.select_to_cursor_pos();
             }
         }
     }
@@ -801,6 +853,7 @@ impl Pane for CopyOverlay {
                     PriorMatchPage => render.prior_match_page(),
                     NextMatchPage => render.next_match_page(),
                     CycleMatchType => render.cycle_match_type(),
+                    SetMatchType(match_type) => render.set_match_type(*match_type),
                     ClearPattern => render.clear_pattern(),
                     EditPattern => render.edit_pattern(),
                     AcceptPattern => render.accept_pattern(),
@@ -816,7 +869,7 @@ impl Pane for CopyOverlay {
         anyhow::bail!("ignoring mouse while copying");
     }
 
-    fn perform_actions(&self, actions: Vec<termwiz::escape::Action>) {
+    fn perform_actions(&self, actions: &mut Vec<termwiz::escape::Action>) {
         self.delegate.perform_actions(actions)
     }
 
@@ -1008,6 +1061,25 @@ pub fn search_key_table() -> KeyTable {
             Modifiers::CTRL,
             KeyAssignment::CopyMode(CopyModeAssignment::CycleMatchType),
         ),
+        (
+            WKeyCode::Char('c'),
+            Modifiers::ALT,
+            KeyAssignment::CopyMode(CopyModeAssignment::SetMatchType(
+                SearchMatchType::CaseSensitive,
+            )),
+        ),
+        (
+            WKeyCode::Char('i'),
+            Modifiers::ALT,
+            KeyAssignment::CopyMode(CopyModeAssignment::SetMatchType(
+                SearchMatchType::CaseInSensitive,
+            )),
+        ),
+        (
+            WKeyCode::Char('r'),
+            Modifiers::ALT,
+            KeyAssignment::CopyMode(CopyModeAssignment::SetMatchType(SearchMatchType::Regex)),
+        ),
         (
             WKeyCode::Char('u'),
             Modifiers::CTRL,
@@ -1174,6 +1246,28 @@ pub fn copy_key_table() -> KeyTable {
                 SelectionMode::Block,
             ))),
         ),
+        (
+            WKeyCode::Char('V'),
+            Modifiers::NONE,
+            KeyAssignment::CopyMode(CopyModeAssignment::SetSelectionMode(Some(
+                SelectionMode::Line,
+            ))),
+        ),
+        (
+            WKeyCode::Char('V'),
+            Modifiers::SHIFT,
+            KeyAssignment::CopyMode(CopyModeAssignment::SetSelectionMode(Some(
+                SelectionMode::Line,
+            ))),
+        ),
+        (
+            WKeyCode::Char('y'),
+            Modifiers::NONE,
+            KeyAssignment::Multiple(vec![
+                KeyAssignment::CopyTo(ClipboardCopyDestination::ClipboardAndPrimarySelection),
+                KeyAssignment::CopyMode(CopyModeAssignment::Close),
+            ]),
+        ),
         (
             WKeyCode::Char('G'),
             Modifiers::SHIFT,
@@ -1239,6 +1333,29 @@ pub fn copy_key_table() -> KeyTable {
             Modifiers::CTRL,
             KeyAssignment::CopyMode(CopyModeAssignment::PageDown),
         ),
+        (
+            WKeyCode::Char('/'),
+            Modifiers::NONE,
+            // An empty pattern keeps the current search pattern
+            KeyAssignment::Search(config::keyassignment::Pattern::CaseSensitiveString(
+                String::new(),
+            )),
+        ),
+        (
+            WKeyCode::Char('n'),
+            Modifiers::NONE,
+            KeyAssignment::CopyMode(CopyModeAssignment::NextMatch),
+        ),
+        (
+            WKeyCode::Char('N'),
+            Modifiers::NONE,
+            KeyAssignment::CopyMode(CopyModeAssignment::PriorMatch),
+        ),
+        (
+            WKeyCode::Char('N'),
+            Modifiers::SHIFT,
+            KeyAssignment::CopyMode(CopyModeAssignment::PriorMatch),
+        ),
     ] {
         table.insert((key, mods), KeyTableEntry { action });
     }
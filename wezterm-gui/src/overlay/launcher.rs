@@ -41,6 +41,70 @@ pub struct LauncherTabEntry {
     pub pane_count: usize,
 }
 
+/// A summary of a workspace, used to help the user
+/// recognize it when choosing which one to switch to
+pub struct LauncherWorkspaceEntry {
+    pub name: String,
+    pub window_count: usize,
+    pub tab_count: usize,
+    /// The title of the active pane of the first window
+    pub title: Option<String>,
+    /// The size of the active tab of the first window, in cells
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl LauncherWorkspaceEntry {
+    /// Must be called on the Mux thread!
+    fn new(mux: &Mux, name: String) -> Self {
+        let window_ids = mux.iter_windows_in_workspace(&name);
+        let mut tab_count = 0;
+        let mut title = None;
+        let mut cols = 0;
+        let mut rows = 0;
+        for (idx, window_id) in window_ids.iter().enumerate() {
+            if let Some(window) = mux.get_window(*window_id) {
+                tab_count += window.len();
+                if idx == 0 {
+                    if let Some(tab) = window.get_active() {
+                        let size = tab.get_size();
+                        cols = size.cols as usize;
+                        rows = size.rows as usize;
+                        title = tab.get_active_pane().map(|pane| pane.get_title());
+                    }
+                }
+            }
+        }
+        Self {
+            name,
+            window_count: window_ids.len(),
+            tab_count,
+            title,
+            cols,
+            rows,
+        }
+    }
+
+    fn label(&self) -> String {
+        let mut label = format!(
+            "Switch to workspace: `{}` ({} window{}, {} tab{}",
+            self.name,
+            self.window_count,
+            if self.window_count == 1 { "" } else { "s" },
+            self.tab_count,
+            if self.tab_count == 1 { "" } else { "s" },
+        );
+        if self.cols > 0 && self.rows > 0 {
+            label.push_str(&format!(", {}x{}", self.cols, self.rows));
+        }
+        label.push(')');
+        if let Some(title) = &self.title {
+            label.push_str(&format!(" {}", title));
+        }
+        label
+    }
+}
+
 #[derive(Debug)]
 pub struct LauncherDomainEntry {
     pub domain_id: DomainId,
@@ -57,7 +121,7 @@ pub struct LauncherArgs {
     domain_id_of_current_tab: DomainId,
     title: String,
     active_workspace: String,
-    workspaces: Vec<String>,
+    workspaces: Vec<LauncherWorkspaceEntry>,
 }
 
 impl LauncherArgs {
@@ -75,6 +139,9 @@ impl LauncherArgs {
 
         let workspaces = if flags.contains(LauncherFlags::WORKSPACES) {
             mux.iter_workspaces()
+                .into_iter()
+                .map(|name| LauncherWorkspaceEntry::new(&mux, name))
+                .collect()
         } else {
             vec![]
         };
@@ -209,18 +276,28 @@ impl LauncherState {
 
     fn build_entries(&mut self, args: LauncherArgs) {
         let config = configuration();
+
+        if args.flags.contains(LauncherFlags::TAB_ACTIONS) {
+            self.entries.push(Entry {
+                label: "Close Tab".to_string(),
+                action: KeyAssignment::CloseCurrentTab { confirm: true },
+            });
+            self.entries.push(Entry {
+                label: "Close Other Tabs".to_string(),
+                action: KeyAssignment::CloseOtherTabs { confirm: true },
+            });
+            self.entries.push(Entry {
+                label: "Rename Tab".to_string(),
+                action: KeyAssignment::RenameTab,
+            });
+        }
+
         // Pull in the user defined entries from the launch_menu
         // section of the configuration.
         if args.flags.contains(LauncherFlags::LAUNCH_MENU_ITEMS) {
             for item in &config.launch_menu {
                 self.entries.push(Entry {
-                    label: match item.label.as_ref() {
-                        Some(label) => label.to_string(),
-                        None => match item.args.as_ref() {
-                            Some(args) => args.join(" "),
-                            None => "(default shell)".to_string(),
-                        },
-                    },
+                    label: item.launcher_label(),
                     action: KeyAssignment::SpawnCommandInNewTab(item.clone()),
                 });
             }
@@ -253,11 +330,11 @@ impl LauncherState {
 
         if args.flags.contains(LauncherFlags::WORKSPACES) {
             for ws in &args.workspaces {
-                if *ws != args.active_workspace {
+                if ws.name != args.active_workspace {
                     self.entries.push(Entry {
-                        label: format!("Switch to workspace: `{}`", ws),
+                        label: ws.label(),
                         action: KeyAssignment::SwitchToWorkspace {
-                            name: Some(ws.clone()),
+                            name: Some(ws.name.clone()),
                             spawn: None,
                         },
                     });
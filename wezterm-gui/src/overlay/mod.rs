@@ -1,24 +1,34 @@
 use crate::termwindow::TermWindow;
 use mux::pane::{Pane, PaneId};
 use mux::tab::{Tab, TabId};
-use mux::termwiztermtab::{allocate, TermWizTerminal};
+use mux::termwiztermtab::{allocate, allocate_with_background, TermWizTerminal};
 use portable_pty::PtySize;
 use std::pin::Pin;
 use std::rc::Rc;
+use wezterm_term::StableRowIndex;
 
+pub mod charselect;
+pub mod command_history;
 pub mod confirm_close_pane;
 pub mod copy;
 pub mod debug;
 pub mod launcher;
 pub mod quickselect;
+pub mod rename_tab;
+pub mod rename_workspace;
 
+pub use charselect::char_select;
+pub use command_history::command_history;
 pub use confirm_close_pane::{
-    confirm_close_pane, confirm_close_tab, confirm_close_window, confirm_quit_program,
+    confirm_close_other_tabs, confirm_close_pane, confirm_close_tab, confirm_close_window,
+    confirm_download, confirm_quit_program,
 };
 pub use copy::{CopyModeParams, CopyOverlay};
 pub use debug::show_debug_overlay;
 pub use launcher::{launcher, LauncherArgs, LauncherFlags};
 pub use quickselect::QuickSelectOverlay;
+pub use rename_tab::rename_tab;
+pub use rename_workspace::rename_workspace;
 
 pub fn start_overlay<T, F>(
     term_window: &TermWindow,
@@ -49,6 +59,10 @@ where
     (tw_tab, Box::pin(future))
 }
 
+/// Starts an overlay that covers just `pane`.
+/// The overlay's screen starts out as a copy of the visible portion
+/// of `pane`, so a program that draws without clearing the screen
+/// appears on top of the pane's content.
 pub fn start_overlay_pane<T, F>(
     term_window: &TermWindow,
     pane: &Rc<dyn Pane>,
@@ -70,7 +84,11 @@ where
         pixel_height: term_window.render_metrics.cell_size.height as u16
             * dims.viewport_rows as u16,
     };
-    let (tw_term, tw_tab) = allocate(size);
+    let top = term_window
+        .get_viewport(pane_id)
+        .unwrap_or(dims.physical_top);
+    let (_first_row, background) = pane.get_lines(top..top + dims.viewport_rows as StableRowIndex);
+    let (tw_term, tw_tab) = allocate_with_background(size, background);
 
     let window = term_window.window.clone().unwrap();
 
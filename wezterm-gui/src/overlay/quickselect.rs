@@ -1,12 +1,16 @@
 use crate::selection::{SelectionCoordinate, SelectionRange};
 use crate::termwindow::{TermWindow, TermWindowNotif};
-use config::keyassignment::{ClipboardCopyDestination, QuickSelectArguments, ScrollbackEraseMode};
+use config::keyassignment::{
+    ClipboardCopyDestination, QuickSelectAction, QuickSelectArguments, QuickSelectPatternAction,
+    ScrollbackEraseMode,
+};
 use config::ConfigHandle;
 use mux::domain::DomainId;
 use mux::pane::{Pane, PaneId, Pattern, SearchResult};
 use mux::renderable::*;
 use portable_pty::PtySize;
 use rangeset::RangeSet;
+use regex::Regex;
 use std::cell::{RefCell, RefMut};
 use std::collections::HashMap;
 use std::ops::Range;
@@ -51,6 +55,57 @@ const PATTERNS: [&str; 14] = [
     r"[0-9]{4,}",
 ];
 
+/// Finds the first of the `quick_select_actions` entries whose pattern
+/// matches the entirety of `text`, and returns its action.
+/// The arguments of a `RunCommand` action are returned with the
+/// match and capture group references expanded.
+fn pattern_action_for_text(
+    pattern_actions: &[QuickSelectPatternAction],
+    text: &str,
+) -> Option<QuickSelectAction> {
+    for pa in pattern_actions {
+        let re = match Regex::new(&format!("^(?:{})$", pa.pattern)) {
+            Ok(re) => re,
+            Err(err) => {
+                log::error!(
+                    "invalid quick_select_actions pattern {}: {:#}",
+                    pa.pattern,
+                    err
+                );
+                continue;
+            }
+        };
+        let captures = match re.captures(text) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        return Some(match &pa.action {
+            QuickSelectAction::RunCommand(args) => QuickSelectAction::RunCommand(
+                args.iter()
+                    .map(|arg| {
+                        let mut expanded = String::new();
+                        captures.expand(arg, &mut expanded);
+                        expanded
+                    })
+                    .collect(),
+            ),
+            action => action.clone(),
+        });
+    }
+    None
+}
+
+fn run_command(args: &[String]) {
+    if args.is_empty() {
+        return;
+    }
+    let mut cmd = smol::process::Command::new(&args[0]);
+    cmd.args(&args[1..]);
+    if let Err(err) = cmd.stdin(smol::process::Stdio::null()).spawn() {
+        log::error!("quick select failed to run {:?}: {:#}", args, err);
+    }
+}
+
 /// This function computes a set of labels for a given alphabet.
 /// It is derived from https://github.com/fcsonline/tmux-thumbs/blob/master/src/alphabets.rs
 /// which is Copyright (c) 2019 Ferran Basora and provided under the MIT license
@@ -100,6 +155,52 @@ pub fn compute_labels_for_alphabet(alphabet: &str, num_matches: usize) -> Vec<St
         .collect()
 }
 
+#[cfg(test)]
+mod pattern_action_test {
+    use super::*;
+
+    #[test]
+    fn first_full_match_wins() {
+        let actions = vec![
+            QuickSelectPatternAction {
+                pattern: r"JIRA-(\d+)".to_string(),
+                action: QuickSelectAction::RunCommand(vec![
+                    "open-ticket".to_string(),
+                    "--id=$1".to_string(),
+                    "${0}".to_string(),
+                ]),
+            },
+            QuickSelectPatternAction {
+                pattern: r"https?://\S+".to_string(),
+                action: QuickSelectAction::OpenUrl,
+            },
+            QuickSelectPatternAction {
+                pattern: r"\S+".to_string(),
+                action: QuickSelectAction::Paste,
+            },
+        ];
+
+        assert_eq!(
+            pattern_action_for_text(&actions, "JIRA-123"),
+            Some(QuickSelectAction::RunCommand(vec![
+                "open-ticket".to_string(),
+                "--id=123".to_string(),
+                "JIRA-123".to_string(),
+            ]))
+        );
+        assert_eq!(
+            pattern_action_for_text(&actions, "https://wezfurlong.org/"),
+            Some(QuickSelectAction::OpenUrl)
+        );
+        // Only the entire text is considered a match
+        assert_eq!(pattern_action_for_text(&actions, "see JIRA-123"), None);
+        assert_eq!(
+            pattern_action_for_text(&actions, "JIRA-123x"),
+            Some(QuickSelectAction::Paste)
+        );
+    }
+}
+
 #[cfg(test)]
 mod alphabet_test {
     use super::*;
@@ -218,6 +319,13 @@ impl QuickSelectOverlay {
                 pattern.push_str(p);
                 have_patterns = true;
             }
+            for p in &config.quick_select_actions {
+                if have_patterns {
+                    pattern.push('|');
+                }
+                pattern.push_str(&p.pattern);
+                have_patterns = true;
+            }
         }
         pattern.push(')');
 
@@ -390,7 +498,7 @@ impl Pane for QuickSelectOverlay {
         self.delegate.mouse_event(event)
     }
 
-    fn perform_actions(&self, actions: Vec<termwiz::escape::Action>) {
+    fn perform_actions(&self, actions: &mut Vec<termwiz::escape::Action>) {
         self.delegate.perform_actions(actions)
     }
 
@@ -715,6 +823,7 @@ impl QuickSelectRenderable {
 
         let pane_id = self.delegate.pane_id();
         let action = self.args.action.clone();
+        let pattern_actions = self.config.quick_select_actions.clone();
         self.window
             .notify(TermWindowNotif::Apply(Box::new(move |term_window| {
                 let mux = mux::Mux::get().unwrap();
@@ -748,10 +857,25 @@ impl QuickSelectRenderable {
                         if let Some(action) = action {
                             let _ = term_window.perform_key_assignment(&pane, &action);
                         } else {
-                            term_window.copy_to_clipboard(
-                                ClipboardCopyDestination::ClipboardAndPrimarySelection,
-                                text,
-                            );
+                            match pattern_action_for_text(&pattern_actions, &text) {
+                                Some(QuickSelectAction::Copy) | None => {
+                                    term_window.copy_to_clipboard(
+                                        ClipboardCopyDestination::ClipboardAndPrimarySelection,
+                                        text,
+                                    );
+                                }
+                                Some(QuickSelectAction::Paste) => {
+                                    if !paste {
+                                        let _ = pane.send_paste(&text);
+                                    }
+                                }
+                                Some(QuickSelectAction::OpenUrl) => {
+                                    wezterm_open_url::open_url(&text);
+                                }
+                                Some(QuickSelectAction::RunCommand(args)) => {
+                                    run_command(&args);
+                                }
+                            }
                         }
                     }
                 }
@@ -0,0 +1,56 @@
+use crate::termwindow::TermWindowNotif;
+use crate::TermWindow;
+use mux::tab::TabId;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+use termwiz::lineedit::{LineEditor, NopLineEditorHost};
+use termwiz::surface::Change;
+use termwiz::terminal::Terminal;
+
+/// Prompts for a new title for the tab.  Accepting an empty title
+/// clears any previously assigned title, restoring the default
+/// pane-derived title.  Cancelling leaves the title unchanged.
+pub fn rename_tab(
+    tab_id: TabId,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+    current_title: String,
+) -> anyhow::Result<()> {
+    term.render(&[Change::Title("Rename Tab".to_string())])?;
+    if !current_title.is_empty() {
+        term.render(&[Change::Text(format!(
+            "Current title: {}\r\n",
+            current_title
+        ))])?;
+    }
+    term.render(&[Change::Text(
+        "Enter a new title, or an empty title to restore the default.\r\n".to_string(),
+    )])?;
+
+    let mut host = NopLineEditorHost::default();
+    let title = {
+        let mut editor = LineEditor::new(&mut term);
+        editor.set_prompt("Tab title: ");
+        editor.read_line(&mut host)?
+    };
+
+    if let Some(title) = title {
+        window.notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+            let mux = Mux::get().unwrap();
+            if let Some(tab) = mux.get_tab(tab_id) {
+                let title = title.trim();
+                tab.set_title(title);
+                if let Some(domain) = tab
+                    .get_active_pane()
+                    .and_then(|pane| mux.get_domain(pane.domain_id()))
+                {
+                    domain.local_tab_title_changed(tab_id, title);
+                }
+            }
+            term_window.update_title_post_status();
+        })));
+    }
+    TermWindow::schedule_cancel_overlay(window, tab_id, None);
+
+    Ok(())
+}
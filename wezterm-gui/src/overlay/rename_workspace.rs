@@ -0,0 +1,68 @@
+use crate::termwindow::TermWindowNotif;
+use crate::TermWindow;
+use mux::tab::TabId;
+use mux::termwiztermtab::TermWizTerminal;
+use mux::Mux;
+use termwiz::lineedit::{LineEditor, NopLineEditorHost};
+use termwiz::surface::Change;
+use termwiz::terminal::Terminal;
+
+/// Prompts for a new name for the workspace.  Cancelling, or
+/// accepting an empty name, leaves the name unchanged.
+/// Names that are already used by another workspace are refused
+/// and the user is prompted again.
+pub fn rename_workspace(
+    tab_id: TabId,
+    mut term: TermWizTerminal,
+    window: ::window::Window,
+    current_name: String,
+    existing_names: Vec<String>,
+) -> anyhow::Result<()> {
+    term.render(&[Change::Title("Rename Workspace".to_string())])?;
+    term.render(&[Change::Text(format!(
+        "Current workspace: {}\r\n",
+        current_name
+    ))])?;
+
+    let mut host = NopLineEditorHost::default();
+    let name = loop {
+        let name = {
+            let mut editor = LineEditor::new(&mut term);
+            editor.set_prompt("Workspace name: ");
+            editor.read_line(&mut host)?
+        };
+        match name {
+            Some(name) => {
+                let name = name.trim().to_string();
+                if name != current_name && existing_names.contains(&name) {
+                    term.render(&[Change::Text(format!(
+                        "There is already a workspace named {}\r\n",
+                        name
+                    ))])?;
+                    continue;
+                }
+                break Some(name);
+            }
+            None => break None,
+        }
+    };
+
+    if let Some(name) = name {
+        if !name.is_empty() {
+            window.notify(TermWindowNotif::Apply(Box::new(move |term_window| {
+                let mux = Mux::get().unwrap();
+                if let Err(err) = mux.rename_workspace(&current_name, &name) {
+                    log::error!("Failed to rename workspace: {:#}", err);
+                    return;
+                }
+                for domain in mux.iter_domains() {
+                    domain.local_workspace_renamed(&current_name, &name);
+                }
+                term_window.update_title_post_status();
+            })));
+        }
+    }
+    TermWindow::schedule_cancel_overlay(window, tab_id, None);
+
+    Ok(())
+}
@@ -130,4 +130,41 @@ impl<'a> Quad<'a> {
         self.vert[V_BOT_LEFT].position = (left, bottom);
         self.vert[V_BOT_RIGHT].position = (right, bottom);
     }
+
+    /// Crop the quad so that it lies within `top..bottom`, adjusting
+    /// the texture coordinates so that the visible part of the texture
+    /// stays in place.  A quad that is entirely outside is collapsed
+    /// so that it draws nothing.
+    pub fn clip_vertically(&mut self, top: f32, bottom: f32) {
+        let y1 = self.vert[V_TOP_LEFT].position.1;
+        let y2 = self.vert[V_BOT_LEFT].position.1;
+        if y1 >= top && y2 <= bottom {
+            return;
+        }
+        if y2 <= top || y1 >= bottom || y2 <= y1 {
+            for v in self.vert.iter_mut() {
+                v.position.1 = top;
+            }
+            return;
+        }
+
+        let ty1 = self.vert[V_TOP_LEFT].tex.1;
+        let ty2 = self.vert[V_BOT_LEFT].tex.1;
+        let tex_y = |y: f32| ty1 + (ty2 - ty1) * (y - y1) / (y2 - y1);
+
+        if y1 < top {
+            let t = tex_y(top);
+            for &v in &[V_TOP_LEFT, V_TOP_RIGHT] {
+                self.vert[v].position.1 = top;
+                self.vert[v].tex.1 = t;
+            }
+        }
+        if y2 > bottom {
+            let t = tex_y(bottom);
+            for &v in &[V_BOT_LEFT, V_BOT_RIGHT] {
+                self.vert[v].position.1 = bottom;
+                self.vert[v].tex.1 = t;
+            }
+        }
+    }
 }
@@ -43,6 +43,26 @@ impl<'a> MappedQuads<'a> {
 
         Ok(quad)
     }
+
+    /// Returns the index of the next quad to be allocated, for use
+    /// with `clip_vertically_from`
+    pub fn next_index(&self) -> usize {
+        *self.next
+    }
+
+    /// Crops the quads allocated since `start` so that they lie
+    /// within `top..bottom`, eg: to hide the part of a row that has
+    /// been scrolled out of a pane by a fractional amount
+    pub fn clip_vertically_from(&mut self, start: usize, top: f32, bottom: f32) {
+        let end = (*self.next).min(self.capacity);
+        for idx in start..end {
+            let idx = idx * VERTICES_PER_CELL;
+            let mut quad = Quad {
+                vert: &mut self.mapping[idx..idx + VERTICES_PER_CELL],
+            };
+            quad.clip_vertically(top, bottom);
+        }
+    }
 }
 
 pub struct TripleVertexBuffer {
@@ -191,6 +211,10 @@ pub struct RenderState {
     pub glyph_cache: RefCell<GlyphCache<SrgbTexture2d>>,
     pub util_sprites: UtilSprites<SrgbTexture2d>,
     pub glyph_prog: glium::Program,
+    /// Variant of glyph_prog that outputs linear values so that the
+    /// sRGB framebuffer blends in linear space.  None if the context
+    /// doesn't support sRGB framebuffers.
+    pub linear_glyph_prog: Option<glium::Program>,
     pub layers: RefCell<Vec<Rc<RenderLayer>>>,
 }
 
@@ -206,7 +230,18 @@ impl RenderState {
             let result = UtilSprites::new(&mut *glyph_cache.borrow_mut(), metrics);
             match result {
                 Ok(util_sprites) => {
-                    let glyph_prog = Self::compile_prog(&context, Self::glyph_shader)?;
+                    let glyph_prog = Self::compile_prog(&context, Self::glyph_shader, true)?;
+                    let linear_glyph_prog = if context.get_capabilities().srgb {
+                        match Self::compile_prog(&context, Self::linear_glyph_shader, false) {
+                            Ok(prog) => Some(prog),
+                            Err(err) => {
+                                log::warn!("linear blending is unavailable: {:#}", err);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
 
                     let main_layer = Rc::new(RenderLayer::new(&context, 1024, 0)?);
 
@@ -215,6 +250,7 @@ impl RenderState {
                         glyph_cache,
                         util_sprites,
                         glyph_prog,
+                        linear_glyph_prog,
                         layers: RefCell::new(vec![main_layer]),
                     });
                 }
@@ -283,6 +319,7 @@ impl RenderState {
     fn compile_prog(
         context: &Rc<GliumContext>,
         fragment_shader: fn(&str) -> (String, String),
+        outputs_srgb: bool,
     ) -> anyhow::Result<glium::Program> {
         let mut errors = vec![];
 
@@ -294,7 +331,7 @@ impl RenderState {
             let source = glium::program::ProgramCreationInput::SourceCode {
                 vertex_shader: &vertex_shader,
                 fragment_shader: &fragment_shader,
-                outputs_srgb: true,
+                outputs_srgb,
                 tessellation_control_shader: None,
                 tessellation_evaluation_shader: None,
                 transform_feedback_varyings: None,
@@ -323,6 +360,14 @@ impl RenderState {
         )
     }
 
+    fn linear_glyph_shader(version: &str) -> (String, String) {
+        let (vertex_shader, fragment_shader) = Self::glyph_shader(version);
+        (
+            vertex_shader,
+            fragment_shader.replacen('\n', "\n#define LINEAR_BLENDING\n", 1),
+        )
+    }
+
     pub fn recreate_texture_atlas(
         &mut self,
         fonts: &Rc<FontConfiguration>,
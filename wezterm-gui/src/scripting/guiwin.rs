@@ -47,6 +47,10 @@ impl UserData for GuiWin {
         methods.add_method("get_appearance", |_, _, _: ()| {
             Ok(Connection::get().unwrap().get_appearance().to_string())
         });
+        methods.add_method("request_attention", |_, this, _: ()| {
+            this.window.request_attention();
+            Ok(())
+        });
         methods.add_method("set_right_status", |_, this, status: String| {
             this.window.notify(TermWindowNotif::SetRightStatus(status));
             Ok(())
@@ -5,6 +5,7 @@ use mlua::{UserData, UserDataMethods};
 use mux::pane::{Pane, PaneId};
 use mux::Mux;
 use std::rc::Rc;
+use wezterm_dynamic::ToDynamic;
 
 #[derive(Clone)]
 pub struct PaneObject {
@@ -60,6 +61,25 @@ impl UserData for PaneObject {
         methods.add_method("has_unseen_output", |_, this, _: ()| {
             Ok(this.pane()?.has_unseen_output())
         });
+        methods.add_method("get_command_history", |lua, this, _: ()| {
+            let history = this.pane()?.get_command_history();
+            luahelper::dynamic_to_lua_value(lua, history.to_dynamic())
+        });
+
+        // Sets the unicode version used for width calculations in
+        // this pane, in the same way as the iTerm2 UnicodeVersion
+        // escape sequence, overriding the unicode_version config.
+        methods.add_method("set_unicode_version", |_, this, version: u8| {
+            use termwiz::escape::osc::{ITermProprietary, ITermUnicodeVersionOp};
+            use termwiz::escape::{Action, OperatingSystemCommand};
+            this.pane()?
+                .perform_actions(&mut vec![Action::OperatingSystemCommand(Box::new(
+                    OperatingSystemCommand::ITermProprietary(ITermProprietary::UnicodeVersion(
+                        ITermUnicodeVersionOp::Set(version),
+                    )),
+                ))]);
+            Ok(())
+        });
 
         // When called with no arguments, returns the lines from the
         // viewport as plain text (no escape sequences).
@@ -1,5 +1,5 @@
 use crate::termwindow::{PaneInformation, TabInformation, UIItem, UIItemType};
-use config::{ConfigHandle, TabBarColors};
+use config::{ConfigHandle, TabBarColors, TitleElision};
 use mlua::FromLua;
 use termwiz::cell::{unicode_column_width, Cell, CellAttributes};
 use termwiz::color::ColorSpec;
@@ -8,6 +8,7 @@ use termwiz::escape::parser::Parser;
 use termwiz::escape::{Action, ControlCode, CSI};
 use termwiz::surface::SEQ_ZERO;
 use termwiz_funcs::{format_as_escapes, FormatItem};
+use unicode_segmentation::UnicodeSegmentation;
 use wezterm_term::Line;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -20,6 +21,7 @@ pub struct TabBarState {
 pub enum TabBarItem {
     None,
     Tab { tab_idx: usize, active: bool },
+    CloseTabButton { tab_idx: usize },
     NewTabButton,
 }
 
@@ -111,22 +113,49 @@ fn compute_tab_title(
         Some(title) => title,
         None => {
             let title = if let Some(pane) = &tab.active_pane {
-                let mut title = pane.title.clone();
+                // An explicitly assigned tab title takes precedence
+                // over the title of the active pane
+                let pane_title = if tab.tab_title.is_empty() {
+                    &pane.title
+                } else {
+                    &tab.tab_title
+                };
+                let indicator = if tab.has_unseen_bell {
+                    config.tab_bar_style.bell_indicator.as_str()
+                } else if tab.has_unseen_output {
+                    config.tab_bar_style.activity_indicator.as_str()
+                } else {
+                    ""
+                };
                 let classic_spacing = if config.use_fancy_tab_bar { "" } else { " " };
-                if config.show_tab_index_in_tab_bar {
-                    title = format!(
-                        "{}{}: {}{}",
-                        classic_spacing,
-                        tab.tab_index
-                            + if config.tab_and_split_indices_are_zero_based {
-                                0
-                            } else {
-                                1
-                            },
-                        pane.title,
+                let (prefix, suffix) = if config.show_tab_index_in_tab_bar {
+                    (
+                        format!(
+                            "{}{}{}: ",
+                            classic_spacing,
+                            indicator,
+                            tab.tab_index
+                                + if config.tab_and_split_indices_are_zero_based {
+                                    0
+                                } else {
+                                    1
+                                },
+                        ),
                         classic_spacing,
-                    );
-                }
+                    )
+                } else {
+                    (indicator.to_string(), "")
+                };
+                // Elide the pane title, rather than the index and
+                // indicators, if the title is too wide for the tab
+                let pane_title = elide_title(
+                    pane_title,
+                    tab_max_width.saturating_sub(
+                        unicode_column_width(&prefix, None) + unicode_column_width(suffix, None),
+                    ),
+                    config.tab_title_elision,
+                );
+                let mut title = format!("{}{}{}", prefix, pane_title, suffix);
                 // We have a preferred soft minimum on tab width to make it
                 // easier to click on tab titles, but we'll still go below
                 // this if there are too many tabs to fit the window at
@@ -149,6 +178,52 @@ fn compute_tab_title(
     }
 }
 
+/// Shorten `title` so that it is no wider than `max_width` cells,
+/// replacing the removed text with an ellipsis.
+fn elide_title(title: &str, max_width: usize, elision: TitleElision) -> String {
+    if unicode_column_width(title, None) <= max_width {
+        return title.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    /// Collects graphemes from the iterator until they would
+    /// exceed `width` cells
+    fn take_width<'a>(graphemes: impl Iterator<Item = &'a str>, width: usize) -> Vec<&'a str> {
+        let mut used = 0;
+        let mut result = vec![];
+        for g in graphemes {
+            let w = unicode_column_width(g, None);
+            if used + w > width {
+                break;
+            }
+            used += w;
+            result.push(g);
+        }
+        result
+    }
+
+    // Leave room for the ellipsis
+    let budget = max_width - 1;
+    match elision {
+        TitleElision::End => format!("{}…", take_width(title.graphemes(true), budget).concat()),
+        TitleElision::Start => {
+            let mut tail = take_width(title.graphemes(true).rev(), budget);
+            tail.reverse();
+            format!("…{}", tail.concat())
+        }
+        TitleElision::Middle => {
+            let head_width = (budget + 1) / 2;
+            let head = take_width(title.graphemes(true), head_width);
+            let head_used = unicode_column_width(&head.concat(), None);
+            let mut tail = take_width(title.graphemes(true).rev(), budget - head_used);
+            tail.reverse();
+            format!("{}…{}", head.concat(), tail.concat())
+        }
+    }
+}
+
 fn is_tab_hover(mouse_x: Option<usize>, x: usize, tab_title_len: usize) -> bool {
     return mouse_x
         .map(|mouse_x| mouse_x >= x && mouse_x < x + tab_title_len)
@@ -214,6 +289,24 @@ impl TabBarState {
             },
         );
 
+        // The fancy tab bar renders its own close buttons.
+        // Reserve room for the wider of the two states of the button, so
+        // that hovering over it doesn't shift the rest of the tab bar.
+        let close_tab_width = if config.show_close_tab_button_in_tabs
+            && config.show_close_tab_button_in_retro_tab_bar
+            && !config.use_fancy_tab_bar
+        {
+            let close_tab =
+                parse_status_text(&config.tab_bar_style.close_tab, CellAttributes::default());
+            let close_tab_hover = parse_status_text(
+                &config.tab_bar_style.close_tab_hover,
+                CellAttributes::default(),
+            );
+            close_tab.cells().len().max(close_tab_hover.cells().len())
+        } else {
+            0
+        };
+
         // We ultimately want to produce a line looking like this:
         // ` | tab1-title x | tab2-title x |  +      . - X `
         // Where the `+` sign will spawn a new tab (or show a context
@@ -225,25 +318,28 @@ impl TabBarState {
         let tab_titles: Vec<TitleText> = tab_info
             .iter()
             .map(|tab| {
-                if tab.is_active {
+                let max_width = if tab.is_active {
                     active_tab_no = tab.tab_index;
-                }
-                compute_tab_title(
-                    tab,
-                    tab_info,
-                    pane_info,
-                    config,
-                    false,
-                    config.tab_max_width,
-                )
+                    config
+                        .active_tab_max_width
+                        .unwrap_or(0)
+                        .max(config.tab_max_width)
+                } else {
+                    config.tab_max_width
+                };
+                compute_tab_title(tab, tab_info, pane_info, config, false, max_width)
             })
             .collect();
         let titles_len: usize = tab_titles.iter().map(|s| s.len).sum();
         let number_of_tabs = tab_titles.len();
 
-        let available_cells =
-            title_width.saturating_sub(number_of_tabs.saturating_sub(1) + new_tab.cells().len());
-        let tab_width_max = if config.use_fancy_tab_bar || available_cells >= titles_len {
+        let available_cells = title_width.saturating_sub(
+            number_of_tabs.saturating_sub(1)
+                + new_tab.cells().len()
+                + number_of_tabs * close_tab_width,
+        );
+        let has_room = config.use_fancy_tab_bar || available_cells >= titles_len;
+        let tab_width_max = if has_room {
             // We can render each title with its full width
             usize::max_value()
         } else {
@@ -251,6 +347,12 @@ impl TabBarState {
             available_cells / number_of_tabs
         }
         .min(config.tab_max_width);
+        // The active tab may be wider, but only while there is room
+        // to do so without squeezing the others.
+        let active_tab_width_max = match config.active_tab_max_width {
+            Some(width) if has_room => width.max(tab_width_max),
+            _ => tab_width_max,
+        };
 
         let mut line = Line::with_width(0, SEQ_ZERO);
 
@@ -258,9 +360,14 @@ impl TabBarState {
         let mut items = vec![];
 
         for (tab_idx, tab_title) in tab_titles.iter().enumerate() {
-            let tab_title_len = tab_title.len.min(tab_width_max);
             let active = tab_idx == active_tab_no;
-            let hover = !active && is_tab_hover(mouse_x, x, tab_title_len);
+            let tab_width_max = if active {
+                active_tab_width_max
+            } else {
+                tab_width_max
+            };
+            let tab_title_len = tab_title.len.min(tab_width_max);
+            let hover = !active && is_tab_hover(mouse_x, x, tab_title_len + close_tab_width);
 
             // Recompute the title so that it factors in both the hover state
             // and the adjusted maximum tab width based on available space.
@@ -309,6 +416,36 @@ impl TabBarState {
 
             line.append_line(tab_line, SEQ_ZERO);
             x += width;
+
+            if close_tab_width > 0 {
+                let close_hover = is_tab_hover(mouse_x, x, close_tab_width);
+                let mut close_tab = parse_status_text(
+                    if close_hover {
+                        &config.tab_bar_style.close_tab_hover
+                    } else {
+                        &config.tab_bar_style.close_tab
+                    },
+                    cell_attrs.clone(),
+                );
+                let padding = close_tab_width.saturating_sub(close_tab.cells().len());
+                if padding > 0 {
+                    close_tab.append_line(
+                        Line::from_text(&" ".repeat(padding), cell_attrs, SEQ_ZERO, None),
+                        SEQ_ZERO,
+                    );
+                }
+                let width = close_tab.cells().len();
+
+                items.push(TabEntry {
+                    item: TabBarItem::CloseTabButton { tab_idx },
+                    title: close_tab.clone(),
+                    x,
+                    width,
+                });
+
+                line.append_line(close_tab, SEQ_ZERO);
+                x += width;
+            }
         }
 
         // New tab button
@@ -478,3 +615,20 @@ fn parse_status_text(text: &str, default_cell: CellAttributes) -> Line {
     flush_print(&mut print_buffer, &mut cells, &pen);
     Line::from_cells(cells, SEQ_ZERO)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn elision() {
+        let title = "vim ~/src/wezterm/README.md";
+        assert_eq!(elide_title(title, 40, TitleElision::End), title);
+        assert_eq!(elide_title(title, 10, TitleElision::End), "vim ~/src…");
+        assert_eq!(elide_title(title, 10, TitleElision::Start), "…README.md");
+        assert_eq!(elide_title(title, 10, TitleElision::Middle), "vim ~…E.md");
+        assert_eq!(elide_title(title, 0, TitleElision::Middle), "");
+        // Double width characters are never split
+        assert_eq!(elide_title("日本語の題名", 6, TitleElision::End), "日本…");
+    }
+}
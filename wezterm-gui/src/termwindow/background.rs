@@ -398,10 +398,11 @@ impl crate::TermWindow {
 
         let color = bg_color.mul_alpha(layer.def.opacity);
 
-        let (sprite, next_due) = gl_state
-            .glyph_cache
-            .borrow_mut()
-            .cached_image(&layer.source, None)?;
+        let (sprite, next_due) = gl_state.glyph_cache.borrow_mut().cached_image(
+            &layer.source,
+            None,
+            self.animate_images(),
+        )?;
         self.update_next_frame_time(next_due);
 
         let pixel_width = self.dimensions.pixel_width as f32;
@@ -1,25 +1,104 @@
 use crate::termwindow::TermWindowNotif;
 use crate::TermWindow;
 use config::keyassignment::{ClipboardCopyDestination, ClipboardPasteSource};
+use config::{ClipboardImagePaste, DroppedFileQuoting};
 use mux::pane::Pane;
 use mux::Mux;
+use std::io::Write;
 use std::rc::Rc;
+use tempfile::TempPath;
 use window::{Clipboard, WindowOps};
 
+/// The content to be delivered to the pane for a paste
+enum PasteContent {
+    /// Text that is pasted as usual
+    Text(String),
+    /// An escape sequence that is sent to the pane verbatim,
+    /// bypassing bracketed paste
+    Escape(String),
+    /// The quoted path of a temporary file that is pasted as text.
+    /// The file is removed when the window is closed.
+    TempFile(String, TempPath),
+}
+
+/// Produce the content for an image found in the clipboard,
+/// according to the `clipboard_image_paste` configuration
+fn image_paste_content(
+    mode: ClipboardImagePaste,
+    quoting: DroppedFileQuoting,
+    png: Vec<u8>,
+) -> anyhow::Result<Option<PasteContent>> {
+    match mode {
+        ClipboardImagePaste::Ignore => Ok(None),
+        ClipboardImagePaste::InlineImage => Ok(Some(PasteContent::Escape(format!(
+            "\x1b]1337;File=inline=1;size={}:{}\x07",
+            png.len(),
+            base64::encode(&png)
+        )))),
+        ClipboardImagePaste::TempFile => {
+            // tempfile picks an unpredictable name and creates
+            // the file with 0600 permissions
+            let mut file = tempfile::Builder::new()
+                .prefix("wezterm-clipboard-")
+                .suffix(".png")
+                .tempfile()?;
+            file.write_all(&png)?;
+            let path = file.into_temp_path();
+            Ok(Some(PasteContent::TempFile(
+                quoting.escape(&path.to_string_lossy()),
+                path,
+            )))
+        }
+    }
+}
+
+/// Returns the clipboards that make up a copy destination
+fn destination_clipboards(clipboard: ClipboardCopyDestination) -> &'static [Clipboard] {
+    match clipboard {
+        ClipboardCopyDestination::Clipboard => &[Clipboard::Clipboard],
+        ClipboardCopyDestination::PrimarySelection => &[Clipboard::PrimarySelection],
+        ClipboardCopyDestination::ClipboardAndPrimarySelection => {
+            &[Clipboard::Clipboard, Clipboard::PrimarySelection]
+        }
+    }
+}
+
 impl TermWindow {
     pub fn copy_to_clipboard(&self, clipboard: ClipboardCopyDestination, text: String) {
-        let clipboard = match clipboard {
-            ClipboardCopyDestination::Clipboard => [Some(Clipboard::Clipboard), None],
-            ClipboardCopyDestination::PrimarySelection => [Some(Clipboard::PrimarySelection), None],
-            ClipboardCopyDestination::ClipboardAndPrimarySelection => [
-                Some(Clipboard::Clipboard),
-                Some(Clipboard::PrimarySelection),
-            ],
-        };
-        for &c in &clipboard {
-            if let Some(c) = c {
-                self.window.as_ref().unwrap().set_clipboard(c, text.clone());
-            }
+        self.copy_to_clipboard_with_uri_list(clipboard, text, vec![]);
+    }
+
+    /// Copies text to the clipboard, also offering `uris` as a
+    /// `text/uri-list` on systems that support it, so that links
+    /// can be dropped into browsers and file managers
+    pub fn copy_to_clipboard_with_uri_list(
+        &self,
+        clipboard: ClipboardCopyDestination,
+        text: String,
+        uris: Vec<String>,
+    ) {
+        for &c in destination_clipboards(clipboard) {
+            self.window.as_ref().unwrap().set_clipboard_with_uri_list(
+                c,
+                text.clone(),
+                uris.clone(),
+            );
+        }
+    }
+
+    /// Copies `html` to the clipboard as `text/html` on systems
+    /// that support it, with `text` as the plain text fallback
+    pub fn copy_to_clipboard_with_html(
+        &self,
+        clipboard: ClipboardCopyDestination,
+        text: String,
+        html: String,
+    ) {
+        for &c in destination_clipboards(clipboard) {
+            self.window
+                .as_ref()
+                .unwrap()
+                .set_clipboard_with_html(c, text.clone(), html.clone());
         }
     }
 
@@ -35,24 +114,62 @@ impl TermWindow {
             ClipboardPasteSource::Clipboard => Clipboard::Clipboard,
             ClipboardPasteSource::PrimarySelection => Clipboard::PrimarySelection,
         };
+        let image_mode = self.config.clipboard_image_paste;
+        let quoting = self.config.quote_dropped_files;
         let future = window.get_clipboard(clipboard);
         promise::spawn::spawn(async move {
-            if let Ok(clip) = future.await {
-                window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
-                    if let Some(pane) = myself
-                        .pane_state(pane_id)
-                        .overlay
-                        .as_ref()
-                        .map(|overlay| overlay.pane.clone())
-                        .or_else(|| {
-                            let mux = Mux::get().unwrap();
-                            mux.get_pane(pane_id)
-                        })
-                    {
-                        pane.trickle_paste(clip).ok();
+            let text = future.await;
+
+            // Text takes precedence; images are only considered
+            // when the clipboard has no text to offer
+            let image = match &text {
+                Ok(text) if !text.is_empty() => None,
+                _ if image_mode != ClipboardImagePaste::Ignore => {
+                    window.get_clipboard_image(clipboard).await.ok().flatten()
+                }
+                _ => None,
+            };
+
+            let content = match image {
+                Some(png) => match image_paste_content(image_mode, quoting, png) {
+                    Ok(Some(content)) => content,
+                    Ok(None) => return,
+                    Err(err) => {
+                        log::error!("Failed to paste clipboard image: {:#}", err);
+                        return;
+                    }
+                },
+                None => match text {
+                    Ok(text) => PasteContent::Text(text),
+                    Err(_) => return,
+                },
+            };
+
+            window.notify(TermWindowNotif::Apply(Box::new(move |myself| {
+                if let Some(pane) = myself
+                    .pane_state(pane_id)
+                    .overlay
+                    .as_ref()
+                    .map(|overlay| overlay.pane.clone())
+                    .or_else(|| {
+                        let mux = Mux::get().unwrap();
+                        mux.get_pane(pane_id)
+                    })
+                {
+                    match content {
+                        PasteContent::Text(text) => {
+                            pane.trickle_paste(text).ok();
+                        }
+                        PasteContent::Escape(seq) => {
+                            pane.writer().write_all(seq.as_bytes()).ok();
+                        }
+                        PasteContent::TempFile(text, path) => {
+                            pane.trickle_paste(text).ok();
+                            myself.clipboard_image_files.push(path);
+                        }
                     }
-                })));
-            }
+                }
+            })));
         })
         .detach();
         self.maybe_scroll_to_bottom_for_input(&pane);
@@ -7,6 +7,7 @@ use smol::Timer;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 use termwiz::input::KeyboardEncoding;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Debug, Clone)]
 pub struct KeyTableStateEntry {
@@ -564,6 +565,11 @@ impl super::TermWindow {
                     return;
                 }
                 self.key_table_state.did_process_key();
+                let s = if self.config.normalize_output_to_unicode_nfc {
+                    s.nfc().collect()
+                } else {
+                    s
+                };
                 if self.config.debug_key_events {
                     log::info!("send to pane string={:?}", s);
                 }
@@ -7,9 +7,10 @@ use crate::frontend::{front_end, try_front_end};
 use crate::glium::texture::SrgbTexture2d;
 use crate::inputmap::InputMap;
 use crate::overlay::{
-    confirm_close_pane, confirm_close_tab, confirm_close_window, confirm_quit_program, launcher,
-    start_overlay, start_overlay_pane, CopyModeParams, CopyOverlay, LauncherArgs, LauncherFlags,
-    QuickSelectOverlay,
+    char_select, command_history, confirm_close_other_tabs, confirm_close_pane, confirm_close_tab,
+    confirm_close_window, confirm_download, confirm_quit_program, launcher, rename_tab,
+    rename_workspace, start_overlay, start_overlay_pane, CopyModeParams, CopyOverlay, LauncherArgs,
+    LauncherFlags, QuickSelectOverlay,
 };
 use crate::scripting::guiwin::GuiWin;
 use crate::scripting::pane::PaneObject;
@@ -30,12 +31,13 @@ use config::keyassignment::{
     QuickSelectArguments, RotationDirection, SpawnCommand, SplitSize,
 };
 use config::{
-    configuration, AudibleBell, ConfigHandle, Dimension, DimensionContext, TermConfig,
-    WindowCloseConfirmation,
+    configuration, AudibleBell, ConfigHandle, Dimension, DimensionContext, DroppedDirectoryAction,
+    DroppedFileAction, GeometryOrigin, TermConfig, WindowCloseConfirmation,
 };
 use mlua::{FromLua, UserData, UserDataFields};
 use mux::pane::{CloseReason, Pane, PaneId, Pattern as MuxPattern};
 use mux::renderable::RenderableDimensions;
+use mux::ssh::RemoteSshDomain;
 use mux::tab::{
     PositionedPane, PositionedSplit, SplitDirection, SplitRequest, SplitSize as MuxSplitSize, Tab,
     TabId,
@@ -46,8 +48,9 @@ use portable_pty::PtySize;
 use smol::channel::Sender;
 use smol::Timer;
 use std::cell::{RefCell, RefMut};
-use std::collections::HashMap;
-use std::ops::Add;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Add, Range};
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -96,6 +99,16 @@ pub fn get_window_class() -> String {
     WINDOW_CLASS.lock().unwrap().clone()
 }
 
+/// Returns the number of rows to reserve at the top of each pane
+/// for the pane header
+fn pane_header_rows(config: &ConfigHandle) -> usize {
+    if config.show_pane_header {
+        1
+    } else {
+        0
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MouseCapture {
     UI,
@@ -168,6 +181,12 @@ pub struct SemanticZoneCache {
     zones: Vec<StableRowIndex>,
 }
 
+#[derive(Clone, Default)]
+pub struct FailedCommandCache {
+    seqno: SequenceNo,
+    ranges: Vec<Range<StableRowIndex>>,
+}
+
 pub struct OverlayState {
     pub pane: Rc<dyn Pane>,
     key_table_state: KeyTableState,
@@ -179,6 +198,20 @@ pub struct PaneState {
     /// Otherwise, the viewport is at the bottom of the
     /// scrollback.
     viewport: Option<StableRowIndex>,
+    /// When scrolled back, the physical_top of the pane at the
+    /// time that the viewport left the bottom of the scrollback;
+    /// used to count how much output arrived since then.
+    scrolled_back_from: Option<StableRowIndex>,
+    /// The fractional number of lines left over from scaling
+    /// wheel events by the wheel_scroll_speed
+    wheel_scroll_remainder: f64,
+    /// When scrolled back by a precise device such as a touchpad,
+    /// the number of pixels that the content is shifted up from the
+    /// top of the viewport row.  Always less than the cell height.
+    viewport_pixel_offset: f64,
+    /// The pixels left over from converting precise scroll events
+    /// into whole lines for the application or a mouse binding
+    pixel_scroll_remainder: f64,
     selection: Selection,
     /// If is_some(), rather than display the actual tab
     /// contents, we're overlaying a little internal application
@@ -196,13 +229,19 @@ pub struct TabInformation {
     pub tab_index: usize,
     pub is_active: bool,
     pub active_pane: Option<PaneInformation>,
+    pub tab_title: String,
+    pub has_unseen_output: bool,
+    pub has_unseen_bell: bool,
 }
 
 impl UserData for TabInformation {
     fn add_fields<'lua, F: UserDataFields<'lua, Self>>(fields: &mut F) {
         fields.add_field_method_get("tab_id", |_, this| Ok(this.tab_id));
+        fields.add_field_method_get("tab_title", |_, this| Ok(this.tab_title.clone()));
         fields.add_field_method_get("tab_index", |_, this| Ok(this.tab_index));
         fields.add_field_method_get("is_active", |_, this| Ok(this.is_active));
+        fields.add_field_method_get("has_unseen_output", |_, this| Ok(this.has_unseen_output));
+        fields.add_field_method_get("has_unseen_bell", |_, this| Ok(this.has_unseen_bell));
         fields.add_field_method_get("active_pane", |_, this| {
             if let Some(pane) = &this.active_pane {
                 Ok(Some(pane.clone()))
@@ -232,6 +271,7 @@ pub struct PaneInformation {
     pub pane_index: usize,
     pub is_active: bool,
     pub is_zoomed: bool,
+    pub is_output_paused: bool,
     pub has_unseen_output: bool,
     pub left: usize,
     pub top: usize,
@@ -249,6 +289,7 @@ impl UserData for PaneInformation {
         fields.add_field_method_get("pane_index", |_, this| Ok(this.pane_index));
         fields.add_field_method_get("is_active", |_, this| Ok(this.is_active));
         fields.add_field_method_get("is_zoomed", |_, this| Ok(this.is_zoomed));
+        fields.add_field_method_get("is_output_paused", |_, this| Ok(this.is_output_paused));
         fields.add_field_method_get("has_unseen_output", |_, this| Ok(this.has_unseen_output));
         fields.add_field_method_get("left", |_, this| Ok(this.left));
         fields.add_field_method_get("top", |_, this| Ok(this.top));
@@ -306,6 +347,9 @@ pub struct TabState {
     /// contents, we're overlaying a little internal application
     /// tab.  We'll also route input to it.
     pub overlay: Option<OverlayState>,
+    /// Set when the bell rings in a pane of this tab while
+    /// the tab is not active
+    pub has_unseen_bell: bool,
 }
 
 /// Manages the state/queue of lua based event handlers.
@@ -334,10 +378,16 @@ pub struct TermWindow {
     /// Window dimensions and dpi
     pub dimensions: Dimensions,
     pub window_state: WindowState,
+    /// The screen coordinates of the top left of the client area,
+    /// if the windowing system has told us about them
+    window_position: Option<ScreenPoint>,
     /// Terminal dimensions
     terminal_size: PtySize,
     pub mux_window_id: MuxWindowId,
     pub mux_window_id_for_subscriptions: Arc<Mutex<MuxWindowId>>,
+    /// Panes for which a PaneOutput notification has been queued
+    /// to the window but not yet processed
+    pending_pane_output: Arc<Mutex<HashSet<PaneId>>>,
     pub render_metrics: RenderMetrics,
     render_state: Option<RenderState>,
     input_map: InputMap,
@@ -354,15 +404,24 @@ pub struct TermWindow {
     /// Tracks whether the current mouse-down event is part of click-focus.
     /// If so, we ignore mouse events until released
     is_click_to_focus_window: bool,
+    /// Set when a mouse button was pressed while holding the
+    /// bypass_mouse_reporting_modifiers, so that the rest of that
+    /// drag continues to bypass mouse reporting even if the
+    /// modifiers are released before the button
+    mouse_reporting_bypassed: bool,
     last_mouse_coords: (usize, i64),
     window_drag_position: Option<MouseEvent>,
     current_mouse_event: Option<MouseEvent>,
+    /// true while a timer is pending to continue scrolling a
+    /// selection that is being dragged beyond the pane
+    drag_autoscroll_scheduled: bool,
     prev_cursor: PrevCursorPos,
     last_scroll_info: RenderableDimensions,
 
     tab_state: RefCell<HashMap<TabId, TabState>>,
     pane_state: RefCell<HashMap<PaneId, PaneState>>,
     semantic_zones: HashMap<PaneId, SemanticZoneCache>,
+    failed_commands: HashMap<PaneId, FailedCommandCache>,
 
     window_background: Vec<LoadedBackgroundLayer>,
 
@@ -375,6 +434,10 @@ pub struct TermWindow {
     /// The URL over which we are currently hovering
     current_highlight: Option<Arc<Hyperlink>>,
 
+    /// Images pasted via `clipboard_image_paste = "TempFile"`;
+    /// the files are removed when these are dropped
+    clipboard_image_files: Vec<tempfile::TempPath>,
+
     shape_cache:
         RefCell<LruCache<ShapeCacheKey, anyhow::Result<Rc<Vec<ShapedInfo<SrgbTexture2d>>>>>>,
 
@@ -395,6 +458,9 @@ pub struct TermWindow {
     /// We use this to attempt to do something reasonable
     /// if we run out of texture space
     allow_images: bool,
+    /// Set at the start of each paint when the system has asked
+    /// for reduced motion and the config respects that
+    reduce_motion: bool,
     scheduled_animation: RefCell<Option<Instant>>,
 
     gl: Option<Rc<glium::backend::Context>>,
@@ -620,10 +686,12 @@ impl TermWindow {
             focused: None,
             mux_window_id,
             mux_window_id_for_subscriptions: Arc::new(Mutex::new(mux_window_id)),
+            pending_pane_output: Arc::new(Mutex::new(HashSet::new())),
             fonts: Rc::clone(&fontconfig),
             render_metrics,
             dimensions,
             window_state: WindowState::default(),
+            window_position: None,
             terminal_size,
             render_state,
             input_map: InputMap::new(&config),
@@ -637,6 +705,7 @@ impl TermWindow {
             last_mouse_coords: (0, -1),
             window_drag_position: None,
             current_mouse_event: None,
+            drag_autoscroll_scheduled: false,
             prev_cursor: PrevCursorPos::new(),
             last_scroll_info: RenderableDimensions::default(),
             tab_state: RefCell::new(HashMap::new()),
@@ -676,13 +745,17 @@ impl TermWindow {
             has_animation: RefCell::new(None),
             scheduled_animation: RefCell::new(None),
             allow_images: true,
+            reduce_motion: false,
             semantic_zones: HashMap::new(),
+            failed_commands: HashMap::new(),
             ui_items: vec![],
             dragging: None,
             last_ui_item: None,
             is_click_to_focus_window: false,
+            mouse_reporting_bypassed: false,
             key_table_state: KeyTableState::default(),
             modal: RefCell::new(None),
+            clipboard_image_files: vec![],
         };
 
         let tw = Rc::new(RefCell::new(myself));
@@ -693,6 +766,15 @@ impl TermWindow {
             .unwrap()
             .take()
             .map(|pos| (Some(pos.x), Some(pos.y), pos.origin))
+            .or_else(|| {
+                crate::windowsize::initial_position(&config).map(|pos| {
+                    (
+                        Some(Dimension::Pixels(pos.x as f32)),
+                        Some(Dimension::Pixels(pos.y as f32)),
+                        GeometryOrigin::ScreenCoordinateSystem,
+                    )
+                })
+            })
             .unwrap_or((None, None, Default::default()));
 
         let geometry = RequestedWindowGeometry {
@@ -745,6 +827,7 @@ impl TermWindow {
             }
 
             myself.created(&window, Rc::clone(&gl))?;
+            myself.apply_pane_header_rows();
             myself.subscribe_to_pane_updates();
             myself.emit_window_event("window-config-reloaded", None);
             myself.emit_status_event();
@@ -792,6 +875,14 @@ impl TermWindow {
                 self.resize(dimensions, window_state, window, live_resizing);
                 Ok(true)
             }
+            WindowEvent::Moved(position) => {
+                self.window_position.replace(position);
+                self.record_window_bounds(window);
+                if self.window_state.can_resize() {
+                    crate::windowsize::save_position(&self.config, position);
+                }
+                Ok(true)
+            }
             WindowEvent::RawKeyEvent(event) => {
                 self.raw_key_event_impl(event, window);
                 Ok(true)
@@ -822,6 +913,32 @@ impl TermWindow {
                     Some(pane) => pane,
                     None => return Ok(true),
                 };
+                if let [path] = paths.as_slice() {
+                    if self.config.dropped_directory_action
+                        == DroppedDirectoryAction::ChangeDirectory
+                        && path.is_dir()
+                    {
+                        let command = format!(
+                            "cd {}\r",
+                            self.config
+                                .quote_dropped_files
+                                .escape(&path.to_string_lossy())
+                        );
+                        pane.writer().write_all(command.as_bytes())?;
+                        return Ok(true);
+                    }
+                }
+                if self.config.dropped_file_action == DroppedFileAction::UploadViaSsh
+                    && paths.iter().all(|path| path.is_file())
+                {
+                    let mux = Mux::get().unwrap();
+                    if let Some(domain) = mux.get_domain(pane.domain_id()) {
+                        if domain.downcast_ref::<RemoteSshDomain>().is_some() {
+                            self.upload_dropped_files(domain, pane, paths);
+                            return Ok(true);
+                        }
+                    }
+                }
                 let paths = paths
                     .iter()
                     .map(|path| {
@@ -956,8 +1073,39 @@ impl TermWindow {
 
                     let mut per_pane = self.pane_state(pane_id);
                     per_pane.bell_start.replace(Instant::now());
+                    drop(per_pane);
+
+                    let mux = Mux::get().expect("on main thread");
+                    if let Some((_domain_id, window_id, tab_id)) = mux.resolve_pane_id(pane_id) {
+                        if window_id == self.mux_window_id {
+                            let is_active_tab = mux
+                                .get_active_tab_for_window(window_id)
+                                .map(|tab| tab.tab_id() == tab_id)
+                                .unwrap_or(false);
+                            if !is_active_tab {
+                                self.tab_state(tab_id).has_unseen_bell = true;
+                                self.update_title();
+                            }
+                            if self.config.window_attention_on_bell
+                                && (!is_active_tab || self.focused.is_none())
+                            {
+                                window.request_attention();
+                            }
+                        }
+                    }
                     window.invalidate();
                 }
+                MuxNotification::Alert {
+                    alert: Alert::RequestAttention,
+                    pane_id,
+                } => {
+                    let mux = Mux::get().expect("on main thread");
+                    if let Some((_domain_id, window_id, _tab_id)) = mux.resolve_pane_id(pane_id) {
+                        if window_id == self.mux_window_id {
+                            window.request_attention();
+                        }
+                    }
+                }
                 MuxNotification::Alert {
                     alert: Alert::ToastNotification { .. },
                     ..
@@ -966,6 +1114,8 @@ impl TermWindow {
                     self.mux_pane_output_event(pane_id);
                 }
                 MuxNotification::WindowInvalidated(_) => {
+                    // Tabs may have been added to the window
+                    self.apply_pane_header_rows();
                     window.invalidate();
                 }
                 MuxNotification::WindowRemoved(_window_id) => {
@@ -974,8 +1124,12 @@ impl TermWindow {
                 MuxNotification::AssignClipboard { .. } => {
                     // Handled by frontend
                 }
-                MuxNotification::SaveToDownloads { .. } => {
-                    // Handled by frontend
+                MuxNotification::SaveToDownloads {
+                    pane_id,
+                    name,
+                    data,
+                } => {
+                    self.confirm_save_to_downloads(pane_id, name, data);
                 }
                 MuxNotification::PaneAdded(_)
                 | MuxNotification::PaneRemoved(_)
@@ -1015,6 +1169,7 @@ impl TermWindow {
                         tab.resize(self.terminal_size);
                     }
                 };
+                self.apply_pane_header_rows();
                 self.update_title();
                 window.invalidate();
             }
@@ -1062,12 +1217,71 @@ impl TermWindow {
     }
 
     fn mux_pane_output_event(&mut self, pane_id: PaneId) {
+        // Any output that arrives from here on needs a fresh notification
+        self.pending_pane_output.lock().unwrap().remove(&pane_id);
         metrics::histogram!("mux.pane_output_event.rate", 1.);
         if self.is_pane_visible(pane_id) {
             if let Some(ref win) = self.window {
                 win.invalidate();
             }
+            self.update_accessibility_text();
+        }
+    }
+
+    /// Provides the visible text of the active pane, and the cursor
+    /// position within it, to the window so that it can be exposed
+    /// to screen readers
+    fn update_accessibility_text(&mut self) {
+        if !self.config.screen_reader_support {
+            return;
+        }
+        if cfg!(not(target_os = "macos")) {
+            // AT-SPI and UI Automation support hasn't been written yet,
+            // so don't pay for gathering text that nothing will read
+            static WARNED: std::sync::atomic::AtomicBool =
+                std::sync::atomic::AtomicBool::new(false);
+            if !WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                log::warn!("screen_reader_support is currently only implemented on macOS");
+            }
+            return;
+        }
+        let window = match self.window.as_ref() {
+            Some(window) => window.clone(),
+            None => return,
+        };
+        let pane = match self.get_active_pane_or_overlay() {
+            Some(pane) => pane,
+            None => return,
+        };
+
+        let dims = pane.get_dimensions();
+        let top = self
+            .get_viewport(pane.pane_id())
+            .unwrap_or(dims.physical_top);
+        let cursor = pane.get_cursor_position();
+        let (first_row, lines) = pane.get_lines(top..top + dims.viewport_rows as StableRowIndex);
+
+        let mut text = String::new();
+        let mut num_chars = 0;
+        let mut cursor_offset = 0;
+        for (idx, line) in lines.iter().enumerate() {
+            let line_text = line.as_str();
+            let line_text = line_text.trim_end();
+            let line_chars = line_text.chars().count();
+            if first_row + idx as StableRowIndex == cursor.y {
+                let cursor_chars: usize = line
+                    .visible_cells()
+                    .take_while(|(col, _cell)| *col < cursor.x)
+                    .map(|(_col, cell)| cell.str().chars().count())
+                    .sum();
+                cursor_offset = num_chars + cursor_chars.min(line_chars);
+            }
+            text.push_str(line_text);
+            text.push('\n');
+            num_chars += line_chars + 1;
         }
+
+        window.set_accessibility_text(text, cursor_offset);
     }
 
     fn mux_pane_output_event_callback(
@@ -1075,6 +1289,7 @@ impl TermWindow {
         window: &Window,
         mux_window_id: MuxWindowId,
         dead: &Arc<AtomicBool>,
+        pending_output: &Arc<Mutex<HashSet<PaneId>>>,
     ) -> bool {
         if dead.load(Ordering::Relaxed) {
             // Subscription cancelled asynchronously
@@ -1084,7 +1299,11 @@ impl TermWindow {
         match n {
             MuxNotification::Alert {
                 pane_id,
-                alert: Alert::OutputSinceFocusLost | Alert::TitleMaybeChanged | Alert::Bell,
+                alert:
+                    Alert::OutputSinceFocusLost
+                    | Alert::TitleMaybeChanged
+                    | Alert::Bell
+                    | Alert::RequestAttention,
             }
             | MuxNotification::PaneOutput(pane_id) => {
                 // Ideally we'd check to see if pane_id is part of this window,
@@ -1105,6 +1324,14 @@ impl TermWindow {
                 }
                 let _ = pane_id;
             }
+            MuxNotification::SaveToDownloads { pane_id, .. } => {
+                // Only the window containing the pane should prompt
+                let mux = Mux::get().expect("mux is calling us");
+                match mux.resolve_pane_id(pane_id) {
+                    Some((_domain_id, window_id, _tab_id)) if window_id == mux_window_id => {}
+                    _ => return true,
+                }
+            }
             MuxNotification::PaneAdded(_pane_id) => {
                 // If some other client spawns a pane inside this window, this
                 // gives us an opportunity to attach it to the clipboard.
@@ -1125,7 +1352,6 @@ impl TermWindow {
                 ..
             }
             | MuxNotification::AssignClipboard { .. }
-            | MuxNotification::SaveToDownloads { .. }
             | MuxNotification::PaneRemoved(_)
             | MuxNotification::WindowCreated(_)
             | MuxNotification::ActiveWorkspaceChanged(_)
@@ -1133,6 +1359,16 @@ impl TermWindow {
             | MuxNotification::WindowWorkspaceChanged(_) => return true,
         }
 
+        if let MuxNotification::PaneOutput(pane_id) = &n {
+            // Coalesce output notifications; if one is already queued
+            // for this pane then it will take care of this output too,
+            // so there is no need to queue another.
+            if !pending_output.lock().unwrap().insert(*pane_id) {
+                metrics::histogram!("mux.pane_output_event.coalesced", 1.);
+                return true;
+            }
+        }
+
         window.notify(TermWindowNotif::MuxNotification(n));
 
         true
@@ -1142,10 +1378,11 @@ impl TermWindow {
         let window = self.window.clone().expect("window to be valid on startup");
         let mux_window_id = Arc::clone(&self.mux_window_id_for_subscriptions);
         let mux = Mux::get().expect("mux started and running on main thread");
+        let pending_output = Arc::clone(&self.pending_pane_output);
         let dead = Arc::new(AtomicBool::new(false));
         mux.subscribe(move |n| {
             let mux_window_id = *mux_window_id.lock().unwrap();
-            Self::mux_pane_output_event_callback(n, &window, mux_window_id, &dead)
+            Self::mux_pane_output_event_callback(n, &window, mux_window_id, &dead, &pending_output)
         });
     }
 
@@ -1312,6 +1549,18 @@ impl TermWindow {
         self.palette.as_ref().unwrap()
     }
 
+    /// Reserve room at the top of the panes in each of the tabs in
+    /// this window for the pane header, if it is enabled
+    fn apply_pane_header_rows(&self) {
+        let rows = pane_header_rows(&self.config);
+        let mux = Mux::get().expect("to be main thread with mux running");
+        if let Some(window) = mux.get_window(self.mux_window_id) {
+            for tab in window.iter() {
+                tab.set_pane_header_rows(rows);
+            }
+        }
+    }
+
     pub fn config_was_reloaded(&mut self) {
         log::debug!(
             "config was reloaded, overrides: {:?}",
@@ -1388,6 +1637,8 @@ impl TermWindow {
             let term_config: Arc<dyn TerminalConfiguration> =
                 Arc::new(TermConfig::with_config(config.clone()));
             for tab in window.iter() {
+                // Make room for, or reclaim the space used by, the pane header
+                tab.set_pane_header_rows(pane_header_rows(&config));
                 for pane in tab.iter_panes() {
                     pane.pane.set_config(Arc::clone(&term_config));
                 }
@@ -1457,6 +1708,7 @@ impl TermWindow {
     /// been updated; let's update the bar
     pub fn update_title_post_status(&mut self) {
         self.update_title_impl();
+        self.update_accessibility_text();
     }
 
     fn update_title_impl(&mut self) {
@@ -1570,11 +1822,22 @@ impl TermWindow {
             Some(title) => title,
             None => {
                 if let (Some(pos), Some(tab)) = (active_pane, active_tab) {
+                    let paused = if pos.is_output_paused {
+                        "[Paused] "
+                    } else {
+                        ""
+                    };
                     if num_tabs == 1 {
-                        format!("{}{}", if pos.is_zoomed { "[Z] " } else { "" }, pos.title)
+                        format!(
+                            "{}{}{}",
+                            paused,
+                            if pos.is_zoomed { "[Z] " } else { "" },
+                            pos.title
+                        )
                     } else {
                         format!(
-                            "{}[{}/{}] {}",
+                            "{}{}[{}/{}] {}",
+                            paused,
                             if pos.is_zoomed { "[Z] " } else { "" },
                             tab.tab_index + 1,
                             num_tabs,
@@ -1844,6 +2107,28 @@ impl TermWindow {
         &cache.zones
     }
 
+    /// Returns the rows holding the output of commands that
+    /// reported a non-zero exit status
+    fn get_failed_command_ranges(&mut self, pane: &Rc<dyn Pane>) -> &[Range<StableRowIndex>] {
+        let cache = self
+            .failed_commands
+            .entry(pane.pane_id())
+            .or_insert_with(FailedCommandCache::default);
+
+        let seqno = pane.get_current_seqno();
+        if cache.seqno != seqno {
+            cache.ranges = pane
+                .get_command_history()
+                .into_iter()
+                .filter(|entry| matches!(entry.exit_status, Some(status) if status != 0))
+                .map(|entry| entry.output_start_y..entry.output_end_y)
+                .filter(|range| !range.is_empty())
+                .collect();
+            cache.seqno = seqno;
+        }
+        &cache.ranges
+    }
+
     fn scroll_to_prompt(&mut self, amount: isize) -> anyhow::Result<()> {
         let pane = match self.get_active_pane_or_overlay() {
             Some(pane) => pane,
@@ -1905,6 +2190,69 @@ impl TermWindow {
         Ok(())
     }
 
+    /// Advises the front end of where this window is on the screen,
+    /// so that tabs can be dragged between windows
+    fn record_window_bounds(&self, window: &Window) {
+        if let Some(position) = self.window_position {
+            front_end().record_window_bounds(
+                window,
+                ScreenRect::new(
+                    position,
+                    euclid::size2(
+                        self.dimensions.pixel_width as isize,
+                        self.dimensions.pixel_height as isize,
+                    ),
+                ),
+            );
+        }
+    }
+
+    /// Moves the tab at `tab_idx` into the window identified by
+    /// `mux_window_id`, where it becomes the active tab
+    pub fn move_tab_to_window(
+        &mut self,
+        tab_idx: usize,
+        mux_window_id: MuxWindowId,
+    ) -> anyhow::Result<()> {
+        let mux = Mux::get().unwrap();
+        let tab_id = mux
+            .get_window(self.mux_window_id)
+            .ok_or_else(|| anyhow!("no such window"))?
+            .get_by_idx(tab_idx)
+            .ok_or_else(|| anyhow!("no tab at index {}", tab_idx))?
+            .tab_id();
+
+        mux.move_tab_to_window(tab_id, mux_window_id)?;
+        self.update_title();
+        self.update_scrollbar();
+
+        Ok(())
+    }
+
+    /// Detach the tab at tab_idx into a new window of its own.
+    /// This is a no-op if it is the only tab in this window.
+    pub fn move_tab_to_new_window(&mut self, tab_idx: usize) -> anyhow::Result<()> {
+        let mux = Mux::get().unwrap();
+        let tab_id = {
+            let window = mux
+                .get_window(self.mux_window_id)
+                .ok_or_else(|| anyhow!("no such window"))?;
+            if window.len() < 2 {
+                return Ok(());
+            }
+            window
+                .get_by_idx(tab_idx)
+                .ok_or_else(|| anyhow!("no tab at index {}", tab_idx))?
+                .tab_id()
+        };
+
+        mux.move_tab_to_new_window(tab_id)?;
+        self.update_title();
+        self.update_scrollbar();
+
+        Ok(())
+    }
+
     fn move_tab_relative(&mut self, delta: isize) -> anyhow::Result<()> {
         let mux = Mux::get().unwrap();
         let window = mux
@@ -1992,6 +2340,17 @@ impl TermWindow {
             SpawnCommandInNewWindow(spawn) => {
                 self.spawn_command(spawn, SpawnWhere::NewWindow);
             }
+            SpawnLaunchMenuEntry(label) => {
+                match self
+                    .config
+                    .launch_menu
+                    .iter()
+                    .find(|item| item.launcher_label() == *label)
+                {
+                    Some(item) => self.spawn_command(item, SpawnWhere::NewTab),
+                    None => log::error!("launch_menu has no entry labelled {:?}", label),
+                }
+            }
             SplitHorizontal(spawn) => {
                 log::trace!("SplitHorizontal {:?}", spawn);
                 self.spawn_command(
@@ -2021,13 +2380,25 @@ impl TermWindow {
             }
             Copy => {
                 let text = self.selection_text(pane);
-                self.copy_to_clipboard(
+                let uris = self.selection_hyperlinks(pane);
+                self.copy_to_clipboard_with_uri_list(
                     ClipboardCopyDestination::ClipboardAndPrimarySelection,
                     text,
+                    uris,
                 );
             }
             CopyTo(dest) => {
                 let text = self.selection_text(pane);
+                let uris = self.selection_hyperlinks(pane);
+                self.copy_to_clipboard_with_uri_list(*dest, text, uris);
+            }
+            CopyAsHtmlTo(dest) => {
+                let text = self.selection_text(pane);
+                let html = self.selection_html(pane);
+                self.copy_to_clipboard_with_html(*dest, text, html);
+            }
+            CopyWithEscapesTo(dest) => {
+                let text = self.selection_escapes(pane)?;
                 self.copy_to_clipboard(*dest, text);
             }
             Paste => {
@@ -2089,7 +2460,17 @@ impl TermWindow {
                     w.show();
                 }
             }
+            RequestAttention => {
+                if let Some(w) = window.as_ref() {
+                    w.request_attention();
+                }
+            }
             CloseCurrentTab { confirm } => self.close_current_tab(*confirm),
+            CloseOtherTabs { confirm } => self.close_other_tabs(*confirm),
+            RenameTab => self.show_rename_tab(),
+            RenameWorkspace => self.show_rename_workspace(),
+            CharSelect => self.show_char_select(),
+            ShowCommandHistory => self.show_command_history(),
             CloseCurrentPane { confirm } => self.close_current_pane(*confirm),
             Nop | DisableDefaultAssignment => {}
             ReloadConfiguration => config::reload(),
@@ -2147,13 +2528,20 @@ impl TermWindow {
             OpenLinkAtMouseCursor => {
                 self.do_open_link_at_mouse_cursor(pane);
             }
+            CopyLinkAtMouseCursor(dest) => {
+                if let Some(link) = self.current_highlight.as_ref() {
+                    let uri = link.uri().to_string();
+                    self.copy_to_clipboard_with_uri_list(*dest, uri.clone(), vec![uri]);
+                }
+            }
             EmitEvent(name) => {
                 self.emit_window_event(name, None);
             }
             CompleteSelectionOrOpenLinkAtMouseCursor(dest) => {
                 let text = self.selection_text(pane);
                 if !text.is_empty() {
-                    self.copy_to_clipboard(*dest, text);
+                    let uris = self.selection_hyperlinks(pane);
+                    self.copy_to_clipboard_with_uri_list(*dest, text, uris);
                     let window = self.window.as_ref().unwrap();
                     window.invalidate();
                 } else {
@@ -2163,7 +2551,8 @@ impl TermWindow {
             CompleteSelection(dest) => {
                 let text = self.selection_text(pane);
                 if !text.is_empty() {
-                    self.copy_to_clipboard(*dest, text);
+                    let uris = self.selection_hyperlinks(pane);
+                    self.copy_to_clipboard_with_uri_list(*dest, text, uris);
                     let window = self.window.as_ref().unwrap();
                     window.invalidate();
                 }
@@ -2306,6 +2695,12 @@ impl TermWindow {
                 };
                 tab.toggle_zoom();
             }
+            TogglePaneOutputPaused => {
+                let mux = Mux::get().unwrap();
+                let pane_id = pane.pane_id();
+                mux.set_pane_output_paused(pane_id, !mux.is_pane_output_paused(pane_id));
+                self.update_title();
+            }
             SwitchWorkspaceRelative(delta) => {
                 let mux = Mux::get().unwrap();
                 let workspace = mux.active_workspace();
@@ -2451,7 +2846,7 @@ impl TermWindow {
         // triggering our WndProc recursively.
         // We get that assurance for free as part of the async dispatch that we
         // perform below; here we allow the user to define an `open-uri` event
-        // handler that can bypass the normal `open::that` functionality.
+        // handler that can bypass the normal `open_url` functionality.
         if let Some(link) = self.current_highlight.as_ref().cloned() {
             let window = GuiWin::new(self);
             let pane = PaneObject::new(pane);
@@ -2476,7 +2871,7 @@ impl TermWindow {
                 };
                 if default_click {
                     log::info!("clicking {}", link);
-                    open::that_in_background(&link);
+                    wezterm_open_url::open_url(&link);
                 }
                 Ok(())
             }
@@ -2512,6 +2907,49 @@ impl TermWindow {
         }
     }
 
+    /// Prompts the user before saving a file that was sent by
+    /// the application running in the pane via the iTerm2 file
+    /// transfer protocol
+    fn confirm_save_to_downloads(
+        &mut self,
+        pane_id: PaneId,
+        name: Option<String>,
+        data: Arc<Vec<u8>>,
+    ) {
+        if !self.config.allow_download_protocols {
+            log::error!(
+                "Ignoring download request for {:?}, \
+                 as allow_download_protocols=false",
+                name
+            );
+            return;
+        }
+        if data.len() > self.config.max_download_size {
+            let message = format!(
+                "Ignoring download request for {:?} of {}, as it is larger \
+                 than max_download_size={}",
+                name,
+                crate::download::format_size(data.len()),
+                crate::download::format_size(self.config.max_download_size)
+            );
+            log::error!("{}", message);
+            wezterm_toast_notification::persistent_toast_notification("Download refused", &message);
+            return;
+        }
+
+        let mux = Mux::get().unwrap();
+        let pane = match mux.get_pane(pane_id) {
+            Some(pane) => pane,
+            None => return,
+        };
+        let window = self.window.clone().unwrap();
+        let (overlay, future) = start_overlay_pane(self, &pane, move |pane_id, term| {
+            confirm_download(pane_id, name, data, term, window)
+        });
+        self.assign_overlay_for_pane(pane_id, overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
     fn close_specific_tab(&mut self, tab_idx: usize, confirm: bool) {
         let mux = Mux::get().unwrap();
         let mux_window_id = self.mux_window_id;
@@ -2563,6 +3001,165 @@ impl TermWindow {
         }
     }
 
+    fn close_other_tabs(&mut self, confirm: bool) {
+        let mux = Mux::get().unwrap();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+        let other_tabs: Vec<Rc<Tab>> = match mux.get_window(self.mux_window_id) {
+            Some(win) => win
+                .iter()
+                .filter(|t| t.tab_id() != tab.tab_id())
+                .cloned()
+                .collect(),
+            None => return,
+        };
+
+        let need_prompt = confirm
+            && other_tabs
+                .iter()
+                .any(|t| !t.can_close_without_prompting(CloseReason::Tab));
+        let other_tab_ids: Vec<TabId> = other_tabs.iter().map(|t| t.tab_id()).collect();
+
+        if need_prompt {
+            let window = self.window.clone().unwrap();
+            let (overlay, future) = start_overlay(self, &tab, move |tab_id, term| {
+                confirm_close_other_tabs(tab_id, term, other_tab_ids, window)
+            });
+            self.assign_overlay(tab.tab_id(), overlay);
+            promise::spawn::spawn(future).detach();
+        } else {
+            for tab_id in other_tab_ids {
+                mux.remove_tab(tab_id);
+            }
+        }
+    }
+
+    fn show_rename_tab(&mut self) {
+        let mux = Mux::get().unwrap();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let window = self.window.as_ref().unwrap().clone();
+        let title = tab.get_title();
+
+        let (overlay, future) = start_overlay(self, &tab, move |tab_id, term| {
+            rename_tab(tab_id, term, window, title)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    /// Uploads files that were dropped onto a pane from an ssh domain
+    /// to the remote host, then pastes their remote paths into the pane.
+    /// The files are placed in the working directory of the pane, if
+    /// the shell has reported it, or the remote home directory otherwise.
+    fn upload_dropped_files(
+        &self,
+        domain: Arc<dyn mux::domain::Domain>,
+        pane: Rc<dyn Pane>,
+        paths: Vec<PathBuf>,
+    ) {
+        let quoting = self.config.quote_dropped_files;
+        let remote_dir = pane.get_current_working_dir().map(|url| {
+            percent_encoding::percent_decode_str(url.path())
+                .decode_utf8_lossy()
+                .to_string()
+        });
+        promise::spawn::spawn(async move {
+            let ssh_domain = match domain.downcast_ref::<RemoteSshDomain>() {
+                Some(ssh_domain) => ssh_domain,
+                None => return,
+            };
+            let mut remote_paths = vec![];
+            for path in &paths {
+                match ssh_domain.upload_file(path, remote_dir.as_deref()).await {
+                    Ok(remote_path) => remote_paths.push(quoting.escape(&remote_path)),
+                    Err(err) => {
+                        log::error!("Failed to upload {}: {:#}", path.display(), err);
+                        return;
+                    }
+                }
+            }
+            if let Err(err) = pane.trickle_paste(remote_paths.join(" ")) {
+                log::error!("Failed to paste uploaded paths: {:#}", err);
+            }
+        })
+        .detach();
+    }
+
+    fn show_rename_workspace(&mut self) {
+        let mux = Mux::get().unwrap();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let window = self.window.as_ref().unwrap().clone();
+        let workspace = match mux.get_window(self.mux_window_id) {
+            Some(mux_window) => mux_window.get_workspace().to_string(),
+            None => return,
+        };
+        let existing_names = mux.iter_workspaces();
+
+        let (overlay, future) = start_overlay(self, &tab, move |tab_id, term| {
+            rename_workspace(tab_id, term, window, workspace, existing_names)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_char_select(&mut self) {
+        let mux = Mux::get().unwrap();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let pane_id = match tab.get_active_pane() {
+            Some(pane) => pane.pane_id(),
+            None => return,
+        };
+
+        let window = self.window.as_ref().unwrap().clone();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            char_select(pane_id, term, window)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_command_history(&mut self) {
+        let mux = Mux::get().unwrap();
+        let tab = match mux.get_active_tab_for_window(self.mux_window_id) {
+            Some(tab) => tab,
+            None => return,
+        };
+
+        let pane = match tab.get_active_pane() {
+            Some(pane) => pane,
+            None => return,
+        };
+        let pane_id = pane.pane_id();
+        let history = pane.get_command_history();
+
+        let window = self.window.as_ref().unwrap().clone();
+
+        let (overlay, future) = start_overlay(self, &tab, move |_tab_id, term| {
+            command_history(pane_id, history, term, window)
+        });
+        self.assign_overlay(tab.tab_id(), overlay);
+        promise::spawn::spawn(future).detach();
+    }
+
+    fn show_tab_actions(&mut self) {
+        self.show_launcher_impl("Tab", LauncherFlags::TAB_ACTIONS);
+    }
+
     fn close_tab_idx(&mut self, idx: usize) -> anyhow::Result<()> {
         let mux = Mux::get().unwrap();
         if let Some(mut win) = mux.get_window_mut(self.mux_window_id) {
@@ -2589,6 +3186,19 @@ impl TermWindow {
         self.pane_state(pane_id).viewport
     }
 
+    /// Returns the number of lines of output that have arrived in
+    /// the pane since its viewport was scrolled back
+    pub fn new_lines_since_scrolled_back(
+        &self,
+        pane_id: PaneId,
+        dims: &RenderableDimensions,
+    ) -> usize {
+        match self.pane_state(pane_id).scrolled_back_from {
+            Some(physical_top) => (dims.physical_top - physical_top).max(0) as usize,
+            None => 0,
+        }
+    }
+
     pub fn set_viewport(
         &mut self,
         pane_id: PaneId,
@@ -2608,7 +3218,13 @@ impl TermWindow {
         };
 
         let mut state = self.pane_state(pane_id);
+        state.viewport_pixel_offset = 0.;
         if pos != state.viewport {
+            if pos.is_none() {
+                state.scrolled_back_from = None;
+            } else if state.viewport.is_none() {
+                state.scrolled_back_from = Some(dims.physical_top);
+            }
             state.viewport = pos;
 
             // This is a bit gross.  If we add other overlays that need this information,
@@ -2636,7 +3252,20 @@ impl TermWindow {
     }
 
     fn scroll_to_bottom(&mut self, pane: &Rc<dyn Pane>) {
-        self.pane_state(pane.pane_id()).viewport = None;
+        let mut state = self.pane_state(pane.pane_id());
+        state.viewport = None;
+        state.viewport_pixel_offset = 0.;
+    }
+
+    /// Returns the number of pixels by which the content of the
+    /// pane is shifted up from the top of its viewport row
+    pub fn get_viewport_pixel_offset(&self, pane_id: PaneId) -> f32 {
+        let state = self.pane_state(pane_id);
+        if state.viewport.is_some() {
+            state.viewport_pixel_offset.round() as f32
+        } else {
+            0.
+        }
     }
 
     fn get_active_pane_no_overlay(&self) -> Option<Rc<dyn Pane>> {
@@ -2700,6 +3329,9 @@ impl TermWindow {
             pane_index: pos.index,
             is_active: pos.is_active,
             is_zoomed: pos.is_zoomed,
+            is_output_paused: Mux::get()
+                .map(|mux| mux.is_pane_output_paused(pos.pane.pane_id()))
+                .unwrap_or(false),
             has_unseen_output: pos.pane.has_unseen_output(),
             left: pos.left,
             top: pos.top,
@@ -2725,15 +3357,29 @@ impl TermWindow {
             .enumerate()
             .map(|(idx, tab)| {
                 let panes = self.get_pos_panes_for_tab(tab);
+                let is_active = tab_index == idx;
+
+                let has_unseen_bell = {
+                    // The bell indicator is cleared once the tab is activated
+                    let mut state = self.tab_state(tab.tab_id());
+                    if is_active {
+                        state.has_unseen_bell = false;
+                    }
+                    state.has_unseen_bell
+                };
 
                 TabInformation {
                     tab_index: idx,
                     tab_id: tab.tab_id(),
-                    is_active: tab_index == idx,
+                    is_active,
+                    has_unseen_output: !is_active
+                        && panes.iter().any(|p| p.pane.has_unseen_output()),
+                    has_unseen_bell,
                     active_pane: panes
                         .iter()
                         .find(|p| p.is_active)
                         .map(Self::pos_pane_to_pane_info),
+                    tab_title: tab.get_title(),
                 }
             })
             .collect()
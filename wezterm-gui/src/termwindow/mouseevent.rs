@@ -1,14 +1,18 @@
+use crate::frontend::front_end;
 use crate::tabbar::TabBarItem;
 use crate::termwindow::keyevent::window_mods_to_termwiz_mods;
-use crate::termwindow::{MouseCapture, PositionedSplit, ScrollHit, UIItem, UIItemType, TMB};
+use crate::termwindow::{
+    MouseCapture, PositionedSplit, ScrollHit, TermWindowNotif, UIItem, UIItemType, TMB,
+};
 use ::window::{
     MouseButtons as WMB, MouseCursor, MouseEvent, MouseEventKind as WMEK, MousePress, WindowOps,
     WindowState,
 };
 use config::keyassignment::{MouseEventTrigger, SpawnTabDomain};
-use mux::pane::Pane;
+use mux::pane::{Pane, PaneId};
 use mux::tab::SplitDirection;
 use mux::Mux;
+use smol::Timer;
 use std::convert::TryInto;
 use std::ops::Sub;
 use std::rc::Rc;
@@ -81,7 +85,11 @@ impl super::TermWindow {
 
         let x = (event.coords.x.sub(padding_left as isize).max(0) as f32)
             / self.render_metrics.cell_size.width as f32;
-        let x = if !pane.is_mouse_grabbed() {
+        let bypass_mouse_reporting = self.mouse_reporting_bypassed
+            || event
+                .modifiers
+                .contains(self.config.bypass_mouse_reporting_modifiers);
+        let x = if !pane.is_mouse_grabbed() || bypass_mouse_reporting {
             // Round the x coordinate so that we're a bit more forgiving of
             // the horizontal position when selecting cells
             x.round()
@@ -111,19 +119,26 @@ impl super::TermWindow {
         match event.kind {
             WMEK::Release(ref press) => {
                 self.current_mouse_capture = None;
+                self.mouse_reporting_bypassed = false;
                 self.current_mouse_buttons.retain(|p| p != press);
                 if press == &MousePress::Left && self.window_drag_position.take().is_some() {
                     // Completed a window drag
                     return;
                 }
-                if press == &MousePress::Left && self.dragging.take().is_some() {
-                    // Completed a drag
-                    return;
+                if press == &MousePress::Left {
+                    if let Some((item, start_event)) = self.dragging.take() {
+                        // Completed a drag
+                        self.drop_ui_item(item, start_event, &event);
+                        return;
+                    }
                 }
             }
 
             WMEK::Press(ref press) => {
                 capture_mouse = true;
+                self.mouse_reporting_bypassed = event
+                    .modifiers
+                    .contains(self.config.bypass_mouse_reporting_modifiers);
 
                 // Perform click counting
                 let button = mouse_press_to_tmb(press);
@@ -219,6 +234,7 @@ impl super::TermWindow {
                 event,
                 context,
                 capture_mouse,
+                bypass_mouse_reporting,
             );
         }
     }
@@ -326,12 +342,85 @@ impl super::TermWindow {
             UIItemType::ScrollThumb => {
                 self.drag_scroll_thumb(item, start_event, event, context);
             }
+            UIItemType::TabBar(TabBarItem::Tab { .. }) => {
+                self.drag_tab(item, start_event, event, context);
+            }
             _ => {
                 log::error!("drag not implemented for {:?}", item);
             }
         }
     }
 
+    fn drag_tab(
+        &mut self,
+        mut item: UIItem,
+        start_event: MouseEvent,
+        event: MouseEvent,
+        context: &dyn WindowOps,
+    ) {
+        context.set_cursor(Some(MouseCursor::Arrow));
+
+        let tab_idx = match item.item_type {
+            UIItemType::TabBar(TabBarItem::Tab { tab_idx, .. }) => tab_idx,
+            _ => return,
+        };
+        let over_idx = match self.resolve_ui_item(&event).map(|item| item.item_type) {
+            Some(UIItemType::TabBar(TabBarItem::Tab { tab_idx, .. })) => Some(tab_idx),
+            _ => None,
+        };
+
+        // If we're over a different tab, move the dragged tab
+        // into its place.  Pressing the mouse on the dragged
+        // tab activated it, so move_tab will move the dragged tab.
+        if let Some(over_idx) = over_idx.filter(|&idx| idx != tab_idx) {
+            match self.move_tab(over_idx) {
+                Ok(_) => {
+                    item.item_type = UIItemType::TabBar(TabBarItem::Tab {
+                        tab_idx: over_idx,
+                        active: true,
+                    });
+                    context.invalidate();
+                }
+                Err(err) => log::error!("Failed to move tab: {:#}", err),
+            }
+        }
+
+        self.dragging.replace((item, start_event));
+    }
+
+    fn drop_ui_item(&mut self, item: UIItem, _start_event: MouseEvent, event: &MouseEvent) {
+        match item.item_type {
+            UIItemType::TabBar(TabBarItem::Tab { tab_idx, .. }) => {
+                let outside_window = event.coords.x < 0
+                    || event.coords.y < 0
+                    || event.coords.x as usize >= self.dimensions.pixel_width
+                    || event.coords.y as usize >= self.dimensions.pixel_height;
+                if outside_window {
+                    match front_end()
+                        .mux_window_at_screen_point(event.screen_coords)
+                        .filter(|&id| id != self.mux_window_id)
+                    {
+                        Some(mux_window_id) => {
+                            // Dropped the tab onto another of our windows:
+                            // move it into that window
+                            if let Err(err) = self.move_tab_to_window(tab_idx, mux_window_id) {
+                                log::error!("Failed to move tab to window: {:#}", err);
+                            }
+                        }
+                        None => {
+                            // Dragged the tab off of the window: detach it
+                            // into a window of its own
+                            if let Err(err) = self.move_tab_to_new_window(tab_idx) {
+                                log::error!("Failed to move tab to a new window: {:#}", err);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn mouse_event_ui_item(
         &mut self,
         item: UIItem,
@@ -342,8 +431,14 @@ impl super::TermWindow {
     ) {
         self.last_ui_item.replace(item.clone());
         match item.item_type {
-            UIItemType::TabBar(item) => {
-                self.mouse_event_tab_bar(item, event, context);
+            UIItemType::TabBar(tab_bar_item) => {
+                if let (WMEK::Press(MousePress::Left), TabBarItem::Tab { .. }) =
+                    (&event.kind, tab_bar_item)
+                {
+                    // Potentially starting to drag the tab
+                    self.dragging.replace((item.clone(), event.clone()));
+                }
+                self.mouse_event_tab_bar(tab_bar_item, event, context);
             }
             UIItemType::AboveScrollThumb => {
                 self.mouse_event_above_scroll_thumb(item, pane, event, context);
@@ -390,6 +485,9 @@ impl super::TermWindow {
                 TabBarItem::Tab { tab_idx, .. } => {
                     self.activate_tab(tab_idx as isize).ok();
                 }
+                TabBarItem::CloseTabButton { tab_idx } => {
+                    self.close_specific_tab(tab_idx, true);
+                }
                 TabBarItem::NewTabButton { .. } => {
                     self.spawn_tab(&SpawnTabDomain::CurrentPaneDomain);
                 }
@@ -408,16 +506,20 @@ impl super::TermWindow {
                 TabBarItem::Tab { tab_idx, .. } => {
                     self.close_tab_idx(tab_idx).ok();
                 }
-                TabBarItem::NewTabButton { .. } | TabBarItem::None => {}
+                TabBarItem::CloseTabButton { .. }
+                | TabBarItem::NewTabButton { .. }
+                | TabBarItem::None => {}
             },
             WMEK::Press(MousePress::Right) => match item {
-                TabBarItem::Tab { .. } => {
-                    self.show_tab_navigator();
+                TabBarItem::Tab { tab_idx, .. } => {
+                    if self.activate_tab(tab_idx as isize).is_ok() {
+                        self.show_tab_actions();
+                    }
                 }
                 TabBarItem::NewTabButton { .. } => {
                     self.show_launcher();
                 }
-                TabBarItem::None => {}
+                TabBarItem::CloseTabButton { .. } | TabBarItem::None => {}
             },
             WMEK::Move => match item {
                 TabBarItem::None => {
@@ -517,6 +619,43 @@ impl super::TermWindow {
         }
     }
 
+    /// Arrange to replay the most recent mouse move shortly, so that
+    /// a selection dragged beyond the top or bottom of the pane keeps
+    /// scrolling the viewport even when the mouse isn't moving
+    fn schedule_drag_autoscroll(&mut self) {
+        if self.drag_autoscroll_scheduled {
+            return;
+        }
+        if let Some(window) = self.window.as_ref() {
+            self.drag_autoscroll_scheduled = true;
+            let window = window.clone();
+            promise::spawn::spawn(async move {
+                Timer::after(Duration::from_millis(50)).await;
+                window.notify(TermWindowNotif::Apply(Box::new(|tw| {
+                    tw.drag_autoscroll();
+                })));
+            })
+            .detach();
+        }
+    }
+
+    fn drag_autoscroll(&mut self) {
+        self.drag_autoscroll_scheduled = false;
+        if self.current_mouse_buttons.is_empty() {
+            // The drag has finished
+            return;
+        }
+        let event = match self.current_mouse_event.as_ref() {
+            Some(event) if event.kind == WMEK::Move => event.clone(),
+            _ => return,
+        };
+        if let Some(window) = self.window.clone() {
+            // If the mouse is still beyond the pane, this scrolls
+            // and schedules the next replay
+            self.mouse_event_impl(event, &window);
+        }
+    }
+
     fn mouse_event_terminal(
         &mut self,
         mut pane: Rc<dyn Pane>,
@@ -524,6 +663,7 @@ impl super::TermWindow {
         event: MouseEvent,
         context: &dyn WindowOps,
         capture_mouse: bool,
+        bypass_mouse_reporting: bool,
     ) {
         let mut is_click_to_focus_pane = false;
 
@@ -568,7 +708,7 @@ impl super::TermWindow {
                             }
                         }
                         WMEK::Release(_) | WMEK::HorzWheel(_) => {}
-                        WMEK::VertWheel(_) => {
+                        WMEK::VertWheel(_) | WMEK::VertPixelScroll(_) => {
                             // Let wheel events route to the hovered pane,
                             // even if it doesn't have focus
                             pane = Rc::clone(&pos.pane);
@@ -592,6 +732,58 @@ impl super::TermWindow {
                         * (pos.top as isize - position.row as isize);
                 }
 
+                // Dragging beyond the top or bottom of the pane scrolls
+                // the viewport, so that a selection can be extended to
+                // include lines that are not currently in view
+                let bottom = (pos.top + pos.height) as i64;
+                let scroll_amount = if position.row < pos.top as i64 {
+                    position.row - pos.top as i64
+                } else if position.row >= bottom {
+                    position.row - bottom + 1
+                } else {
+                    0
+                };
+                if scroll_amount != 0
+                    && event.kind == WMEK::Move
+                    && !self.current_mouse_buttons.is_empty()
+                    && (!pane.is_mouse_grabbed() || bypass_mouse_reporting)
+                {
+                    let dims = pane.get_dimensions();
+                    let viewport = self
+                        .get_viewport(pane.pane_id())
+                        .unwrap_or(dims.physical_top)
+                        .saturating_add(scroll_amount as StableRowIndex);
+                    self.set_viewport(pane.pane_id(), Some(viewport), dims);
+                    context.invalidate();
+                    // Keep scrolling while the mouse is held still
+                    self.schedule_drag_autoscroll();
+                }
+
+                // Keep the position within the captured pane, so that
+                // dragging over a neighboring pane neither extends the
+                // selection past the edge of this one, nor reports an
+                // out of range position to an application that has
+                // grabbed the mouse.
+                // The selection may include the column just past the
+                // last cell, as that is how the rounded position
+                // selects the final cell.
+                let max_column = if pane.is_mouse_grabbed() && !bypass_mouse_reporting {
+                    pos.width.saturating_sub(1)
+                } else {
+                    pos.width
+                };
+                if column > max_column {
+                    x_pixel_offset +=
+                        self.render_metrics.cell_size.width * (column - max_column) as isize;
+                    column = max_column;
+                }
+                let max_row = pos.height.saturating_sub(1) as i64;
+                if row > max_row {
+                    y_pixel_offset +=
+                        self.render_metrics.cell_size.height * (row - max_row) as isize;
+                    row = max_row;
+                }
+
                 break;
             }
         }
@@ -600,6 +792,31 @@ impl super::TermWindow {
             self.current_mouse_capture = Some(MouseCapture::TerminalPane(pane.pane_id()));
         }
 
+        // The content is shifted up when it has been scrolled by a
+        // fractional number of rows
+        let viewport_pixel_offset = self.get_viewport_pixel_offset(pane.pane_id()) as isize;
+        if viewport_pixel_offset > 0 {
+            y_pixel_offset += viewport_pixel_offset;
+            if y_pixel_offset >= self.render_metrics.cell_size.height {
+                y_pixel_offset -= self.render_metrics.cell_size.height;
+                row += 1;
+            }
+        }
+
+        let event = match event.kind {
+            WMEK::VertPixelScroll(pixels) => match self.pixel_scroll(&pane, &event, pixels) {
+                Some(lines) => MouseEvent {
+                    kind: WMEK::VertWheel(lines),
+                    ..event
+                },
+                None => {
+                    context.invalidate();
+                    return;
+                }
+            },
+            _ => event,
+        };
+
         let is_focused = if let Some(focused) = self.focused.as_ref() {
             !self.config.swallow_mouse_click_on_window_focus
                 || (focused.elapsed() > Duration::from_millis(200))
@@ -627,7 +844,10 @@ impl super::TermWindow {
         }
 
         let allow_action = if self.is_click_to_focus_window || !is_focused {
-            matches!(&event.kind, WMEK::VertWheel(_) | WMEK::HorzWheel(_))
+            matches!(
+                &event.kind,
+                WMEK::VertWheel(_) | WMEK::HorzWheel(_) | WMEK::VertPixelScroll(_)
+            )
         } else {
             true
         };
@@ -679,7 +899,7 @@ impl super::TermWindow {
             (Some(old_link), Some(new_link)) if Arc::ptr_eq(&old_link, &new_link) => {
                 // Unchanged
             }
-            (Some(old_link), Some(new_link)) if *old_link == new_link => {
+            (Some(old_link), Some(new_link)) if old_link.is_same_link(&new_link) => {
                 // Unchanged
                 // Note: ideally this case wouldn't exist, as we *should*
                 // only be matching distinct instances of the hyperlink.
@@ -701,7 +921,7 @@ impl super::TermWindow {
             // When hovering over a hyperlink, show an appropriate
             // mouse cursor to give the cue that it is clickable
             MouseCursor::Hand
-        } else if pane.is_mouse_grabbed() {
+        } else if pane.is_mouse_grabbed() && !bypass_mouse_reporting {
             MouseCursor::Arrow
         } else {
             MouseCursor::Text
@@ -754,26 +974,20 @@ impl super::TermWindow {
                     None
                 }
             }
-            WMEK::VertWheel(amount) if !pane.is_mouse_grabbed() && !pane.is_alt_screen_active() => {
-                // adjust viewport
-                let dims = pane.get_dimensions();
-                let position = self
-                    .get_viewport(pane.pane_id())
-                    .unwrap_or(dims.physical_top)
-                    .saturating_sub((*amount).into());
-                self.set_viewport(pane.pane_id(), Some(position), dims);
-                context.invalidate();
-                return;
-            }
-            WMEK::VertWheel(_) | WMEK::HorzWheel(_) => None,
+            WMEK::VertWheel(amount) => Some(MouseEventTrigger::Down {
+                streak: 1,
+                // Bindings are matched against a single wheel step,
+                // regardless of the magnitude of the event
+                button: if *amount > 0 {
+                    TMB::WheelUp(1)
+                } else {
+                    TMB::WheelDown(1)
+                },
+            }),
+            WMEK::HorzWheel(_) | WMEK::VertPixelScroll(_) => None,
         };
 
-        if allow_action
-            && (!pane.is_mouse_grabbed()
-                || event
-                    .modifiers
-                    .contains(self.config.bypass_mouse_reporting_modifiers))
-        {
+        if allow_action && (!pane.is_mouse_grabbed() || bypass_mouse_reporting) {
             if let Some(event_trigger_type) = event_trigger_type {
                 let mut modifiers = event.modifiers;
 
@@ -795,10 +1009,31 @@ impl super::TermWindow {
             }
         }
 
+        if let WMEK::VertWheel(amount) = event.kind {
+            if !pane.is_mouse_grabbed() && !pane.is_alt_screen_active() {
+                let amount = self.scale_wheel_scroll(pane.pane_id(), amount);
+                if amount == 0 {
+                    return;
+                }
+                // adjust viewport
+                let dims = pane.get_dimensions();
+                let position = self
+                    .get_viewport(pane.pane_id())
+                    .unwrap_or(dims.physical_top)
+                    .saturating_sub(amount.into());
+                self.set_viewport(pane.pane_id(), Some(position), dims);
+                context.invalidate();
+                return;
+            }
+        }
+
         let mouse_event = wezterm_term::MouseEvent {
             kind: match event.kind {
                 WMEK::Move => TMEK::Move,
-                WMEK::VertWheel(_) | WMEK::HorzWheel(_) | WMEK::Press(_) => TMEK::Press,
+                WMEK::VertWheel(_)
+                | WMEK::HorzWheel(_)
+                | WMEK::VertPixelScroll(_)
+                | WMEK::Press(_) => TMEK::Press,
                 WMEK::Release(_) => TMEK::Release,
             },
             button: match event.kind {
@@ -821,7 +1056,7 @@ impl super::TermWindow {
                         TMB::WheelDown((-amount) as usize)
                     }
                 }
-                WMEK::HorzWheel(_) => TMB::None,
+                WMEK::HorzWheel(_) | WMEK::VertPixelScroll(_) => TMB::None,
             },
             x: column,
             y: row,
@@ -843,6 +1078,95 @@ impl super::TermWindow {
             }
         }
     }
+
+    /// Scale a wheel movement by the wheel_scroll_speed, accumulating
+    /// fractional lines so that slow speeds and small touchpad
+    /// movements still add up to whole lines over time.
+    fn scale_wheel_scroll(&self, pane_id: PaneId, amount: i16) -> isize {
+        let speed = self.config.wheel_scroll_speed;
+        let mut state = self.pane_state(pane_id);
+        let amount = amount as f64 * speed;
+        if amount.signum() != state.wheel_scroll_remainder.signum() {
+            // Reset remainder when changing scroll direction
+            state.wheel_scroll_remainder = 0.;
+        }
+        let amount = amount + state.wheel_scroll_remainder;
+        state.wheel_scroll_remainder = amount.fract();
+        amount.trunc() as isize
+    }
+
+    /// Handles a precise scroll from a device such as a touchpad.
+    /// When a wheel event would move the viewport, the viewport is
+    /// scrolled by that many pixels and None is returned.  Otherwise
+    /// the pixels are accumulated into whole lines, which are returned
+    /// so that they can be handled as a wheel event by a mouse binding
+    /// or by the application.
+    fn pixel_scroll(
+        &mut self,
+        pane: &Rc<dyn Pane>,
+        event: &MouseEvent,
+        pixels: i16,
+    ) -> Option<i16> {
+        let has_binding = !pane.is_mouse_grabbed()
+            && self
+                .input_map
+                .lookup_mouse(
+                    MouseEventTrigger::Down {
+                        streak: 1,
+                        button: if pixels > 0 {
+                            TMB::WheelUp(1)
+                        } else {
+                            TMB::WheelDown(1)
+                        },
+                    },
+                    event.modifiers,
+                )
+                .is_some();
+
+        if !has_binding && !pane.is_mouse_grabbed() && !pane.is_alt_screen_active() {
+            let pixels = pixels as f64 * self.config.wheel_scroll_speed;
+            self.scroll_viewport_by_pixels(pane, pixels);
+            return None;
+        }
+
+        let cell_height = self.render_metrics.cell_size.height as f64;
+        let mut state = self.pane_state(pane.pane_id());
+        let pixels = pixels as f64;
+        if pixels.signum() != state.pixel_scroll_remainder.signum() {
+            // Reset remainder when changing scroll direction
+            state.pixel_scroll_remainder = 0.;
+        }
+        let pixels = pixels + state.pixel_scroll_remainder;
+        let lines = (pixels / cell_height).trunc();
+        state.pixel_scroll_remainder = pixels - lines * cell_height;
+        if lines == 0. {
+            None
+        } else {
+            Some(lines as i16)
+        }
+    }
+
+    /// Moves the viewport by a number of pixels, which may be a
+    /// fraction of a row.  Positive values scroll up.
+    fn scroll_viewport_by_pixels(&mut self, pane: &Rc<dyn Pane>, pixels: f64) {
+        let cell_height = self.render_metrics.cell_size.height as f64;
+        let pane_id = pane.pane_id();
+        let dims = pane.get_dimensions();
+        let top = self.get_viewport(pane_id).unwrap_or(dims.physical_top);
+
+        // The distance of the top of the visible content, measured
+        // downwards from the top of the current viewport row
+        let offset = self.pane_state(pane_id).viewport_pixel_offset - pixels;
+        let rows = (offset / cell_height).floor();
+        let new_top = top.saturating_add(rows as StableRowIndex);
+
+        self.set_viewport(pane_id, Some(new_top), dims);
+        // Keep the fractional part, unless we ran into either
+        // end of the scrollback
+        if self.get_viewport(pane_id) == Some(new_top) {
+            self.pane_state(pane_id).viewport_pixel_offset = offset - rows * cell_height;
+        }
+    }
 }
 
 fn mouse_press_to_tmb(press: &MousePress) -> TMB {
@@ -17,11 +17,11 @@ use ::window::glium::uniforms::{
     MagnifySamplerFilter, MinifySamplerFilter, Sampler, SamplerWrapFunction,
 };
 use ::window::glium::{uniform, BlendingFunction, LinearBlendingFactor, Surface};
-use ::window::{glium, DeadKeyStatus, PointF, RectF, SizeF, WindowOps};
+use ::window::{glium, Connection, ConnectionOps, DeadKeyStatus, PointF, RectF, SizeF, WindowOps};
 use anyhow::anyhow;
 use config::{
-    ConfigHandle, Dimension, DimensionContext, HsbTransform, TabBarColors, TextStyle,
-    VisualBellTarget,
+    BlendColorSpace, ConfigHandle, Dimension, DimensionContext, FailedCommandHighlight,
+    HsbTransform, TabBarColors, TextBlinkStyle, TextStyle, VisualBellTarget,
 };
 use euclid::num::Zero;
 use mux::pane::Pane;
@@ -31,9 +31,10 @@ use smol::Timer;
 use std::ops::Range;
 use std::rc::Rc;
 use std::time::Instant;
-use termwiz::cell::{unicode_column_width, Blink};
+use termwiz::cell::{unicode_column_width, Blink, Underline};
 use termwiz::cellcluster::CellCluster;
-use termwiz::surface::{CursorShape, CursorVisibility};
+use termwiz::surface::{CursorShape, CursorVisibility, SEQ_ZERO};
+use termwiz_funcs::truncate_right;
 use wezterm_bidi::Direction;
 use wezterm_font::shaper::PresentationWidth;
 use wezterm_font::units::{IntPixelLength, PixelLength};
@@ -43,6 +44,14 @@ use wezterm_term::{CellAttributes, Line, StableRowIndex};
 use window::bitmaps::Texture2d;
 use window::color::LinearRgba;
 
+/// How far the background of blinking text is blended towards the
+/// foreground color when text_blink_style = "Tint"
+const BLINK_TINT_AMOUNT: f32 = 0.25;
+
+/// The minimum contrast ratio between the text under a block cursor
+/// and the cursor color before we fall back to using the cell colors
+const MIN_CURSOR_TEXT_CONTRAST: f32 = 1.5;
+
 pub const TOP_LEFT_ROUNDED_CORNER: &[Poly] = &[Poly {
     path: &[
         PolyCommand::MoveTo(BlockCoord::One, BlockCoord::One),
@@ -146,6 +155,9 @@ pub struct RenderScreenLineOpenGLParams<'a> {
     pub left_pixel_x: f32,
     pub pixel_width: f32,
     pub stable_line_idx: Option<StableRowIndex>,
+    /// false if the pane knows that none of the cells on this line
+    /// belong to the hovered hyperlink
+    pub may_have_highlight: bool,
     pub line: &'a Line,
     pub selection: Range<usize>,
     pub cursor: &'a StableCursorPosition,
@@ -241,6 +253,10 @@ impl super::TermWindow {
         *self.has_animation.borrow_mut() = None;
         // Start with the assumption that we should allow images to render
         self.allow_images = true;
+        self.reduce_motion = self.config.respect_reduced_motion
+            && Connection::get()
+                .map(|conn| conn.prefers_reduced_motion())
+                .unwrap_or(false);
 
         let start = Instant::now();
 
@@ -318,7 +334,7 @@ impl super::TermWindow {
         // If self.has_animation is some, then the last render detected
         // image attachments with multiple frames, so we also need to
         // invalidate the viewport when the next frame is due
-        if self.focused.is_some() {
+        if self.animate_images() {
             if let Some(next_due) = *self.has_animation.borrow() {
                 let prior = self.scheduled_animation.borrow_mut().take();
                 match prior {
@@ -343,6 +359,13 @@ impl super::TermWindow {
         }
     }
 
+    /// Returns true if animated images should advance to their
+    /// next frame when it is due
+    pub fn animate_images(&self) -> bool {
+        !self.reduce_motion
+            && (self.focused.is_some() || !self.config.pause_image_animation_when_unfocused)
+    }
+
     pub fn update_next_frame_time(&self, next_due: Option<Instant>) {
         if let Some(next_due) = next_due {
             let mut has_anim = self.has_animation.borrow_mut();
@@ -367,6 +390,21 @@ impl super::TermWindow {
         let mut per_pane = self.pane_state(pane.pane_id());
         if let Some(ringing) = per_pane.bell_start {
             if config.visual_bell.target == target {
+                if self.reduce_motion {
+                    // Show a steady tint for the duration of the bell
+                    // rather than fading it in and out
+                    let duration = std::time::Duration::from_millis(
+                        config.visual_bell.fade_in_duration_ms
+                            + config.visual_bell.fade_out_duration_ms,
+                    );
+                    if ringing.elapsed() < duration {
+                        self.update_next_frame_time(Some(ringing + duration));
+                        return Some(1.0);
+                    }
+                    per_pane.bell_start.take();
+                    return None;
+                }
+
                 let mut color_ease = ColorEase::new(
                     config.visual_bell.fade_in_duration_ms,
                     config.visual_bell.fade_in_function,
@@ -533,6 +571,9 @@ impl super::TermWindow {
                         bg: rgbcolor_to_window_color(colors.inactive_tab.bg_color).into(),
                         text: rgbcolor_to_window_color(colors.inactive_tab.fg_color).into(),
                     }),
+                TabBarItem::CloseTabButton { .. } => {
+                    element.item_type(UIItemType::TabBar(item.item.clone()))
+                }
                 TabBarItem::NewTabButton => Element::new(
                     &font,
                     ElementContent::Poly {
@@ -707,6 +748,11 @@ impl super::TermWindow {
                     elem.content = match elem.content {
                         ElementContent::Text(_) => unreachable!(),
                         ElementContent::Poly { .. } => unreachable!(),
+                        ElementContent::Children(kids)
+                            if !self.config.show_close_tab_button_in_tabs =>
+                        {
+                            ElementContent::Children(kids)
+                        }
                         ElementContent::Children(mut kids) => {
                             let x_button = Element::new(
                                 &font,
@@ -934,6 +980,7 @@ impl super::TermWindow {
                 left_pixel_x: 0.,
                 pixel_width: self.dimensions.pixel_width as f32,
                 stable_line_idx: None,
+                may_have_highlight: true,
                 line: self.tab_bar.line(),
                 selection: 0..0,
                 cursor: &Default::default(),
@@ -1046,6 +1093,11 @@ impl super::TermWindow {
         };
         */
 
+        let failed_command_ranges = match self.config.failed_command_highlight {
+            FailedCommandHighlight::None => vec![],
+            _ => self.get_failed_command_ranges(&pos.pane).to_vec(),
+        };
+
         let global_cursor_fg = self.palette().cursor_fg;
         let global_cursor_bg = self.palette().cursor_bg;
         let config = &self.config;
@@ -1073,13 +1125,22 @@ impl super::TermWindow {
         }
 
         let current_viewport = self.get_viewport(pos.pane.pane_id());
-        let (stable_top, lines);
+        let (stable_top, mut lines);
         let dims = pos.pane.get_dimensions();
 
+        // When scrolled by a fractional number of rows, the content is
+        // shifted up and an extra row is partially revealed at the bottom
+        let viewport_pixel_offset = self.get_viewport_pixel_offset(pos.pane.pane_id());
+        let num_rows = if viewport_pixel_offset > 0. {
+            dims.viewport_rows + 1
+        } else {
+            dims.viewport_rows
+        };
+
         {
             let stable_range = match current_viewport {
-                Some(top) => top..top + dims.viewport_rows as StableRowIndex,
-                None => dims.physical_top..dims.physical_top + dims.viewport_rows as StableRowIndex,
+                Some(top) => top..top + num_rows as StableRowIndex,
+                None => dims.physical_top..dims.physical_top + num_rows as StableRowIndex,
             };
 
             let start = Instant::now();
@@ -1095,6 +1156,37 @@ impl super::TermWindow {
             lines = vp_lines;
         }
 
+        // Rather than yanking the viewport to the bottom when new output
+        // arrives while scrolled back, indicate how much has arrived
+        let new_lines = self.new_lines_since_scrolled_back(pos.pane.pane_id(), &dims);
+        if new_lines > 0 {
+            let last_full_row = dims.viewport_rows.min(lines.len()).saturating_sub(1);
+            if let Some(line) = lines.get_mut(last_full_row) {
+                let text = format!(
+                    " {} new line{} \u{2193} ",
+                    new_lines,
+                    if new_lines == 1 { "" } else { "s" }
+                );
+                let width = unicode_column_width(&text, None);
+                let attrs = CellAttributes::default().set_reverse(true).clone();
+                line.overlay_text_with_attribute(
+                    dims.cols.saturating_sub(width),
+                    &text,
+                    attrs,
+                    line.current_seqno(),
+                );
+            }
+        }
+
+        // The vertical extent of the pane, for cropping the rows that
+        // are partially scrolled out of it
+        let pane_clip = {
+            let cell_height = self.render_metrics.cell_size.height as f32;
+            let top = top_pixel_y + (pos.top as f32 * cell_height)
+                - (self.dimensions.pixel_height as f32 / 2.);
+            (top, top + (pos.height as f32 * cell_height))
+        };
+
         let gl_state = self.render_state.as_ref().unwrap();
         let layer = gl_state.layer_for_zindex(0)?;
         let vbs = layer.vb.borrow();
@@ -1263,6 +1355,65 @@ impl super::TermWindow {
             }
         }
 
+        if !failed_command_ranges.is_empty() {
+            let first_quad = layers[0].next_index();
+            // Mark the output of failed commands using the ANSI red color
+            let cell_width = self.render_metrics.cell_size.width as f32;
+            let cell_height = self.render_metrics.cell_size.height as f32;
+            let LinearRgba(r, g, b, _) = palette.colors.0[1].to_linear();
+            let stable_bottom = stable_top + lines.len() as StableRowIndex;
+
+            for range in &failed_command_ranges {
+                let first = range.start.max(stable_top);
+                let last = range.end.min(stable_bottom);
+                if first >= last {
+                    continue;
+                }
+
+                let pane_x = padding_left + (pos.left as f32 * cell_width);
+                let (x, width, color) = match config.failed_command_highlight {
+                    FailedCommandHighlight::Gutter => {
+                        // Sits just to the left of the pane, in the padding
+                        // or the split separator
+                        let width = (cell_width / 4.).max(1.);
+                        (
+                            pane_x - width,
+                            width,
+                            LinearRgba::with_components(r, g, b, 1.0),
+                        )
+                    }
+                    FailedCommandHighlight::Tint => (
+                        pane_x,
+                        pos.width as f32 * cell_width,
+                        LinearRgba::with_components(r, g, b, 0.15),
+                    ),
+                    FailedCommandHighlight::None => continue,
+                };
+
+                let mut quad = self.filled_rectangle(
+                    &mut layers[0],
+                    euclid::rect(
+                        x,
+                        top_pixel_y + (pos.top as f32 + (first - stable_top) as f32) * cell_height
+                            - viewport_pixel_offset,
+                        width,
+                        (last - first) as f32 * cell_height,
+                    ),
+                    color,
+                )?;
+
+                quad.set_hsv(if pos.is_active {
+                    None
+                } else {
+                    Some(config.inactive_pane_hsb)
+                });
+            }
+
+            if viewport_pixel_offset > 0. {
+                layers[0].clip_vertically_from(first_quad, pane_clip.0, pane_clip.1);
+            }
+        }
+
         // TODO: we only have a single scrollbar in a single position.
         // We only update it for the active pane, but we should probably
         // do a per-pane scrollbar.  That will require more extensive
@@ -1349,6 +1500,20 @@ impl super::TermWindow {
         let cursor_is_default_color =
             palette.cursor_fg == global_cursor_fg && palette.cursor_bg == global_cursor_bg;
 
+        let first_quads = [
+            layers[0].next_index(),
+            layers[1].next_index(),
+            layers[2].next_index(),
+        ];
+
+        // When hovering over a link with an id, ask the pane which rows
+        // hold its cells, rather than comparing every cell with it
+        let highlight_rows = self
+            .current_highlight
+            .as_ref()
+            .and_then(|link| link.id())
+            .and_then(|id| pos.pane.get_hyperlink_rows(id));
+
         for (line_idx, line) in lines.iter().enumerate() {
             let stable_row = stable_top + line_idx as StableRowIndex;
 
@@ -1359,11 +1524,15 @@ impl super::TermWindow {
             self.render_screen_line_opengl(
                 RenderScreenLineOpenGLParams {
                     top_pixel_y: top_pixel_y
-                        + (line_idx + pos.top) as f32 * self.render_metrics.cell_size.height as f32,
+                        + (line_idx + pos.top) as f32 * self.render_metrics.cell_size.height as f32
+                        - viewport_pixel_offset,
                     left_pixel_x: padding_left
                         + (pos.left as f32 * self.render_metrics.cell_size.width as f32),
                     pixel_width: dims.cols as f32 * self.render_metrics.cell_size.width as f32,
                     stable_line_idx: Some(stable_row),
+                    may_have_highlight: highlight_rows
+                        .as_ref()
+                        .map_or(true, |rows| rows.binary_search(&stable_row).is_ok()),
                     line: &line,
                     selection: selrange,
                     cursor: &cursor,
@@ -1391,6 +1560,80 @@ impl super::TermWindow {
                 &mut layers,
             )?;
         }
+
+        if viewport_pixel_offset > 0. {
+            for (layer, &first_quad) in layers.iter_mut().zip(first_quads.iter()) {
+                layer.clip_vertically_from(first_quad, pane_clip.0, pane_clip.1);
+            }
+        }
+
+        // Lines that are drawn over or around the pane content,
+        // as (row, column, width, line) in window cell coordinates
+        let mut decorations = vec![];
+        let header_rows = mux::Mux::get()
+            .and_then(|mux| mux.get_active_tab_for_window(self.mux_window_id))
+            .map(|tab| tab.get_pane_header_rows())
+            .unwrap_or(0);
+        if header_rows > 0 && pos.top >= header_rows {
+            decorations.push((
+                pos.top - header_rows,
+                pos.left,
+                pos.width,
+                pane_header_line(&pos.pane, pos.width),
+            ));
+        }
+        let output_paused = mux::Mux::get()
+            .map(|mux| mux.is_pane_output_paused(pos.pane.pane_id()))
+            .unwrap_or(false);
+        if output_paused {
+            let badge = paused_badge_line(pos.width);
+            let width = badge.len();
+            decorations.push((pos.top, pos.left + pos.width - width, width, badge));
+        }
+
+        for (row, left, width, line) in decorations {
+            self.render_screen_line_opengl(
+                RenderScreenLineOpenGLParams {
+                    top_pixel_y: top_pixel_y
+                        + row as f32 * self.render_metrics.cell_size.height as f32,
+                    left_pixel_x: padding_left
+                        + (left as f32 * self.render_metrics.cell_size.width as f32),
+                    pixel_width: width as f32 * self.render_metrics.cell_size.width as f32,
+                    stable_line_idx: None,
+                    may_have_highlight: true,
+                    line: &line,
+                    selection: 0..0,
+                    cursor: &Default::default(),
+                    palette: &palette,
+                    dims: &RenderableDimensions {
+                        cols: width,
+                        physical_top: 0,
+                        scrollback_rows: 0,
+                        scrollback_top: 0,
+                        viewport_rows: 1,
+                    },
+                    config: &config,
+                    cursor_border_color,
+                    foreground,
+                    is_active: pos.is_active,
+                    pane: None,
+                    selection_fg,
+                    selection_bg,
+                    cursor_fg,
+                    cursor_bg,
+                    cursor_is_default_color,
+                    white_space,
+                    filled_box,
+                    window_is_transparent,
+                    default_bg,
+                    font: None,
+                    style: None,
+                    use_pixel_positioning: self.config.experimental_pixel_positioning,
+                    render_metrics: self.render_metrics,
+                },
+                &mut layers,
+            )?;
+        }
         /*
         if let Some(zone) = zone {
             // TODO: render a thingy to jump to prior prompt
@@ -1471,6 +1714,11 @@ impl super::TermWindow {
             foreground_text_hsb.brightness,
         );
 
+        let glyph_prog = match (self.config.blend_color_space, &gl_state.linear_glyph_prog) {
+            (BlendColorSpace::Linear, Some(prog)) => prog,
+            _ => &gl_state.glyph_prog,
+        };
+
         for layer in gl_state.layers.borrow().iter() {
             for idx in 0..3 {
                 let vb = &layer.vb.borrow()[idx];
@@ -1482,7 +1730,7 @@ impl super::TermWindow {
                     frame.draw(
                         vertices.slice(0..vertex_count).unwrap(),
                         vb.indices.slice(0..index_count).unwrap(),
-                        &gl_state.glyph_prog,
+                        glyph_prog,
                         &uniform! {
                             projection: projection,
                             atlas_nearest_sampler:  atlas_nearest_sampler,
@@ -1712,19 +1960,27 @@ impl super::TermWindow {
             {
                 let attrs = &cluster.attrs;
                 let style = self.fonts.match_style(params.config, attrs);
-                let is_highlited_hyperlink = match (attrs.hyperlink(), &self.current_highlight) {
-                    (Some(ref this), &Some(ref highlight)) => **this == *highlight,
-                    _ => false,
-                };
-                // underline and strikethrough
+                let is_highlited_hyperlink = params.may_have_highlight
+                    && match (attrs.hyperlink(), &self.current_highlight) {
+                        (Some(ref this), &Some(ref highlight)) => this.is_same_link(highlight),
+                        _ => false,
+                    };
+                // underline and strikethrough.
+                // Concealed text is rendered as blanks, so its
+                // decorations are hidden along with the glyphs.
+                let invisible = attrs.invisible();
                 let underline_tex_rect = gl_state
                     .glyph_cache
                     .borrow_mut()
                     .cached_line_sprite(
-                        is_highlited_hyperlink,
-                        attrs.strikethrough(),
-                        attrs.underline(),
-                        attrs.overline(),
+                        is_highlited_hyperlink && !invisible,
+                        attrs.strikethrough() && !invisible,
+                        if invisible {
+                            Underline::None
+                        } else {
+                            attrs.underline()
+                        },
+                        attrs.overline() && !invisible,
                         &params.render_metrics,
                     )?
                     .texture_coords();
@@ -1747,12 +2003,34 @@ impl super::TermWindow {
                     // part of blinking then set fg = bg.  This is a cheap
                     // means of getting it done without impacting other
                     // features.
-                    let blink_rate = match attrs.blink() {
-                        Blink::None => None,
-                        Blink::Slow => {
+                    // When reducing motion, blinking text is
+                    // tinted rather than animated
+                    let text_blink_style = match params.config.text_blink_style {
+                        TextBlinkStyle::Blink if self.reduce_motion => TextBlinkStyle::Tint,
+                        style => style,
+                    };
+                    let blink_rate = match (text_blink_style, attrs.blink()) {
+                        (_, Blink::None) | (TextBlinkStyle::None, _) => None,
+                        (TextBlinkStyle::Tint, _) => {
+                            // Render steadily, but blend the background
+                            // towards the foreground so that the text
+                            // still stands out.  There is no animation,
+                            // so no need to schedule another frame.
+                            let (r1, g1, b1, a) = bg.tuple();
+                            let (r, g, b, _a) = fg.tuple();
+                            bg = LinearRgba::with_components(
+                                r1 + (r - r1) * BLINK_TINT_AMOUNT,
+                                g1 + (g - g1) * BLINK_TINT_AMOUNT,
+                                b1 + (b - b1) * BLINK_TINT_AMOUNT,
+                                a,
+                            );
+                            bg_default = false;
+                            None
+                        }
+                        (TextBlinkStyle::Blink, Blink::Slow) => {
                             Some((params.config.text_blink_rate, self.blink_state.borrow_mut()))
                         }
-                        Blink::Rapid => Some((
+                        (TextBlinkStyle::Blink, Blink::Rapid) => Some((
                             params.config.text_blink_rate_rapid,
                             self.rapid_blink_state.borrow_mut(),
                         )),
@@ -1981,18 +2259,37 @@ impl super::TermWindow {
         // Need to consider:
         // * background when it is not the default color
         // * Reverse video attribute
+        // * images that are placed beneath the background
+        let mut visual_cell_idx = 0;
         for item in &shaped {
             let cluster = &item.cluster;
             let attrs = &cluster.attrs;
             let cluster_width = cluster.width;
 
+            if let Some(images) = attrs.images() {
+                for cell_idx in visual_cell_idx..visual_cell_idx + cluster_width {
+                    for img in images.iter().filter(|img| img.is_below_background()) {
+                        self.populate_image_quad(
+                            img,
+                            gl_state,
+                            &mut layers[0],
+                            cell_idx,
+                            &params,
+                            hsv,
+                            item.style.fg_color,
+                        )?;
+                    }
+                }
+            }
+            visual_cell_idx += cluster_width;
+
             let bg_is_default = attrs.background() == ColorAttribute::Default;
             let bg_color = params.palette.resolve_bg(attrs.background()).to_linear();
 
             let fg_color =
                 resolve_fg_color_attr(&attrs, attrs.foreground(), &params, &Default::default());
 
-            let (bg_color, bg_is_default) = {
+            let (fg_color, bg_color, bg_is_default) = {
                 let mut fg = fg_color;
                 let mut bg = bg_color;
                 let mut bg_default = bg_is_default;
@@ -2004,6 +2301,7 @@ impl super::TermWindow {
                 }
 
                 (
+                    fg,
                     bg.mul_alpha(self.config.text_background_opacity),
                     bg_default,
                 )
@@ -2031,6 +2329,27 @@ impl super::TermWindow {
                 }
             }
 
+            // When using reverse video for the selection, the selected
+            // portion of this cluster takes on its foreground color as
+            // its background
+            if self.config.force_reverse_video_selection && !params.selection.is_empty() {
+                let first_cell = phys(cluster.first_cell_idx, num_cols, direction);
+                let start = first_cell.max(params.selection.start);
+                let end = (first_cell + cluster_width).min(params.selection.end);
+                if start < end {
+                    let rect = euclid::rect(
+                        params.left_pixel_x + start as f32 * cell_width,
+                        params.top_pixel_y,
+                        (end - start) as f32 * cell_width,
+                        cell_height,
+                    );
+                    if let Some(rect) = rect.intersection(&bounding_rect) {
+                        let mut quad = self.filled_rectangle(&mut layers[0], rect, fg_color)?;
+                        quad.set_hsv(hsv);
+                    }
+                }
+            }
+
             // Underlines
             if item.style.underline_tex_rect != params.white_space {
                 // Draw one per cell, otherwise curly underlines
@@ -2055,19 +2374,59 @@ impl super::TermWindow {
             }
         }
 
+        // When using reverse video for the selection, the portion of the
+        // selection that extends beyond the cells in the line takes on
+        // the default foreground color as its background
+        if self.config.force_reverse_video_selection && !params.selection.is_empty() {
+            let covered = match direction {
+                Direction::LeftToRight => 0..visual_cell_idx,
+                Direction::RightToLeft => num_cols.saturating_sub(visual_cell_idx)..num_cols,
+            };
+            let fill_color = if params.line.is_reverse() {
+                params.default_bg
+            } else {
+                params.foreground
+            };
+            for (start, end) in [
+                (
+                    params.selection.start,
+                    params.selection.end.min(covered.start),
+                ),
+                (
+                    params.selection.start.max(covered.end),
+                    params.selection.end,
+                ),
+            ] {
+                if start < end {
+                    let rect = euclid::rect(
+                        params.left_pixel_x + start as f32 * cell_width,
+                        params.top_pixel_y,
+                        (end - start) as f32 * cell_width,
+                        cell_height,
+                    );
+                    if let Some(rect) = rect.intersection(&bounding_rect) {
+                        let mut quad = self.filled_rectangle(&mut layers[0], rect, fill_color)?;
+                        quad.set_hsv(hsv);
+                    }
+                }
+            }
+        }
+
         // Render the selection background color.
         // This always uses a physical x position, regardles of the line
         // direction.
         let selection_pixel_range = if !params.selection.is_empty() {
             let start = params.left_pixel_x + (params.selection.start as f32 * cell_width);
             let width = (params.selection.end - params.selection.start) as f32 * cell_width;
-            let mut quad = self.filled_rectangle(
-                &mut layers[0],
-                euclid::rect(start, params.top_pixel_y, width, cell_height),
-                params.selection_bg,
-            )?;
+            if !self.config.force_reverse_video_selection {
+                let mut quad = self.filled_rectangle(
+                    &mut layers[0],
+                    euclid::rect(start, params.top_pixel_y, width, cell_height),
+                    params.selection_bg,
+                )?;
 
-            quad.set_hsv(hsv);
+                quad.set_hsv(hsv);
+            }
 
             start..start + width
         } else {
@@ -2185,7 +2544,7 @@ impl super::TermWindow {
 
                 for glyph_idx in 0..info.pos.num_cells as usize {
                     for img in &images {
-                        if img.z_index() < 0 {
+                        if img.z_index() < 0 && !img.is_below_background() {
                             self.populate_image_quad(
                                 &img,
                                 gl_state,
@@ -2224,6 +2583,12 @@ impl super::TermWindow {
                         }
                     }
 
+                    // Concealed text is rendered as blanks; the text remains
+                    // in the model so that it can still be copied.
+                    if cluster.attrs.invisible() {
+                        texture.take();
+                    }
+
                     if let Some(texture) = texture {
                         // TODO: clipping, but we can do that based on pixels
 
@@ -2482,10 +2847,11 @@ impl super::TermWindow {
             padding.next_power_of_two()
         };
 
-        let (sprite, next_due) = gl_state
-            .glyph_cache
-            .borrow_mut()
-            .cached_image(image.image_data(), Some(padding))?;
+        let (sprite, next_due) = gl_state.glyph_cache.borrow_mut().cached_image(
+            image.image_data(),
+            Some(padding),
+            self.animate_images(),
+        )?;
         self.update_next_frame_time(next_due);
         let width = sprite.coords.size.width;
         let height = sprite.coords.size.height;
@@ -2627,11 +2993,17 @@ impl super::TermWindow {
             visibility,
         ) {
             // Selected text overrides colors
-            (true, _, _, CursorVisibility::Hidden) => (
-                params.selection_fg.when_fully_transparent(params.fg_color),
-                params.selection_bg,
-                params.cursor_bg,
-            ),
+            (true, _, _, CursorVisibility::Hidden) => {
+                if self.config.force_reverse_video_selection {
+                    (params.bg_color, params.fg_color, params.cursor_bg)
+                } else {
+                    (
+                        params.selection_fg.when_fully_transparent(params.fg_color),
+                        params.selection_bg,
+                        params.cursor_bg,
+                    )
+                }
+            }
             // block Cursor cell overrides colors
             (
                 _,
@@ -2642,7 +3014,11 @@ impl super::TermWindow {
                 if self.config.force_reverse_video_cursor && params.cursor_is_default_color {
                     (params.bg_color, params.fg_color, params.fg_color)
                 } else {
-                    (params.cursor_fg, params.cursor_bg, params.cursor_bg)
+                    (
+                        cursor_text_color(&params),
+                        params.cursor_bg,
+                        params.cursor_bg,
+                    )
                 }
             }
             (
@@ -2668,6 +3044,7 @@ impl super::TermWindow {
             && params.is_active_pane
             && cursor_shape.is_blinking()
             && params.config.cursor_blink_rate != 0
+            && !self.reduce_motion
             && self.focused.is_some();
 
         let mut fg_color_alt = fg_color;
@@ -2758,6 +3135,42 @@ impl super::TermWindow {
                 }
             }
 
+            if font.is_last_resort(info.font_idx) {
+                if let Some(c) = line
+                    .cells()
+                    .get(cell_idx)
+                    .and_then(|c| c.str().chars().next())
+                {
+                    // No font has a glyph for this codepoint.  Rather than the
+                    // generic placeholder from the Last Resort font, show a box
+                    // with the codepoint in it, drawn from the top of the cell
+                    let cells = info.num_cells.max(1);
+                    let texture = glyph_cache.cached_block(
+                        BlockKey::Tofu {
+                            codepoint: c as u32,
+                            cells,
+                        },
+                        metrics,
+                    )?;
+                    glyphs.push(Rc::new(CachedGlyph {
+                        brightness_adjust: 1.0,
+                        has_color: false,
+                        texture: Some(texture),
+                        x_advance: PixelLength::new(
+                            (metrics.cell_size.width * cells as isize) as f64,
+                        ),
+                        x_offset: PixelLength::zero(),
+                        y_offset: PixelLength::zero(),
+                        bearing_x: PixelLength::zero(),
+                        bearing_y: PixelLength::new(
+                            metrics.cell_size.height as f64 + metrics.descender.get(),
+                        ),
+                        scale: 1.0,
+                    }));
+                    continue;
+                }
+            }
+
             let followed_by_space = match line.cells().get(cell_idx + 1) {
                 Some(cell) => cell.str() == " ",
                 None => false,
@@ -2878,6 +3291,24 @@ pub fn rgbcolor_alpha_to_window_color(color: RgbColor, alpha: f32) -> LinearRgba
     LinearRgba::with_components(red, green, blue, alpha)
 }
 
+/// Returns the color to use for the text under a block cursor.
+/// This is normally cursor_fg, but if that has insufficient contrast
+/// with the cursor color, for example, because an application changed
+/// the cursor color via OSC 12, then whichever of the cell's own
+/// foreground and background colors has better contrast is used instead.
+fn cursor_text_color(params: &ComputeCellFgBgParams) -> LinearRgba {
+    if params.cursor_fg.contrast_ratio(params.cursor_bg) >= MIN_CURSOR_TEXT_CONTRAST {
+        return params.cursor_fg;
+    }
+    if params.fg_color.contrast_ratio(params.cursor_bg)
+        >= params.bg_color.contrast_ratio(params.cursor_bg)
+    {
+        params.fg_color
+    } else {
+        params.bg_color
+    }
+}
+
 fn resolve_fg_color_attr(
     attrs: &CellAttributes,
     fg: ColorAttribute,
@@ -2911,3 +3342,34 @@ fn resolve_fg_color_attr(
     }
     .to_linear()
 }
+
+/// Builds the header line that is shown above a pane when
+/// `show_pane_header` is enabled.  It holds the title of the pane,
+/// its current working directory and its dimensions, in reverse video.
+fn pane_header_line(pane: &Rc<dyn Pane>, width: usize) -> Line {
+    let dims = pane.get_dimensions();
+    let mut text = format!(" {}", pane.get_title());
+    if let Some(cwd) = pane.get_current_working_dir() {
+        let cwd = percent_encoding::percent_decode_str(cwd.path()).decode_utf8_lossy();
+        text.push_str(&format!(" \u{2502} {}", cwd));
+    }
+    text.push_str(&format!(" \u{2502} {}x{} ", dims.cols, dims.viewport_rows));
+
+    let mut text = truncate_right(&text, width);
+    let text_width = unicode_column_width(&text, None);
+    text.push_str(&" ".repeat(width.saturating_sub(text_width)));
+
+    let attrs = CellAttributes::default().set_reverse(true).clone();
+    Line::from_text(&text, &attrs, SEQ_ZERO, None)
+}
+
+/// Builds the badge that is shown in the top right corner of a pane
+/// whose output has been paused by `TogglePaneOutputPaused`.
+fn paused_badge_line(width: usize) -> Line {
+    let text = truncate_right(" PAUSED ", width);
+    let attrs = CellAttributes::default()
+        .set_reverse(true)
+        .set_intensity(wezterm_term::Intensity::Bold)
+        .clone();
+    Line::from_text(&text, &attrs, SEQ_ZERO, None)
+}
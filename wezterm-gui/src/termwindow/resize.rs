@@ -1,3 +1,4 @@
+use crate::termwindow::background::reload_background_image;
 use crate::utilsprites::RenderMetrics;
 use ::window::{Dimensions, Window, WindowOps, WindowState};
 use config::{ConfigHandle, DimensionContext};
@@ -54,6 +55,12 @@ impl super::TermWindow {
         if let Some(modal) = self.get_modal() {
             modal.reconfigure(self);
         }
+        self.record_window_bounds(window);
+        if !live_resizing
+            && !window_state.intersects(WindowState::MAXIMIZED | WindowState::FULL_SCREEN)
+        {
+            crate::windowsize::save_size(&self.config, &self.terminal_size);
+        }
         self.emit_window_event("window-resized", None);
     }
 
@@ -111,6 +118,25 @@ impl super::TermWindow {
         if let Err(err) = self.recreate_texture_atlas(None) {
             log::error!("recreate_texture_atlas: {:#}", err);
         }
+
+        // The fancy tab bar and any modal overlay have their layout
+        // computed in terms of the prior font metrics; force them to
+        // be recomputed at the new scale.
+        self.invalidate_fancy_tab_bar();
+        self.invalidate_modal();
+
+        if dimensions.dpi != prior_dpi {
+            // Gradients and other background layers are rasterized
+            // relative to the dpi, so when moving to a display with
+            // a different scale factor we need to regenerate them
+            // to avoid them appearing blurry or incorrectly sized.
+            self.window_background = reload_background_image(
+                &self.config,
+                &self.window_background,
+                dimensions,
+                &self.render_metrics,
+            );
+        }
     }
 
     pub fn apply_dimensions(
@@ -403,8 +429,10 @@ impl super::TermWindow {
             pixel_height: (render_metrics.cell_size.height as u16 * size.rows),
         };
 
-        let show_tab_bar = config.enable_tab_bar && !config.hide_tab_bar_if_only_one_tab;
-        let tab_bar_height = if show_tab_bar {
+        // Use the current tab bar state rather than the config, as the
+        // tab bar may be visible despite hide_tab_bar_if_only_one_tab
+        // when there are multiple tabs
+        let tab_bar_height = if self.show_tab_bar {
             self.tab_bar_pixel_height()? as usize
         } else {
             0
@@ -2,16 +2,48 @@ use crate::selection::{Selection, SelectionCoordinate, SelectionMode, SelectionR
 use ::window::WindowOps;
 use mux::pane::{Pane, PaneId};
 use std::cell::RefMut;
+use std::ops::Range;
 use std::rc::Rc;
-use wezterm_term::StableRowIndex;
+use termwiz::cell::CellAttributes;
+use termwiz::html::HtmlBuilder;
+use termwiz::surface::Change;
+use wezterm_term::{Line, StableRowIndex};
+
+/// Like Line::columns_as_str, but replaces concealed cells with spaces
+fn columns_as_str_without_concealed(line: &Line, range: Range<usize>) -> String {
+    let mut s = String::new();
+    for (n, c) in line.visible_cells() {
+        if n < range.start {
+            continue;
+        }
+        if n >= range.end {
+            break;
+        }
+        if c.attrs().invisible() {
+            for _ in 0..c.width() {
+                s.push(' ');
+            }
+        } else {
+            s.push_str(c.str());
+        }
+    }
+    s
+}
 
 impl super::TermWindow {
     pub fn selection(&self, pane_id: PaneId) -> RefMut<Selection> {
         RefMut::map(self.pane_state(pane_id), |state| &mut state.selection)
     }
 
-    pub fn selection_text(&self, pane: &Rc<dyn Pane>) -> String {
-        let mut s = String::new();
+    /// Calls `func` for each portion of a physical line that is covered
+    /// by the selection, passing the line, the selected columns, whether
+    /// a newline should separate it from the preceding portion and whether
+    /// it is the last physical line of its logical line.
+    fn for_each_selected_line<F: FnMut(&Line, Range<usize>, bool, bool)>(
+        &self,
+        pane: &Rc<dyn Pane>,
+        mut func: F,
+    ) {
         let rectangular = self.selection(pane.pane_id()).rectangular;
         if let Some(sel) = self
             .selection(pane.pane_id())
@@ -24,9 +56,7 @@ impl super::TermWindow {
             let last_row = sel.rows().end;
 
             for line in pane.get_logical_lines(sel.rows()) {
-                if !s.is_empty() && !last_was_wrapped {
-                    s.push('\n');
-                }
+                let mut newline = !last_was_wrapped;
                 let last_idx = line.physical_lines.len().saturating_sub(1);
                 for (idx, phys) in line.physical_lines.iter().enumerate() {
                     let this_row = line.first_row + idx as StableRowIndex;
@@ -34,14 +64,8 @@ impl super::TermWindow {
                         let last_phys_idx = phys.cells().len().saturating_sub(1);
                         let cols = sel.cols_for_row(this_row, rectangular);
                         let last_col_idx = cols.end.saturating_sub(1).min(last_phys_idx);
-                        let col_span = phys.columns_as_str(cols);
-                        // Only trim trailing whitespace if we are the last line
-                        // in a wrapped sequence
-                        if idx == last_idx {
-                            s.push_str(col_span.trim_end());
-                        } else {
-                            s.push_str(&col_span);
-                        }
+                        func(phys, cols, newline, idx == last_idx);
+                        newline = false;
 
                         last_was_wrapped = last_col_idx == last_phys_idx
                             && phys
@@ -53,10 +77,102 @@ impl super::TermWindow {
                 }
             }
         }
+    }
 
+    pub fn selection_text(&self, pane: &Rc<dyn Pane>) -> String {
+        let mut s = String::new();
+        let copy_concealed = self.config.copy_concealed_text;
+        self.for_each_selected_line(pane, |phys, cols, newline, last| {
+            if newline && !s.is_empty() {
+                s.push('\n');
+            }
+            let col_span = if copy_concealed {
+                phys.columns_as_str(cols)
+            } else {
+                columns_as_str_without_concealed(phys, cols)
+            };
+            // Only trim trailing whitespace if we are the last line
+            // in a wrapped sequence
+            if last {
+                s.push_str(col_span.trim_end());
+            } else {
+                s.push_str(&col_span);
+            }
+        });
         s
     }
 
+    /// Returns the distinct hyperlink URIs that are present in the
+    /// selection, in the order that they appear
+    pub fn selection_hyperlinks(&self, pane: &Rc<dyn Pane>) -> Vec<String> {
+        let mut uris: Vec<String> = vec![];
+        self.for_each_selected_line(pane, |phys, cols, _newline, _last| {
+            for (_idx, cell) in phys
+                .visible_cells()
+                .filter(|(idx, _cell)| cols.contains(idx))
+            {
+                if let Some(link) = cell.attrs().hyperlink() {
+                    if !uris.iter().any(|uri| uri == link.uri()) {
+                        uris.push(link.uri().to_string());
+                    }
+                }
+            }
+        });
+        uris
+    }
+
+    /// Like `selection_text`, but includes the escape sequences needed
+    /// to reproduce the colors, styling and hyperlinks of the selected text
+    pub fn selection_escapes(&self, pane: &Rc<dyn Pane>) -> anyhow::Result<String> {
+        let copy_concealed = self.config.copy_concealed_text;
+        let mut changes = vec![];
+        let mut current_attr = CellAttributes::default();
+        self.for_each_selected_line(pane, |phys, cols, newline, last| {
+            if newline && !changes.is_empty() {
+                changes.push(Change::Text("\n".to_string()));
+            }
+            let mut cells: Vec<_> = phys
+                .visible_cells()
+                .filter(|(idx, _cell)| cols.contains(idx))
+                .map(|(_idx, cell)| cell)
+                .collect();
+            // Only trim trailing whitespace if we are the last line
+            // in a wrapped sequence
+            if last {
+                while cells.last().map(|c| c.str().trim().is_empty()) == Some(true) {
+                    cells.pop();
+                }
+            }
+            for cell in cells {
+                if *cell.attrs() != current_attr {
+                    current_attr = cell.attrs().clone();
+                    changes.push(Change::AllAttributes(current_attr.clone()));
+                }
+                if cell.attrs().invisible() && !copy_concealed {
+                    changes.push(Change::Text(" ".repeat(cell.width())));
+                } else {
+                    changes.push(Change::Text(cell.str().to_string()));
+                }
+            }
+        });
+        termwiz_funcs::changes_as_escapes(changes)
+    }
+
+    /// Like `selection_text`, but produces HTML that preserves
+    /// the colors, styling and hyperlinks of the selected text
+    pub fn selection_html(&self, pane: &Rc<dyn Pane>) -> String {
+        let palette = pane.palette();
+        let mut html =
+            HtmlBuilder::new(&palette).include_concealed(self.config.copy_concealed_text);
+        self.for_each_selected_line(pane, |phys, cols, newline, last| {
+            if newline && !html.is_empty() {
+                html.push_newline();
+            }
+            html.push_line(phys, cols, last);
+        });
+        html.finish()
+    }
+
     pub fn clear_selection(&mut self, pane: &Rc<dyn Pane>) {
         let mut selection = self.selection(pane.pane_id());
         selection.clear();
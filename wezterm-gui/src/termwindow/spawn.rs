@@ -19,7 +19,7 @@ pub enum SpawnWhere {
 impl super::TermWindow {
     pub fn spawn_command(&self, spawn: &SpawnCommand, spawn_where: SpawnWhere) {
         let size = if spawn_where == SpawnWhere::NewWindow {
-            self.config.initial_size()
+            crate::windowsize::initial_size(&self.config)
         } else {
             self.terminal_size
         };
@@ -4,6 +4,7 @@ use ::window::bitmaps::{BitmapImage, Image, Texture2d};
 use ::window::color::SrgbaPixel;
 use ::window::{Point, Rect, Size};
 use anyhow::Context;
+use config::DimensionContext;
 use std::rc::Rc;
 use wezterm_font::units::*;
 use wezterm_font::{FontConfiguration, FontMetrics};
@@ -15,7 +16,11 @@ pub struct RenderMetrics {
     pub descender_plus_two: IntPixelLength,
     pub underline_height: IntPixelLength,
     pub strike_row: IntPixelLength,
+    pub overline_row: IntPixelLength,
     pub cell_size: Size,
+    /// The configured thickness of the bar, underline and outline
+    /// cursors, overriding the thickness derived from underline_height
+    pub cursor_thickness: Option<IntPixelLength>,
 }
 
 impl RenderMetrics {
@@ -31,15 +36,28 @@ impl RenderMetrics {
             (cell_height as f64 + (metrics.descender - metrics.underline_position).get()) as isize;
         let descender_plus_two =
             (2 * underline_height + descender_row).min(cell_height as isize - underline_height);
-        let strike_row = descender_row / 2;
+
+        let baseline_row = cell_height as f64 + metrics.descender.get();
+        let strike_row = match metrics.strikethrough_position {
+            Some(position) => decoration_row(baseline_row, position, underline_height, cell_height),
+            None => descender_row / 2,
+        };
+        let overline_row = decoration_row(
+            baseline_row,
+            metrics.ascender,
+            underline_height,
+            cell_height,
+        );
 
         Self {
             descender: metrics.descender,
             descender_row,
             descender_plus_two,
             strike_row,
+            overline_row,
             cell_size: Size::new(cell_width as isize, cell_height as isize),
             underline_height,
+            cursor_thickness: None,
         }
     }
 
@@ -57,7 +75,9 @@ impl RenderMetrics {
             descender_plus_two: self.descender_plus_two - adjust,
             underline_height: self.underline_height,
             strike_row: self.strike_row,
+            overline_row: self.overline_row,
             cell_size: size,
+            cursor_thickness: self.cursor_thickness,
         }
     }
 
@@ -72,8 +92,9 @@ impl RenderMetrics {
             .default_font_metrics()
             .context("failed to get font metrics!?")?;
 
-        let line_height = fonts.config().line_height;
-        let cell_width = fonts.config().cell_width;
+        let config = fonts.config();
+        let line_height = config.line_height;
+        let cell_width = config.cell_width;
 
         let (cell_height, cell_width) = (
             (metrics.cell_height.get() * line_height).ceil() as usize,
@@ -84,26 +105,79 @@ impl RenderMetrics {
         // such that we are horizontally centered.
         let line_height_y_adjust = (cell_height as f64 - metrics.cell_height.get().ceil()) / 2.;
 
-        let underline_height = metrics.underline_thickness.get().round().max(1.) as isize;
-
-        let descender_row = (cell_height as f64
-            + (metrics.descender - metrics.underline_position).get()
+        // The decoration overrides may be expressed relative to the cell height
+        let context = DimensionContext {
+            dpi: fonts.get_dpi() as f32,
+            pixel_max: cell_height as f32,
+            pixel_cell: cell_height as f32,
+        };
+
+        let underline_height = match config.underline_thickness {
+            Some(thickness) => thickness.evaluate_as_pixels(context).max(1.) as isize,
+            None => metrics.underline_thickness.get().round().max(1.) as isize,
+        };
+        let underline_position = match config.underline_position {
+            Some(position) => PixelLength::new(position.evaluate_as_pixels(context) as f64),
+            None => metrics.underline_position,
+        };
+        let cursor_thickness = config
+            .cursor_thickness
+            .map(|thickness| thickness.evaluate_as_pixels(context).max(1.) as isize);
+
+        let descender_row = (cell_height as f64 + (metrics.descender - underline_position).get()
             - line_height_y_adjust) as isize;
         let descender_plus_two =
             (2 * underline_height + descender_row).min(cell_height as isize - underline_height);
-        let strike_row = descender_row / 2;
+
+        // Prefer the positions specified by the config, then by the font,
+        // over fixed fractions of the cell
+        let baseline_row = cell_height as f64 + metrics.descender.get() - line_height_y_adjust;
+        let strikethrough_position = match config.strikethrough_position {
+            Some(position) => Some(PixelLength::new(position.evaluate_as_pixels(context) as f64)),
+            None => metrics.strikethrough_position,
+        };
+        let strike_row = match strikethrough_position {
+            Some(position) => decoration_row(baseline_row, position, underline_height, cell_height),
+            None => descender_row / 2,
+        };
+        let overline_position = match config.overline_position {
+            Some(position) => PixelLength::new(position.evaluate_as_pixels(context) as f64),
+            None => metrics.ascender,
+        };
+        let overline_row = decoration_row(
+            baseline_row,
+            overline_position,
+            underline_height,
+            cell_height,
+        );
 
         Ok(Self {
             descender: metrics.descender - PixelLength::new(line_height_y_adjust),
             descender_row,
             descender_plus_two,
             strike_row,
+            overline_row,
             cell_size: Size::new(cell_width as isize, cell_height as isize),
             underline_height,
+            cursor_thickness,
         })
     }
 }
 
+/// Computes the row at which to draw the top of a decoration line that
+/// is positioned `position` above the baseline, keeping the line inside
+/// the cell.
+fn decoration_row(
+    baseline_row: f64,
+    position: PixelLength,
+    line_height: IntPixelLength,
+    cell_height: usize,
+) -> IntPixelLength {
+    ((baseline_row - position.get()) as isize)
+        .min(cell_height as isize - line_height)
+        .max(0)
+}
+
 pub struct UtilSprites<T: Texture2d> {
     pub white_space: Sprite<T>,
     pub filled_box: Sprite<T>,
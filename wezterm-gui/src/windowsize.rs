@@ -0,0 +1,125 @@
+//! Remembers the size and position of the most recently resized or
+//! moved terminal window, so that new windows can be created at that
+//! size rather than always using initial_cols and initial_rows, and
+//! so that the first window can be placed where it was last time.
+//! A separate placement is remembered for each arrangement of displays.
+use ::window::{Connection, ConnectionOps, ScreenPoint};
+use config::ConfigHandle;
+use portable_pty::PtySize;
+use serde::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+struct SavedWindowPlacement {
+    cols: u16,
+    rows: u16,
+    #[serde(default)]
+    position: Option<(isize, isize)>,
+}
+
+/// The saved placements, keyed by the display configuration
+type SavedPlacements = HashMap<String, SavedWindowPlacement>;
+
+lazy_static::lazy_static! {
+    static ref SAVED: Mutex<SavedPlacements> = Mutex::new(load_saved_placements());
+}
+
+/// Set once the first window has consumed the saved position
+static POSITION_USED: AtomicBool = AtomicBool::new(false);
+
+fn saved_placement_file_name() -> PathBuf {
+    config::RUNTIME_DIR.join("window_placement.json")
+}
+
+fn load_saved_placements() -> SavedPlacements {
+    std::fs::read(saved_placement_file_name())
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Identifies the current arrangement of displays
+fn display_configuration() -> String {
+    Connection::get()
+        .and_then(|conn| conn.display_configuration())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+fn saved_placement() -> Option<SavedWindowPlacement> {
+    SAVED.lock().unwrap().get(&display_configuration()).copied()
+}
+
+/// Applies `update` to the saved placement for the current display
+/// configuration, and persists it if that changed it
+fn update_saved_placement<F: FnOnce(&mut SavedWindowPlacement)>(update: F) {
+    let key = display_configuration();
+    let mut saved = SAVED.lock().unwrap();
+    let mut placement = saved.get(&key).copied().unwrap_or_default();
+    update(&mut placement);
+    if saved.get(&key) == Some(&placement) {
+        return;
+    }
+    saved.insert(key, placement);
+
+    let result = serde_json::to_vec(&*saved)
+        .map_err(anyhow::Error::from)
+        .and_then(|data| Ok(std::fs::write(saved_placement_file_name(), data)?));
+    if let Err(err) = result {
+        log::warn!("Failed to save window placement: {:#}", err);
+    }
+}
+
+/// Returns the size to use for the first tab in a new window.
+/// This is the last remembered window size if remember_window_placement
+/// is enabled and one has been saved, otherwise the size computed
+/// from initial_cols and initial_rows.
+pub fn initial_size(config: &ConfigHandle) -> PtySize {
+    let size = config.initial_size();
+    if !config.remember_window_placement {
+        return size;
+    }
+    match saved_placement() {
+        Some(saved) if saved.cols > 0 && saved.rows > 0 => PtySize {
+            cols: saved.cols,
+            rows: saved.rows,
+            ..size
+        },
+        _ => size,
+    }
+}
+
+/// Returns the remembered position for the first window that is
+/// created by this process.  Subsequent windows are placed by the
+/// window system, so that they don't stack on top of each other.
+pub fn initial_position(config: &ConfigHandle) -> Option<ScreenPoint> {
+    if !config.remember_window_placement || POSITION_USED.swap(true, Ordering::SeqCst) {
+        return None;
+    }
+    let (x, y) = saved_placement()?.position?;
+    Some(ScreenPoint::new(x, y))
+}
+
+/// Record the size of a window so that it can be used by initial_size
+pub fn save_size(config: &ConfigHandle, size: &PtySize) {
+    if !config.remember_window_placement || size.cols == 0 || size.rows == 0 {
+        return;
+    }
+    update_saved_placement(|placement| {
+        placement.cols = size.cols;
+        placement.rows = size.rows;
+    });
+}
+
+/// Record the position of a window so that it can be used by
+/// initial_position
+pub fn save_position(config: &ConfigHandle, position: ScreenPoint) {
+    if !config.remember_window_placement {
+        return;
+    }
+    update_saved_placement(|placement| {
+        placement.position.replace((position.x, position.y));
+    });
+}
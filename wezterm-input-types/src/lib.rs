@@ -11,6 +11,7 @@ pub struct ScreenPixelUnit;
 pub type Point = euclid::Point2D<isize, PixelUnit>;
 pub type PointF = euclid::Point2D<f32, PixelUnit>;
 pub type ScreenPoint = euclid::Point2D<isize, ScreenPixelUnit>;
+pub type ScreenRect = euclid::Rect<isize, ScreenPixelUnit>;
 
 /// Which key is pressed.  Not all of these are probable to appear
 /// on most systems.  A lot of this list is @wez trawling docs and
@@ -1024,6 +1025,10 @@ pub enum MouseEventKind {
     Release(MousePress),
     VertWheel(i16),
     HorzWheel(i16),
+    /// A vertical scroll measured in pixels, from devices such as
+    /// touchpads that report precise deltas.  Positive values scroll
+    /// up, in the same direction as a positive VertWheel.
+    VertPixelScroll(i16),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
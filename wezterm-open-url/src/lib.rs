@@ -0,0 +1,74 @@
+//! Opens URLs and filesystem paths with the application that the
+//! system associates with them, or with the `open_command` from the
+//! configuration when that has been set.
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+mod macos;
+mod unix;
+mod windows;
+
+#[cfg(target_os = "macos")]
+use macos as backend;
+#[cfg(all(not(target_os = "macos"), not(windows)))]
+use unix as backend;
+#[cfg(windows)]
+use windows as backend;
+
+/// Open `url` using the configured `open_command`, or the system
+/// default handler if none is configured.
+/// The work happens on a background thread so that the caller
+/// isn't blocked while the handler is launched.
+pub fn open_url(url: &str) {
+    open_target(url.into());
+}
+
+/// Open `path` using the configured `open_command`, or the system
+/// default handler if none is configured.
+pub fn open_path(path: &Path) {
+    open_target(path.as_os_str().to_owned());
+}
+
+/// Open `url` using the specified application, bypassing the
+/// configured `open_command`.
+pub fn open_with(url: &str, app: &str) {
+    let url: OsString = url.into();
+    let app = app.to_string();
+    std::thread::spawn(move || {
+        if let Err(err) = backend::open_with(&url, &app) {
+            log::error!("Failed to open {:?} with {}: {}", url, app, err);
+        }
+    });
+}
+
+fn open_target(target: OsString) {
+    let command = config::configuration().open_command.clone();
+    std::thread::spawn(move || {
+        let result = match &command {
+            Some(argv) if !argv.is_empty() => run_command(argv, &target),
+            _ => backend::open(&target),
+        };
+        if let Err(err) = result {
+            log::error!("Failed to open {:?}: {}", target, err);
+        }
+    });
+}
+
+/// Run `argv` with `target` appended to its arguments, waiting
+/// for it to complete
+fn run_command<S: AsRef<OsStr>>(argv: &[S], target: &OsStr) -> std::io::Result<()> {
+    let status = Command::new(&argv[0])
+        .args(&argv[1..])
+        .arg(target)
+        .stdin(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("{:?} {}", argv[0].as_ref(), status),
+        ))
+    }
+}
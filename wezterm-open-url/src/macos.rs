@@ -0,0 +1,12 @@
+#![cfg(target_os = "macos")]
+
+use crate::run_command;
+use std::ffi::OsStr;
+
+pub fn open(target: &OsStr) -> std::io::Result<()> {
+    run_command(&["/usr/bin/open"], target)
+}
+
+pub fn open_with(target: &OsStr, app: &str) -> std::io::Result<()> {
+    run_command(&["/usr/bin/open", "-a", app], target)
+}
@@ -0,0 +1,39 @@
+#![cfg(all(not(target_os = "macos"), not(windows)))]
+
+use crate::run_command;
+use std::ffi::OsStr;
+use std::io::{Error, ErrorKind};
+
+/// The helpers that we know how to use, in order of preference.
+/// The first one that is installed is used.
+const OPENERS: &[&[&str]] = &[
+    &["xdg-open"],
+    &["gio", "open"],
+    &["gnome-open"],
+    &["kde-open"],
+    &["wslview"],
+];
+
+pub fn open(target: &OsStr) -> std::io::Result<()> {
+    for opener in OPENERS {
+        match run_command(opener, target) {
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            result => return result,
+        }
+    }
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!(
+            "none of {} are installed; consider setting open_command",
+            OPENERS
+                .iter()
+                .map(|opener| opener[0])
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    ))
+}
+
+pub fn open_with(target: &OsStr, app: &str) -> std::io::Result<()> {
+    run_command(&[app], target)
+}
@@ -0,0 +1,48 @@
+#![cfg(windows)]
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::ptr::null_mut;
+use winapi::um::shellapi::ShellExecuteW;
+use winapi::um::winuser::SW_SHOWNORMAL;
+
+/// Convert a rust string to a windows wide string
+fn wide_string(s: &OsStr) -> Vec<u16> {
+    s.encode_wide().chain(std::iter::once(0)).collect()
+}
+
+/// ShellExecuteW reports success by returning a value greater than 32
+fn shell_execute(file: &OsStr, params: Option<&OsStr>) -> std::io::Result<()> {
+    let verb = wide_string(OsStr::new("open"));
+    let file = wide_string(file);
+    let params = params.map(wide_string);
+    let result = unsafe {
+        ShellExecuteW(
+            null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            params
+                .as_ref()
+                .map(|p| p.as_ptr())
+                .unwrap_or(std::ptr::null()),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    if result as usize > 32 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+pub fn open(target: &OsStr) -> std::io::Result<()> {
+    shell_execute(target, None)
+}
+
+pub fn open_with(target: &OsStr, app: &str) -> std::io::Result<()> {
+    let mut quoted = std::ffi::OsString::from("\"");
+    quoted.push(target);
+    quoted.push("\"");
+    shell_execute(OsStr::new(app), Some(&quoted))
+}
@@ -711,11 +711,225 @@ impl Config {
     }
 }
 
+/// A command that is spawned to establish the connection to a host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProxyCommand {
+    /// An explicit `ProxyCommand`, which is run via the shell
+    Shell(String),
+    /// A `ProxyJump` translated into an `ssh -W` invocation.
+    /// This is spawned directly rather than via the shell, so the
+    /// values taken from the config need no quoting.
+    Argv(Vec<String>),
+}
+
+impl ProxyCommand {
+    /// Returns the command in the form expected by `/bin/sh -c`,
+    /// which is how libssh runs its ProxyCommand.
+    pub fn to_shell_command(&self) -> String {
+        match self {
+            Self::Shell(cmd) => cmd.to_string(),
+            Self::Argv(args) => shell_words::join(args),
+        }
+    }
+
+    /// Returns a `Command` that will run the proxy.
+    /// A `Shell` command is run via `sh` on unix and `%COMSPEC%`
+    /// on Windows.
+    pub fn to_command(&self) -> std::process::Command {
+        match self {
+            Self::Shell(proxy_command) => {
+                let mut cmd;
+                if cfg!(windows) {
+                    let comspec = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd".to_string());
+                    cmd = std::process::Command::new(comspec);
+                    cmd.args(&["/c", proxy_command.as_str()]);
+                } else {
+                    cmd = std::process::Command::new("sh");
+                    cmd.args(&["-c", &format!("exec {}", proxy_command)]);
+                }
+                cmd
+            }
+            Self::Argv(args) => {
+                let mut cmd = std::process::Command::new(&args[0]);
+                cmd.args(&args[1..]);
+                cmd
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ProxyCommand {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Shell(cmd) => write!(fmt, "{}", cmd),
+            Self::Argv(args) => write!(fmt, "{}", shell_words::join(args)),
+        }
+    }
+}
+
+/// Returns the command that should be used to establish the connection
+/// to the host described by `config`, if any.
+/// An explicit `ProxyCommand` takes precedence, otherwise a `ProxyJump`
+/// is translated into the equivalent `ssh -W` invocation.
+pub(crate) fn resolve_proxy_command(config: &ConfigMap) -> Option<ProxyCommand> {
+    fn is_set(value: &str) -> bool {
+        !value.is_empty() && value != "none"
+    }
+
+    if let Some(cmd) = config.get("proxycommand") {
+        if is_set(cmd) {
+            return Some(ProxyCommand::Shell(cmd.to_string()));
+        }
+        return None;
+    }
+
+    let jump = config.get("proxyjump").filter(|jump| is_set(jump))?;
+    let hostname = config.get("hostname")?;
+    let port = config.get("port").map(|p| p.as_str()).unwrap_or("22");
+    proxy_jump_to_command(jump, hostname, port).map(ProxyCommand::Argv)
+}
+
+/// Translate a comma separated list of `[user@]host[:port]` or
+/// `ssh://[user@]host[:port]` jump hosts into an `ssh` command that
+/// connects through all of them to `hostname:port`.
+/// The jump hosts are resolved by `ssh`, so aliases defined in
+/// `~/.ssh/config` can be used.
+fn proxy_jump_to_command(jump: &str, hostname: &str, port: &str) -> Option<Vec<String>> {
+    let hops: Vec<&str> = jump
+        .split(',')
+        .map(|hop| hop.trim())
+        .filter(|hop| !hop.is_empty())
+        .collect();
+    let (last, prior) = hops.split_last()?;
+
+    let spec = last.strip_prefix("ssh://").unwrap_or(last);
+    let (user, host_port) = match spec.rfind('@') {
+        Some(idx) => (Some(&spec[..idx]), &spec[idx + 1..]),
+        None => (None, spec),
+    };
+    let (host, jump_port) = if let Some(rest) = host_port.strip_prefix('[') {
+        let end = rest.find(']')?;
+        let port = rest[end + 1..].strip_prefix(':');
+        (&rest[..end], port)
+    } else {
+        match host_port.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        }
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    let target = if hostname.contains(':') {
+        format!("[{}]:{}", hostname, port)
+    } else {
+        format!("{}:{}", hostname, port)
+    };
+
+    let mut args = vec!["ssh".to_string()];
+    if let Some(user) = user {
+        args.push("-l".to_string());
+        args.push(user.to_string());
+    }
+    if let Some(port) = jump_port {
+        args.push("-p".to_string());
+        args.push(port.to_string());
+    }
+    if !prior.is_empty() {
+        args.push("-J".to_string());
+        args.push(prior.join(","));
+    }
+    args.push("-W".to_string());
+    args.push(target);
+    args.push(host.to_string());
+
+    Some(args)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use k9::snapshot;
 
+    #[test]
+    fn proxy_jump() {
+        let mut config = Config::new();
+
+        let mut fake_env = ConfigMap::new();
+        fake_env.insert("HOME".to_string(), "/home/me".to_string());
+        fake_env.insert("USER".to_string(), "me".to_string());
+        config.assign_environment(fake_env);
+
+        config.add_config_string(
+            r#"
+        Host inner
+            HostName 10.0.0.2
+            ProxyJump bastion
+
+        Host deep
+            HostName 10.0.0.3
+            ProxyJump ssh://admin@gw1:2222,me@bastion:2200
+
+        Host direct
+            ProxyCommand none
+            ProxyJump bastion
+            "#,
+        );
+
+        let shell_command =
+            |opts: &ConfigMap| resolve_proxy_command(opts).map(|cmd| cmd.to_shell_command());
+
+        let opts = config.for_host("inner");
+        assert_eq!(
+            shell_command(&opts),
+            Some("ssh -W 10.0.0.2:22 bastion".to_string())
+        );
+
+        let opts = config.for_host("deep");
+        assert_eq!(
+            shell_command(&opts),
+            Some("ssh -l me -p 2200 -J ssh://admin@gw1:2222 -W 10.0.0.3:22 bastion".to_string())
+        );
+
+        let opts = config.for_host("direct");
+        assert_eq!(resolve_proxy_command(&opts), None);
+
+        assert_eq!(
+            proxy_jump_to_command("[::1]:2022", "::2", "22"),
+            Some(vec![
+                "ssh".to_string(),
+                "-p".to_string(),
+                "2022".to_string(),
+                "-W".to_string(),
+                "[::2]:22".to_string(),
+                "::1".to_string()
+            ])
+        );
+
+        // The argv is spawned directly, so shell metacharacters are
+        // passed through untouched; they are quoted only when the
+        // command has to be run via the shell
+        let cmd = ProxyCommand::Argv(
+            proxy_jump_to_command("me;reboot@bastion", "10.0.0.2", "22 $(id)").unwrap(),
+        );
+        assert_eq!(
+            cmd,
+            ProxyCommand::Argv(vec![
+                "ssh".to_string(),
+                "-l".to_string(),
+                "me;reboot".to_string(),
+                "-W".to_string(),
+                "10.0.0.2:22 $(id)".to_string(),
+                "bastion".to_string()
+            ])
+        );
+        assert_eq!(
+            cmd.to_shell_command(),
+            "ssh -l 'me;reboot' -W '10.0.0.2:22 $(id)' bastion"
+        );
+    }
+
     #[test]
     fn parse_user() {
         let mut config = Config::new();
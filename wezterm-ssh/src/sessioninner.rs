@@ -1,5 +1,5 @@
 use crate::channelwrap::ChannelWrap;
-use crate::config::ConfigMap;
+use crate::config::{resolve_proxy_command, ConfigMap};
 use crate::dirwrap::DirWrap;
 use crate::filewrap::FileWrap;
 use crate::pty::*;
@@ -189,8 +189,10 @@ impl SessionInner {
                 break;
             }
         }
-        if let Some(cmd) = self.config.get("proxycommand") {
-            sess.set_option(libssh_rs::SshOption::ProxyCommand(Some(cmd.to_string())))?;
+        if let Some(cmd) = resolve_proxy_command(&self.config) {
+            sess.set_option(libssh_rs::SshOption::ProxyCommand(Some(
+                cmd.to_shell_command(),
+            )))?;
         }
         if let Some(types) = self.config.get("pubkeyacceptedtypes") {
             sess.set_option(libssh_rs::SshOption::PublicKeyAcceptedTypes(
@@ -254,23 +256,8 @@ impl SessionInner {
             ))))
             .context("notifying user of banner")?;
 
-        let sock: Socket = if let Some(proxy_command) =
-            self.config.get("proxycommand").and_then(|c| {
-                if !c.is_empty() && c != "none" {
-                    Some(c)
-                } else {
-                    None
-                }
-            }) {
-            let mut cmd;
-            if cfg!(windows) {
-                let comspec = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd".to_string());
-                cmd = std::process::Command::new(comspec);
-                cmd.args(&["/c", proxy_command]);
-            } else {
-                cmd = std::process::Command::new("sh");
-                cmd.args(&["-c", &format!("exec {}", proxy_command)]);
-            }
+        let sock: Socket = if let Some(proxy_command) = resolve_proxy_command(&self.config) {
+            let mut cmd = proxy_command.to_command();
 
             let (a, b) = socketpair()?;
 
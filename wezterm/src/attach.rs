@@ -0,0 +1,452 @@
+use anyhow::{anyhow, Context};
+use clap::Parser;
+use codec::{
+    GetPaneRenderChanges, GetPaneRenderChangesResponse, InputSerial, PaneRemoved, Pdu, Resize,
+    SendKeyDown, SendPaste,
+};
+use mux::pane::PaneId;
+use mux::renderable::{RenderableDimensions, StableCursorPosition};
+use mux::tab::TabId;
+use portable_pty::PtySize;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+use termwiz::caps::{Capabilities, SecondaryDeviceAttributes};
+use termwiz::cell::{unicode_column_width, CellAttributes};
+use termwiz::input::{InputEvent, KeyCode, KeyEvent, Modifiers};
+use termwiz::surface::{Change, CursorVisibility, Position};
+use termwiz::terminal::buffered::BufferedTerminal;
+use termwiz::terminal::{new_terminal, Terminal};
+use wezterm_client::client::Client;
+use wezterm_term::Line;
+
+/// The key that, when pressed with CTRL, introduces a command
+/// to the attach client itself rather than to the remote pane
+const PREFIX_KEY: char = 'b';
+
+#[derive(Debug, Parser, Clone)]
+pub struct AttachCommand {
+    /// Render the session using a text UI inside the terminal
+    /// in which this command is running, rather than in a
+    /// wezterm GUI window
+    #[clap(long = "tui")]
+    tui: bool,
+
+    /// Don't automatically start the server
+    #[clap(long = "no-auto-start")]
+    no_auto_start: bool,
+
+    /// Prefer connecting to a background mux server.
+    /// The default is to prefer connecting to a running
+    /// wezterm gui instance
+    #[clap(long = "prefer-mux")]
+    prefer_mux: bool,
+
+    /// When connecting to a gui instance, if you started the
+    /// gui with `--class SOMETHING`, you should also pass
+    /// that same value here in order for the client to find
+    /// the correct gui instance.
+    #[clap(long = "class")]
+    class: Option<String>,
+
+    /// Specify the pane to attach to.
+    /// The default is to use the current pane based on the
+    /// environment variable WEZTERM_PANE, or the most recently
+    /// focused pane.
+    #[clap(long = "pane-id")]
+    pane_id: Option<PaneId>,
+}
+
+impl AttachCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        if !self.tui {
+            anyhow::bail!(
+                "only `wezterm attach --tui` is currently supported; \
+                 use `wezterm connect` to attach using a GUI window"
+            );
+        }
+
+        let executor = promise::spawn::ScopedExecutor::new();
+        promise::spawn::block_on(executor.run(async move { self.run_async().await }))
+    }
+
+    async fn run_async(&self) -> anyhow::Result<()> {
+        let mut ui = mux::connui::ConnectionUI::new_headless();
+        let initial = true;
+
+        let client = Client::new_default_unix_domain(
+            initial,
+            &mut ui,
+            self.no_auto_start,
+            self.prefer_mux,
+            self.class
+                .as_deref()
+                .unwrap_or(wezterm_gui_subcommands::DEFAULT_WINDOW_CLASS),
+        )?;
+        client.verify_version_compat(&ui).await?;
+
+        if self.pane_id.is_none() && std::env::var_os("WEZTERM_PANE").is_some() {
+            // resolve_pane_id would pick the pane that we're running in,
+            // which would just echo our own output back to us
+            anyhow::bail!(
+                "this terminal is already a wezterm pane; \
+                 use --pane-id to specify which pane to attach to"
+            );
+        }
+        let pane_id = crate::resolve_pane_id(&client, self.pane_id).await?;
+        let tab_id = resolve_tab_id(&client, pane_id).await?;
+
+        let unilateral = client.subscribe_unilateral().await?;
+
+        let mut caps = Capabilities::new_from_env()?;
+        match probe_secondary_device_attributes() {
+            Ok(Some(da)) => {
+                log::debug!("outer terminal identified itself as {:?}", da);
+                caps = caps.apply_secondary_device_attributes(&da);
+            }
+            Ok(None) => {}
+            Err(err) => log::debug!("failed to probe the outer terminal: {:#}", err),
+        }
+        let mut terminal = new_terminal(caps)?;
+        terminal.set_raw_mode()?;
+        terminal.enter_alternate_screen()?;
+        let mut buf = BufferedTerminal::new(terminal)?;
+
+        // Relay the render changes from the client thread so that
+        // they can wake us up while we're waiting for input
+        let (tx, rx) = channel();
+        let waker = buf.terminal().waker();
+        std::thread::spawn(move || {
+            while let Ok(pdu) = smol::block_on(unilateral.recv()) {
+                if tx.send(pdu).is_err() {
+                    break;
+                }
+                waker.wake().ok();
+            }
+        });
+
+        let mut session = AttachSession {
+            client,
+            pane_id,
+            tab_id,
+            rx,
+            dims: None,
+            cursor: StableCursorPosition::default(),
+            title: String::new(),
+            lines: vec![],
+            prefix_armed: false,
+        };
+
+        let result = session.run(&mut buf).await;
+
+        buf.add_change(Change::AllAttributes(CellAttributes::default()));
+        buf.add_change(Change::CursorVisibility(CursorVisibility::Visible));
+        buf.add_change(Change::ClearScreen(Default::default()));
+        buf.flush().ok();
+        buf.terminal().exit_alternate_screen().ok();
+        buf.terminal().set_cooked_mode().ok();
+
+        match result? {
+            Some(reason) => println!("{}", reason),
+            None => println!("[detached from pane {}]", pane_id),
+        }
+        Ok(())
+    }
+}
+
+/// Ask the terminal that we're running in to identify itself via the
+/// secondary device attributes query, so that we don't emit colors and
+/// attributes that an older terminal (or a terminal multiplexer sitting
+/// between us and it) can't render.
+/// The query is followed by a primary device attributes query: that is
+/// answered by practically every terminal, so its response tells us that
+/// there is no point waiting any longer for the secondary response.
+#[cfg(unix)]
+fn probe_secondary_device_attributes() -> anyhow::Result<Option<SecondaryDeviceAttributes>> {
+    use filedescriptor::{poll, pollfd, FileDescriptor, POLLIN};
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+    use termios::{cfmakeraw, tcsetattr, Termios, TCSAFLUSH, TCSANOW};
+
+    fn primary_response_complete(data: &[u8]) -> bool {
+        data.windows(3)
+            .position(|w| w == b"\x1b[?")
+            .map(|start| data[start..].contains(&b'c'))
+            .unwrap_or(false)
+    }
+
+    let mut tty = FileDescriptor::new(
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")?,
+    );
+    let fd = tty.as_raw_fd();
+    let saved = Termios::from_fd(fd).context("get_termios failed")?;
+    let mut raw = saved;
+    cfmakeraw(&mut raw);
+    tcsetattr(fd, TCSAFLUSH, &raw).context("set_termios failed")?;
+
+    let mut read_response = || -> anyhow::Result<Vec<u8>> {
+        tty.write_all(b"\x1b[>c\x1b[c")?;
+
+        let deadline = Instant::now() + Duration::from_millis(500);
+        let mut response = vec![];
+        let mut buf = [0u8; 256];
+        while !primary_response_complete(&response) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let mut pfd = [pollfd {
+                fd,
+                events: POLLIN,
+                revents: 0,
+            }];
+            if remaining == Duration::ZERO || poll(&mut pfd, Some(remaining))? == 0 {
+                break;
+            }
+            let len = tty.read(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            response.extend_from_slice(&buf[..len]);
+        }
+        Ok(response)
+    };
+    let response = read_response();
+
+    tcsetattr(fd, TCSANOW, &saved).context("set_termios failed")?;
+    Ok(SecondaryDeviceAttributes::parse(&response?))
+}
+
+#[cfg(not(unix))]
+fn probe_secondary_device_attributes() -> anyhow::Result<Option<SecondaryDeviceAttributes>> {
+    Ok(None)
+}
+
+async fn resolve_tab_id(client: &Client, pane_id: PaneId) -> anyhow::Result<TabId> {
+    let panes = client.list_panes().await?;
+
+    for tabroot in panes.tabs {
+        let mut cursor = tabroot.into_tree().cursor();
+
+        loop {
+            if let Some(entry) = cursor.leaf_mut() {
+                if entry.pane_id == pane_id {
+                    return Ok(entry.tab_id);
+                }
+            }
+            match cursor.preorder_next() {
+                Ok(c) => cursor = c,
+                Err(_) => break,
+            }
+        }
+    }
+
+    Err(anyhow!("pane {} was not found", pane_id))
+}
+
+struct AttachSession {
+    client: Client,
+    pane_id: PaneId,
+    tab_id: TabId,
+    rx: Receiver<Pdu>,
+    dims: Option<RenderableDimensions>,
+    cursor: StableCursorPosition,
+    title: String,
+    lines: Vec<Line>,
+    prefix_armed: bool,
+}
+
+impl AttachSession {
+    /// Runs until the user detaches, returning None, or until the
+    /// remote pane goes away, returning a description of why.
+    async fn run<T: Terminal>(
+        &mut self,
+        buf: &mut BufferedTerminal<T>,
+    ) -> anyhow::Result<Option<String>> {
+        self.resize_remote(buf).await?;
+        self.client
+            .get_pane_render_changes(GetPaneRenderChanges {
+                pane_id: self.pane_id,
+            })
+            .await
+            .context("requesting initial pane content")?;
+
+        loop {
+            while let Ok(pdu) = self.rx.try_recv() {
+                match pdu {
+                    Pdu::GetPaneRenderChangesResponse(changes)
+                        if changes.pane_id == self.pane_id =>
+                    {
+                        self.apply_changes(changes);
+                    }
+                    Pdu::PaneRemoved(PaneRemoved { pane_id }) if pane_id == self.pane_id => {
+                        return Ok(Some(format!("[pane {} was closed]", pane_id)));
+                    }
+                    _ => {}
+                }
+            }
+
+            self.render(buf);
+            buf.flush()?;
+
+            match buf.terminal().poll_input(Some(Duration::from_secs(1)))? {
+                Some(InputEvent::Resized { .. }) => {
+                    buf.check_for_resize()?;
+                    self.resize_remote(buf).await?;
+                }
+                Some(InputEvent::Key(key)) => {
+                    if !self.process_key(key).await? {
+                        return Ok(None);
+                    }
+                }
+                Some(InputEvent::Paste(data)) => {
+                    self.client
+                        .send_paste(SendPaste {
+                            pane_id: self.pane_id,
+                            data,
+                        })
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns false if the key requested that we detach
+    async fn process_key(&mut self, key: KeyEvent) -> anyhow::Result<bool> {
+        let is_prefix = key.key == KeyCode::Char(PREFIX_KEY) && key.modifiers == Modifiers::CTRL;
+
+        if !self.prefix_armed {
+            if is_prefix {
+                self.prefix_armed = true;
+                return Ok(true);
+            }
+            self.send_key(key).await?;
+            return Ok(true);
+        }
+
+        self.prefix_armed = false;
+        match key.key {
+            KeyCode::Char('d') if key.modifiers == Modifiers::NONE => Ok(false),
+            _ => {
+                // Pressing the prefix twice sends it through to the pane;
+                // anything else isn't bound, so pass along both keys
+                self.send_key(KeyEvent {
+                    key: KeyCode::Char(PREFIX_KEY),
+                    modifiers: Modifiers::CTRL,
+                })
+                .await?;
+                if !is_prefix {
+                    self.send_key(key).await?;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    async fn send_key(&self, event: KeyEvent) -> anyhow::Result<()> {
+        self.client
+            .key_down(SendKeyDown {
+                pane_id: self.pane_id,
+                event,
+                input_serial: InputSerial::now(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Size the remote pane to fit our screen, less the status line
+    async fn resize_remote<T: Terminal>(&self, buf: &BufferedTerminal<T>) -> anyhow::Result<()> {
+        let (cols, rows) = buf.dimensions();
+        self.client
+            .resize(Resize {
+                containing_tab_id: self.tab_id,
+                pane_id: self.pane_id,
+                size: PtySize {
+                    rows: rows.saturating_sub(1).max(1) as u16,
+                    cols: cols.max(1) as u16,
+                    pixel_width: 0,
+                    pixel_height: 0,
+                },
+            })
+            .await?;
+        Ok(())
+    }
+
+    fn apply_changes(&mut self, changes: GetPaneRenderChangesResponse) {
+        let dims = changes.dimensions;
+        self.lines
+            .resize_with(dims.viewport_rows, || Line::with_width(0, 0));
+
+        let (lines, _images) = changes.bonus_lines.extract_data();
+        for (stable_row, line) in lines {
+            let idx = stable_row - dims.physical_top;
+            if idx >= 0 && (idx as usize) < dims.viewport_rows {
+                self.lines[idx as usize] = line;
+            }
+        }
+
+        self.cursor = changes.cursor_position;
+        self.title = changes.title;
+        self.dims.replace(dims);
+    }
+
+    fn render<T: Terminal>(&self, buf: &mut BufferedTerminal<T>) {
+        let (cols, rows) = buf.dimensions();
+        let pane_rows = rows.saturating_sub(1);
+
+        buf.add_change(Change::AllAttributes(CellAttributes::default()));
+        buf.add_change(Change::ClearScreen(Default::default()));
+
+        for (row, line) in self.lines.iter().take(pane_rows).enumerate() {
+            let mut line = line.clone();
+            line.resize(cols, 0);
+            buf.add_change(Change::CursorPosition {
+                x: Position::Absolute(0),
+                y: Position::Absolute(row),
+            });
+            buf.add_change(Change::AllAttributes(CellAttributes::default()));
+            buf.add_changes(line.changes(&CellAttributes::default()));
+        }
+
+        let marker = if self.prefix_armed { "^B " } else { "" };
+        let mut status = format!(
+            " {}pane {}: {} | Ctrl-{} d to detach",
+            marker,
+            self.pane_id,
+            self.title,
+            PREFIX_KEY.to_ascii_uppercase()
+        );
+        let status_width = unicode_column_width(&status, None);
+        if status_width < cols {
+            status.push_str(&" ".repeat(cols - status_width));
+        }
+        let mut status_attr = CellAttributes::default();
+        status_attr.set_reverse(true);
+        let mut status = Line::from_text(&status, &status_attr, 0, None);
+        status.resize(cols, 0);
+
+        buf.add_change(Change::CursorPosition {
+            x: Position::Absolute(0),
+            y: Position::Absolute(pane_rows),
+        });
+        buf.add_change(Change::AllAttributes(CellAttributes::default()));
+        buf.add_changes(status.changes(&CellAttributes::default()));
+        buf.add_change(Change::AllAttributes(CellAttributes::default()));
+
+        let cursor_row = self
+            .dims
+            .map(|dims| self.cursor.y - dims.physical_top)
+            .unwrap_or(-1);
+        if cursor_row >= 0 && (cursor_row as usize) < pane_rows && self.cursor.x < cols {
+            buf.add_change(Change::CursorPosition {
+                x: Position::Absolute(self.cursor.x),
+                y: Position::Absolute(cursor_row as usize),
+            });
+            buf.add_change(Change::CursorShape(self.cursor.shape));
+            buf.add_change(Change::CursorVisibility(self.cursor.visibility));
+        } else {
+            buf.add_change(Change::CursorVisibility(CursorVisibility::Hidden));
+        }
+    }
+}
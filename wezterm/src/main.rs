@@ -20,6 +20,7 @@ use wezterm_client::client::{unix_connect_with_retry, Client};
 use wezterm_gui_subcommands::*;
 
 mod asciicast;
+mod attach;
 
 //    let message = "; ❤ 😍🤢\n\x1b[91;mw00t\n\x1b[37;104;m bleet\x1b[0;m.";
 
@@ -71,6 +72,12 @@ enum SubCommand {
     #[clap(name = "connect", about = "Connect to wezterm multiplexer")]
     Connect(ConnectCommand),
 
+    #[clap(
+        name = "attach",
+        about = "Attach to a pane in the wezterm multiplexer from the current terminal"
+    )]
+    Attach(attach::AttachCommand),
+
     #[clap(name = "ls-fonts", about = "Display information about fonts")]
     LsFonts(LsFontsCommand),
 
@@ -93,6 +100,12 @@ enum SubCommand {
     #[clap(name = "replay", about = "Replay an asciicast terminal session")]
     Replay(asciicast::PlayCommand),
 
+    #[clap(
+        name = "show-config",
+        about = "Print the effective configuration, including default values, in TOML format"
+    )]
+    ShowConfig,
+
     /// Generate shell completion information
     #[clap(name = "shell-completion")]
     ShellCompletion {
@@ -476,8 +489,14 @@ fn run() -> anyhow::Result<()> {
         SubCommand::ImageCat(cmd) => cmd.run(),
         SubCommand::SetCwd(cmd) => cmd.run(),
         SubCommand::Cli(cli) => run_cli(config, cli),
+        SubCommand::Attach(cmd) => cmd.run(),
         SubCommand::Record(cmd) => cmd.run(config),
         SubCommand::Replay(cmd) => cmd.run(),
+        SubCommand::ShowConfig => {
+            let config = config::configuration_result()?;
+            print!("{}", config.to_toml_string()?);
+            Ok(())
+        }
         SubCommand::ShellCompletion { shell } => {
             use clap::CommandFactory;
             let mut cmd = Opt::command();
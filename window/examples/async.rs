@@ -84,6 +84,7 @@ impl MyWindow {
             | WindowEvent::FocusChanged(_)
             | WindowEvent::DraggedFile(_)
             | WindowEvent::DroppedFile(_)
+            | WindowEvent::Moved(_)
             | WindowEvent::MouseLeave => {}
         }
     }
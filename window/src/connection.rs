@@ -41,6 +41,12 @@ pub trait ConnectionOps {
         Appearance::Light
     }
 
+    /// Returns true if the user has asked the system to minimize
+    /// non-essential motion, such as animations and blinking.
+    fn prefers_reduced_motion(&self) -> bool {
+        false
+    }
+
     /// Hide the application.
     /// This actions hides all of the windows of the application and switches
     /// focus away from it.
@@ -48,4 +54,12 @@ pub trait ConnectionOps {
 
     /// Perform the system beep/notification sound
     fn beep(&self) {}
+
+    /// Returns a string that describes the current arrangement of the
+    /// displays, so that state that depends on it, such as the placement
+    /// of windows, can be remembered separately for each arrangement.
+    /// Returns None if the arrangement is unknown.
+    fn display_configuration(&self) -> Option<String> {
+        None
+    }
 }
@@ -25,6 +25,18 @@ pub fn default_dpi() -> f64 {
     }
 }
 
+/// Formats a list of URIs as the body of a `text/uri-list`,
+/// which RFC 2483 specifies as CRLF terminated lines.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn uri_list_from_uris(uris: &[String]) -> String {
+    let mut list = String::new();
+    for uri in uris {
+        list.push_str(uri);
+        list.push_str("\r\n");
+    }
+    list
+}
+
 mod egl;
 
 pub use bitmaps::{BitmapImage, Image};
@@ -152,6 +164,13 @@ pub enum WindowEvent {
         live_resizing: bool,
     },
 
+    /// Called when the window has been moved.  The coordinates
+    /// are of the top left pixel of the client area, the same as
+    /// those accepted by WindowOps::set_window_position.
+    /// This is not sent by backends that don't allow windows to
+    /// know their position (Wayland).
+    Moved(ScreenPoint),
+
     /// Called when the window has been invalidated and needs to
     /// be repainted
     NeedRepaint,
@@ -282,6 +301,30 @@ pub trait WindowOps {
     /// Set some text in the clipboard
     fn set_clipboard(&self, clipboard: Clipboard, text: String);
 
+    /// Set some text in the clipboard, and also offer the list of
+    /// URIs as `text/uri-list` to applications that prefer it.
+    /// Systems that don't support this set just the text.
+    fn set_clipboard_with_uri_list(&self, clipboard: Clipboard, text: String, uris: Vec<String>) {
+        let _ = uris;
+        self.set_clipboard(clipboard, text)
+    }
+
+    /// Set some text in the clipboard, and also offer `html` as
+    /// `text/html` to applications that prefer it, with the text
+    /// serving as the plain text fallback.
+    /// Systems that don't support this set just the text.
+    fn set_clipboard_with_html(&self, clipboard: Clipboard, text: String, html: String) {
+        let _ = html;
+        self.set_clipboard(clipboard, text)
+    }
+
+    /// Initiate transfer of an image from the clipboard.
+    /// Resolves to the PNG encoded image, or `None` if the clipboard
+    /// doesn't hold an image or the system doesn't support this.
+    fn get_clipboard_image(&self, _clipboard: Clipboard) -> Future<Option<Vec<u8>>> {
+        Future::ok(None)
+    }
+
     /// Set the icon for the window.
     /// Depending on the system this may be shown in its titlebar
     /// and/or in the task manager/task switcher
@@ -289,6 +332,18 @@ pub trait WindowOps {
 
     fn toggle_fullscreen(&self) {}
 
+    /// Ask the desktop environment to draw the user's attention
+    /// to the window; this is typically shown by flashing or
+    /// highlighting it in the taskbar or dock.
+    /// This may not be supported by all environments.
+    fn request_attention(&self) {}
+
+    /// Provide the text that is visible in the window, along with the
+    /// char offset of the text cursor within it, so that it can be
+    /// exposed to screen readers via the platform accessibility APIs.
+    /// This is currently only implemented on macOS.
+    fn set_accessibility_text(&self, _text: String, _cursor_offset: usize) {}
+
     fn config_did_change(&self, _config: &config::ConfigHandle) {}
 
     /// Configure the Window so that the desktop environment
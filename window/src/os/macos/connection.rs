@@ -6,8 +6,9 @@ use super::window::WindowInner;
 use crate::connection::ConnectionOps;
 use crate::spawn::*;
 use crate::Appearance;
-use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular};
-use cocoa::base::{id, nil};
+use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular, NSScreen};
+use cocoa::base::{id, nil, BOOL, YES};
+use cocoa::foundation::NSArray;
 use objc::*;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -99,6 +100,14 @@ impl ConnectionOps for Connection {
         }
     }
 
+    fn prefers_reduced_motion(&self) -> bool {
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let reduce: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            reduce == YES
+        }
+    }
+
     fn run_message_loop(&self) -> anyhow::Result<()> {
         unsafe {
             self.ns_app.run();
@@ -118,6 +127,22 @@ impl ConnectionOps for Connection {
             NSBeep();
         }
     }
+
+    fn display_configuration(&self) -> Option<String> {
+        unsafe {
+            let screens = NSScreen::screens(nil);
+            let displays: Vec<String> = (0..screens.count())
+                .map(|idx| {
+                    let frame = NSScreen::frame(screens.objectAtIndex(idx));
+                    format!(
+                        "{}x{}+{}+{}",
+                        frame.size.width, frame.size.height, frame.origin.x, frame.origin.y
+                    )
+                })
+                .collect();
+            Some(displays.join(","))
+        }
+    }
 }
 
 extern "C" {
@@ -425,6 +425,7 @@ impl Window {
                 tracking_rect_tag: 0,
                 hscroll_remainder: 0.,
                 vscroll_remainder: 0.,
+                vscroll_pixel_remainder: 0.,
                 last_wheel: Instant::now(),
                 key_is_down: None,
                 dead_pending: None,
@@ -434,6 +435,8 @@ impl Window {
                 ime_last_event: None,
                 live_resizing: false,
                 ime_text: String::new(),
+                accessibility_text: String::new(),
+                accessibility_cursor: 0,
             }));
 
             let window: id = msg_send![get_window_class(), alloc];
@@ -447,8 +450,15 @@ impl Window {
 
             apply_decorations_to_window(&window, config.window_decorations);
 
-            // Prevent Cocoa native tabs from being used
-            let _: () = msg_send![*window, setTabbingMode:2 /* NSWindowTabbingModeDisallowed */];
+            if config.native_macos_window_tabs {
+                // Group our windows together as Cocoa native tabs
+                let _: () = msg_send![*window, setTabbingMode:1 /* NSWindowTabbingModePreferred */];
+                let _: () = msg_send![*window, setTabbingIdentifier: *nsstring("wezterm")];
+            } else {
+                // Prevent Cocoa native tabs from being used
+                let _: () =
+                    msg_send![*window, setTabbingMode:2 /* NSWindowTabbingModeDisallowed */];
+            }
             let _: () = msg_send![*window, setRestorable: NO];
 
             window.setReleasedWhenClosed_(NO);
@@ -668,6 +678,34 @@ impl WindowOps for Window {
             .ok();
     }
 
+    fn set_clipboard_with_html(&self, _clipboard: Clipboard, text: String, html: String) {
+        unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            let _: NSInteger = msg_send![pasteboard, clearContents];
+            // NSPasteboardTypeString
+            let _: BOOL = msg_send![pasteboard, setString: *nsstring(&text)
+                forType: *nsstring("public.utf8-plain-text")];
+            // NSPasteboardTypeHTML
+            let _: BOOL = msg_send![pasteboard, setString: *nsstring(&html)
+                forType: *nsstring("public.html")];
+        }
+    }
+
+    fn get_clipboard_image(&self, _clipboard: Clipboard) -> Future<Option<Vec<u8>>> {
+        Future::ok(unsafe {
+            let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+            // NSPasteboardTypePNG
+            let data: id = msg_send![pasteboard, dataForType: *nsstring("public.png")];
+            if data.is_null() {
+                None
+            } else {
+                let len: usize = msg_send![data, length];
+                let bytes: *const u8 = msg_send![data, bytes];
+                Some(std::slice::from_raw_parts(bytes, len).to_vec())
+            }
+        })
+    }
+
     fn toggle_fullscreen(&self) {
         Connection::with_window_inner(self.id, move |inner| {
             inner.toggle_fullscreen();
@@ -682,6 +720,26 @@ impl WindowOps for Window {
         });
     }
 
+    fn request_attention(&self) {
+        Connection::with_window_inner(self.id, move |_inner| {
+            unsafe {
+                // NSInformationalRequest bounces the dock icon once;
+                // the request is cancelled when the app is activated.
+                const NS_INFORMATIONAL_REQUEST: NSInteger = 10;
+                let app = NSApplication::sharedApplication(nil);
+                let _: NSInteger = msg_send![app, requestUserAttention: NS_INFORMATIONAL_REQUEST];
+            }
+            Ok(())
+        });
+    }
+
+    fn set_accessibility_text(&self, text: String, cursor_offset: usize) {
+        Connection::with_window_inner(self.id, move |inner| {
+            inner.set_accessibility_text(text, cursor_offset);
+            Ok(())
+        });
+    }
+
     fn config_did_change(&self, config: &ConfigHandle) {
         let config = config.clone();
         Connection::with_window_inner(self.id, move |inner| {
@@ -1018,6 +1076,31 @@ impl WindowInner {
         }
     }
 
+    fn set_accessibility_text(&mut self, text: String, cursor_offset: usize) {
+        if let Some(window_view) = WindowView::get_this(unsafe { &**self.view }) {
+            {
+                let mut view_inner = window_view.inner.borrow_mut();
+                if view_inner.accessibility_text == text
+                    && view_inner.accessibility_cursor == cursor_offset
+                {
+                    return;
+                }
+                view_inner.accessibility_text = text;
+                view_inner.accessibility_cursor = cursor_offset;
+            }
+            unsafe {
+                NSAccessibilityPostNotification(
+                    *self.view,
+                    NSAccessibilityValueChangedNotification,
+                );
+                NSAccessibilityPostNotification(
+                    *self.view,
+                    NSAccessibilitySelectedTextChangedNotification,
+                );
+            }
+        }
+    }
+
     fn toggle_fullscreen(&mut self) {
         let native_fullscreen = self.config.native_macos_fullscreen_mode;
 
@@ -1128,6 +1211,8 @@ struct Inner {
     tracking_rect_tag: NSInteger,
     hscroll_remainder: f64,
     vscroll_remainder: f64,
+    /// Fractional pixels left over from precise vertical scrolling
+    vscroll_pixel_remainder: f64,
     last_wheel: Instant,
     /// We use this to avoid double-emitting events when
     /// procesing key-up events.
@@ -1154,6 +1239,10 @@ struct Inner {
     live_resizing: bool,
 
     ime_text: String,
+
+    /// The text and cursor position exposed to screen readers
+    accessibility_text: String,
+    accessibility_cursor: usize,
 }
 
 #[repr(C)]
@@ -1192,6 +1281,10 @@ extern "C" {
 
     static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
 
+    fn NSAccessibilityPostNotification(element: id, notification: id);
+    static NSAccessibilityValueChangedNotification: id;
+    static NSAccessibilitySelectedTextChangedNotification: id;
+
     fn UCKeyTranslate(
         layout: *const UCKeyboardLayout,
         virtualKeyCode: u16,
@@ -1748,6 +1841,54 @@ impl WindowView {
         YES
     }
 
+    // NSAccessibility; we present the visible terminal text as a
+    // read-only text area so that screen readers can announce it.
+
+    extern "C" fn is_accessibility_element(_this: &mut Object, _sel: Sel) -> BOOL {
+        YES
+    }
+
+    extern "C" fn accessibility_role(_this: &mut Object, _sel: Sel) -> id {
+        nsstring("AXTextArea").autorelease()
+    }
+
+    extern "C" fn accessibility_value(this: &mut Object, _sel: Sel) -> id {
+        let text = match Self::get_this(this) {
+            Some(this) => this.inner.borrow().accessibility_text.clone(),
+            None => String::new(),
+        };
+        nsstring(&text).autorelease()
+    }
+
+    extern "C" fn accessibility_number_of_characters(this: &mut Object, _sel: Sel) -> NSInteger {
+        match Self::get_this(this) {
+            Some(this) => this
+                .inner
+                .borrow()
+                .accessibility_text
+                .encode_utf16()
+                .count() as _,
+            None => 0,
+        }
+    }
+
+    extern "C" fn accessibility_selected_text_range(this: &mut Object, _sel: Sel) -> NSRange {
+        match Self::get_this(this) {
+            Some(this) => {
+                let inner = this.inner.borrow();
+                // NSString offsets are measured in UTF-16 code units
+                let offset: usize = inner
+                    .accessibility_text
+                    .chars()
+                    .take(inner.accessibility_cursor)
+                    .map(char::len_utf16)
+                    .sum();
+                NSRange::new(offset as _, 0)
+            }
+            None => NSRange::new(0, 0),
+        }
+    }
+
     extern "C" fn view_did_change_effective_appearance(this: &mut Object, _sel: Sel) {
         if let Some(this) = Self::get_this(this) {
             let appearance = Connection::get().unwrap().get_appearance();
@@ -1907,13 +2048,52 @@ impl WindowView {
         }
     }
 
+    /// Devices with precise deltas, such as touchpads, report the number
+    /// of points scrolled, including during the momentum phase after the
+    /// fingers have left the pad.  Those are passed on as pixels so that
+    /// the terminal can scroll smoothly rather than a line at a time.
+    fn precise_vert_scroll(this: &mut Object, nsevent: id, delta: f64) {
+        let view = this as id;
+        let pixels = unsafe {
+            let rect = NSRect::new(NSPoint::new(0., 0.), NSSize::new(0., delta));
+            let backing_rect = NSView::convertRectToBacking(view, rect);
+            f64::copysign(backing_rect.size.height, delta)
+        };
+
+        let mut pixels = pixels;
+        if let Some(myself) = Self::get_this(this) {
+            let mut inner = myself.inner.borrow_mut();
+            // Reset remainder when changing scroll direction
+            if pixels.signum() != inner.vscroll_pixel_remainder.signum() {
+                inner.vscroll_pixel_remainder = 0.;
+            }
+            pixels += inner.vscroll_pixel_remainder;
+            inner.vscroll_pixel_remainder = pixels.fract();
+            pixels = pixels.trunc();
+        }
+
+        if pixels == 0. {
+            return;
+        }
+        let pixels = pixels.max(i16::MIN as f64).min(i16::MAX as f64) as i16;
+        Self::mouse_common(this, nsevent, MouseEventKind::VertPixelScroll(pixels));
+    }
+
     extern "C" fn scroll_wheel(this: &mut Object, _sel: Sel, nsevent: id) {
         let precise = unsafe { nsevent.hasPreciseScrollingDeltas() } == YES;
+        if precise {
+            let vert_delta = unsafe { nsevent.scrollingDeltaY() };
+            let horz_delta = unsafe { nsevent.scrollingDeltaX() };
+            if vert_delta.abs() >= horz_delta.abs() {
+                Self::precise_vert_scroll(this, nsevent, vert_delta);
+                return;
+            }
+        }
         let scale = if precise {
             // Devices with precise deltas report number of pixels scrolled.
-            // At this layer we don't know how many pixels comprise a cell
-            // in the terminal widget, and our abstraction doesn't allow being
-            // told what that amount should be, so we come up with a hard
+            // Horizontal scrolling is only reported in whole columns,
+            // and at this layer we don't know how many pixels comprise
+            // a cell in the terminal widget, so we come up with a hard
             // coded factor based on the likely default font size and dpi
             // to make the scroll speed feel a bit better.
             15.0
@@ -2396,6 +2576,28 @@ impl WindowView {
         }
     }
 
+    extern "C" fn did_move(this: &mut Object, _sel: Sel, _notification: id) {
+        if let Some(this) = Self::get_this(this) {
+            let mut inner = this.inner.borrow_mut();
+            let position = inner.window.as_ref().map(|window| {
+                let window = window.load();
+                unsafe {
+                    let frame = NSWindow::frame(*window);
+                    let content_frame = NSWindow::contentRectForFrameRect_(*window, frame);
+                    // The origin is the bottom left of the content area,
+                    // but we want to report the top left
+                    cartesian_to_screen_point(NSPoint::new(
+                        content_frame.origin.x,
+                        content_frame.origin.y + content_frame.size.height,
+                    ))
+                }
+            });
+            if let Some(position) = position {
+                inner.events.dispatch(WindowEvent::Moved(position));
+            }
+        }
+    }
+
     extern "C" fn will_start_live_resize(this: &mut Object, _sel: Sel, _notification: id) {
         if let Some(this) = Self::get_this(this) {
             let mut inner = this.inner.borrow_mut();
@@ -2604,6 +2806,10 @@ impl WindowView {
                 sel!(windowDidResize:),
                 Self::did_resize as extern "C" fn(&mut Object, Sel, id),
             );
+            cls.add_method(
+                sel!(windowDidMove:),
+                Self::did_move as extern "C" fn(&mut Object, Sel, id),
+            );
             cls.add_method(
                 sel!(windowDidChangeScreen:),
                 Self::did_change_screen as extern "C" fn(&mut Object, Sel, id),
@@ -2682,6 +2888,29 @@ impl WindowView {
                 Self::accepts_first_responder as extern "C" fn(&mut Object, Sel) -> BOOL,
             );
 
+            cls.add_method(
+                sel!(isAccessibilityElement),
+                Self::is_accessibility_element as extern "C" fn(&mut Object, Sel) -> BOOL,
+            );
+            cls.add_method(
+                sel!(accessibilityRole),
+                Self::accessibility_role as extern "C" fn(&mut Object, Sel) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilityValue),
+                Self::accessibility_value as extern "C" fn(&mut Object, Sel) -> id,
+            );
+            cls.add_method(
+                sel!(accessibilityNumberOfCharacters),
+                Self::accessibility_number_of_characters
+                    as extern "C" fn(&mut Object, Sel) -> NSInteger,
+            );
+            cls.add_method(
+                sel!(accessibilitySelectedTextRange),
+                Self::accessibility_selected_text_range
+                    as extern "C" fn(&mut Object, Sel) -> NSRange,
+            );
+
             cls.add_method(
                 sel!(acceptsFirstMouse:),
                 Self::accepts_first_mouse as extern "C" fn(&mut Object, Sel, id) -> BOOL,
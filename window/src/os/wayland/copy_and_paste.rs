@@ -16,6 +16,10 @@ use crate::Clipboard;
 #[derive(Default)]
 pub struct CopyAndPaste {
     data_offer: Option<WlDataOffer>,
+    /// The most recently announced offer that includes an image
+    image_capable_offer: Option<WlDataOffer>,
+    /// The current selection, if it includes an image
+    image_offer: Option<WlDataOffer>,
     pub(crate) last_serial: u32,
 }
 
@@ -29,6 +33,9 @@ impl std::fmt::Debug for CopyAndPaste {
 }
 
 pub const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+pub const IMAGE_MIME_TYPE: &str = "image/png";
+pub const URI_LIST_MIME_TYPE: &str = "text/uri-list";
+pub const HTML_MIME_TYPE: &str = "text/html";
 
 impl CopyAndPaste {
     pub fn create() -> Arc<Mutex<Self>> {
@@ -73,7 +80,39 @@ impl CopyAndPaste {
         }
     }
 
-    pub fn set_clipboard_data(&mut self, clipboard: Clipboard, data: String) {
+    /// Returns a pipe from which the clipboard image can be read,
+    /// or None if the clipboard doesn't hold an image.
+    /// Images are not supported for the primary selection.
+    pub fn get_clipboard_image_data(
+        &mut self,
+        clipboard: Clipboard,
+    ) -> anyhow::Result<Option<FileDescriptor>> {
+        let offer = match (clipboard, self.image_offer.as_ref()) {
+            (Clipboard::Clipboard, Some(offer)) => offer,
+            _ => return Ok(None),
+        };
+        let pipe = Pipe::new().map_err(Error::msg)?;
+        offer.receive(IMAGE_MIME_TYPE.to_string(), pipe.write.as_raw_fd());
+        Ok(Some(pipe.read))
+    }
+
+    /// Offers `data` as text, along with `uri_list` as a `text/uri-list`
+    /// and `html` as `text/html` if they are present
+    pub fn set_clipboard_data(
+        &mut self,
+        clipboard: Clipboard,
+        data: String,
+        uri_list: Option<String>,
+        html: Option<String>,
+    ) {
+        let mut mime_types = vec![TEXT_MIME_TYPE.to_string()];
+        if uri_list.is_some() {
+            mime_types.push(URI_LIST_MIME_TYPE.to_string());
+        }
+        if html.is_some() {
+            mime_types.push(HTML_MIME_TYPE.to_string());
+        }
+
         let conn = crate::Connection::get().unwrap().wayland();
         let pointer = conn.pointer.borrow();
         let primary_selection = if let Clipboard::PrimarySelection = clipboard {
@@ -89,7 +128,7 @@ impl CopyAndPaste {
             Some((manager, device)) => {
                 let source = PrimarySelectionSource::new(
                     &manager,
-                    &[TEXT_MIME_TYPE.to_string()],
+                    &mime_types,
                     move |event, _dispatch_data| match event {
                         PrimarySelectionSourceEvent::Cancelled => {
                             crate::Connection::get()
@@ -100,9 +139,12 @@ impl CopyAndPaste {
                                 .data_device
                                 .set_selection(None, 0);
                         }
-                        PrimarySelectionSourceEvent::Send { pipe, .. } => {
+                        PrimarySelectionSourceEvent::Send { pipe, mime_type } => {
                             let fd = unsafe { FileDescriptor::from_raw_fd(pipe.into_raw_fd()) };
-                            write_selection_to_pipe(fd, &data);
+                            write_selection_to_pipe(
+                                fd,
+                                selection_for_mime_type(&mime_type, &data, &uri_list, &html),
+                            );
                         }
                     },
                 );
@@ -115,12 +157,17 @@ impl CopyAndPaste {
                     .require_global::<WlDataDeviceManager>()
                     .create_data_source();
                 source.quick_assign(move |_source, event, _dispatch_data| {
-                    if let DataSourceEvent::Send { fd, .. } = event {
+                    if let DataSourceEvent::Send { fd, mime_type } = event {
                         let fd = unsafe { FileDescriptor::from_raw_fd(fd) };
-                        write_selection_to_pipe(fd, &data);
+                        write_selection_to_pipe(
+                            fd,
+                            selection_for_mime_type(&mime_type, &data, &uri_list, &html),
+                        );
                     }
                 });
-                source.offer(TEXT_MIME_TYPE.to_string());
+                for mime_type in mime_types {
+                    source.offer(mime_type);
+                }
                 conn.pointer
                     .borrow()
                     .data_device
@@ -135,6 +182,9 @@ impl CopyAndPaste {
                 if mime_type == TEXT_MIME_TYPE {
                     offer.accept(self.last_serial, Some(mime_type));
                     self.data_offer.replace(offer);
+                } else if mime_type == IMAGE_MIME_TYPE {
+                    offer.accept(self.last_serial, None);
+                    self.image_capable_offer.replace(offer);
                 } else {
                     // Refuse other mime types
                     offer.accept(self.last_serial, None);
@@ -151,10 +201,27 @@ impl CopyAndPaste {
     }
 
     pub fn confirm_selection(&mut self, offer: WlDataOffer) {
+        self.image_offer = self
+            .image_capable_offer
+            .take()
+            .filter(|image_offer| *image_offer == offer);
         self.data_offer.replace(offer);
     }
 }
 
+fn selection_for_mime_type<'a>(
+    mime_type: &str,
+    data: &'a str,
+    uri_list: &'a Option<String>,
+    html: &'a Option<String>,
+) -> &'a str {
+    match (uri_list, html) {
+        (Some(uri_list), _) if mime_type == URI_LIST_MIME_TYPE => uri_list,
+        (_, Some(html)) if mime_type == HTML_MIME_TYPE => html,
+        _ => data,
+    }
+}
+
 fn write_selection_to_pipe(fd: FileDescriptor, text: &str) {
     if let Err(e) = write_pipe_with_timeout(fd, text.as_bytes()) {
         log::error!("while sending primary selection to pipe: {}", e);
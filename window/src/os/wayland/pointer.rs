@@ -8,7 +8,9 @@ use toolkit::reexports::client::protocol::wl_data_device::{
     Event as DataDeviceEvent, WlDataDevice,
 };
 use toolkit::reexports::client::protocol::wl_data_offer::{Event as DataOfferEvent, WlDataOffer};
-use toolkit::reexports::client::protocol::wl_pointer::{Axis, ButtonState, Event as PointerEvent};
+use toolkit::reexports::client::protocol::wl_pointer::{
+    Axis, AxisSource, ButtonState, Event as PointerEvent,
+};
 use toolkit::reexports::client::protocol::wl_surface::WlSurface;
 use toolkit::seat::pointer::{ThemeManager, ThemeSpec, ThemedPointer};
 use wayland_client::protocol::wl_compositor::WlCompositor;
@@ -108,6 +110,9 @@ pub struct PendingMouse {
     surface_coords: Option<(f64, f64)>,
     button: Vec<(MousePress, ButtonState)>,
     scroll: Option<(f64, f64)>,
+    /// Whether the scroll values come from a device such as a touchpad,
+    /// rather than from wheel clicks
+    scroll_is_precise: bool,
     in_window: bool,
 }
 
@@ -118,6 +123,7 @@ impl PendingMouse {
             copy_and_paste: Arc::clone(copy_and_paste),
             button: vec![],
             scroll: None,
+            scroll_is_precise: false,
             surface_coords: None,
             in_window: false,
         }))
@@ -177,6 +183,11 @@ impl PendingMouse {
                 self.button.push((button, state));
                 changed
             }
+            PointerEvent::AxisSource { axis_source } => {
+                self.scroll_is_precise =
+                    matches!(axis_source, AxisSource::Finger | AxisSource::Continuous);
+                false
+            }
             PointerEvent::Axis {
                 axis: Axis::VerticalScroll,
                 value,
@@ -218,6 +229,10 @@ impl PendingMouse {
         pending.lock().unwrap().scroll.take()
     }
 
+    pub fn scroll_is_precise(pending: &Arc<Mutex<Self>>) -> bool {
+        pending.lock().unwrap().scroll_is_precise
+    }
+
     pub fn in_window(pending: &Arc<Mutex<Self>>) -> bool {
         pending.lock().unwrap().in_window
     }
@@ -330,12 +345,17 @@ impl PointerDispatcher {
         }
     }
 
-    pub fn set_cursor(&self, name: &str, serial: Option<u32>) {
+    /// Set the cursor to the first of the candidate names that
+    /// is present in the cursor theme.
+    pub fn set_cursor(&self, names: &[&str], serial: Option<u32>) {
         let inner = self.inner.lock().unwrap();
         let serial = serial.unwrap_or(inner.serial);
-        if let Err(err) = self.auto_pointer.set_cursor(name, Some(serial)) {
-            log::error!("Unable to set cursor to {}: {:#}", name, err);
+        for name in names {
+            if self.auto_pointer.set_cursor(name, Some(serial)).is_ok() {
+                return;
+            }
         }
+        log::error!("Unable to set cursor to any of {:?}", names);
     }
 }
 
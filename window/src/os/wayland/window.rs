@@ -546,6 +546,9 @@ impl WaylandWindowInner {
                 self.events.dispatch(WindowEvent::MouseEvent(event));
             }
 
+            // Touchpads report the distance in surface coordinates,
+            // which the terminal can use to scroll smoothly by pixels
+            let precise = PendingMouse::scroll_is_precise(&pending_mouse);
             if value_y.signum() != self.vscroll_remainder.signum() {
                 self.vscroll_remainder = 0.0;
             }
@@ -554,7 +557,11 @@ impl WaylandWindowInner {
             self.vscroll_remainder = scaled_y - discrete_y;
             if discrete_y != 0. {
                 let event = MouseEvent {
-                    kind: MouseEventKind::VertWheel(-discrete_y as i16),
+                    kind: if precise {
+                        MouseEventKind::VertPixelScroll(-discrete_y as i16)
+                    } else {
+                        MouseEventKind::VertWheel(-discrete_y as i16)
+                    },
                     coords: self.last_mouse_coords,
                     screen_coords: ScreenPoint::new(
                         self.last_mouse_coords.x + self.dimensions.pixel_width as isize,
@@ -956,19 +963,79 @@ impl WindowOps for WaylandWindow {
         future
     }
 
+    fn get_clipboard_image(&self, clipboard: Clipboard) -> Future<Option<Vec<u8>>> {
+        let mut promise = Promise::new();
+        let future = promise.get_future().unwrap();
+        let promise = Arc::new(Mutex::new(promise));
+        WaylandConnection::with_window_inner(self.0, move |inner| {
+            let read = inner
+                .copy_and_paste
+                .lock()
+                .unwrap()
+                .get_clipboard_image_data(clipboard)?;
+            let promise = Arc::clone(&promise);
+            std::thread::spawn(move || {
+                let mut promise = promise.lock().unwrap();
+                match read.map(read_pipe_bytes_with_timeout).transpose() {
+                    Ok(result) => {
+                        promise.ok(result);
+                    }
+                    Err(e) => {
+                        log::error!("while reading clipboard image: {}", e);
+                        promise.err(anyhow!("{}", e));
+                    }
+                };
+            });
+            Ok(())
+        });
+        future
+    }
+
     fn set_clipboard(&self, clipboard: Clipboard, text: String) {
         WaylandConnection::with_window_inner(self.0, move |inner| {
             inner
                 .copy_and_paste
                 .lock()
                 .unwrap()
-                .set_clipboard_data(clipboard, text);
+                .set_clipboard_data(clipboard, text, None, None);
             Ok(())
         });
     }
+
+    fn set_clipboard_with_uri_list(&self, clipboard: Clipboard, text: String, uris: Vec<String>) {
+        let uri_list = if uris.is_empty() {
+            None
+        } else {
+            Some(crate::uri_list_from_uris(&uris))
+        };
+        WaylandConnection::with_window_inner(self.0, move |inner| {
+            inner
+                .copy_and_paste
+                .lock()
+                .unwrap()
+                .set_clipboard_data(clipboard, text, uri_list, None);
+            Ok(())
+        });
+    }
+
+    fn set_clipboard_with_html(&self, clipboard: Clipboard, text: String, html: String) {
+        WaylandConnection::with_window_inner(self.0, move |inner| {
+            inner.copy_and_paste.lock().unwrap().set_clipboard_data(
+                clipboard,
+                text,
+                None,
+                Some(html),
+            );
+            Ok(())
+        });
+    }
+}
+
+fn read_pipe_with_timeout(file: FileDescriptor) -> anyhow::Result<String> {
+    Ok(String::from_utf8(read_pipe_bytes_with_timeout(file)?)?)
 }
 
-fn read_pipe_with_timeout(mut file: FileDescriptor) -> anyhow::Result<String> {
+fn read_pipe_bytes_with_timeout(mut file: FileDescriptor) -> anyhow::Result<Vec<u8>> {
     let mut result = Vec::new();
 
     file.set_non_blocking(true)?;
@@ -996,7 +1063,7 @@ fn read_pipe_with_timeout(mut file: FileDescriptor) -> anyhow::Result<String> {
         }
     }
 
-    Ok(String::from_utf8(result)?)
+    Ok(result)
 }
 
 impl WaylandWindowInner {
@@ -1031,12 +1098,14 @@ impl WaylandWindowInner {
     }
 
     fn set_cursor(&mut self, cursor: Option<MouseCursor>) {
-        let cursor = match cursor {
-            Some(MouseCursor::Arrow) => "arrow",
-            Some(MouseCursor::Hand) => "hand",
-            Some(MouseCursor::SizeUpDown) => "ns-resize",
-            Some(MouseCursor::SizeLeftRight) => "ew-resize",
-            Some(MouseCursor::Text) => "xterm",
+        // Themes vary in whether they use the legacy X11 names or
+        // the CSS names, so offer both
+        let cursor: &[&str] = match cursor {
+            Some(MouseCursor::Arrow) => &["arrow", "default", "left_ptr"],
+            Some(MouseCursor::Hand) => &["hand", "pointer", "hand2"],
+            Some(MouseCursor::SizeUpDown) => &["ns-resize", "sb_v_double_arrow"],
+            Some(MouseCursor::SizeLeftRight) => &["ew-resize", "sb_h_double_arrow"],
+            Some(MouseCursor::Text) => &["xterm", "text", "ibeam"],
             None => return,
         };
         let conn = Connection::get().unwrap().wayland();
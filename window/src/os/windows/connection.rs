@@ -7,6 +7,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ptr::null_mut;
 use std::rc::Rc;
+use winapi::shared::minwindef::{BOOL, FALSE, TRUE};
 use winapi::um::winbase::INFINITE;
 use winapi::um::winnt::HANDLE;
 use winapi::um::winuser::*;
@@ -45,6 +46,20 @@ impl ConnectionOps for Connection {
         get_appearance()
     }
 
+    fn prefers_reduced_motion(&self) -> bool {
+        // Corresponds to the "Show animations in Windows" setting
+        let mut animations: BOOL = TRUE;
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                &mut animations as *mut BOOL as *mut _,
+                0,
+            )
+        };
+        ok != 0 && animations == FALSE
+    }
+
     fn run_message_loop(&self) -> anyhow::Result<()> {
         let mut msg: MSG = unsafe { std::mem::zeroed() };
         loop {
@@ -77,6 +92,19 @@ impl ConnectionOps for Connection {
             MessageBeep(MB_OK);
         }
     }
+
+    fn display_configuration(&self) -> Option<String> {
+        unsafe {
+            Some(format!(
+                "{}:{}x{}+{}+{}",
+                GetSystemMetrics(SM_CMONITORS),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN),
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN)
+            ))
+        }
+    }
 }
 
 impl Connection {
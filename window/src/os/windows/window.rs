@@ -98,6 +98,7 @@ pub(crate) struct WindowInner {
 
     last_size: Option<Dimensions>,
     in_size_move: bool,
+    last_position: Option<ScreenPoint>,
     dead_pending: Option<(Modifiers, u32)>,
     saved_placement: Option<WINDOWPLACEMENT>,
     track_mouse_leave: bool,
@@ -622,6 +623,7 @@ impl Window {
             keyboard_info: KeyboardLayoutInfo::new(),
             last_size: None,
             in_size_move: false,
+            last_position: None,
             dead_pending: None,
             saved_placement: None,
             track_mouse_leave: false,
@@ -878,6 +880,23 @@ impl WindowOps for Window {
         });
     }
 
+    fn request_attention(&self) {
+        Connection::with_window_inner(self.0, move |inner| {
+            let mut info = FLASHWINFO {
+                cbSize: std::mem::size_of::<FLASHWINFO>() as u32,
+                hwnd: inner.hwnd.0,
+                // Flash the taskbar button until the window is focused
+                dwFlags: FLASHW_TRAY | FLASHW_TIMERNOFG,
+                uCount: 0,
+                dwTimeout: 0,
+            };
+            unsafe {
+                FlashWindowEx(&mut info);
+            }
+            Ok(())
+        });
+    }
+
     fn config_did_change(&self, config: &ConfigHandle) {
         let config = config.clone();
         Connection::with_window_inner(self.0, move |inner| {
@@ -1357,6 +1376,14 @@ unsafe fn wm_windowposchanged(
 ) -> Option<LRESULT> {
     // let pos = &*(lparam as *const WINDOWPOS);
     wm_size(hwnd, 0, 0, 0)?;
+    if let Some(inner) = rc_from_hwnd(hwnd) {
+        let mut inner = inner.borrow_mut();
+        let position = client_to_screen(hwnd, Point::new(0, 0));
+        if inner.last_position != Some(position) {
+            inner.last_position.replace(position);
+            inner.events.dispatch(WindowEvent::Moved(position));
+        }
+    }
     Some(0)
 }
 
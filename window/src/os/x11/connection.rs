@@ -30,6 +30,10 @@ pub struct XConnection {
     pub atom_utf8_string: Atom,
     pub atom_xsel_data: Atom,
     pub atom_targets: Atom,
+    pub atom_image_png: Atom,
+    pub atom_incr: Atom,
+    pub atom_uri_list: Atom,
+    pub atom_text_html: Atom,
     pub atom_clipboard: Atom,
     pub atom_gtk_edge_constraints: Atom,
     pub atom_xsettings_selection: Atom,
@@ -39,6 +43,7 @@ pub struct XConnection {
     pub atom_state_maximized_horz: Atom,
     pub atom_state_hidden: Atom,
     pub atom_state_fullscreen: Atom,
+    pub atom_state_demands_attention: Atom,
     pub atom_net_wm_state: Atom,
     pub atom_motif_wm_hints: Atom,
     pub atom_net_wm_pid: Atom,
@@ -54,6 +59,9 @@ pub struct XConnection {
     pub(crate) ime: RefCell<std::pin::Pin<Box<xcb_imdkit::ImeClient>>>,
     pub(crate) ime_process_event_result: RefCell<anyhow::Result<()>>,
     pub(crate) has_randr: bool,
+    /// The size of the root window, which spans all of the monitors;
+    /// kept up to date via RandR ScreenChangeNotify events
+    screen_size: RefCell<(u16, u16)>,
     pub(crate) atom_names: RefCell<HashMap<Atom, String>>,
 }
 
@@ -121,6 +129,11 @@ impl ConnectionOps for XConnection {
         *self.default_dpi.borrow()
     }
 
+    fn display_configuration(&self) -> Option<String> {
+        let (width, height) = *self.screen_size.borrow();
+        Some(format!("{}x{}", width, height))
+    }
+
     fn get_appearance(&self) -> Appearance {
         if let Some(XSetting::String(name)) = self.xsettings.borrow().get("Net/ThemeName") {
             let lower = name.to_ascii_lowercase();
@@ -142,6 +155,13 @@ impl ConnectionOps for XConnection {
         }
     }
 
+    fn prefers_reduced_motion(&self) -> bool {
+        matches!(
+            self.xsettings.borrow().get("Gtk/EnableAnimations"),
+            Some(XSetting::Integer(0))
+        )
+    }
+
     fn run_message_loop(&self) -> anyhow::Result<()> {
         self.conn.flush()?;
 
@@ -281,6 +301,8 @@ impl XConnection {
             //    - update modifiers state
             //    - update keymap/state on keyboard changes
             self.keyboard.process_xkb_event(&self.conn, event)?;
+        } else if let xcb::Event::RandR(xcb::randr::Event::ScreenChangeNotify(e)) = event {
+            *self.screen_size.borrow_mut() = (e.width(), e.height());
         }
         Ok(())
     }
@@ -337,6 +359,10 @@ impl XConnection {
         let atom_utf8_string = Self::intern_atom(&conn, "UTF8_STRING")?;
         let atom_xsel_data = Self::intern_atom(&conn, "XSEL_DATA")?;
         let atom_targets = Self::intern_atom(&conn, "TARGETS")?;
+        let atom_image_png = Self::intern_atom(&conn, "image/png")?;
+        let atom_incr = Self::intern_atom(&conn, "INCR")?;
+        let atom_uri_list = Self::intern_atom(&conn, "text/uri-list")?;
+        let atom_text_html = Self::intern_atom(&conn, "text/html")?;
         let atom_clipboard = Self::intern_atom(&conn, "CLIPBOARD")?;
         let atom_gtk_edge_constraints = Self::intern_atom(&conn, "_GTK_EDGE_CONSTRAINTS")?;
         let atom_xsettings_selection =
@@ -347,6 +373,8 @@ impl XConnection {
         let atom_state_maximized_horz = Self::intern_atom(&conn, "_NET_WM_STATE_MAXIMIZED_HORZ")?;
         let atom_state_hidden = Self::intern_atom(&conn, "_NET_WM_STATE_HIDDEN")?;
         let atom_state_fullscreen = Self::intern_atom(&conn, "_NET_WM_STATE_FULLSCREEN")?;
+        let atom_state_demands_attention =
+            Self::intern_atom(&conn, "_NET_WM_STATE_DEMANDS_ATTENTION")?;
         let atom_net_wm_state = Self::intern_atom(&conn, "_NET_WM_STATE")?;
         let atom_motif_wm_hints = Self::intern_atom(&conn, "_MOTIF_WM_HINTS")?;
         let atom_net_wm_pid = Self::intern_atom(&conn, "_NET_WM_PID")?;
@@ -405,6 +433,13 @@ impl XConnection {
         .context("OpenFont")?;
 
         let root = screen.root();
+        let screen_size = RefCell::new((screen.width_in_pixels(), screen.height_in_pixels()));
+        if has_randr {
+            conn.send_request(&xcb::randr::SelectInput {
+                window: root,
+                enable: xcb::randr::NotifyMask::SCREEN_CHANGE,
+            });
+        }
 
         let xrm =
             crate::x11::xrm::parse_root_resource_manager(&conn, root).unwrap_or(HashMap::new());
@@ -453,6 +488,7 @@ impl XConnection {
             atom_state_maximized_horz,
             atom_state_hidden,
             atom_state_fullscreen,
+            atom_state_demands_attention,
             atom_net_wm_state,
             atom_motif_wm_hints,
             atom_net_wm_pid,
@@ -464,6 +500,10 @@ impl XConnection {
             atom_utf8_string,
             atom_xsel_data,
             atom_targets,
+            atom_image_png,
+            atom_incr,
+            atom_uri_list,
+            atom_text_html,
             windows: RefCell::new(HashMap::new()),
             should_terminate: RefCell::new(false),
             depth,
@@ -472,6 +512,7 @@ impl XConnection {
             ime: RefCell::new(ime),
             ime_process_event_result: RefCell::new(Ok(())),
             has_randr,
+            screen_size,
             atom_names: RefCell::new(HashMap::new()),
         });
 
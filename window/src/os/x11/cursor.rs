@@ -311,11 +311,11 @@ impl CursorInfo {
         }
 
         let names: &[&str] = match cursor.unwrap_or(MouseCursor::Arrow) {
-            MouseCursor::Arrow => &["top_left_arrow", "left_ptr"],
-            MouseCursor::Hand => &["hand2"],
-            MouseCursor::Text => &["xterm"],
-            MouseCursor::SizeUpDown => &["sb_v_double_arrow"],
-            MouseCursor::SizeLeftRight => &["sb_h_double_arrow"],
+            MouseCursor::Arrow => &["top_left_arrow", "left_ptr", "default"],
+            MouseCursor::Hand => &["hand2", "pointer", "hand1"],
+            MouseCursor::Text => &["xterm", "text", "ibeam"],
+            MouseCursor::SizeUpDown => &["sb_v_double_arrow", "ns-resize", "size_ver"],
+            MouseCursor::SizeLeftRight => &["sb_h_double_arrow", "ew-resize", "size_hor"],
         };
 
         for dir in &self.icon_path {
@@ -21,14 +21,22 @@ use std::sync::{Arc, Mutex};
 use wezterm_font::FontConfiguration;
 use wezterm_input_types::{KeyCode, KeyEvent, Modifiers};
 use xcb::x::{Atom, PropMode};
-use xcb::{Event, Xid};
+use xcb::{BaseEvent, Event, Xid};
 
 #[derive(Default)]
 struct CopyAndPaste {
     clipboard_owned: Option<String>,
     primary_selection_owned: Option<String>,
+    clipboard_uri_list: Option<String>,
+    primary_selection_uri_list: Option<String>,
+    clipboard_html: Option<String>,
+    primary_selection_html: Option<String>,
     clipboard_request: Option<Promise<String>>,
     selection_request: Option<Promise<String>>,
+    image_request: Option<Promise<Option<Vec<u8>>>>,
+    /// The data received so far while the owner of the selection
+    /// is transferring an image to us using the INCR protocol
+    image_incr: Option<Vec<u8>>,
     time: u32,
 }
 
@@ -47,6 +55,34 @@ impl CopyAndPaste {
         }
     }
 
+    fn uri_list(&self, clipboard: Clipboard) -> &Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &self.primary_selection_uri_list,
+            Clipboard::Clipboard => &self.clipboard_uri_list,
+        }
+    }
+
+    fn uri_list_mut(&mut self, clipboard: Clipboard) -> &mut Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &mut self.primary_selection_uri_list,
+            Clipboard::Clipboard => &mut self.clipboard_uri_list,
+        }
+    }
+
+    fn html(&self, clipboard: Clipboard) -> &Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &self.primary_selection_html,
+            Clipboard::Clipboard => &self.clipboard_html,
+        }
+    }
+
+    fn html_mut(&mut self, clipboard: Clipboard) -> &mut Option<String> {
+        match clipboard {
+            Clipboard::PrimarySelection => &mut self.primary_selection_html,
+            Clipboard::Clipboard => &mut self.clipboard_html,
+        }
+    }
+
     fn request_mut(&mut self, clipboard: Clipboard) -> &mut Option<Promise<String>> {
         match clipboard {
             Clipboard::PrimarySelection => &mut self.selection_request,
@@ -74,6 +110,7 @@ pub(crate) struct XWindowInner {
     paint_throttled: bool,
     pending: Vec<WindowEvent>,
     sure_about_geometry: bool,
+    last_position: Option<ScreenPoint>,
 }
 
 impl Drop for XWindowInner {
@@ -419,6 +456,15 @@ impl XWindowInner {
             }
             Event::X(xcb::x::Event::ConfigureNotify(cfg)) => {
                 self.configure_notify("X::ConfigureNotify", cfg.width(), cfg.height())?;
+                // Per ICCCM 4.1.5, when the window manager moves us
+                // it sends a synthetic ConfigureNotify holding our
+                // position relative to the root window.  The real
+                // events hold coordinates relative to the (frame)
+                // parent, so we ignore the position in those, which
+                // saves a round trip to the server per event.
+                if cfg.is_from_send_event() {
+                    self.moved_to(ScreenPoint::new(cfg.x().into(), cfg.y().into()));
+                }
             }
             Event::X(xcb::x::Event::KeyPress(key_press)) => {
                 self.copy_and_paste.time = key_press.time();
@@ -513,6 +559,13 @@ impl XWindowInner {
                     }
                 }
 
+                if msg.atom() == conn.atom_xsel_data
+                    && msg.state() == xcb::x::Property::NewValue
+                    && self.copy_and_paste.image_incr.is_some()
+                {
+                    self.image_incr_chunk()?;
+                }
+
                 if msg.atom() == conn.atom_net_wm_state {
                     // Change in window state should be accompanied by
                     // a Configure Notify but not all WMs send these
@@ -636,6 +689,8 @@ impl XWindowInner {
     fn selection_clear(&mut self, request: &xcb::x::SelectionClearEvent) -> anyhow::Result<()> {
         if let Some(clipboard) = self.selection_atom_to_clipboard(request.selection()) {
             self.copy_and_paste.clipboard_mut(clipboard).take();
+            self.copy_and_paste.uri_list_mut(clipboard).take();
+            self.copy_and_paste.html_mut(clipboard).take();
             self.copy_and_paste.request_mut(clipboard).take();
             self.update_selection_owner(clipboard)?;
         }
@@ -666,7 +721,15 @@ impl XWindowInner {
 
         let selprop = if request.target() == conn.atom_targets {
             // They want to know which targets we support
-            let atoms: [Atom; 1] = [conn.atom_utf8_string];
+            let mut atoms: Vec<Atom> = vec![conn.atom_utf8_string];
+            if let Some(clipboard) = self.selection_atom_to_clipboard(request.selection()) {
+                if self.copy_and_paste.uri_list(clipboard).is_some() {
+                    atoms.push(conn.atom_uri_list);
+                }
+                if self.copy_and_paste.html(clipboard).is_some() {
+                    atoms.push(conn.atom_text_html);
+                }
+            }
             conn.send_request(&xcb::x::ChangeProperty {
                 mode: PropMode::Replace,
                 window: request.requestor(),
@@ -702,6 +765,40 @@ impl XWindowInner {
             } else {
                 xcb::x::ATOM_NONE
             }
+        } else if request.target() == conn.atom_uri_list {
+            match self
+                .selection_atom_to_clipboard(request.selection())
+                .and_then(|clipboard| self.copy_and_paste.uri_list(clipboard).as_ref())
+            {
+                Some(uri_list) => {
+                    conn.send_request(&xcb::x::ChangeProperty {
+                        mode: PropMode::Replace,
+                        window: request.requestor(),
+                        property: request.property(),
+                        r#type: request.target(),
+                        data: uri_list.as_bytes(),
+                    });
+                    request.property()
+                }
+                None => xcb::x::ATOM_NONE,
+            }
+        } else if request.target() == conn.atom_text_html {
+            match self
+                .selection_atom_to_clipboard(request.selection())
+                .and_then(|clipboard| self.copy_and_paste.html(clipboard).as_ref())
+            {
+                Some(html) => {
+                    conn.send_request(&xcb::x::ChangeProperty {
+                        mode: PropMode::Replace,
+                        window: request.requestor(),
+                        property: request.property(),
+                        r#type: request.target(),
+                        data: html.as_bytes(),
+                    });
+                    request.property()
+                }
+                None => xcb::x::ATOM_NONE,
+            }
         } else {
             // We didn't support their request, so there is nothing
             // we can report back to them.
@@ -738,6 +835,10 @@ impl XWindowInner {
             self.conn().atom_utf8_string,
         );
 
+        if selection.target() == conn.atom_image_png {
+            return self.image_selection_notify(selection);
+        }
+
         if let Some(clipboard) = self.selection_atom_to_clipboard(selection.selection()) {
             if selection.property() != xcb::x::ATOM_NONE
                 // Restrict to strictly UTF-8 to avoid crashing; see
@@ -773,6 +874,100 @@ impl XWindowInner {
         Ok(())
     }
 
+    fn image_selection_notify(
+        &mut self,
+        selection: &xcb::x::SelectionNotifyEvent,
+    ) -> anyhow::Result<()> {
+        let conn = self.conn();
+        let mut promise = match self.copy_and_paste.image_request.take() {
+            Some(promise) => promise,
+            None => return Ok(()),
+        };
+
+        if selection.property() == xcb::x::ATOM_NONE {
+            // The owner can't provide an image
+            promise.ok(None);
+            return Ok(());
+        }
+
+        let prop = conn.wait_for_reply(conn.send_request(&xcb::x::GetProperty {
+            delete: false,
+            window: selection.requestor(),
+            property: selection.property(),
+            r#type: xcb::x::ATOM_ANY,
+            long_offset: 0,
+            long_length: u32::max_value(),
+        }));
+        conn.send_request(&xcb::x::DeleteProperty {
+            window: self.window_id,
+            property: conn.atom_xsel_data,
+        });
+
+        match prop {
+            Ok(prop) if prop.r#type() == conn.atom_image_png => {
+                promise.ok(Some(prop.value::<u8>().to_vec()));
+            }
+            Ok(prop) if prop.r#type() == conn.atom_incr => {
+                // The image is too large to transfer in one go.
+                // Deleting the property (above) asks the owner to start
+                // sending it in chunks, each of which is announced by a
+                // PropertyNotify; see image_incr_chunk.
+                // The property holds a lower bound for the total size.
+                let size_hint = prop.value::<u32>().first().copied().unwrap_or(0);
+                log::trace!("clipboard: INCR image transfer of ~{} bytes", size_hint);
+                self.copy_and_paste.image_incr = Some(Vec::with_capacity(size_hint as usize));
+                self.copy_and_paste.image_request.replace(promise);
+            }
+            Ok(prop) => {
+                log::warn!(
+                    "clipboard: unsupported image transfer type {}",
+                    conn.atom_name(prop.r#type())
+                );
+                promise.ok(None);
+            }
+            Err(err) => {
+                promise.err(anyhow!("getting clipboard image: {:?}", err));
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive the next chunk of an INCR image transfer.
+    /// A zero length chunk marks the end of the data.
+    fn image_incr_chunk(&mut self) -> anyhow::Result<()> {
+        let conn = self.conn();
+        let prop = conn.wait_for_reply(conn.send_request(&xcb::x::GetProperty {
+            // Deleting the property asks the owner for the next chunk
+            delete: true,
+            window: self.window_id,
+            property: conn.atom_xsel_data,
+            r#type: xcb::x::ATOM_ANY,
+            long_offset: 0,
+            long_length: u32::max_value(),
+        }));
+
+        match prop {
+            Ok(prop) if !prop.value::<u8>().is_empty() => {
+                if let Some(data) = self.copy_and_paste.image_incr.as_mut() {
+                    data.extend_from_slice(prop.value::<u8>());
+                }
+            }
+            Ok(_) => {
+                let data = self.copy_and_paste.image_incr.take();
+                if let Some(mut promise) = self.copy_and_paste.image_request.take() {
+                    promise.ok(data);
+                }
+            }
+            Err(err) => {
+                self.copy_and_paste.image_incr.take();
+                if let Some(mut promise) = self.copy_and_paste.image_request.take() {
+                    promise.err(anyhow!("getting clipboard image chunk: {:?}", err));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn get_window_state(&self) -> anyhow::Result<WindowState> {
         let conn = self.conn();
 
@@ -830,6 +1025,30 @@ impl XWindowInner {
         Ok(())
     }
 
+    fn request_attention(&mut self) {
+        let conn = self.conn();
+        let data: [u32; 5] = [
+            1, // _NET_WM_STATE_ADD
+            conn.atom_state_demands_attention.resource_id(),
+            0,
+            0,
+            0,
+        ];
+
+        // The window manager clears this state when the window is focused
+        conn.send_request(&xcb::x::SendEvent {
+            propagate: true,
+            destination: xcb::x::SendEventDest::Window(conn.root),
+            event_mask: xcb::x::EventMask::SUBSTRUCTURE_REDIRECT
+                | xcb::x::EventMask::SUBSTRUCTURE_NOTIFY,
+            event: &xcb::x::ClientMessageEvent::new(
+                self.window_id,
+                conn.atom_net_wm_state,
+                xcb::x::ClientMessageData::Data32(data),
+            ),
+        });
+    }
+
     #[allow(clippy::identity_op)]
     fn adjust_decorations(&mut self, decorations: WindowDecorations) -> anyhow::Result<()> {
         // Set the motif hints to disable decorations.
@@ -1024,6 +1243,7 @@ impl XWindow {
                 invalidated: false,
                 pending: vec![],
                 sure_about_geometry: false,
+                last_position: None,
             }))
         };
 
@@ -1203,6 +1423,13 @@ impl XWindowInner {
         self.update_ime_position();
     }
 
+    fn moved_to(&mut self, position: ScreenPoint) {
+        if self.last_position != Some(position) {
+            self.last_position.replace(position);
+            self.queue_pending(WindowEvent::Moved(position));
+        }
+    }
+
     fn update_ime_position(&mut self) {
         if !self.has_focus.unwrap_or(false) {
             return;
@@ -1343,6 +1570,13 @@ impl WindowOps for XWindow {
         });
     }
 
+    fn request_attention(&self) {
+        XConnection::with_window_inner(self.0, |inner| {
+            inner.request_attention();
+            Ok(())
+        });
+    }
+
     fn config_did_change(&self, config: &ConfigHandle) {
         let config = config.clone();
         XConnection::with_window_inner(self.0, move |inner| {
@@ -1458,6 +1692,37 @@ impl WindowOps for XWindow {
         future
     }
 
+    fn get_clipboard_image(&self, clipboard: Clipboard) -> Future<Option<Vec<u8>>> {
+        let mut promise = Promise::new();
+        let future = promise.get_future().unwrap();
+        let mut promise = Some(promise);
+        XConnection::with_window_inner(self.0, move |inner| {
+            let mut promise = promise.take().unwrap();
+            if inner.copy_and_paste.clipboard(clipboard).is_some() {
+                // We own the selection, and we only ever hold text
+                promise.ok(None);
+                return Ok(());
+            }
+            inner.copy_and_paste.image_request.replace(promise);
+            // Abandon any incomplete INCR transfer
+            inner.copy_and_paste.image_incr.take();
+            let conn = inner.conn();
+            conn.send_request(&xcb::x::ConvertSelection {
+                requestor: inner.window_id,
+                selection: match clipboard {
+                    Clipboard::Clipboard => conn.atom_clipboard,
+                    Clipboard::PrimarySelection => xcb::x::ATOM_PRIMARY,
+                },
+                target: conn.atom_image_png,
+                property: conn.atom_xsel_data,
+                time: inner.copy_and_paste.time,
+            });
+            Ok(())
+        });
+
+        future
+    }
+
     /// Set some text in the clipboard
     fn set_clipboard(&self, clipboard: Clipboard, text: String) {
         XConnection::with_window_inner(self.0, move |inner| {
@@ -1465,6 +1730,44 @@ impl WindowOps for XWindow {
                 .copy_and_paste
                 .clipboard_mut(clipboard)
                 .replace(text.clone());
+            inner.copy_and_paste.uri_list_mut(clipboard).take();
+            inner.copy_and_paste.html_mut(clipboard).take();
+            inner.update_selection_owner(clipboard)?;
+            Ok(())
+        });
+    }
+
+    fn set_clipboard_with_uri_list(&self, clipboard: Clipboard, text: String, uris: Vec<String>) {
+        if uris.is_empty() {
+            return self.set_clipboard(clipboard, text);
+        }
+        let uri_list = crate::uri_list_from_uris(&uris);
+        XConnection::with_window_inner(self.0, move |inner| {
+            inner
+                .copy_and_paste
+                .clipboard_mut(clipboard)
+                .replace(text.clone());
+            inner
+                .copy_and_paste
+                .uri_list_mut(clipboard)
+                .replace(uri_list.clone());
+            inner.copy_and_paste.html_mut(clipboard).take();
+            inner.update_selection_owner(clipboard)?;
+            Ok(())
+        });
+    }
+
+    fn set_clipboard_with_html(&self, clipboard: Clipboard, text: String, html: String) {
+        XConnection::with_window_inner(self.0, move |inner| {
+            inner
+                .copy_and_paste
+                .clipboard_mut(clipboard)
+                .replace(text.clone());
+            inner.copy_and_paste.uri_list_mut(clipboard).take();
+            inner
+                .copy_and_paste
+                .html_mut(clipboard)
+                .replace(html.clone());
             inner.update_selection_owner(clipboard)?;
             Ok(())
         });
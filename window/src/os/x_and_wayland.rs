@@ -138,6 +138,14 @@ impl ConnectionOps for Connection {
         }
     }
 
+    fn prefers_reduced_motion(&self) -> bool {
+        match self {
+            Self::X11(x) => x.prefers_reduced_motion(),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.prefers_reduced_motion(),
+        }
+    }
+
     fn beep(&self) {
         match self {
             Self::X11(x) => x.beep(),
@@ -145,6 +153,14 @@ impl ConnectionOps for Connection {
             Self::Wayland(w) => w.beep(),
         }
     }
+
+    fn display_configuration(&self) -> Option<String> {
+        match self {
+            Self::X11(x) => x.display_configuration(),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.display_configuration(),
+        }
+    }
 }
 
 impl Window {
@@ -337,4 +353,25 @@ impl WindowOps for Window {
             Self::Wayland(w) => w.set_clipboard(clipboard, text),
         }
     }
+    fn set_clipboard_with_uri_list(&self, clipboard: Clipboard, text: String, uris: Vec<String>) {
+        match self {
+            Self::X11(x) => x.set_clipboard_with_uri_list(clipboard, text, uris),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.set_clipboard_with_uri_list(clipboard, text, uris),
+        }
+    }
+    fn set_clipboard_with_html(&self, clipboard: Clipboard, text: String, html: String) {
+        match self {
+            Self::X11(x) => x.set_clipboard_with_html(clipboard, text, html),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.set_clipboard_with_html(clipboard, text, html),
+        }
+    }
+    fn get_clipboard_image(&self, clipboard: Clipboard) -> Future<Option<Vec<u8>>> {
+        match self {
+            Self::X11(x) => x.get_clipboard_image(clipboard),
+            #[cfg(feature = "wayland")]
+            Self::Wayland(w) => w.get_clipboard_image(clipboard),
+        }
+    }
 }